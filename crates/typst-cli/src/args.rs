@@ -59,6 +59,9 @@ pub enum Command {
     /// Lists all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 
+    /// Translates LaTeX math formulas into Typst math syntax
+    Latex(LatexCommand),
+
     /// Self update the Typst CLI
     #[cfg_attr(not(feature = "self-update"), doc = " (disabled)")]
     Update(UpdateCommand),
@@ -71,7 +74,7 @@ pub struct CompileCommand {
     #[clap(flatten)]
     pub common: SharedArgs,
 
-    /// Path to output file (PDF, PNG, or SVG).
+    /// Path to output file (PDF, PNG, SVG, HTML, or EPUB).
     /// Use `-` to write output to stdout; For output formats emitting one file per page,
     /// a page number template must be present if the source document renders to multiple pages.
     /// Use `{p}` for page numbers, `{0p}` for zero padded page numbers, `{t}` for page count.
@@ -155,6 +158,17 @@ pub struct QueryCommand {
     pub format: SerializationFormat,
 }
 
+/// Translates LaTeX math formulas into Typst math syntax
+#[derive(Debug, Clone, Parser)]
+pub struct LatexCommand {
+    /// Path to a LaTeX file or formula, use `-` to read input from stdin
+    ///
+    /// The formulas of a LaTeX document are translated one per line. Input
+    /// without math delimiters is translated as a single formula.
+    #[clap(value_parser = input_value_parser)]
+    pub input: Input,
+}
+
 // Output file format for query command
 #[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
@@ -390,6 +404,8 @@ pub enum OutputFormat {
     Pdf,
     Png,
     Svg,
+    Html,
+    Epub,
 }
 
 impl Display for OutputFormat {
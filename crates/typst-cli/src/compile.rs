@@ -11,6 +11,7 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use typst::diag::{bail, At, Severity, SourceDiagnostic, StrResult};
 use typst::eval::Tracer;
 use typst::foundations::{Datetime, Smart};
+use typst::html::HtmlDocument;
 use typst::layout::{Frame, PageRanges};
 use typst::model::Document;
 use typst::syntax::{FileId, Source, Span};
@@ -40,6 +41,8 @@ impl CompileCommand {
                     OutputFormat::Pdf => "pdf",
                     OutputFormat::Png => "png",
                     OutputFormat::Svg => "svg",
+                    OutputFormat::Html => "html",
+                    OutputFormat::Epub => "epub",
                 },
             ))
         })
@@ -56,6 +59,13 @@ impl CompileCommand {
                 Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
                 Some(ext) if ext.eq_ignore_ascii_case("png") => OutputFormat::Png,
                 Some(ext) if ext.eq_ignore_ascii_case("svg") => OutputFormat::Svg,
+                Some(ext)
+                    if ext.eq_ignore_ascii_case("html")
+                        || ext.eq_ignore_ascii_case("htm") =>
+                {
+                    OutputFormat::Html
+                }
+                Some(ext) if ext.eq_ignore_ascii_case("epub") => OutputFormat::Epub,
                 _ => bail!("could not infer output format for path {}.\nconsider providing the format manually with `--format/-f`", output.display()),
             }
         } else {
@@ -111,13 +121,20 @@ pub fn compile_once(
     }
 
     let mut tracer = Tracer::new();
-    let result = typst::compile(world, &mut tracer);
+    let result = match command.output_format()? {
+        OutputFormat::Html => typst::compile_html(world, &mut tracer)
+            .map(|document| export_html(&document, command)),
+        OutputFormat::Epub => typst::compile_html(world, &mut tracer)
+            .map(|document| export_epub(&document, command)),
+        _ => typst::compile(world, &mut tracer)
+            .map(|document| export(world, &document, command, watching)),
+    };
     let warnings = tracer.warnings();
 
     match result {
-        // Export the PDF / PNG.
-        Ok(document) => {
-            export(world, &document, command, watching)?;
+        // Export the PDF / PNG / SVG / HTML / EPUB.
+        Ok(exported) => {
+            exported?;
             let duration = start.elapsed();
 
             if watching {
@@ -176,6 +193,9 @@ fn export(
             export_image(world, document, command, watching, ImageExportFormat::Svg)
         }
         OutputFormat::Pdf => export_pdf(document, command),
+        OutputFormat::Html | OutputFormat::Epub => {
+            unreachable!("HTML documents are exported separately")
+        }
     }
 }
 
@@ -193,6 +213,30 @@ fn export_pdf(document: &Document, command: &CompileCommand) -> StrResult<()> {
     Ok(())
 }
 
+/// Export to an HTML file.
+fn export_html(document: &HtmlDocument, command: &CompileCommand) -> StrResult<()> {
+    let html = typst_html::html(document);
+    command
+        .output()
+        .write(html.as_bytes())
+        .map_err(|err| eco_format!("failed to write HTML file ({err})"))?;
+    Ok(())
+}
+
+/// Export to an EPUB file.
+fn export_epub(document: &HtmlDocument, command: &CompileCommand) -> StrResult<()> {
+    let timestamp = convert_datetime(
+        command.common.creation_timestamp.unwrap_or_else(chrono::Utc::now),
+    );
+    let buffer = typst_epub::epub(document, Smart::Auto, timestamp)
+        .map_err(|err| eco_format!("failed to create EPUB file ({err})"))?;
+    command
+        .output()
+        .write(&buffer)
+        .map_err(|err| eco_format!("failed to write EPUB file ({err})"))?;
+    Ok(())
+}
+
 /// Convert [`chrono::DateTime`] to [`Datetime`]
 fn convert_datetime(date_time: chrono::DateTime<chrono::Utc>) -> Option<Datetime> {
     Datetime::from_ymd_hms(
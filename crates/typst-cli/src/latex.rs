@@ -0,0 +1,127 @@
+use std::fs;
+use std::io::{self, Read};
+
+use ecow::eco_format;
+use typst::diag::StrResult;
+use typst::math::latex_to_typst;
+
+use crate::args::{Input, LatexCommand};
+
+/// LaTeX environments that contain a displayed formula.
+const DISPLAY_ENVIRONMENTS: &[&str] = &[
+    "equation",
+    "equation*",
+    "align",
+    "align*",
+    "gather",
+    "gather*",
+    "multline",
+    "multline*",
+    "displaymath",
+];
+
+/// Execute a LaTeX translation command.
+pub fn latex(command: &LatexCommand) -> StrResult<()> {
+    let text = match &command.input {
+        Input::Stdin => {
+            let mut buf = String::new();
+            io::stdin()
+                .read_to_string(&mut buf)
+                .map_err(|err| eco_format!("failed to read from stdin ({err})"))?;
+            buf
+        }
+        Input::Path(path) => fs::read_to_string(path)
+            .map_err(|err| eco_format!("failed to read input file ({err})"))?,
+    };
+
+    // Input without math delimiters is a single formula.
+    let formulas = extract(&text);
+    if formulas.is_empty() {
+        println!("{}", latex_to_typst(text.trim())?);
+        return Ok(());
+    }
+
+    for formula in formulas {
+        let converted = latex_to_typst(formula.body)
+            .map_err(|err| eco_format!("{err} (line {})", formula.line))?;
+        if formula.display {
+            println!("$ {converted} $");
+        } else {
+            println!("${converted}$");
+        }
+    }
+
+    Ok(())
+}
+
+/// A formula in a LaTeX document.
+struct Formula<'a> {
+    /// The LaTeX source of the formula, without its delimiters.
+    body: &'a str,
+    /// Whether the formula is displayed in its own block.
+    display: bool,
+    /// The line on which the formula starts.
+    line: usize,
+}
+
+/// Find the formulas in a LaTeX document.
+fn extract(text: &str) -> Vec<Formula<'_>> {
+    let mut formulas = vec![];
+    let mut cursor = 0;
+    while let Some(c) = text[cursor..].chars().next() {
+        let rest = &text[cursor..];
+
+        // Skip comments and escaped characters.
+        if c == '%' {
+            cursor += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        } else if rest.starts_with("\\$") || rest.starts_with("\\\\") {
+            cursor += 2;
+            continue;
+        }
+
+        let delims = if rest.starts_with("$$") {
+            Some(("$$".to_string(), "$$".to_string(), true))
+        } else if rest.starts_with('$') {
+            Some(("$".into(), "$".into(), false))
+        } else if rest.starts_with("\\[") {
+            Some(("\\[".into(), "\\]".into(), true))
+        } else if rest.starts_with("\\(") {
+            Some(("\\(".into(), "\\)".into(), false))
+        } else {
+            DISPLAY_ENVIRONMENTS
+                .iter()
+                .map(|env| (format!("\\begin{{{env}}}"), format!("\\end{{{env}}}"), true))
+                .find(|(open, _, _)| rest.starts_with(open.as_str()))
+        };
+
+        let Some((open, close, display)) = delims else {
+            cursor += c.len_utf8();
+            continue;
+        };
+
+        let start = cursor + open.len();
+        let Some(len) = find_close(&text[start..], &close) else { break };
+        formulas.push(Formula {
+            body: text[start..start + len].trim(),
+            display,
+            line: text[..cursor].matches('\n').count() + 1,
+        });
+        cursor = start + len + close.len();
+    }
+
+    formulas
+}
+
+/// Find the closing delimiter of a formula, skipping escaped characters.
+fn find_close(text: &str, close: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if text[i..].starts_with(close) {
+            return Some(i);
+        } else if c == '\\' {
+            chars.next();
+        }
+    }
+    None
+}
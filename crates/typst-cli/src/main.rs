@@ -3,6 +3,7 @@ mod compile;
 mod download;
 mod fonts;
 mod init;
+mod latex;
 mod package;
 mod query;
 mod terminal;
@@ -42,6 +43,7 @@ fn main() -> ExitCode {
         Command::Init(command) => crate::init::init(command),
         Command::Query(command) => crate::query::query(command),
         Command::Fonts(command) => crate::fonts::fonts(command),
+        Command::Latex(command) => crate::latex::latex(command),
         Command::Update(command) => crate::update::update(command),
     };
 
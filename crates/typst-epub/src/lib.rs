@@ -0,0 +1,903 @@
+//! Exporting of Typst documents into EPUB 3.
+
+mod zip;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+use typst::diag::StrResult;
+use typst::foundations::{Bytes, Datetime, Packed, Smart, StyleChain};
+use typst::html::{HtmlDocument, HtmlElement, HtmlNode, HtmlTag};
+use typst::layout::Dir;
+use typst::model::{HeadingElem, OutlineElem};
+use typst::text::{Font, FontFlags, FontStyle, Lang, LocalName};
+use typst::utils::NonZeroExt;
+use typst::visualize::{Image, ImageFormat, RasterFormat, VectorFormat};
+use typst_html::{Syntax, Writer};
+
+use crate::zip::ZipWriter;
+
+/// The XML namespace of XHTML.
+const XHTML_NS: &str = "http://www.w3.org/1999/xhtml";
+
+/// The XML namespace of the EPUB structural semantics vocabulary.
+const EPUB_NS: &str = "http://www.idpf.org/2007/ops";
+
+/// The container file, which points reading systems to the package document.
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="EPUB/package.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// Export a document into an EPUB file.
+///
+/// Returns the raw bytes making up the EPUB file, or an error if the book is
+/// too large for the ZIP container format.
+///
+/// The document is split into one chapter per top-level heading. The
+/// navigation document is generated from the document's outline and the
+/// fonts the text is set in are embedded, so that the book looks alike in all
+/// reading systems.
+///
+/// The `ident` parameter, if given, shall be a string that uniquely and stably
+/// identifies the document. It should not change between compilations of the
+/// same document. Its hash is used as the book's identifier. If `ident` is
+/// `Auto`, a hash of the document's title and author is used instead. If the
+/// document lacks a title or an author, the hash of its contents is used,
+/// which changes whenever the document is edited.
+///
+/// The `timestamp`, if given, is expected to be the current date as a UTC
+/// datetime. It is used as the book's modification date, and as its
+/// publication date if `set document(date: ..)` is `auto`.
+#[typst_macros::time(name = "epub")]
+pub fn epub(
+    document: &HtmlDocument,
+    ident: Smart<&str>,
+    timestamp: Option<Datetime>,
+) -> StrResult<Vec<u8>> {
+    let lang = document.lang.unwrap_or(Lang::ENGLISH);
+    let title = document
+        .title
+        .clone()
+        .or_else(|| document.outline.first().map(|entry| entry.title.clone()))
+        .unwrap_or_else(|| "Untitled".into());
+
+    let chapters = chapters(document, &title);
+    let fonts = fonts(&document.fonts);
+    let mut images = vec![];
+
+    let mut zip = ZipWriter::new();
+
+    // The MIME type must come first and must not be compressed, so that the
+    // format can be detected from the file's first bytes.
+    zip.stored("mimetype", b"application/epub+zip")?;
+    zip.deflated("META-INF/container.xml", CONTAINER.as_bytes())?;
+
+    for chapter in &chapters {
+        let mut writer = Writer::new(Syntax::Xml).with_image_src(|image| {
+            let index = match images.iter().position(|other| other == image) {
+                Some(index) => index,
+                None => {
+                    images.push(image.clone());
+                    images.len() - 1
+                }
+            };
+            image_path(image, index)
+        });
+        let page = page(lang, &chapter.title, chapter.nodes.clone());
+        write_xhtml(&mut writer, &page);
+        zip.deflated(&format!("EPUB/{}", chapter.path), writer.finish().as_bytes())?;
+    }
+
+    let mut writer = Writer::new(Syntax::Xml);
+    write_xhtml(&mut writer, &nav(document, lang, &chapters));
+    zip.deflated("EPUB/nav.xhtml", writer.finish().as_bytes())?;
+    zip.deflated("EPUB/style.css", stylesheet(&fonts).as_bytes())?;
+
+    for font in &fonts {
+        zip.deflated(&format!("EPUB/{}", font.path), font.data.as_slice())?;
+    }
+
+    for (i, image) in images.iter().enumerate() {
+        zip.deflated(&format!("EPUB/{}", image_path(image, i)), image.data().as_slice())?;
+    }
+
+    let package =
+        package(document, ident, timestamp, lang, &title, &chapters, &fonts, &images);
+    zip.deflated("EPUB/package.opf", package.as_bytes())?;
+    zip.finish()
+}
+
+/// A part of the book that is stored in its own content document.
+struct Chapter {
+    /// The path of the content document, relative to the package document.
+    path: EcoString,
+    /// The chapter's title.
+    title: EcoString,
+    /// The chapter's contents.
+    nodes: Vec<HtmlNode>,
+    /// Whether the chapter contains inline SVG.
+    svg: bool,
+    /// Whether the chapter contains MathML.
+    mathml: bool,
+}
+
+/// Split the document into chapters at its top-level headings.
+///
+/// Links between chapters are adjusted to point to the right content
+/// document.
+fn chapters(document: &HtmlDocument, title: &EcoString) -> Vec<Chapter> {
+    let mut parts: Vec<Vec<HtmlNode>> = vec![vec![]];
+    for node in &document.body {
+        let current = parts.last_mut().unwrap();
+        if is_top_level_heading(node)
+            && current.iter().any(|node| !matches!(node, HtmlNode::Tag(_)))
+        {
+            parts.push(vec![]);
+        }
+        parts.last_mut().unwrap().push(node.clone());
+    }
+
+    let paths: Vec<EcoString> =
+        (1..=parts.len()).map(|i| eco_format!("chapter-{i}.xhtml")).collect();
+
+    // Find out which chapter each `id` ends up in.
+    let mut targets = HashMap::new();
+    for (nodes, path) in parts.iter().zip(&paths) {
+        collect_ids(nodes, path, &mut targets);
+    }
+
+    parts
+        .into_iter()
+        .zip(paths)
+        .map(|(mut nodes, path)| {
+            relink(&mut nodes, &path, &targets);
+            let title = document
+                .outline
+                .iter()
+                .find(|entry| targets.get(&entry.id) == Some(&path))
+                .map_or_else(|| title.clone(), |entry| entry.title.clone());
+            let svg = contains_svg(&nodes);
+            let mathml = contains_mathml(&nodes);
+            Chapter { path, title, nodes, svg, mathml }
+        })
+        .collect()
+}
+
+/// Whether a node is the start of a level-one heading.
+fn is_top_level_heading(node: &HtmlNode) -> bool {
+    let HtmlNode::Tag(elem) = node else { return false };
+    elem.to_packed::<HeadingElem>().is_some_and(|heading| {
+        heading.resolve_level(StyleChain::default()) == NonZeroUsize::ONE
+    })
+}
+
+/// Remember the content document of all elements with an `id`.
+fn collect_ids(
+    nodes: &[HtmlNode],
+    path: &EcoString,
+    targets: &mut HashMap<EcoString, EcoString>,
+) {
+    for node in nodes {
+        if let HtmlNode::Element(element) = node {
+            if let Some(id) = element.attrs.get("id") {
+                targets.insert(id.clone(), path.clone());
+            }
+            collect_ids(&element.children, path, targets);
+        }
+    }
+}
+
+/// Make links to elements in other content documents point there.
+fn relink(
+    nodes: &mut [HtmlNode],
+    path: &EcoString,
+    targets: &HashMap<EcoString, EcoString>,
+) {
+    for node in nodes {
+        let HtmlNode::Element(element) = node else { continue };
+        let href = element.attrs.get("href").and_then(|href| href.strip_prefix('#'));
+        if let Some(target) = href.and_then(|id| targets.get(id)) {
+            if target != path {
+                let href = eco_format!("{target}{}", element.attrs.get("href").unwrap());
+                element.attrs.push("href", href);
+            }
+        }
+        relink(&mut element.children, path, targets);
+    }
+}
+
+/// Whether any of the nodes is exported as inline SVG.
+fn contains_svg(nodes: &[HtmlNode]) -> bool {
+    nodes.iter().any(|node| match node {
+        HtmlNode::Frame(_) | HtmlNode::Equation(_) => true,
+        HtmlNode::Element(element) => {
+            element.tag.as_str() == "svg" || contains_svg(&element.children)
+        }
+        _ => false,
+    })
+}
+
+/// Whether any of the nodes is exported as MathML.
+fn contains_mathml(nodes: &[HtmlNode]) -> bool {
+    nodes.iter().any(|node| match node {
+        HtmlNode::Equation(_) => true,
+        HtmlNode::Element(element) => contains_mathml(&element.children),
+        _ => false,
+    })
+}
+
+/// Create the navigation document from the document's outline.
+fn nav(document: &HtmlDocument, lang: Lang, chapters: &[Chapter]) -> HtmlElement {
+    let mut targets = HashMap::new();
+    for chapter in chapters {
+        collect_ids(&chapter.nodes, &chapter.path, &mut targets);
+    }
+
+    let mut entries: Vec<_> = document
+        .outline
+        .iter()
+        .filter_map(|entry| {
+            let path = targets.get(&entry.id)?;
+            Some((entry.level, entry.title.clone(), eco_format!("{path}#{}", entry.id)))
+        })
+        .collect();
+
+    // The table of contents must not be empty.
+    if entries.is_empty() {
+        entries.extend(chapters.iter().map(|chapter| {
+            (NonZeroUsize::ONE, chapter.title.clone(), chapter.path.clone())
+        }));
+    }
+
+    let heading = <Packed<OutlineElem> as LocalName>::local_name(lang, None);
+    let toc = HtmlElement::new(HtmlTag::constant("nav"))
+        .with_attr("epub:type", "toc")
+        .with_attr("id", "toc")
+        .with_children(vec![
+            HtmlElement::new(HtmlTag::constant("h1"))
+                .with_children(vec![HtmlNode::text(heading)])
+                .into(),
+            nav_list(&entries).into(),
+        ]);
+
+    page(lang, &EcoString::from(heading), vec![toc.into()])
+}
+
+/// Create a nested list from outline entries.
+fn nav_list(entries: &[(NonZeroUsize, EcoString, EcoString)]) -> HtmlElement {
+    let mut items = vec![];
+    let mut i = 0;
+    while let Some((level, title, href)) = entries.get(i) {
+        // The entries up to the next one on the same or a higher level are
+        // nested below this one.
+        let end = entries[i + 1..]
+            .iter()
+            .position(|(other, _, _)| other <= level)
+            .map_or(entries.len(), |offset| i + 1 + offset);
+
+        let mut children = vec![HtmlElement::new(HtmlTag::constant("a"))
+            .with_attr("href", href.clone())
+            .with_children(vec![HtmlNode::text(title.clone())])
+            .into()];
+        if end > i + 1 {
+            children.push(nav_list(&entries[i + 1..end]).into());
+        }
+
+        items.push(
+            HtmlElement::new(HtmlTag::constant("li"))
+                .with_children(children)
+                .into(),
+        );
+        i = end;
+    }
+
+    HtmlElement::new(HtmlTag::constant("ol")).with_children(items)
+}
+
+/// Create an XHTML content document.
+fn page(lang: Lang, title: &EcoString, body: Vec<HtmlNode>) -> HtmlElement {
+    let head = vec![
+        HtmlElement::new(HtmlTag::constant("meta"))
+            .with_attr("charset", "utf-8")
+            .into(),
+        HtmlElement::new(HtmlTag::constant("title"))
+            .with_children(vec![HtmlNode::text(title.clone())])
+            .into(),
+        HtmlElement::new(HtmlTag::constant("link"))
+            .with_attr("rel", "stylesheet")
+            .with_attr("type", "text/css")
+            .with_attr("href", "style.css")
+            .into(),
+    ];
+
+    HtmlElement::new(HtmlTag::constant("html"))
+        .with_attr("xmlns", XHTML_NS)
+        .with_attr("xmlns:epub", EPUB_NS)
+        .with_attr("lang", lang.as_str())
+        .with_attr("xml:lang", lang.as_str())
+        .with_children(vec![
+            HtmlElement::new(HtmlTag::constant("head")).with_children(head).into(),
+            HtmlElement::new(HtmlTag::constant("body")).with_children(body).into(),
+        ])
+}
+
+/// Write an XHTML content document with its prolog.
+fn write_xhtml(writer: &mut Writer, html: &HtmlElement) {
+    writer.raw("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n");
+    writer.element(html);
+    writer.raw("\n");
+}
+
+/// A font file that is embedded into the book.
+struct EmbeddedFont {
+    /// The path of the file, relative to the package document.
+    path: EcoString,
+    /// The file's MIME type.
+    mime: &'static str,
+    /// The font data.
+    data: Bytes,
+    /// The faces that are defined by the file.
+    faces: Vec<Font>,
+}
+
+/// Determine the font files to embed.
+///
+/// Fonts from collections can't be embedded as-is and are skipped.
+fn fonts(fonts: &[Font]) -> Vec<EmbeddedFont> {
+    let mut files: Vec<EmbeddedFont> = vec![];
+    for font in fonts {
+        let data = font.data();
+        if data.starts_with(b"ttcf") {
+            continue;
+        }
+
+        if let Some(file) = files.iter_mut().find(|file| file.data == *data) {
+            file.faces.push(font.clone());
+            continue;
+        }
+
+        let (ext, mime) = if data.starts_with(b"OTTO") {
+            ("otf", "font/otf")
+        } else {
+            ("ttf", "font/ttf")
+        };
+        files.push(EmbeddedFont {
+            path: eco_format!("fonts/font-{}.{ext}", files.len() + 1),
+            mime,
+            data: data.clone(),
+            faces: vec![font.clone()],
+        });
+    }
+    files
+}
+
+/// Create the stylesheet, which declares the embedded fonts.
+fn stylesheet(fonts: &[EmbeddedFont]) -> String {
+    let mut css = String::new();
+    let mut families = vec![];
+    let mut monospace = vec![];
+
+    for file in fonts {
+        for font in &file.faces {
+            let info = font.info();
+            let family = info.family.replace('"', "");
+            let style = match info.variant.style {
+                FontStyle::Normal => "normal",
+                FontStyle::Italic => "italic",
+                FontStyle::Oblique => "oblique",
+            };
+
+            writeln!(css, "@font-face {{").unwrap();
+            writeln!(css, "  font-family: \"{family}\";").unwrap();
+            writeln!(css, "  font-style: {style};").unwrap();
+            writeln!(css, "  font-weight: {};", info.variant.weight.to_number()).unwrap();
+            writeln!(css, "  src: url(\"{}\");", file.path).unwrap();
+            writeln!(css, "}}\n").unwrap();
+
+            let list = if info.flags.contains(FontFlags::MONOSPACE) {
+                &mut monospace
+            } else {
+                &mut families
+            };
+            let quoted = format!("\"{family}\"");
+            if !list.contains(&quoted) {
+                list.push(quoted);
+            }
+        }
+    }
+
+    if !families.is_empty() {
+        writeln!(css, "body {{\n  font-family: {};\n}}\n", families.join(", ")).unwrap();
+    }
+
+    monospace.push("monospace".into());
+    writeln!(css, "code, pre {{\n  font-family: {};\n}}\n", monospace.join(", "))
+        .unwrap();
+
+    // Glyphs may extend beyond the frame they were laid out in, like italic
+    // letters overhanging their advance.
+    css.push_str("svg.typst-doc {\n  overflow: visible;\n}\n\n");
+
+    // Equations are written both as MathML and as SVG, of which only one is
+    // shown depending on whether the reading system supports MathML.
+    css.push_str(
+        "@supports (math-depth: auto-add) {\n  \
+         .math svg.typst-doc {\n    display: none;\n  }\n}\n\n",
+    );
+    css.push_str(
+        "@supports not (math-depth: auto-add) {\n  \
+         .math math {\n    display: none;\n  }\n}\n\n",
+    );
+    css.push_str("img {\n  max-width: 100%;\n}\n");
+    css
+}
+
+/// The path of an embedded image, relative to the package document.
+fn image_path(image: &Image, index: usize) -> EcoString {
+    let ext = match image.format() {
+        ImageFormat::Raster(RasterFormat::Png) => "png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "jpg",
+        ImageFormat::Raster(RasterFormat::Gif) => "gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "svg",
+    };
+    eco_format!("images/image-{}.{ext}", index + 1)
+}
+
+/// Create the package document, which holds the book's metadata and lists
+/// its resources.
+#[allow(clippy::too_many_arguments)]
+fn package(
+    document: &HtmlDocument,
+    ident: Smart<&str>,
+    timestamp: Option<Datetime>,
+    lang: Lang,
+    title: &str,
+    chapters: &[Chapter],
+    fonts: &[EmbeddedFont],
+    images: &[Image],
+) -> String {
+    let mut opf = String::new();
+    opf.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        opf,
+        "<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" \
+         unique-identifier=\"uid\" xml:lang=\"{}\">",
+        lang.as_str()
+    )
+    .unwrap();
+
+    // Write the metadata.
+    opf.push_str("  <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+    let id = identifier(document, ident);
+    writeln!(opf, "    <dc:identifier id=\"uid\">{id}</dc:identifier>").unwrap();
+    writeln!(opf, "    <dc:title>{}</dc:title>", escape(title)).unwrap();
+    for author in &document.author {
+        writeln!(opf, "    <dc:creator>{}</dc:creator>", escape(author)).unwrap();
+    }
+    writeln!(opf, "    <dc:language>{}</dc:language>", lang.as_str()).unwrap();
+    for keyword in &document.keywords {
+        writeln!(opf, "    <dc:subject>{}</dc:subject>", escape(keyword)).unwrap();
+    }
+
+    let date = document.date.unwrap_or(timestamp);
+    if let Some(date) = date.and_then(|date| format_date(date, false)) {
+        writeln!(opf, "    <dc:date>{date}</dc:date>").unwrap();
+    }
+
+    // The modification date is required.
+    if let Some(modified) = timestamp.or(date).and_then(|date| format_date(date, true)) {
+        writeln!(opf, "    <meta property=\"dcterms:modified\">{modified}</meta>")
+            .unwrap();
+    }
+
+    writeln!(
+        opf,
+        "    <meta name=\"generator\" content=\"Typst {}\"/>",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    opf.push_str("  </metadata>\n");
+
+    // Write the manifest.
+    opf.push_str("  <manifest>\n");
+    item(&mut opf, "nav", "nav.xhtml", "application/xhtml+xml", Some("nav"));
+    item(&mut opf, "style", "style.css", "text/css", None);
+    for (i, chapter) in chapters.iter().enumerate() {
+        let id = format!("chapter-{}", i + 1);
+        // Equations come with an SVG fallback.
+        let properties = if chapter.mathml {
+            Some("mathml svg")
+        } else {
+            chapter.svg.then_some("svg")
+        };
+        item(&mut opf, &id, &chapter.path, "application/xhtml+xml", properties);
+    }
+    for (i, font) in fonts.iter().enumerate() {
+        item(&mut opf, &format!("font-{}", i + 1), &font.path, font.mime, None);
+    }
+    for (i, image) in images.iter().enumerate() {
+        let id = format!("image-{}", i + 1);
+        let mime = typst_html::mime_type(image.format());
+        item(&mut opf, &id, &image_path(image, i), mime, None);
+    }
+    opf.push_str("  </manifest>\n");
+
+    // Write the reading order.
+    if lang.dir() == Dir::RTL {
+        opf.push_str("  <spine page-progression-direction=\"rtl\">\n");
+    } else {
+        opf.push_str("  <spine>\n");
+    }
+    for i in 1..=chapters.len() {
+        writeln!(opf, "    <itemref idref=\"chapter-{i}\"/>").unwrap();
+    }
+    opf.push_str("  </spine>\n");
+
+    opf.push_str("</package>\n");
+    opf
+}
+
+/// Write an item of the package manifest.
+fn item(opf: &mut String, id: &str, href: &str, mime: &str, properties: Option<&str>) {
+    write!(opf, "    <item id=\"{id}\" href=\"{href}\" media-type=\"{mime}\"").unwrap();
+    if let Some(properties) = properties {
+        write!(opf, " properties=\"{properties}\"").unwrap();
+    }
+    opf.push_str("/>\n");
+}
+
+/// Determine the book's unique identifier. It should be as stable as possible.
+fn identifier(document: &HtmlDocument, ident: Smart<&str>) -> EcoString {
+    let hash = if let Smart::Custom(ident) = ident {
+        typst::utils::hash128(&("EPUB", ident))
+    } else if document.title.is_some() && !document.author.is_empty() {
+        typst::utils::hash128(&("EPUB", &document.title, &document.author))
+    } else {
+        typst::utils::hash128(&document.body)
+    };
+
+    // Format the hash as a name-based UUID.
+    let hash = (hash & !(0xf << 76) & !(0x3 << 62)) | (0x5 << 76) | (0x2 << 62);
+    eco_format!(
+        "urn:uuid:{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        hash >> 96,
+        (hash >> 80) & 0xffff,
+        (hash >> 64) & 0xffff,
+        (hash >> 48) & 0xffff,
+        hash & 0xffff_ffff_ffff,
+    )
+}
+
+/// Format a date as specified by W3CDTF. If `full` is true, the time is
+/// always included, as required for the modification date.
+fn format_date(date: Datetime, full: bool) -> Option<EcoString> {
+    let year = date.year().filter(|&year| year >= 0)?;
+    let month = date.month()?;
+    let day = date.day()?;
+    let mut formatted = eco_format!("{year:04}-{month:02}-{day:02}");
+    if let (Some(hour), Some(minute), Some(second)) =
+        (date.hour(), date.minute(), date.second())
+    {
+        write!(formatted, "T{hour:02}:{minute:02}:{second:02}Z").unwrap();
+    } else if full {
+        formatted.push_str("T00:00:00Z");
+    }
+    Some(formatted)
+}
+
+/// Escape text for use in XML.
+fn escape(text: &str) -> EcoString {
+    let mut escaped = EcoString::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::DeflateDecoder;
+    use flate2::Crc;
+    use typst::foundations::{Content, NativeElement};
+    use typst::html::HtmlOutlineEntry;
+
+    use super::*;
+
+    /// A file read back from an archive.
+    struct File {
+        name: String,
+        method: u16,
+        data: Vec<u8>,
+    }
+
+    /// Read the files of an archive through its central directory and check
+    /// that it agrees with the local headers.
+    fn unzip(buf: &[u8]) -> Vec<File> {
+        let u16_at = |at: usize| u16::from_le_bytes(buf[at..at + 2].try_into().unwrap());
+        let u32_at = |at: usize| u32::from_le_bytes(buf[at..at + 4].try_into().unwrap());
+
+        let end = buf.len() - 22;
+        assert_eq!(u32_at(end), 0x06054b50);
+        let count = u16_at(end + 10) as usize;
+        assert_eq!(u16_at(end + 8) as usize, count);
+        let size = u32_at(end + 12) as usize;
+        let start = u32_at(end + 16) as usize;
+        assert_eq!(start + size, end);
+
+        let mut files = vec![];
+        let mut at = start;
+        for _ in 0..count {
+            assert_eq!(u32_at(at), 0x02014b50);
+            let method = u16_at(at + 10);
+            let crc = u32_at(at + 16);
+            let compressed_size = u32_at(at + 20) as usize;
+            let size = u32_at(at + 24) as usize;
+            let name_len = u16_at(at + 28) as usize;
+            let offset = u32_at(at + 42) as usize;
+            let name = std::str::from_utf8(&buf[at + 46..at + 46 + name_len]).unwrap();
+            at += 46 + name_len;
+
+            assert_eq!(u32_at(offset), 0x04034b50);
+            assert_eq!(u16_at(offset + 8), method);
+            assert_eq!(u32_at(offset + 14), crc);
+            assert_eq!(u32_at(offset + 18) as usize, compressed_size);
+            assert_eq!(u32_at(offset + 22) as usize, size);
+            assert_eq!(u16_at(offset + 26) as usize, name_len);
+            assert_eq!(&buf[offset + 30..offset + 30 + name_len], name.as_bytes());
+
+            let payload = offset + 30 + name_len;
+            let payload = &buf[payload..payload + compressed_size];
+            let data = match method {
+                0 => payload.to_vec(),
+                8 => {
+                    let mut data = vec![];
+                    DeflateDecoder::new(payload).read_to_end(&mut data).unwrap();
+                    data
+                }
+                _ => panic!("unknown compression method {method}"),
+            };
+            assert_eq!(data.len(), size);
+
+            let mut check = Crc::new();
+            check.update(&data);
+            assert_eq!(check.sum(), crc);
+
+            files.push(File { name: name.into(), method, data });
+        }
+
+        assert_eq!(at, end);
+        files
+    }
+
+    fn heading(level: usize) -> HtmlNode {
+        let level = NonZeroUsize::new(level).unwrap();
+        HtmlNode::Tag(
+            HeadingElem::new(Content::empty())
+                .with_level(Smart::Custom(level))
+                .pack(),
+        )
+    }
+
+    fn element(tag: &'static str, id: &str, href: &str) -> HtmlNode {
+        let mut element = HtmlElement::new(HtmlTag::constant(tag));
+        if !id.is_empty() {
+            element = element.with_attr("id", id);
+        }
+        if !href.is_empty() {
+            element = element.with_attr("href", href);
+        }
+        element.into()
+    }
+
+    fn hrefs(nodes: &[HtmlNode]) -> Vec<&str> {
+        nodes
+            .iter()
+            .filter_map(|node| match node {
+                HtmlNode::Element(element) => element.attrs.get("href"),
+                _ => None,
+            })
+            .map(EcoString::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn test_epub_starts_with_uncompressed_mimetype() {
+        let buf = epub(&HtmlDocument::default(), Smart::Auto, None).unwrap();
+        assert_eq!(&buf[..4], b"PK\x03\x04");
+        assert_eq!(&buf[30..38], b"mimetype");
+        assert_eq!(&buf[38..58], b"application/epub+zip");
+    }
+
+    #[test]
+    fn test_epub_round_trip() {
+        let data = typst_assets::fonts().next().unwrap();
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        let svg = "<svg xmlns='http://www.w3.org/2000/svg' width='1' height='1'/>";
+        let image = Image::new(
+            svg.as_bytes().into(),
+            ImageFormat::Vector(VectorFormat::Svg),
+            Some("A dot".into()),
+        )
+        .unwrap();
+
+        let entry = |title: &str, id: &str| HtmlOutlineEntry {
+            level: NonZeroUsize::ONE,
+            title: title.into(),
+            id: id.into(),
+        };
+
+        let document = HtmlDocument {
+            body: vec![
+                heading(1),
+                element("h1", "intro", ""),
+                HtmlNode::Image(image),
+                heading(1),
+                element("h1", "details", ""),
+            ],
+            title: Some("Fish & Chips".into()),
+            author: vec!["Jane".into(), "John".into()],
+            keywords: vec!["food".into(), "recipes".into()],
+            date: Smart::Custom(Datetime::from_ymd(2024, 3, 1)),
+            lang: Some(Lang::GERMAN),
+            outline: vec![entry("Intro", "intro"), entry("Details", "details")],
+            fonts: vec![font],
+            ..HtmlDocument::default()
+        };
+
+        let timestamp = Datetime::from_ymd_hms(2024, 5, 6, 7, 8, 9);
+        let files = unzip(&epub(&document, Smart::Custom("book"), timestamp).unwrap());
+        let names: Vec<_> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "mimetype",
+                "META-INF/container.xml",
+                "EPUB/chapter-1.xhtml",
+                "EPUB/chapter-2.xhtml",
+                "EPUB/nav.xhtml",
+                "EPUB/style.css",
+                "EPUB/fonts/font-1.ttf",
+                "EPUB/images/image-1.svg",
+                "EPUB/package.opf",
+            ]
+        );
+
+        let file = |name: &str| {
+            let file = files.iter().find(|file| file.name == name).unwrap();
+            std::str::from_utf8(&file.data).unwrap_or_default()
+        };
+
+        assert_eq!(files[0].method, 0);
+        assert_eq!(files[0].data, b"application/epub+zip");
+        assert!(files[1..].iter().all(|file| file.method == 8));
+        assert_eq!(file("META-INF/container.xml"), CONTAINER);
+        assert_eq!(files[6].data, data);
+        assert_eq!(files[7].data, svg.as_bytes());
+
+        assert!(file("EPUB/chapter-1.xhtml")
+            .contains("<img src=\"images/image-1.svg\" alt=\"A dot\"/>"));
+        assert!(file("EPUB/style.css").contains("src: url(\"fonts/font-1.ttf\");"));
+
+        let nav = file("EPUB/nav.xhtml");
+        assert!(nav.contains("<nav epub:type=\"toc\" id=\"toc\">"));
+        assert!(nav.contains("<a href=\"chapter-1.xhtml#intro\">Intro</a>"));
+        assert!(nav.contains("<a href=\"chapter-2.xhtml#details\">Details</a>"));
+
+        let opf = file("EPUB/package.opf");
+        let id = identifier(&document, Smart::Custom("book"));
+        for line in [
+            eco_format!("<dc:identifier id=\"uid\">{id}</dc:identifier>"),
+            "<dc:title>Fish &amp; Chips</dc:title>".into(),
+            "<dc:creator>Jane</dc:creator>".into(),
+            "<dc:creator>John</dc:creator>".into(),
+            "<dc:language>de</dc:language>".into(),
+            "<dc:subject>food</dc:subject>".into(),
+            "<dc:subject>recipes</dc:subject>".into(),
+            "<dc:date>2024-03-01</dc:date>".into(),
+            "<meta property=\"dcterms:modified\">2024-05-06T07:08:09Z</meta>".into(),
+        ] {
+            assert!(opf.contains(line.as_str()), "missing {line}");
+        }
+
+        let start = opf.find("  <manifest>").unwrap();
+        assert_eq!(
+            &opf[start..],
+            "  <manifest>
+    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>
+    <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>
+    <item id=\"chapter-1\" href=\"chapter-1.xhtml\" media-type=\"application/xhtml+xml\"/>
+    <item id=\"chapter-2\" href=\"chapter-2.xhtml\" media-type=\"application/xhtml+xml\"/>
+    <item id=\"font-1\" href=\"fonts/font-1.ttf\" media-type=\"font/ttf\"/>
+    <item id=\"image-1\" href=\"images/image-1.svg\" media-type=\"image/svg+xml\"/>
+  </manifest>
+  <spine>
+    <itemref idref=\"chapter-1\"/>
+    <itemref idref=\"chapter-2\"/>
+  </spine>
+</package>
+",
+        );
+    }
+
+    #[test]
+    fn test_epub_rejects_long_file_names() {
+        let mut zip = ZipWriter::new();
+        let name = "a".repeat(usize::from(u16::MAX) + 1);
+        assert!(zip.deflated(&name, b"").is_err());
+    }
+
+    #[test]
+    fn test_epub_rejects_too_many_files() {
+        let mut zip = ZipWriter::new();
+        for i in 0..u16::MAX {
+            zip.stored(&format!("{i}"), b"").unwrap();
+        }
+        assert!(zip.finish().is_err());
+    }
+
+    #[test]
+    fn test_epub_splits_chapters_at_top_level_headings() {
+        let document = HtmlDocument {
+            body: vec![
+                heading(1),
+                element("h1", "a", ""),
+                element("a", "", "#c"),
+                heading(2),
+                element("h2", "b", ""),
+                element("a", "", "#b"),
+                heading(1),
+                element("h1", "c", ""),
+                element("a", "", "#a"),
+            ],
+            ..HtmlDocument::default()
+        };
+
+        let chapters = chapters(&document, &"Book".into());
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].path, "chapter-1.xhtml");
+        assert_eq!(hrefs(&chapters[0].nodes), ["chapter-2.xhtml#c", "#b"]);
+        assert_eq!(hrefs(&chapters[1].nodes), ["chapter-1.xhtml#a"]);
+    }
+
+    #[test]
+    fn test_epub_nav_nests_entries() {
+        let entries: Vec<_> = [1, 2, 3, 2, 1]
+            .into_iter()
+            .map(|level| (NonZeroUsize::new(level).unwrap(), "".into(), "".into()))
+            .collect();
+
+        fn shape(list: &HtmlElement) -> String {
+            let mut out = String::from("(");
+            for item in &list.children {
+                let HtmlNode::Element(item) = item else { continue };
+                out.push('i');
+                for child in &item.children {
+                    match child {
+                        HtmlNode::Element(child) if child.tag.as_str() == "ol" => {
+                            out.push_str(&shape(child))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            out.push(')');
+            out
+        }
+
+        assert_eq!(shape(&nav_list(&entries)), "(i(i(i)i)i)");
+    }
+}
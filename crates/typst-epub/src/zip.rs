@@ -0,0 +1,172 @@
+//! Writing of ZIP archives, the container format of EPUB.
+//!
+//! The ZIP64 extensions are not supported, so archives are limited to 4 GiB
+//! and 65534 files.
+
+use std::io::Write;
+
+use ecow::eco_format;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use typst::diag::{bail, StrResult};
+
+/// The ZIP version that is needed to extract the archive (2.0).
+const VERSION: u16 = 20;
+
+/// The compression method for uncompressed files.
+const STORED: u16 = 0;
+
+/// The compression method for deflated files.
+const DEFLATED: u16 = 8;
+
+/// The modification date of all files (1980-01-01) in MS-DOS format. Using a
+/// fixed date keeps the output reproducible.
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+/// Writes files into a ZIP archive.
+pub struct ZipWriter {
+    /// The archive so far.
+    buf: Vec<u8>,
+    /// The files that were written, for the central directory.
+    entries: Vec<Entry>,
+}
+
+/// A file in the archive.
+struct Entry {
+    name: String,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+impl ZipWriter {
+    /// Create a new, empty archive.
+    pub fn new() -> Self {
+        Self { buf: vec![], entries: vec![] }
+    }
+
+    /// Add a file without compressing it.
+    pub fn stored(&mut self, name: &str, data: &[u8]) -> StrResult<()> {
+        self.file(name, data, STORED, data)
+    }
+
+    /// Add a file and compress it.
+    pub fn deflated(&mut self, name: &str, data: &[u8]) -> StrResult<()> {
+        let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        self.file(name, data, DEFLATED, &compressed)
+    }
+
+    /// Write the central directory and return the finished archive.
+    pub fn finish(mut self) -> StrResult<Vec<u8>> {
+        // The maximum values are reserved to mark ZIP64 records.
+        let count = u16::try_from(self.entries.len())
+            .ok()
+            .filter(|&count| count != u16::MAX)
+            .ok_or_else(|| {
+                eco_format!("archive has too many files (maximum is {})", u16::MAX - 1)
+            })?;
+
+        let start = self.buf.len();
+        for entry in &self.entries {
+            let buf = &mut self.buf;
+            u32(buf, 0x02014b50);
+            u16(buf, VERSION);
+            u16(buf, VERSION);
+            u16(buf, 0);
+            u16(buf, entry.method);
+            u16(buf, 0);
+            u16(buf, DOS_DATE);
+            u32(buf, entry.crc);
+            u32(buf, entry.compressed_size);
+            u32(buf, entry.size);
+            u16(buf, entry.name.len() as u16);
+            u16(buf, 0);
+            u16(buf, 0);
+            u16(buf, 0);
+            u16(buf, 0);
+            u32(buf, 0);
+            u32(buf, entry.offset);
+            buf.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let size = limit(self.buf.len() - start)?;
+        let start = limit(start)?;
+        let buf = &mut self.buf;
+        u32(buf, 0x06054b50);
+        u16(buf, 0);
+        u16(buf, 0);
+        u16(buf, count);
+        u16(buf, count);
+        u32(buf, size);
+        u32(buf, start);
+        u16(buf, 0);
+
+        Ok(self.buf)
+    }
+
+    /// Write a file with its local header.
+    fn file(
+        &mut self,
+        name: &str,
+        data: &[u8],
+        method: u16,
+        payload: &[u8],
+    ) -> StrResult<()> {
+        if u16::try_from(name.len()).is_err() {
+            bail!("file name is too long (maximum is {} bytes)", u16::MAX);
+        }
+
+        let mut crc = Crc::new();
+        crc.update(data);
+
+        let entry = Entry {
+            name: name.into(),
+            method,
+            crc: crc.sum(),
+            compressed_size: limit(payload.len())?,
+            size: limit(data.len())?,
+            offset: limit(self.buf.len())?,
+        };
+
+        let buf = &mut self.buf;
+        u32(buf, 0x04034b50);
+        u16(buf, VERSION);
+        u16(buf, 0);
+        u16(buf, entry.method);
+        u16(buf, 0);
+        u16(buf, DOS_DATE);
+        u32(buf, entry.crc);
+        u32(buf, entry.compressed_size);
+        u32(buf, entry.size);
+        u16(buf, name.len() as u16);
+        u16(buf, 0);
+        buf.extend_from_slice(name.as_bytes());
+        buf.extend_from_slice(payload);
+
+        self.entries.push(entry);
+        Ok(())
+    }
+}
+
+/// Convert a size or offset into a 32-bit field of the archive. The maximum
+/// value is reserved to mark ZIP64 records.
+fn limit(value: usize) -> StrResult<u32> {
+    u32::try_from(value)
+        .ok()
+        .filter(|&value| value != u32::MAX)
+        .ok_or_else(|| "archive is too large (maximum is 4 GiB)".into())
+}
+
+/// Write a little-endian 16-bit integer.
+fn u16(buf: &mut Vec<u8>, v: u16) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+/// Write a little-endian 32-bit integer.
+fn u32(buf: &mut Vec<u8>, v: u32) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
@@ -0,0 +1,374 @@
+//! Exporting of Typst documents into HTML.
+
+use std::fmt::Write;
+
+use base64::Engine;
+use ecow::{eco_format, EcoString};
+use typst::foundations::StyleChain;
+use typst::html::{HtmlDocument, HtmlElement, HtmlEquation, HtmlNode, HtmlTag};
+use typst::layout::Frame;
+use typst::utils::Numeric;
+use typst::visualize::{Image, ImageFormat, RasterFormat, VectorFormat};
+
+/// Export a document into an HTML file.
+///
+/// Equations are exported as MathML. Content that was laid out into frames,
+/// like shapes, is embedded as inline SVG, which also serves as a fallback for
+/// equations in browsers without MathML support. Images are embedded as data
+/// URLs.
+#[typst_macros::time(name = "html")]
+pub fn html(document: &HtmlDocument) -> String {
+    let mut head = vec![
+        meta(&[("charset", "utf-8")]),
+        meta(&[("name", "viewport"), ("content", "width=device-width, initial-scale=1")]),
+    ];
+
+    head.push(
+        HtmlElement::new(HtmlTag::constant("style"))
+            .with_children(vec![HtmlNode::text(STYLE)])
+            .into(),
+    );
+
+    if let Some(title) = &document.title {
+        head.push(
+            HtmlElement::new(HtmlTag::constant("title"))
+                .with_children(vec![HtmlNode::text(title.clone())])
+                .into(),
+        );
+    }
+
+    if !document.author.is_empty() {
+        head.push(meta(&[("name", "author"), ("content", &document.author.join(", "))]));
+    }
+
+    if !document.keywords.is_empty() {
+        head.push(meta(&[
+            ("name", "keywords"),
+            ("content", &document.keywords.join(", ")),
+        ]));
+    }
+
+    let mut html = HtmlElement::new(HtmlTag::constant("html")).with_children(vec![
+        HtmlElement::new(HtmlTag::constant("head")).with_children(head).into(),
+        HtmlElement::new(HtmlTag::constant("body"))
+            .with_children(document.body.clone())
+            .into(),
+    ]);
+
+    if let Some(lang) = document.lang {
+        html = html.with_attr("lang", lang.as_str());
+    }
+
+    let mut writer = Writer::new(Syntax::Html);
+    writer.raw("<!DOCTYPE html>\n");
+    writer.element(&html);
+    writer.raw("\n");
+    writer.finish()
+}
+
+/// The stylesheet of HTML documents.
+///
+/// Glyphs may extend beyond the frame they were laid out in, like italic
+/// letters overhanging their advance. Equations are written both as MathML
+/// and as SVG, of which only one is shown depending on whether the browser
+/// supports MathML.
+const STYLE: &str = "svg.typst-doc { overflow: visible; } \
+@supports (math-depth: auto-add) { .math svg.typst-doc { display: none; } } \
+@supports not (math-depth: auto-add) { .math math { display: none; } }";
+
+/// Create a `meta` element with the given attributes.
+fn meta(attrs: &[(&str, &str)]) -> HtmlNode {
+    attrs
+        .iter()
+        .fold(HtmlElement::new(HtmlTag::constant("meta")), |element, &(key, value)| {
+            element.with_attr(key, value)
+        })
+        .into()
+}
+
+/// The syntax in which HTML is written.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Syntax {
+    /// The HTML syntax.
+    Html,
+    /// The XML syntax, also known as XHTML. This is used by EPUB.
+    Xml,
+}
+
+/// Serializes HTML nodes.
+pub struct Writer<'a> {
+    /// The output.
+    buf: String,
+    /// The syntax to write in.
+    syntax: Syntax,
+    /// Determines the `src` of an image.
+    image_src: Box<dyn FnMut(&Image) -> EcoString + 'a>,
+    /// The current nesting level of block elements.
+    level: usize,
+    /// Whether to put block-level children on their own lines. This is
+    /// disabled within preformatted elements.
+    pretty: bool,
+    /// The number of frames written so far, which keeps the ids within their
+    /// SVGs apart.
+    frames: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// Create a new writer that embeds images as data URLs.
+    pub fn new(syntax: Syntax) -> Self {
+        Self {
+            buf: String::new(),
+            syntax,
+            image_src: Box::new(data_url),
+            level: 0,
+            pretty: true,
+            frames: 0,
+        }
+    }
+
+    /// Determine the `src` of images with the given function instead of
+    /// embedding them.
+    pub fn with_image_src(mut self, f: impl FnMut(&Image) -> EcoString + 'a) -> Self {
+        self.image_src = Box::new(f);
+        self
+    }
+
+    /// Write raw markup as-is.
+    pub fn raw(&mut self, markup: &str) {
+        self.buf.push_str(markup);
+    }
+
+    /// Write an element with its children.
+    pub fn element(&mut self, element: &HtmlElement) {
+        self.start_tag(element);
+        if element.tag.is_void() {
+            return;
+        }
+
+        let prev = self.pretty;
+        self.pretty &= !element.tag.is_preformatted();
+
+        // Put children on their own lines if there are block-level elements
+        // among them.
+        let pretty = self.pretty && element.children.iter().any(is_block);
+        self.level += 1;
+        for child in &element.children {
+            if pretty {
+                if matches!(child, HtmlNode::Tag(_)) {
+                    continue;
+                }
+                self.newline();
+            }
+            self.node(child);
+        }
+        self.level -= 1;
+
+        if pretty {
+            self.newline();
+        }
+
+        self.pretty = prev;
+        write!(self.buf, "</{}>", element.tag.as_str()).unwrap();
+    }
+
+    /// Finish writing and return the output.
+    pub fn finish(self) -> String {
+        self.buf
+    }
+
+    /// Write a node.
+    fn node(&mut self, node: &HtmlNode) {
+        match node {
+            HtmlNode::Tag(_) => {}
+            HtmlNode::Text(text) => self.text(text),
+            HtmlNode::Element(element) => self.element(element),
+            HtmlNode::Image(image) => self.image(image),
+            HtmlNode::Frame(frame) => self.frame(frame),
+            HtmlNode::Equation(equation) => self.equation(equation),
+        }
+    }
+
+    /// Write the start tag of an element.
+    fn start_tag(&mut self, element: &HtmlElement) {
+        self.buf.push('<');
+        self.buf.push_str(element.tag.as_str());
+        for (key, value) in &element.attrs.0 {
+            write!(self.buf, " {key}=\"").unwrap();
+            self.attr_value(value);
+            self.buf.push('"');
+        }
+        if element.tag.is_void() && self.syntax == Syntax::Xml {
+            self.buf.push('/');
+        }
+        self.buf.push('>');
+    }
+
+    /// Write an image as an `img` element.
+    fn image(&mut self, image: &Image) {
+        let src = (self.image_src)(image);
+        let img = HtmlElement::new(HtmlTag::constant("img"))
+            .with_attr("src", src)
+            .with_attr("alt", image.alt().unwrap_or_default());
+        self.start_tag(&img);
+    }
+
+    /// Write a frame as an inline SVG, which is shifted down by its descent
+    /// so that its baseline aligns with the text.
+    fn frame(&mut self, frame: &Frame) {
+        let svg = typst_svg::svg_embedded(frame, self.frames);
+        self.frames += 1;
+
+        let descent = frame.descent();
+        if descent.is_zero() {
+            self.buf.push_str(&svg);
+        } else {
+            let style =
+                format!("<svg style=\"vertical-align: -{:.2}pt\" ", descent.to_pt());
+            self.buf.push_str(&svg.replacen("<svg ", &style, 1));
+        }
+    }
+
+    /// Write an equation as MathML, followed by its frame as a fallback for
+    /// browsers without MathML support.
+    fn equation(&mut self, equation: &HtmlEquation) {
+        let styles = StyleChain::new(&equation.styles);
+        self.buf.push_str(&typst_mathml::mathml(&equation.elem, styles));
+        self.frame(&equation.frame);
+    }
+
+    /// Write escaped text.
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '&' => self.buf.push_str("&amp;"),
+                '<' => self.buf.push_str("&lt;"),
+                '>' => self.buf.push_str("&gt;"),
+                c => self.buf.push(c),
+            }
+        }
+    }
+
+    /// Write an escaped attribute value.
+    fn attr_value(&mut self, value: &str) {
+        for c in value.chars() {
+            match c {
+                '&' => self.buf.push_str("&amp;"),
+                '"' => self.buf.push_str("&quot;"),
+                '<' => self.buf.push_str("&lt;"),
+                '>' => self.buf.push_str("&gt;"),
+                c => self.buf.push(c),
+            }
+        }
+    }
+
+    /// Start a new line at the current indentation level.
+    fn newline(&mut self) {
+        self.buf.push('\n');
+        for _ in 0..self.level {
+            self.buf.push_str("  ");
+        }
+    }
+}
+
+/// Encode an image as a data URL.
+fn data_url(image: &Image) -> EcoString {
+    let data = base64::engine::general_purpose::STANDARD.encode(image.data());
+    eco_format!("data:{};base64,{data}", mime_type(image.format()))
+}
+
+/// The MIME type of an image format.
+pub fn mime_type(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Raster(RasterFormat::Png) => "image/png",
+        ImageFormat::Raster(RasterFormat::Jpg) => "image/jpeg",
+        ImageFormat::Raster(RasterFormat::Gif) => "image/gif",
+        ImageFormat::Vector(VectorFormat::Svg) => "image/svg+xml",
+    }
+}
+
+/// Whether a node is a block-level element.
+fn is_block(node: &HtmlNode) -> bool {
+    matches!(node, HtmlNode::Element(element) if !element.tag.is_inline())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(tag: &'static str, children: Vec<HtmlNode>) -> HtmlNode {
+        HtmlElement::new(HtmlTag::constant(tag))
+            .with_children(children)
+            .into()
+    }
+
+    fn body(document: &HtmlDocument) -> String {
+        let html = html(document);
+        let start = html.find("<body>").unwrap();
+        let end = html.find("</body>").unwrap();
+        html[start..end + "</body>".len()].into()
+    }
+
+    #[test]
+    fn test_html_escapes_text_and_attributes() {
+        let link = HtmlElement::new(HtmlTag::constant("a"))
+            .with_attr("href", "?a=1&b=\"2\"")
+            .with_children(vec![HtmlNode::text("<x> & y")]);
+        let document = HtmlDocument {
+            body: vec![element("p", vec![link.into()])],
+            ..HtmlDocument::default()
+        };
+        assert_eq!(
+            body(&document),
+            "<body>\n    <p><a href=\"?a=1&amp;b=&quot;2&quot;\">&lt;x&gt; &amp; y</a></p>\n  </body>",
+        );
+    }
+
+    #[test]
+    fn test_html_keeps_preformatted_text() {
+        let code = element("code", vec![HtmlNode::text("a\n  b")]);
+        let document = HtmlDocument {
+            body: vec![element("pre", vec![code]), element("hr", vec![])],
+            ..HtmlDocument::default()
+        };
+        assert_eq!(
+            body(&document),
+            "<body>\n    <pre><code>a\n  b</code></pre>\n    <hr>\n  </body>",
+        );
+    }
+
+    #[test]
+    fn test_xhtml_closes_void_elements() {
+        let svg = "<svg xmlns='http://www.w3.org/2000/svg' width='1' height='1'/>";
+        let image = Image::new(
+            svg.as_bytes().into(),
+            ImageFormat::Vector(VectorFormat::Svg),
+            Some("A dot".into()),
+        )
+        .unwrap();
+
+        let p = HtmlElement::new(HtmlTag::constant("p")).with_children(vec![
+            HtmlNode::text("a"),
+            element("br", vec![]),
+            HtmlNode::Image(image),
+        ]);
+
+        let mut writer =
+            Writer::new(Syntax::Xml).with_image_src(|_| "images/image-1.svg".into());
+        writer.element(&p);
+        assert_eq!(
+            writer.finish(),
+            "<p>a<br/><img src=\"images/image-1.svg\" alt=\"A dot\"/></p>",
+        );
+    }
+
+    #[test]
+    fn test_xhtml_escapes_angle_brackets_in_attributes() {
+        let abbr = HtmlElement::new(HtmlTag::constant("abbr"))
+            .with_attr("title", "a <b> c")
+            .with_children(vec![HtmlNode::text("abc")]);
+
+        let mut writer = Writer::new(Syntax::Xml);
+        writer.element(&abbr);
+        assert_eq!(writer.finish(), "<abbr title=\"a &lt;b&gt; c\">abc</abbr>");
+    }
+}
@@ -0,0 +1,884 @@
+//! Exporting of Typst equations into Presentation MathML.
+
+use ecow::{eco_format, EcoString};
+use typst::foundations::{Content, Packed, SequenceElem, StyleChain, StyledElem};
+use typst::layout::{HElem, Length, Spacing};
+use typst::math::{
+    style_for_denominator, style_for_numerator, style_for_subscript,
+    style_for_superscript, styled_char, AccentElem, AlignPointElem, AttachElem,
+    BinomElem, CancelElem, CasesElem, CeElem, ClassElem, DiagramElem, EdgeElem,
+    EquationElem, FracElem, Limits, LimitsElem, LrElem, MatElem, MathSize, MidElem,
+    NumberElem, OpElem, OverbraceElem, OverbracketElem, OverlineElem, PrimesElem,
+    RootElem, ScriptsElem, UnderbraceElem, UnderbracketElem, UnderlineElem, VecElem,
+    XArrowElem,
+};
+use typst::text::{LinebreakElem, SpaceElem, TextElem};
+use typst::utils::Numeric;
+use unicode_math_class::MathClass;
+use xmlwriter::XmlWriter;
+
+/// The namespace of MathML elements.
+const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Export an equation into a Presentation MathML `<math>` element.
+///
+/// The result can be embedded as is into HTML documents and into SVG images
+/// within a `<foreignObject>`. The styles are those in effect where the
+/// equation appears. They determine things like the variants of letters and
+/// whether attachments are shown as limits. For equations that were
+/// [queried](typst::introspection::Introspector::query) from a document,
+/// `StyleChain::default()` can be used.
+///
+/// Content that has no counterpart in MathML, like boxes and images, is
+/// exported as its plain text.
+pub fn mathml(equation: &Packed<EquationElem>, styles: StyleChain) -> String {
+    let block = equation.block(styles);
+    let size = if block { MathSize::Display } else { MathSize::Text };
+    let style = EquationElem::set_size(size).wrap();
+    let styles = styles.chain(&style);
+
+    let mut items = vec![];
+    convert(equation.body(), styles, &mut items);
+
+    let mut math = Node::new("math").with_attr("xmlns", NAMESPACE);
+    if block {
+        math.set_attr("display", "block");
+    }
+    math.children.push(lines(items, block));
+
+    let mut xml = XmlWriter::new(xmlwriter::Options {
+        indent: xmlwriter::Indent::None,
+        ..Default::default()
+    });
+    math.write(&mut xml);
+    xml.end_document()
+}
+
+/// A part of a converted equation.
+enum Item {
+    /// A MathML element.
+    Node(Node),
+    /// An alignment point.
+    Align,
+    /// A line break.
+    Linebreak,
+}
+
+/// A MathML element.
+struct Node {
+    /// The element's tag name.
+    name: &'static str,
+    /// The element's attributes.
+    attrs: Vec<(&'static str, EcoString)>,
+    /// The text of a token element.
+    text: Option<EcoString>,
+    /// The element's children.
+    children: Vec<Node>,
+}
+
+impl Node {
+    /// Create an empty element.
+    fn new(name: &'static str) -> Self {
+        Self { name, attrs: vec![], text: None, children: vec![] }
+    }
+
+    /// Create a token element like `<mi>` or `<mo>` with text.
+    fn token(name: &'static str, text: impl Into<EcoString>) -> Self {
+        Self { text: Some(text.into()), ..Self::new(name) }
+    }
+
+    /// Create an element with children.
+    fn with_children(name: &'static str, children: Vec<Node>) -> Self {
+        Self { children, ..Self::new(name) }
+    }
+
+    /// Add or replace an attribute.
+    fn with_attr(mut self, key: &'static str, value: impl Into<EcoString>) -> Self {
+        self.set_attr(key, value);
+        self
+    }
+
+    /// Add or replace an attribute.
+    fn set_attr(&mut self, key: &'static str, value: impl Into<EcoString>) {
+        let value = value.into();
+        match self.attrs.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.attrs.push((key, value)),
+        }
+    }
+
+    /// Write the element as XML.
+    fn write(&self, xml: &mut XmlWriter) {
+        xml.start_element(self.name);
+        for (key, value) in &self.attrs {
+            xml.write_attribute(key, value);
+        }
+        if let Some(text) = &self.text {
+            xml.write_text(text);
+        }
+        for child in &self.children {
+            child.write(xml);
+        }
+        xml.end_element();
+    }
+}
+
+/// Convert math content into MathML, appending the results to `out`.
+fn convert(content: &Content, styles: StyleChain, out: &mut Vec<Item>) {
+    // Nested equations are inlined, like in layout.
+    if let Some(elem) = content.to_packed::<EquationElem>() {
+        convert(elem.body(), styles, out);
+    } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        for child in &sequence.children {
+            convert(child, styles, out);
+        }
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        convert(&styled.child, styles.chain(&styled.styles), out);
+    } else if content.is::<SpaceElem>() {
+        // Spacing between math elements is determined by the renderer.
+    } else if content.is::<NumberElem>() {
+        // Line numbers are not part of the math itself.
+    } else if content.is::<EdgeElem>() {
+        // Arrows of diagrams can't be expressed in MathML.
+    } else if content.is::<LinebreakElem>() {
+        out.push(Item::Linebreak);
+    } else if content.is::<AlignPointElem>() {
+        out.push(Item::Align);
+    } else if let Some(elem) = content.to_packed::<ClassElem>() {
+        let style = EquationElem::set_class(Some(*elem.class())).wrap();
+        convert(elem.body(), styles.chain(&style), out);
+    } else if let Some(elem) = content.to_packed::<MidElem>() {
+        let mut items = vec![];
+        convert(elem.body(), styles, &mut items);
+        for item in &mut items {
+            if let Item::Node(node) = item {
+                if node.name == "mo" {
+                    node.set_attr("stretchy", "true");
+                }
+            }
+        }
+        out.extend(items);
+    } else if let Some(node) = convert_node(content, styles) {
+        out.push(Item::Node(node));
+    }
+}
+
+/// Convert math content that corresponds to a single MathML element.
+fn convert_node(content: &Content, styles: StyleChain) -> Option<Node> {
+    Some(if let Some(elem) = content.to_packed::<TextElem>() {
+        text(elem.text(), styles)
+    } else if let Some(elem) = content.to_packed::<HElem>() {
+        space(elem, styles)?
+    } else if let Some(elem) = content.to_packed::<AttachElem>() {
+        attach(elem, styles)
+    } else if let Some(elem) = content.to_packed::<PrimesElem>() {
+        let primes: EcoString = match *elem.count() {
+            1 => "′".into(),
+            2 => "″".into(),
+            3 => "‴".into(),
+            4 => "⁗".into(),
+            count => "′".repeat(count).into(),
+        };
+        Node::token("mo", primes)
+    } else if let Some(elem) = content.to_packed::<ScriptsElem>() {
+        row(elem.body(), styles)
+    } else if let Some(elem) = content.to_packed::<LimitsElem>() {
+        row(elem.body(), styles)
+    } else if let Some(elem) = content.to_packed::<FracElem>() {
+        Node::with_children(
+            "mfrac",
+            vec![
+                row(elem.num(), styles.chain(&style_for_numerator(styles))),
+                row(elem.denom(), styles.chain(&style_for_denominator(styles))),
+            ],
+        )
+    } else if let Some(elem) = content.to_packed::<BinomElem>() {
+        let lower =
+            Content::sequence(elem.lower().iter().enumerate().flat_map(|(i, c)| {
+                (i > 0).then(|| TextElem::packed(',')).into_iter().chain([c.clone()])
+            }));
+        let frac = Node::with_children(
+            "mfrac",
+            vec![
+                row(elem.upper(), styles.chain(&style_for_numerator(styles))),
+                row(&lower, styles.chain(&style_for_denominator(styles))),
+            ],
+        )
+        .with_attr("linethickness", "0");
+        delimited(frac, Some('('), Some(')'))
+    } else if let Some(elem) = content.to_packed::<RootElem>() {
+        let radicand = row(elem.radicand(), styles);
+        match elem.index(styles) {
+            Some(index) => {
+                let style = EquationElem::set_size(MathSize::ScriptScript).wrap();
+                let index = row(&index, styles.chain(&style));
+                Node::with_children("mroot", vec![radicand, index])
+            }
+            None => Node::with_children("msqrt", vec![radicand]),
+        }
+    } else if let Some(elem) = content.to_packed::<LrElem>() {
+        let mut items = vec![];
+        convert(elem.body(), styles, &mut items);
+        let mut nodes: Vec<Node> = items.into_iter().filter_map(Item::node).collect();
+        let len = nodes.len();
+        for i in [0, len.saturating_sub(1)] {
+            if let Some(node) = nodes.get_mut(i).filter(|node| node.name == "mo") {
+                node.set_attr("fence", "true");
+                node.set_attr("stretchy", "true");
+            }
+        }
+        Node::with_children("mrow", nodes)
+    } else if let Some(elem) = content.to_packed::<AccentElem>() {
+        let accent = spacing_accent(elem.accent().get());
+        Node::with_children(
+            "mover",
+            vec![row(elem.base(), styles), Node::token("mo", accent)],
+        )
+        .with_attr("accent", "true")
+    } else if let Some(elem) = content.to_packed::<UnderlineElem>() {
+        under(row(elem.body(), styles), '_')
+    } else if let Some(elem) = content.to_packed::<OverlineElem>() {
+        over(row(elem.body(), styles), '‾')
+    } else if let Some(elem) = content.to_packed::<UnderbraceElem>() {
+        annotated_under(elem.body(), elem.annotation(styles), '⏟', styles)
+    } else if let Some(elem) = content.to_packed::<OverbraceElem>() {
+        annotated_over(elem.body(), elem.annotation(styles), '⏞', styles)
+    } else if let Some(elem) = content.to_packed::<UnderbracketElem>() {
+        annotated_under(elem.body(), elem.annotation(styles), '⎵', styles)
+    } else if let Some(elem) = content.to_packed::<OverbracketElem>() {
+        annotated_over(elem.body(), elem.annotation(styles), '⎴', styles)
+    } else if let Some(elem) = content.to_packed::<MatElem>() {
+        let style = style_for_denominator(styles);
+        let styles = styles.chain(&style);
+        let rows = elem
+            .rows()
+            .iter()
+            .map(|cells| cells.iter().map(|cell| row(cell, styles)).collect())
+            .collect();
+        let delim = elem.delim(styles);
+        delimited(table(rows, None), delim.open(), delim.close())
+    } else if let Some(elem) = content.to_packed::<DiagramElem>() {
+        let rows = elem
+            .rows()
+            .iter()
+            .map(|cells| cells.iter().map(|cell| row(cell, styles)).collect())
+            .collect();
+        table(rows, None)
+    } else if let Some(elem) = content.to_packed::<VecElem>() {
+        let style = style_for_denominator(styles);
+        let styles = styles.chain(&style);
+        let rows = elem.children().iter().map(|child| cells(child, styles)).collect();
+        let delim = elem.delim(styles);
+        delimited(table(rows, None), delim.open(), delim.close())
+    } else if let Some(elem) = content.to_packed::<CasesElem>() {
+        let style = style_for_denominator(styles);
+        let styles = styles.chain(&style);
+        let rows = elem.children().iter().map(|child| cells(child, styles)).collect();
+        let delim = elem.delim(styles);
+        let table = table(rows, Some("left"));
+        if elem.reverse(styles) {
+            delimited(table, None, delim.close())
+        } else {
+            delimited(table, delim.open(), None)
+        }
+    } else if let Some(elem) = content.to_packed::<OpElem>() {
+        let text = elem.text().plain_text();
+        let single = text.chars().count() == 1;
+        let node = Node::token("mi", text);
+        if single {
+            node.with_attr("mathvariant", "normal")
+        } else {
+            node
+        }
+    } else if let Some(elem) = content.to_packed::<XArrowElem>() {
+        let arrow = Node::token("mo", elem.sym(styles)).with_attr("stretchy", "true");
+        let above_style = style_for_superscript(styles);
+        let below_style = style_for_subscript(styles);
+        let above =
+            elem.body(styles).map(|above| row(&above, styles.chain(&above_style)));
+        let below = elem
+            .below(styles)
+            .map(|below| row(&below, styles.chain(&below_style)));
+        match (below, above) {
+            (Some(b), Some(t)) => Node::with_children("munderover", vec![arrow, b, t]),
+            (Some(b), None) => Node::with_children("munder", vec![arrow, b]),
+            (None, Some(t)) => Node::with_children("mover", vec![arrow, t]),
+            (None, None) => arrow,
+        }
+    } else if let Some(elem) = content.to_packed::<CeElem>() {
+        // Malformed formulas are kept in their source notation.
+        match elem.expand() {
+            Ok(expanded) => row(&expanded, styles),
+            Err(_) => Node::token("mtext", elem.text().clone()),
+        }
+    } else if let Some(elem) = content.to_packed::<CancelElem>() {
+        let notation = if elem.cross(styles) {
+            "updiagonalstrike downdiagonalstrike"
+        } else if elem.inverted(styles) {
+            "downdiagonalstrike"
+        } else {
+            "updiagonalstrike"
+        };
+        Node::with_children("menclose", vec![row(elem.body(), styles)])
+            .with_attr("notation", notation)
+    } else {
+        let text = content.plain_text();
+        if text.is_empty() {
+            return None;
+        }
+        Node::token("mtext", text)
+    })
+}
+
+impl Item {
+    /// The element, if this is one.
+    fn node(self) -> Option<Node> {
+        match self {
+            Self::Node(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+/// Convert content into a single element, wrapping it in an `<mrow>` if
+/// needed. Alignment points and line breaks are ignored.
+fn row(content: &Content, styles: StyleChain) -> Node {
+    let mut items = vec![];
+    convert(content, styles, &mut items);
+    group(items)
+}
+
+/// Wrap items into an `<mrow>` unless they are a single element.
+fn group(items: Vec<Item>) -> Node {
+    let mut nodes: Vec<Node> = items.into_iter().filter_map(Item::node).collect();
+    if nodes.len() == 1 {
+        nodes.pop().unwrap()
+    } else {
+        Node::with_children("mrow", nodes)
+    }
+}
+
+/// Convert the body of an equation. Lines and alignment points turn it into
+/// a table.
+fn lines(items: Vec<Item>, block: bool) -> Node {
+    if items.iter().all(|item| matches!(item, Item::Node(_))) {
+        return group(items);
+    }
+
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = vec![];
+    for item in items {
+        match item {
+            Item::Node(_) => cell.push(item),
+            Item::Align => row.push(group(std::mem::take(&mut cell))),
+            Item::Linebreak => {
+                row.push(group(std::mem::take(&mut cell)));
+                rows.push(std::mem::take(&mut row));
+            }
+        }
+    }
+    row.push(group(cell));
+    rows.push(row);
+
+    // Like in layout, alignment points alternate between right and left
+    // alignment.
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let align = if columns == 1 {
+        "center".into()
+    } else {
+        let mut align = EcoString::new();
+        for i in 0..columns {
+            if i > 0 {
+                align.push(' ');
+            }
+            align.push_str(if i % 2 == 0 { "right" } else { "left" });
+        }
+        align
+    };
+
+    let table = table(rows, None).with_attr("columnalign", align);
+    if block {
+        table.with_attr("displaystyle", "true")
+    } else {
+        table
+    }
+}
+
+/// Convert a cell of a matrix-like element, splitting it into columns at its
+/// alignment points.
+fn cells(content: &Content, styles: StyleChain) -> Vec<Node> {
+    let mut items = vec![];
+    convert(content, styles, &mut items);
+
+    let mut cells = vec![];
+    let mut cell = vec![];
+    for item in items {
+        match item {
+            Item::Node(_) => cell.push(item),
+            Item::Align | Item::Linebreak => {
+                cells.push(group(std::mem::take(&mut cell)));
+            }
+        }
+    }
+    cells.push(group(cell));
+    cells
+}
+
+/// Create an `<mtable>` from rows of cells.
+fn table(rows: Vec<Vec<Node>>, align: Option<&str>) -> Node {
+    let rows = rows
+        .into_iter()
+        .map(|cells| {
+            let cells = cells
+                .into_iter()
+                .map(|cell| Node::with_children("mtd", vec![cell]))
+                .collect();
+            Node::with_children("mtr", cells)
+        })
+        .collect();
+    let table = Node::with_children("mtable", rows);
+    match align {
+        Some(align) => table.with_attr("columnalign", align),
+        None => table,
+    }
+}
+
+/// Surround an element with stretchy delimiters.
+fn delimited(node: Node, open: Option<char>, close: Option<char>) -> Node {
+    if open.is_none() && close.is_none() {
+        return node;
+    }
+
+    let fence = |c: char| {
+        Node::token("mo", c)
+            .with_attr("fence", "true")
+            .with_attr("stretchy", "true")
+    };
+
+    let mut children = vec![];
+    children.extend(open.map(fence));
+    children.push(node);
+    children.extend(close.map(fence));
+    Node::with_children("mrow", children)
+}
+
+/// Convert a piece of text.
+fn text(text: &str, styles: StyleChain) -> Node {
+    let mut chars = text.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return glyph(c, styles);
+    }
+
+    let styled: EcoString = text.chars().map(|c| styled_char(styles, c, false)).collect();
+    if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        Node::token("mn", styled)
+    } else {
+        Node::token("mtext", styled)
+    }
+}
+
+/// Convert a single character into an identifier, number or operator.
+fn glyph(c: char, styles: StyleChain) -> Node {
+    let class = EquationElem::class_in(styles).or_else(|| unicode_math_class::class(c));
+    match class {
+        Some(MathClass::Opening | MathClass::Closing | MathClass::Fence) => {
+            // Only delimiters of `lr` stretch.
+            Node::token("mo", c).with_attr("stretchy", "false")
+        }
+        Some(
+            MathClass::Binary
+            | MathClass::Relation
+            | MathClass::Large
+            | MathClass::Punctuation
+            | MathClass::Unary
+            | MathClass::Vary,
+        ) => Node::token("mo", c),
+        _ if c.is_ascii_digit() => Node::token("mn", styled_char(styles, c, false)),
+        _ => identifier(c, styles),
+    }
+}
+
+/// Convert a single character into an identifier.
+///
+/// MathML renderers automatically italicize single-letter identifiers, so
+/// the letter is only replaced by its styled variant when it differs from
+/// the default.
+fn identifier(c: char, styles: StyleChain) -> Node {
+    let styled = styled_char(styles, c, true);
+    if styled == c {
+        let node = Node::token("mi", c);
+        if is_auto_italic(c) {
+            node.with_attr("mathvariant", "normal")
+        } else {
+            node
+        }
+    } else if styled == styled_char(StyleChain::default(), c, true) {
+        Node::token("mi", c)
+    } else {
+        Node::token("mi", styled)
+    }
+}
+
+/// Whether MathML renderers italicize an identifier with just this
+/// character.
+///
+/// <https://www.w3.org/TR/mathml-core/#italic-mappings>
+fn is_auto_italic(c: char) -> bool {
+    matches!(
+        c,
+        'A'..='Z'
+            | 'a'..='z'
+            | 'ı'
+            | 'ȷ'
+            | 'Α'..='Ω'
+            | 'ϴ'
+            | 'α'..='ω'
+            | '∇'
+            | '∂'
+            | 'ϵ'
+            | 'ϑ'
+            | 'ϰ'
+            | 'ϕ'
+            | 'ϱ'
+            | 'ϖ'
+    )
+}
+
+/// Convert horizontal spacing. Only spacing without a relative part is
+/// supported.
+fn space(elem: &Packed<HElem>, styles: StyleChain) -> Option<Node> {
+    let Spacing::Rel(rel) = elem.amount() else { return None };
+    if !rel.rel.is_zero() {
+        return None;
+    }
+
+    let Length { abs, em } = rel.abs;
+    let width = if abs.is_zero() {
+        eco_format!("{}em", round(em.get()))
+    } else {
+        eco_format!("{}pt", round(rel.abs.at(TextElem::size_in(styles)).to_pt()))
+    };
+
+    Some(Node::new("mspace").with_attr("width", width))
+}
+
+/// Convert attachments.
+fn attach(elem: &Packed<AttachElem>, styles: StyleChain) -> Node {
+    let sup_style = style_for_superscript(styles);
+    let sub_style = style_for_subscript(styles);
+    let sup_styles = styles.chain(&sup_style);
+    let sub_styles = styles.chain(&sub_style);
+    let sup = |content: Option<Content>| content.map(|c| row(&c, sup_styles));
+    let sub = |content: Option<Content>| content.map(|c| row(&c, sub_styles));
+
+    let tl = sup(elem.tl(styles));
+    let tr = sup(elem.tr(styles));
+    let t = sup(elem.t(styles));
+    let bl = sub(elem.bl(styles));
+    let br = sub(elem.br(styles));
+    let b = sub(elem.b(styles));
+
+    // Like in layout, top and bottom attachments become scripts unless the
+    // base has limits or there already are scripts on the right.
+    let limits = limits(elem.base(), styles).active(styles);
+    let (t, tr) = if limits || tr.is_some() { (t, tr) } else { (None, t) };
+    let (b, br) = if limits || br.is_some() { (b, br) } else { (None, b) };
+
+    let mut base = row(elem.base(), styles);
+    base = match (b, t) {
+        (Some(b), Some(t)) => Node::with_children("munderover", vec![base, b, t]),
+        (Some(b), None) => Node::with_children("munder", vec![base, b]),
+        (None, Some(t)) => Node::with_children("mover", vec![base, t]),
+        (None, None) => base,
+    };
+
+    if tl.is_some() || bl.is_some() {
+        let none = || Node::new("none");
+        return Node::with_children(
+            "mmultiscripts",
+            vec![
+                base,
+                br.unwrap_or_else(none),
+                tr.unwrap_or_else(none),
+                Node::new("mprescripts"),
+                bl.unwrap_or_else(none),
+                tl.unwrap_or_else(none),
+            ],
+        );
+    }
+
+    match (br, tr) {
+        (Some(br), Some(tr)) => Node::with_children("msubsup", vec![base, br, tr]),
+        (Some(br), None) => Node::with_children("msub", vec![base, br]),
+        (None, Some(tr)) => Node::with_children("msup", vec![base, tr]),
+        (None, None) => base,
+    }
+}
+
+/// How the base of an attachment displays its limits.
+fn limits(base: &Content, styles: StyleChain) -> Limits {
+    if let Some(elem) = base.to_packed::<LimitsElem>() {
+        if elem.inline(styles) {
+            Limits::Always
+        } else {
+            Limits::Display
+        }
+    } else if let Some(elem) = base.to_packed::<OpElem>() {
+        if elem.limits(styles) {
+            Limits::Display
+        } else {
+            Limits::Never
+        }
+    } else if let Some(elem) = base.to_packed::<ClassElem>() {
+        Limits::for_class(*elem.class())
+    } else if let Some(elem) = base.to_packed::<TextElem>() {
+        let mut chars = elem.text().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Limits::for_char(c),
+            _ => Limits::Never,
+        }
+    } else {
+        Limits::Never
+    }
+}
+
+/// Place a stretchy character under an element.
+fn under(base: Node, c: char) -> Node {
+    let mark = Node::token("mo", c).with_attr("stretchy", "true");
+    Node::with_children("munder", vec![base, mark]).with_attr("accentunder", "true")
+}
+
+/// Place a stretchy character over an element.
+fn over(base: Node, c: char) -> Node {
+    let mark = Node::token("mo", c).with_attr("stretchy", "true");
+    Node::with_children("mover", vec![base, mark]).with_attr("accent", "true")
+}
+
+/// Place a brace or bracket with an optional annotation under an element.
+fn annotated_under(
+    body: &Content,
+    annotation: Option<Content>,
+    c: char,
+    styles: StyleChain,
+) -> Node {
+    let node = under(row(body, styles), c);
+    match annotation {
+        Some(annotation) => {
+            let style = style_for_subscript(styles);
+            let annotation = row(&annotation, styles.chain(&style));
+            Node::with_children("munder", vec![node, annotation])
+        }
+        None => node,
+    }
+}
+
+/// Place a brace or bracket with an optional annotation over an element.
+fn annotated_over(
+    body: &Content,
+    annotation: Option<Content>,
+    c: char,
+    styles: StyleChain,
+) -> Node {
+    let node = over(row(body, styles), c);
+    match annotation {
+        Some(annotation) => {
+            let style = style_for_superscript(styles);
+            let annotation = row(&annotation, styles.chain(&style));
+            Node::with_children("mover", vec![node, annotation])
+        }
+        None => node,
+    }
+}
+
+/// The spacing variant of a combining accent, which MathML prefers.
+fn spacing_accent(c: char) -> char {
+    match c {
+        '\u{0300}' => '`',
+        '\u{0301}' => '´',
+        '\u{0302}' => 'ˆ',
+        '\u{0303}' => '˜',
+        '\u{0304}' => '¯',
+        '\u{0305}' => '‾',
+        '\u{0306}' => '˘',
+        '\u{0307}' => '˙',
+        '\u{0308}' => '¨',
+        '\u{030A}' => '˚',
+        '\u{030B}' => '˝',
+        '\u{030C}' => 'ˇ',
+        '\u{20D6}' => '←',
+        '\u{20D7}' => '→',
+        '\u{20E1}' => '↔',
+        _ => c,
+    }
+}
+
+/// Round a number for use in an attribute.
+fn round(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::foundations::{Content, NativeElement, Styles};
+    use typst::math::{Accent, AttachElem, CeElem, EquationElem, FracElem};
+    use typst::text::TextElem;
+
+    use super::*;
+
+    fn export(body: Content, block: bool) -> String {
+        let equation = EquationElem::new(body).with_block(block).pack();
+        mathml(equation.to_packed::<EquationElem>().unwrap(), StyleChain::default())
+    }
+
+    #[test]
+    fn test_mathml_fraction() {
+        let frac = FracElem::new(TextElem::packed("1"), TextElem::packed('x')).pack();
+        assert_eq!(
+            export(frac, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <mfrac><mn>1</mn><mi>x</mi></mfrac></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_limits() {
+        let sum = AttachElem::new(TextElem::packed('∑'))
+            .with_t(Some(TextElem::packed('n')))
+            .pack();
+        assert_eq!(
+            export(sum.clone(), true),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">\
+             <mover><mo>∑</mo><mi>n</mi></mover></math>",
+        );
+        assert_eq!(
+            export(sum, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <msup><mo>∑</mo><mi>n</mi></msup></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_limits_styles() {
+        let base = LimitsElem::new(TextElem::packed('x')).pack();
+        let equation = EquationElem::new(
+            AttachElem::new(base).with_t(Some(TextElem::packed('n'))).pack(),
+        )
+        .pack();
+        let mut styles = Styles::new();
+        styles.set(LimitsElem::set_inline(false));
+        assert_eq!(
+            mathml(equation.to_packed().unwrap(), StyleChain::new(&styles)),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <msup><mi>x</mi><mi>n</mi></msup></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_root() {
+        let sqrt = RootElem::new(TextElem::packed('x')).pack();
+        assert_eq!(
+            export(sqrt, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <msqrt><mi>x</mi></msqrt></math>",
+        );
+
+        let root = RootElem::new(TextElem::packed('x'))
+            .with_index(Some(TextElem::packed("3")))
+            .pack();
+        assert_eq!(
+            export(root, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <mroot><mi>x</mi><mn>3</mn></mroot></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_matrix() {
+        let mat = MatElem::new(vec![
+            vec![TextElem::packed("1"), TextElem::packed('x')],
+            vec![TextElem::packed('y'), TextElem::packed("2")],
+        ])
+        .pack();
+        assert_eq!(
+            export(mat, true),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">\
+             <mrow><mo fence=\"true\" stretchy=\"true\">(</mo><mtable>\
+             <mtr><mtd><mn>1</mn></mtd><mtd><mi>x</mi></mtd></mtr>\
+             <mtr><mtd><mi>y</mi></mtd><mtd><mn>2</mn></mtd></mtr>\
+             </mtable><mo fence=\"true\" stretchy=\"true\">)</mo></mrow></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_lr() {
+        let body = Content::sequence([
+            TextElem::packed('['),
+            TextElem::packed('x'),
+            TextElem::packed(')'),
+        ]);
+        assert_eq!(
+            export(LrElem::new(body).pack(), false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <mrow><mo stretchy=\"true\" fence=\"true\">[</mo><mi>x</mi>\
+             <mo stretchy=\"true\" fence=\"true\">)</mo></mrow></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_accent() {
+        let accent = AccentElem::new(TextElem::packed('v'), Accent::new('→')).pack();
+        assert_eq!(
+            export(accent, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <mover accent=\"true\"><mi>v</mi><mo>→</mo></mover></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_lines() {
+        let body = Content::sequence([
+            TextElem::packed('x'),
+            AlignPointElem::new().pack(),
+            TextElem::packed('='),
+            TextElem::packed("1"),
+            LinebreakElem::new().pack(),
+            TextElem::packed('y'),
+            AlignPointElem::new().pack(),
+            TextElem::packed('='),
+            TextElem::packed("2"),
+        ]);
+        assert_eq!(
+            export(body, true),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">\
+             <mtable columnalign=\"right left\" displaystyle=\"true\">\
+             <mtr><mtd><mi>x</mi></mtd><mtd><mrow><mo>=</mo><mn>1</mn></mrow></mtd></mtr>\
+             <mtr><mtd><mi>y</mi></mtd><mtd><mrow><mo>=</mo><mn>2</mn></mrow></mtd></mtr>\
+             </mtable></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_chem() {
+        let ce = CeElem::new("2H2 + O2 ->[heat] 2H2O".into()).pack();
+        assert_eq!(
+            export(ce, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow>\
+             <mn>2</mn><msub><mi mathvariant=\"normal\">H</mi><mn>2</mn></msub>\
+             <mo>+</mo><msub><mi mathvariant=\"normal\">O</mi><mn>2</mn></msub>\
+             <mover><mo stretchy=\"true\">→</mo><mtext>heat</mtext></mover>\
+             <mn>2</mn><msub><mi mathvariant=\"normal\">H</mi><mn>2</mn></msub>\
+             <mi mathvariant=\"normal\">O</mi></mrow></math>",
+        );
+    }
+
+    #[test]
+    fn test_mathml_escape() {
+        let body = Content::sequence([TextElem::packed('a'), TextElem::packed('<')]);
+        assert_eq!(
+            export(body, false),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">\
+             <mrow><mi>a</mi><mo>&lt;</mo></mrow></math>",
+        );
+    }
+}
@@ -26,7 +26,7 @@ use typst::model::{Document, HeadingElem};
 use typst::text::color::frame_for_glyph;
 use typst::text::{Font, Lang};
 use typst::utils::Deferred;
-use typst::visualize::Image;
+use typst::visualize::{Color, Image, Paint};
 use xmp_writer::{DateTime, LangId, RenditionClass, Timezone, XmpWriter};
 
 use crate::color::ColorSpaces;
@@ -504,9 +504,9 @@ struct ColorFont {
     glyphs: Vec<ColorGlyph>,
     /// The global bounding box of the font.
     bbox: Rect,
-    /// A mapping between glyph IDs and character indices in the `glyphs`
-    /// vector.
-    glyph_indices: HashMap<u16, usize>,
+    /// A mapping between glyph IDs and text colors and character indices in
+    /// the `glyphs` vector.
+    glyph_indices: HashMap<(u16, Color), usize>,
 }
 
 /// A single color glyph.
@@ -534,7 +534,8 @@ impl ColorFontMap {
     /// that can be used to draw a color glyph.
     ///
     /// The glyphs will be de-duplicated if needed.
-    fn get(&mut self, alloc: &mut Ref, font: &Font, gid: u16) -> (Ref, u8) {
+    /// Glyphs whose colors depend on the text color are drawn once per fill.
+    fn get(&mut self, alloc: &mut Ref, font: &Font, gid: u16, fill: &Paint) -> (Ref, u8) {
         let color_font = self.map.entry(font.clone()).or_insert_with(|| {
             let global_bbox = font.ttf().global_bounding_box();
             let bbox = Rect::new(
@@ -551,7 +552,12 @@ impl ColorFontMap {
             }
         });
 
-        if let Some(index_of_glyph) = color_font.glyph_indices.get(&gid) {
+        let foreground = match fill {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(_) | Paint::Pattern(_) => Color::BLACK,
+        };
+
+        if let Some(index_of_glyph) = color_font.glyph_indices.get(&(gid, foreground)) {
             // If we already know this glyph, return it.
             (color_font.refs[index_of_glyph / 256], *index_of_glyph as u8)
         } else {
@@ -564,9 +570,9 @@ impl ColorFontMap {
                 color_font.refs.push(new_ref);
             }
 
-            let instructions = frame_for_glyph(font, gid);
+            let instructions = frame_for_glyph(font, gid, &Paint::Solid(foreground));
             color_font.glyphs.push(ColorGlyph { gid, frame: instructions });
-            color_font.glyph_indices.insert(gid, index);
+            color_font.glyph_indices.insert((gid, foreground), index);
 
             (color_font.refs[index / 256], index as u8)
         }
@@ -12,6 +12,7 @@ use pdf_writer::types::{
 };
 use pdf_writer::writers::{PageLabel, Resources};
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
+use ttf_parser::{GlyphId, OutlineBuilder, Tag};
 use typst::layout::{
     Abs, Em, Frame, FrameItem, GroupItem, Page, Point, Ratio, Size, Transform,
 };
@@ -792,7 +793,7 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
     let has_color_glyphs = tables.sbix.is_some()
         || tables.cbdt.is_some()
         || tables.svg.is_some()
-        || tables.colr.is_some();
+        || ttf.raw_face().table(Tag::from_bytes(b"COLR")).is_some();
     if !has_color_glyphs {
         write_normal_text(ctx, pos, TextItemView::all_of(text));
         return;
@@ -838,7 +839,17 @@ fn write_normal_text(ctx: &mut PageContext, pos: Point, text: TextItemView) {
 
     *ctx.parent.languages.entry(text.item.lang).or_insert(0) += text.glyph_range.len();
 
-    let glyph_set = ctx.parent.glyph_sets.entry(text.item.font.clone()).or_default();
+    // Instances of variable fonts can't be embedded, so we draw their glyphs
+    // as paths and overlay them with invisible text to keep the text
+    // selectable. This text is written with the default instance of the font.
+    let invisible = !text.item.font.variations().is_empty();
+    if invisible {
+        write_outlined_glyphs(ctx, pos, &text);
+    }
+
+    let font = text.item.font.without_variations();
+
+    let glyph_set = ctx.parent.glyph_sets.entry(font.clone()).or_default();
     for g in text.glyphs() {
         let t = text.text();
         let segment = &t[g.range()];
@@ -856,14 +867,16 @@ fn write_normal_text(ctx: &mut PageContext, pos: Point, text: TextItemView) {
         }
     });
 
-    if let Some(stroke) = stroke {
+    if invisible {
+        ctx.set_text_rendering_mode(TextRenderingMode::Invisible);
+    } else if let Some(stroke) = stroke {
         ctx.set_stroke(stroke, true, fill_transform);
         ctx.set_text_rendering_mode(TextRenderingMode::FillStroke);
     } else {
         ctx.set_text_rendering_mode(TextRenderingMode::Fill);
     }
 
-    ctx.set_font(&text.item.font, text.item.size);
+    ctx.set_font(&font, text.item.size);
     ctx.set_opacities(text.item.stroke.as_ref(), Some(&text.item.fill));
     ctx.content.begin_text();
 
@@ -889,11 +902,11 @@ fn write_normal_text(ctx: &mut PageContext, pos: Point, text: TextItemView) {
             adjustment = Em::zero();
         }
 
-        let cid = crate::font::glyph_cid(&text.item.font, glyph.id);
+        let cid = crate::font::glyph_cid(&font, glyph.id);
         encoded.push((cid >> 8) as u8);
         encoded.push((cid & 0xff) as u8);
 
-        if let Some(advance) = text.item.font.advance(glyph.id) {
+        if let Some(advance) = font.advance(glyph.id) {
             adjustment += glyph.x_advance - advance;
         }
 
@@ -909,6 +922,84 @@ fn write_normal_text(ctx: &mut PageContext, pos: Point, text: TextItemView) {
     ctx.content.end_text();
 }
 
+/// Encode the glyphs of a text run as a filled and stroked path.
+fn write_outlined_glyphs(ctx: &mut PageContext, pos: Point, text: &TextItemView) {
+    let scale = text.item.size.to_pt() / text.item.font.units_per_em();
+    let mut builder =
+        GlyphPathBuilder { path: Path::new(), scale, x: 0.0, last: (0.0, 0.0) };
+
+    let size = text.item.size;
+    let mut x = Abs::zero();
+    for glyph in text.glyphs() {
+        builder.x = (x + glyph.x_offset.at(size)).to_pt();
+        text.item.font.ttf().outline_glyph(GlyphId(glyph.id), &mut builder);
+        x += glyph.x_advance.at(size);
+    }
+
+    if builder.path.0.is_empty() {
+        return;
+    }
+
+    let shape = Shape {
+        geometry: Geometry::Path(builder.path),
+        fill: Some(text.item.fill.clone()),
+        stroke: text.item.stroke.clone(),
+    };
+
+    write_shape(ctx, pos, &shape);
+}
+
+/// Builds a path from glyph outlines, converting from the Y-up font
+/// coordinate system.
+struct GlyphPathBuilder {
+    path: Path,
+    scale: f64,
+    x: f64,
+    last: (f32, f32),
+}
+
+impl GlyphPathBuilder {
+    fn point(&self, x: f32, y: f32) -> Point {
+        Point::new(
+            Abs::pt(self.x + f64::from(x) * self.scale),
+            Abs::pt(-f64::from(y) * self.scale),
+        )
+    }
+}
+
+impl OutlineBuilder for GlyphPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.path.move_to(self.point(x, y));
+        self.last = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.path.line_to(self.point(x, y));
+        self.last = (x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        // Elevate the quadratic curve to a cubic one.
+        let (x0, y0) = self.last;
+        self.path.cubic_to(
+            self.point(x0 + 2.0 / 3.0 * (x1 - x0), y0 + 2.0 / 3.0 * (y1 - y0)),
+            self.point(x + 2.0 / 3.0 * (x1 - x), y + 2.0 / 3.0 * (y1 - y)),
+            self.point(x, y),
+        );
+        self.last = (x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.path
+            .cubic_to(self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.last = (x, y);
+    }
+
+    fn close(&mut self) {
+        self.path.close_path();
+    }
+}
+
 // Encodes a text run made only of color glyphs into the content stream
 fn write_color_glyphs(ctx: &mut PageContext, pos: Point, text: TextItemView) {
     let x = pos.x.to_f32();
@@ -922,7 +1013,11 @@ fn write_color_glyphs(ctx: &mut PageContext, pos: Point, text: TextItemView) {
     // displays regular glyphs and not color glyphs.
     ctx.state.font = None;
 
-    let glyph_set = ctx.parent.glyph_sets.entry(text.item.font.clone()).or_default();
+    let glyph_set = ctx
+        .parent
+        .glyph_sets
+        .entry(text.item.font.without_variations())
+        .or_default();
 
     for glyph in text.glyphs() {
         // Retrieve the Type3 font reference and the glyph index in the font.
@@ -930,6 +1025,7 @@ fn write_color_glyphs(ctx: &mut PageContext, pos: Point, text: TextItemView) {
             &mut ctx.parent.alloc,
             &text.item.font,
             glyph.id,
+            &text.item.fill,
         );
 
         if last_font != Some(font.get()) {
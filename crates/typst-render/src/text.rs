@@ -25,7 +25,7 @@ pub fn render_text(canvas: &mut sk::Pixmap, state: State, text: &TextItem) {
                 .pre_translate(Point::new(Abs::raw(offset as _), -text.size))
                 .pre_scale(Axes::new(text_scale, text_scale));
 
-            let glyph_frame = frame_for_glyph(&text.font, glyph.id);
+            let glyph_frame = frame_for_glyph(&text.font, glyph.id, &text.fill);
 
             crate::render_frame(canvas, state, &glyph_frame);
         } else {
@@ -24,7 +24,22 @@ use crate::text::RenderedGlyph;
 /// Export a frame into a SVG file.
 #[typst_macros::time(name = "svg")]
 pub fn svg(frame: &Frame) -> String {
-    let mut renderer = SVGRenderer::new();
+    render_frame(frame, None)
+}
+
+/// Export a frame into an SVG that is embedded into a larger document, like
+/// an HTML page, next to other SVGs.
+///
+/// The ids of the SVG's definitions are qualified with the given scope, so
+/// that they are unique in the document if every SVG has a different scope.
+#[typst_macros::time(name = "svg embedded")]
+pub fn svg_embedded(frame: &Frame, scope: usize) -> String {
+    render_frame(frame, Some(scope))
+}
+
+/// Render a frame into an SVG whose ids are qualified with the scope, if any.
+fn render_frame(frame: &Frame, scope: Option<usize>) -> String {
+    let mut renderer = SVGRenderer::new(scope);
     renderer.write_header(frame.size());
 
     let state = State::new(frame.size(), Transform::identity());
@@ -50,7 +65,7 @@ pub fn svg_merged(document: &Document, padding: Abs) -> String {
             .map(|page| page.frame.height() + padding)
             .sum::<Abs>();
 
-    let mut renderer = SVGRenderer::new();
+    let mut renderer = SVGRenderer::new(None);
     renderer.write_header(Size::new(width, height));
 
     let [x, mut y] = [padding; 2];
@@ -102,6 +117,9 @@ struct SVGRenderer {
     patterns: Deduplicator<Pattern>,
     /// These are the gradients that compose a conic gradient.
     conic_subgradients: Deduplicator<SVGSubGradient>,
+    /// Qualifies the ids of all definitions if the SVG is embedded next to
+    /// others.
+    scope: Option<usize>,
 }
 
 /// Contextual information for rendering.
@@ -144,16 +162,28 @@ impl State {
 
 impl SVGRenderer {
     /// Create a new SVG renderer with empty glyph and clip path.
-    fn new() -> Self {
+    fn new(scope: Option<usize>) -> Self {
         SVGRenderer {
             xml: XmlWriter::new(xmlwriter::Options::default()),
-            glyphs: Deduplicator::new('g'),
-            clip_paths: Deduplicator::new('c'),
-            gradient_refs: Deduplicator::new('g'),
-            gradients: Deduplicator::new('f'),
-            conic_subgradients: Deduplicator::new('s'),
-            pattern_refs: Deduplicator::new('p'),
-            patterns: Deduplicator::new('t'),
+            glyphs: Deduplicator::new('g', scope),
+            clip_paths: Deduplicator::new('c', scope),
+            gradient_refs: Deduplicator::new('g', scope),
+            gradients: Deduplicator::new('f', scope),
+            conic_subgradients: Deduplicator::new('s', scope),
+            pattern_refs: Deduplicator::new('p', scope),
+            patterns: Deduplicator::new('t', scope),
+            scope,
+        }
+    }
+
+    /// Start a group of definitions with the given id.
+    fn start_defs(&mut self, id: &str) {
+        self.xml.start_element("defs");
+        match self.scope {
+            Some(scope) => {
+                self.xml.write_attribute_fmt("id", format_args!("{id}-{scope}"))
+            }
+            None => self.xml.write_attribute("id", id),
         }
     }
 
@@ -258,8 +288,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "clip-path");
+        self.start_defs("clip-path");
 
         for (id, path) in self.clip_paths.iter() {
             self.xml.start_element("clipPath");
@@ -281,13 +310,19 @@ impl SVGRenderer {
 #[derive(Debug, Clone)]
 struct Deduplicator<T> {
     kind: char,
+    scope: Option<usize>,
     vec: Vec<(u128, T)>,
     present: HashMap<u128, Id>,
 }
 
 impl<T> Deduplicator<T> {
-    fn new(kind: char) -> Self {
-        Self { kind, vec: Vec::new(), present: HashMap::new() }
+    fn new(kind: char, scope: Option<usize>) -> Self {
+        Self {
+            kind,
+            scope,
+            vec: Vec::new(),
+            present: HashMap::new(),
+        }
     }
 
     /// Inserts a value into the vector. If the hash is already present, returns
@@ -299,18 +334,14 @@ impl<T> Deduplicator<T> {
         F: FnOnce() -> T,
     {
         *self.present.entry(hash).or_insert_with(|| {
-            let index = self.vec.len();
             self.vec.push((hash, f()));
-            Id(self.kind, hash, index)
+            Id(self.kind, hash, self.scope)
         })
     }
 
     /// Iterate over the elements alongside their ids.
     fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
-        self.vec
-            .iter()
-            .enumerate()
-            .map(|(i, (id, v))| (Id(self.kind, *id, i), v))
+        self.vec.iter().map(|(hash, v)| (Id(self.kind, *hash, self.scope), v))
     }
 
     /// Returns true if the deduplicator is empty.
@@ -321,11 +352,14 @@ impl<T> Deduplicator<T> {
 
 /// Identifies a `<def>`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-struct Id(char, u128, usize);
+struct Id(char, u128, Option<usize>);
 
 impl Display for Id {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{:0X}", self.0, self.1)
+        match self.2 {
+            Some(scope) => write!(f, "{}{scope}-{:0X}", self.0, self.1),
+            None => write!(f, "{}{:0X}", self.0, self.1),
+        }
     }
 }
 
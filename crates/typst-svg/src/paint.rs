@@ -110,8 +110,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "gradients");
+        self.start_defs("gradients");
 
         for (id, (gradient, ratio)) in self.gradients.iter() {
             match &gradient {
@@ -282,8 +281,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "subgradients");
+        self.start_defs("subgradients");
         for (id, gradient) in self.conic_subgradients.iter() {
             let x1 = 2.0 - gradient.t0.cos() as f32 + gradient.center.x.get() as f32;
             let y1 = gradient.t0.sin() as f32 + gradient.center.y.get() as f32;
@@ -318,8 +316,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "gradient-refs");
+        self.start_defs("gradient-refs");
         for (id, gradient_ref) in self.gradient_refs.iter() {
             match gradient_ref.kind {
                 GradientKind::Linear => {
@@ -366,8 +363,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "patterns");
+        self.start_defs("patterns");
 
         for (id, pattern) in
             self.patterns.iter().map(|(i, p)| (i, p.clone())).collect::<Vec<_>>()
@@ -400,8 +396,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "pattern-refs");
+        self.start_defs("pattern-refs");
         for (id, pattern_ref) in self.pattern_refs.iter() {
             self.xml.start_element("pattern");
             self.xml
@@ -28,6 +28,7 @@ impl SVGRenderer {
 
             self.render_svg_glyph(text, id, offset, scale)
                 .or_else(|| self.render_bitmap_glyph(text, id, offset))
+                .or_else(|| self.render_colr_glyph(state, text, id, offset, scale))
                 .or_else(|| {
                     self.render_outline_glyph(
                         state
@@ -115,6 +116,32 @@ impl SVGRenderer {
         Some(())
     }
 
+    /// Render a glyph defined by the font's `COLR` table.
+    ///
+    /// The glyph's paint graph is converted to a frame, so that its gradients,
+    /// transforms and clips are drawn like any other content.
+    fn render_colr_glyph(
+        &mut self,
+        state: State,
+        text: &TextItem,
+        id: GlyphId,
+        x_offset: f64,
+        scale: f64,
+    ) -> Option<()> {
+        if !typst::text::color::is_colr_glyph(&text.font, id.0) {
+            return None;
+        }
+
+        let frame = typst::text::color::frame_for_glyph(&text.font, id.0, &text.fill);
+        let upem = text.font.units_per_em();
+        let ts = Transform::translate(Abs::pt(x_offset), Abs::pt(upem * scale))
+            .pre_concat(Transform::scale(Ratio::new(scale), Ratio::new(-scale)));
+        let state = state.pre_concat(Transform::scale(Ratio::one(), -Ratio::one()));
+        self.render_frame(state.pre_concat(ts), ts, &frame);
+
+        Some(())
+    }
+
     /// Render a glyph defined by an outline.
     fn render_outline_glyph(
         &mut self,
@@ -177,8 +204,7 @@ impl SVGRenderer {
             return;
         }
 
-        self.xml.start_element("defs");
-        self.xml.write_attribute("id", "glyph");
+        self.start_defs("glyph");
 
         for (id, glyph) in self.glyphs.iter() {
             self.xml.start_element("symbol");
@@ -16,6 +16,7 @@ use crate::foundations::{
     IntoValue, Reflect, Repr, Str, Value, Version,
 };
 use crate::syntax::{Span, Spanned};
+use crate::text::{Collator, Lang};
 
 /// Create a new [`Array`] from values.
 #[macro_export]
@@ -815,6 +816,13 @@ impl Array {
     ///
     /// Returns an error if two values could not be compared or if the key
     /// function (if given) yields an error.
+    ///
+    /// ```example
+    /// #let names = ("Zoë", "Ängel", "Anna", "zara")
+    /// #names.sorted() \
+    /// #names.sorted(locale: "de") \
+    /// #names.sorted(locale: "sv")
+    /// ```
     #[func]
     pub fn sorted(
         self,
@@ -828,7 +836,17 @@ impl Array {
         /// determine the keys to sort by.
         #[named]
         key: Option<Func>,
+        /// If given, strings are sorted alphabetically according to the rules
+        /// of this language instead of by their Unicode code points. Case and
+        /// diacritics then only break ties between otherwise equal strings.
+        /// This also applies to strings within arrays, so that, for instance,
+        /// names can be sorted by a key like `{(last, first)}`. The rules come
+        /// from the Unicode CLDR. Languages without specific rules use its
+        /// default order.
+        #[named]
+        locale: Option<Lang>,
     ) -> SourceResult<Array> {
+        let collator = locale.map(Collator::new);
         let mut result = Ok(());
         let mut vec = self.0;
         let mut key_of = |x: Value| match &key {
@@ -840,12 +858,14 @@ impl Array {
         vec.make_mut().sort_by(|a, b| {
             // Until we get `try` blocks :)
             match (key_of(a.clone()), key_of(b.clone())) {
-                (Ok(a), Ok(b)) => ops::compare(&a, &b).unwrap_or_else(|err| {
-                    if result.is_ok() {
-                        result = Err(err).at(span);
-                    }
-                    Ordering::Equal
-                }),
+                (Ok(a), Ok(b)) => {
+                    compare(collator.as_ref(), &a, &b).unwrap_or_else(|err| {
+                        if result.is_ok() {
+                            result = Err(err).at(span);
+                        }
+                        Ordering::Equal
+                    })
+                }
                 (Err(e), _) | (_, Err(e)) => {
                     if result.is_ok() {
                         result = Err(e);
@@ -1106,6 +1126,24 @@ impl<T: FromValue, const N: usize> FromValue for SmallVec<[T; N]> {
     }
 }
 
+/// Compare two values, comparing strings with the collator if one is given.
+fn compare(collator: Option<&Collator>, a: &Value, b: &Value) -> StrResult<Ordering> {
+    let Some(collator) = collator else { return ops::compare(a, b) };
+    match (a, b) {
+        (Value::Str(a), Value::Str(b)) => Ok(collator.compare(a, b)),
+        (Value::Array(a), Value::Array(b)) => {
+            for (x, y) in a.iter().zip(b.iter()) {
+                match compare(Some(collator), x, y)? {
+                    Ordering::Equal => {}
+                    ordering => return Ok(ordering),
+                }
+            }
+            Ok(a.len().cmp(&b.len()))
+        }
+        _ => ops::compare(a, b),
+    }
+}
+
 /// The error message when the array is empty.
 #[cold]
 fn array_is_empty() -> EcoString {
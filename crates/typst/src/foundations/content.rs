@@ -139,10 +139,15 @@ impl Content {
 
     /// Set the label of the content.
     pub fn labelled(mut self, label: Label) -> Self {
-        self.make_mut().label = Some(label);
+        self.set_label(label);
         self
     }
 
+    /// Set the label of the content in place.
+    pub fn set_label(&mut self, label: Label) {
+        self.make_mut().label = Some(label);
+    }
+
     /// Check whether a show rule recipe is disabled.
     pub fn is_guarded(&self, index: RecipeIndex) -> bool {
         self.inner.lifecycle.contains(index.0)
@@ -820,6 +825,11 @@ impl<T: NativeElement> Packed<T> {
     pub fn set_location(&mut self, location: Location) {
         self.0.set_location(location);
     }
+
+    /// Sets the label of the element.
+    pub fn set_label(&mut self, label: Label) {
+        self.0.set_label(label);
+    }
 }
 
 impl<T: NativeElement> AsRef<T> for Packed<T> {
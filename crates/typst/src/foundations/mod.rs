@@ -29,6 +29,7 @@ mod scope;
 mod selector;
 mod str;
 mod styles;
+mod target_;
 mod ty;
 mod value;
 mod version;
@@ -58,6 +59,7 @@ pub use self::scope::*;
 pub use self::selector::*;
 pub use self::str::*;
 pub use self::styles::*;
+pub use self::target_::*;
 pub use self::ty::*;
 pub use self::value::*;
 pub use self::version::*;
@@ -111,6 +113,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict) {
     global.define_func::<assert>();
     global.define_func::<eval>();
     global.define_func::<style>();
+    global.define_func::<target>();
     global.define_module(calc::module());
     global.define_module(sys::module(inputs));
 }
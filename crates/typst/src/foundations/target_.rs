@@ -0,0 +1,59 @@
+use comemo::Tracked;
+
+use crate::diag::HintedStrResult;
+use crate::foundations::{elem, func, Cast, Context};
+
+/// The export target.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Cast)]
+pub enum Target {
+    /// The target that is used for paged, fully laid-out content, like PDF,
+    /// PNG, and SVG export.
+    #[default]
+    Paged,
+    /// The target that is used for HTML and EPUB export.
+    Html,
+}
+
+impl Target {
+    /// Whether this is the HTML target.
+    pub fn is_html(self) -> bool {
+        self == Self::Html
+    }
+}
+
+/// This element exists solely to host the `target` style chain field.
+#[elem]
+pub struct TargetElem {
+    /// The compilation target.
+    #[internal]
+    #[ghost]
+    pub target: Target,
+}
+
+/// Returns the current export target.
+///
+/// This is either `{"paged"}` when the document is laid out into pages, for
+/// instance for PDF, PNG, or SVG export, or `{"html"}` when it is exported to
+/// HTML or EPUB. Together with [`html.elem`]($html.elem), this lets show rules
+/// produce different output for the web.
+///
+/// # Example
+/// ```example
+/// #show heading: it => context {
+///   if target() == "html" {
+///     html.elem("h2", attrs: (class: "title"), it.body)
+///   } else {
+///     it
+///   }
+/// }
+///
+/// = Introduction
+/// The target is #context target().
+/// ```
+#[func(contextual)]
+pub fn target(
+    /// The callsite context.
+    context: Tracked<Context>,
+) -> HintedStrResult<Target> {
+    Ok(TargetElem::target_in(context.styles()?))
+}
@@ -0,0 +1,714 @@
+use std::collections::{HashMap, HashSet};
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, NativeElement, Packed, Selector, SequenceElem, StyleChain, StyledElem,
+    Target, TargetElem,
+};
+use crate::html::{
+    HtmlDocument, HtmlElem, HtmlElement, HtmlEquation, HtmlNode, HtmlOutlineEntry,
+    HtmlTag,
+};
+use crate::introspection::{Location, TagElem};
+use crate::layout::{
+    Abs, Axes, BlockElem, BoxElem, ColbreakElem, FlowElem, Frame, HElem, HideElem,
+    LayoutMultiple, LayoutSingle, PageElem, Paper, PlaceElem, Point, Regions, Size,
+    VElem,
+};
+use crate::math::{EquationElem, MathParItem};
+use crate::model::{
+    Destination, DocumentElem, EnumElem, FootnoteElem, FootnoteEntry, HeadingElem,
+    LinkElem, ListElem, Outlinable, ParElem, TableElem, TermsElem,
+};
+use crate::realize::{realize_doc, realize_flow, Arenas};
+use crate::text::{
+    families, variant, Font, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes,
+    SpaceElem, TextElem,
+};
+use crate::utils::Numeric;
+use crate::visualize::ImageElem;
+use crate::World;
+
+/// The character used to represent objects, like inline equations, to the
+/// smart quoter.
+const OBJ_REPLACE: char = '\u{FFFC}';
+
+/// Realize content into an HTML document.
+#[typst_macros::time(name = "html")]
+pub(crate) fn html_document(
+    engine: &mut Engine,
+    content: &Content,
+    styles: StyleChain,
+) -> SourceResult<HtmlDocument> {
+    let arenas = Arenas::default();
+    let (document, styles) = realize_doc(engine, &arenas, content, styles)?;
+
+    let mut converter = Converter {
+        engine,
+        width: Abs::zero(),
+        footnotes: vec![],
+        linked: HashMap::new(),
+        fonts: vec![],
+    };
+
+    let mut body = vec![];
+    for child in document.children() {
+        let (child, styles) = match child.to_packed::<StyledElem>() {
+            Some(styled) => (&styled.child, styles.chain(&styled.styles)),
+            None => (child, styles),
+        };
+
+        let Some(page) = child.to_packed::<PageElem>() else {
+            bail!(child.span(), "unexpected document child");
+        };
+
+        // Frames are laid out as wide as the page's content area would be.
+        converter.width = page.content_width(styles);
+        if !converter.width.is_finite() {
+            converter.width = Paper::A4.width() * (16.0 / 21.0);
+        }
+
+        converter.flow(page.body(), styles, &mut body)?;
+    }
+
+    // Footnote entries can contain further footnotes, so the list may grow
+    // while we are working through it.
+    let mut entries = vec![];
+    let mut i = 0;
+    while let Some(note) = converter.footnotes.get(i).cloned() {
+        converter.flow(&FootnoteEntry::new(note).pack(), styles, &mut entries)?;
+        i += 1;
+    }
+
+    if !entries.is_empty() {
+        body.push(
+            HtmlElement::new(HtmlTag::constant("section"))
+                .with_attr("class", "footnotes")
+                .with_children(entries)
+                .into(),
+        );
+    }
+
+    // Outlined headings get an id so that they can be navigated to.
+    let mut outline = vec![];
+    for elem in converter.engine.introspector.query(&HeadingElem::elem().select()) {
+        let heading = elem.to_packed::<HeadingElem>().unwrap();
+        let Some(title) = heading.outline(converter.engine, styles)? else { continue };
+        outline.push(HtmlOutlineEntry {
+            level: heading.level(),
+            title: title.plain_text(),
+            id: converter.id(heading.location().unwrap()),
+        });
+    }
+
+    assign_ids(&mut body, &converter.linked, &mut HashSet::new());
+
+    Ok(HtmlDocument {
+        body,
+        title: DocumentElem::title_in(styles).map(|content| content.plain_text()),
+        author: DocumentElem::author_in(styles).0,
+        keywords: DocumentElem::keywords_in(styles).0,
+        date: DocumentElem::date_in(styles),
+        lang: Some(TextElem::lang_in(styles)),
+        outline,
+        fonts: converter.fonts,
+        ..HtmlDocument::default()
+    })
+}
+
+/// Converts realized content into HTML nodes.
+struct Converter<'a, 'e> {
+    /// The engine.
+    engine: &'a mut Engine<'e>,
+    /// The width that content without an HTML counterpart is laid out with.
+    width: Abs,
+    /// The footnotes that were encountered so far, in order.
+    footnotes: Vec<Packed<FootnoteElem>>,
+    /// The ids of unlabelled locations that are the target of an internal
+    /// link, in the order they were first linked to.
+    linked: HashMap<Location, EcoString>,
+    /// The fonts that text was set in so far, in order of first use.
+    fonts: Vec<Font>,
+}
+
+impl Converter<'_, '_> {
+    /// Convert block-level content.
+    fn flow(
+        &mut self,
+        content: &Content,
+        styles: StyleChain,
+        out: &mut Vec<HtmlNode>,
+    ) -> SourceResult<()> {
+        if content.is::<FlowElem>() {
+            return self.block(content, styles, out);
+        }
+
+        let arenas = Arenas::default();
+        let (flow, styles) = realize_flow(self.engine, &arenas, content, styles)?;
+        for child in flow.children() {
+            self.block(child, styles, out)?;
+        }
+        Ok(())
+    }
+
+    /// Convert content and unwrap it from its paragraph if it consists of just
+    /// one. This is used for elements that can hold both inline and block
+    /// content, like list items and table cells.
+    fn contained(
+        &mut self,
+        content: &Content,
+        styles: StyleChain,
+    ) -> SourceResult<Vec<HtmlNode>> {
+        let mut nodes = vec![];
+        self.flow(content, styles, &mut nodes)?;
+
+        let mut elements = nodes.iter().filter(|node| !matches!(node, HtmlNode::Tag(_)));
+        let sole_par = matches!(
+            (elements.next(), elements.next()),
+            (Some(HtmlNode::Element(element)), None) if element.tag.as_str() == "p"
+        );
+
+        if sole_par {
+            nodes = nodes
+                .into_iter()
+                .flat_map(|node| match node {
+                    HtmlNode::Element(element) => element.children,
+                    node => vec![node],
+                })
+                .collect();
+        }
+
+        Ok(nodes)
+    }
+
+    /// Convert a child of a flow.
+    fn block(
+        &mut self,
+        child: &Content,
+        styles: StyleChain,
+        out: &mut Vec<HtmlNode>,
+    ) -> SourceResult<()> {
+        if let Some(styled) = child.to_packed::<StyledElem>() {
+            return self.block(&styled.child, styles.chain(&styled.styles), out);
+        }
+
+        if let Some(elem) = child.to_packed::<TagElem>() {
+            out.push(self.tag(&elem.elem));
+            return Ok(());
+        }
+
+        if let Some(flow) = child.to_packed::<FlowElem>() {
+            for child in flow.children() {
+                self.block(child, styles, out)?;
+            }
+            return Ok(());
+        }
+
+        if HideElem::hidden_in(styles)
+            || child.is::<VElem>()
+            || child.is::<ColbreakElem>()
+        {
+            return Ok(());
+        }
+
+        if let Some(par) = child.to_packed::<ParElem>() {
+            let mut children = self.inline(par.children(), styles)?;
+
+            // Tags at the end of a paragraph belong to the content that
+            // follows it.
+            let end = children
+                .iter()
+                .rposition(|node| !matches!(node, HtmlNode::Tag(_)))
+                .map_or(0, |i| i + 1);
+            let trailing = children.split_off(end);
+
+            if !children.is_empty() {
+                out.push(
+                    HtmlElement::new(HtmlTag::constant("p"))
+                        .with_children(children)
+                        .into(),
+                );
+            }
+            out.extend(trailing);
+        } else if let Some(elem) = child.to_packed::<HtmlElem>() {
+            out.push(self.element(elem, styles)?.into());
+        } else if let Some(block) = child.to_packed::<BlockElem>() {
+            if let Some(body) = block.body(styles) {
+                self.flow(&body, styles, out)?;
+            }
+        } else if let Some(place) = child.to_packed::<PlaceElem>() {
+            self.flow(place.body(), styles, out)?;
+        } else if let Some(list) = child.to_packed::<ListElem>() {
+            let mut items = vec![];
+            for item in list.children() {
+                let children = self.contained(item.body(), styles)?;
+                items.push(
+                    HtmlElement::new(HtmlTag::constant("li"))
+                        .with_children(children)
+                        .into(),
+                );
+            }
+            out.push(
+                HtmlElement::new(HtmlTag::constant("ul")).with_children(items).into(),
+            );
+        } else if let Some(enum_) = child.to_packed::<EnumElem>() {
+            out.push(self.enum_(enum_, styles)?.into());
+        } else if let Some(terms) = child.to_packed::<TermsElem>() {
+            let mut items = vec![];
+            for item in terms.children() {
+                let term = self.contained(item.term(), styles)?;
+                let description = self.contained(item.description(), styles)?;
+                items.push(
+                    HtmlElement::new(HtmlTag::constant("dt")).with_children(term).into(),
+                );
+                items.push(
+                    HtmlElement::new(HtmlTag::constant("dd"))
+                        .with_children(description)
+                        .into(),
+                );
+            }
+            out.push(
+                HtmlElement::new(HtmlTag::constant("dl")).with_children(items).into(),
+            );
+        } else if let Some(table) = child.to_packed::<TableElem>() {
+            out.push(self.table(table, styles)?.into());
+        } else if let Some(image) = child.to_packed::<ImageElem>() {
+            out.push(HtmlNode::Image(image.load(self.engine, styles)?));
+        } else if let Some(elem) = child.to_packed::<EquationElem>() {
+            let frame = self.frame(child, styles)?;
+            let equation =
+                HtmlEquation { elem: elem.clone(), styles: styles.to_map(), frame };
+            out.push(
+                HtmlElement::new(HtmlTag::constant("div"))
+                    .with_attr("class", "math")
+                    .with_children(vec![HtmlNode::Equation(equation)])
+                    .into(),
+            );
+        } else if child.can::<dyn LayoutMultiple>() || child.can::<dyn LayoutSingle>() {
+            let frame = self.frame(child, styles)?;
+            out.push(
+                HtmlElement::new(HtmlTag::constant("div"))
+                    .with_attr("class", "frame")
+                    .with_children(vec![HtmlNode::Frame(frame)])
+                    .into(),
+            );
+        } else {
+            bail!(
+                child.span(),
+                "{} is not supported in HTML export",
+                child.func().name()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Convert the children of a paragraph.
+    fn inline(
+        &mut self,
+        children: &[Content],
+        styles: StyleChain,
+    ) -> SourceResult<Vec<HtmlNode>> {
+        let arenas = Arenas::default();
+        let mut leaves = vec![];
+        for child in children {
+            collect_leaves(&arenas, child, styles, &mut leaves);
+        }
+
+        let mut quoter = SmartQuoter::new();
+        let mut items: Vec<(HtmlNode, Option<EcoString>)> = vec![];
+        for (i, &(child, styles)) in leaves.iter().enumerate() {
+            if let Some(elem) = child.to_packed::<TagElem>() {
+                items.push((self.tag(&elem.elem), None));
+                continue;
+            }
+
+            if HideElem::hidden_in(styles) {
+                continue;
+            }
+
+            let node = if let Some(elem) = child.to_packed::<TextElem>() {
+                if let Some(c) = elem.text().chars().last() {
+                    quoter.last(c, false);
+                }
+                self.font(styles);
+                HtmlNode::text(elem.text().clone())
+            } else if child.is::<SpaceElem>() {
+                quoter.last(' ', false);
+                HtmlNode::text(" ")
+            } else if child.is::<LinebreakElem>() {
+                quoter.last('\n', false);
+                HtmlElement::new(HtmlTag::constant("br")).into()
+            } else if let Some(elem) = child.to_packed::<HElem>() {
+                if elem.amount().is_zero() || elem.weak(styles) {
+                    continue;
+                }
+                quoter.last(' ', false);
+                HtmlNode::text(" ")
+            } else if let Some(elem) = child.to_packed::<SmartQuoteElem>() {
+                let double = elem.double(styles);
+                let text = if elem.enabled(styles) {
+                    let quotes = SmartQuotes::new(
+                        elem.quotes(styles),
+                        TextElem::lang_in(styles),
+                        TextElem::region_in(styles),
+                        elem.alternative(styles),
+                    );
+                    let peeked = leaves.get(i + 1).map(|&(next, _)| {
+                        if let Some(elem) = next.to_packed::<TextElem>() {
+                            elem.text().chars().next().unwrap_or(' ')
+                        } else if next.is::<SmartQuoteElem>() {
+                            '"'
+                        } else if next.is::<SpaceElem>()
+                            || next.is::<HElem>()
+                            || next.is::<LinebreakElem>()
+                            || next.is::<TagElem>()
+                        {
+                            ' '
+                        } else {
+                            OBJ_REPLACE
+                        }
+                    });
+                    quoter.quote(&quotes, double, peeked)
+                } else if double {
+                    "\""
+                } else {
+                    "'"
+                };
+                quoter.last(text.chars().last().unwrap_or(' '), true);
+                HtmlNode::text(text)
+            } else if let Some(elem) = child.to_packed::<EquationElem>() {
+                quoter.last(OBJ_REPLACE, false);
+                self.inline_equation(elem, styles)?.into()
+            } else if let Some(elem) = child.to_packed::<BoxElem>() {
+                quoter.last(OBJ_REPLACE, false);
+                let paged = TargetElem::set_target(Target::Paged).wrap();
+                let styles = styles.chain(&paged);
+                let pod =
+                    Regions::one(Size::new(self.width, Abs::inf()), Axes::splat(false));
+                let frame = elem.layout(self.engine, styles, pod)?;
+                HtmlElement::new(HtmlTag::constant("span"))
+                    .with_attr("class", "frame")
+                    .with_children(vec![HtmlNode::Frame(frame)])
+                    .into()
+            } else if let Some(elem) = child.to_packed::<HtmlElem>() {
+                quoter.last(OBJ_REPLACE, false);
+                self.element(elem, styles)?.into()
+            } else {
+                bail!(
+                    child.span(),
+                    "{} is not supported in HTML export",
+                    child.func().name()
+                );
+            };
+
+            let href =
+                LinkElem::dests_in(styles).first().and_then(|dest| self.href(dest));
+            items.push((node, href));
+        }
+
+        // Group consecutive nodes with the same link destination into one
+        // anchor.
+        let mut nodes = vec![];
+        let mut iter = items.into_iter().peekable();
+        while let Some((node, href)) = iter.next() {
+            let Some(href) = href else {
+                nodes.push(node);
+                continue;
+            };
+
+            let mut children = vec![node];
+            while iter.peek().is_some_and(|(_, next)| next.as_ref() == Some(&href)) {
+                children.push(iter.next().unwrap().0);
+            }
+
+            nodes.push(
+                HtmlElement::new(HtmlTag::constant("a"))
+                    .with_attr("href", href)
+                    .with_children(children)
+                    .into(),
+            );
+        }
+
+        Ok(nodes)
+    }
+
+    /// Convert an explicit HTML element.
+    fn element(
+        &mut self,
+        elem: &Packed<HtmlElem>,
+        styles: StyleChain,
+    ) -> SourceResult<HtmlElement> {
+        let mut element = HtmlElement::new(elem.tag().clone());
+        element.attrs = elem.attrs(styles).clone();
+
+        if let Some(body) = elem.body(styles) {
+            if !element.tag.is_void() {
+                element.children = self.contained(body, styles)?;
+            }
+        }
+
+        Ok(element)
+    }
+
+    /// Convert a numbered list, preserving explicit item numbers.
+    fn enum_(
+        &mut self,
+        elem: &Packed<EnumElem>,
+        styles: StyleChain,
+    ) -> SourceResult<HtmlElement> {
+        let start = elem.start(styles);
+        let mut list = HtmlElement::new(HtmlTag::constant("ol"));
+        if start != 1 {
+            list = list.with_attr("start", eco_format!("{start}"));
+        }
+
+        let mut next = start;
+        for item in elem.children() {
+            let mut li = HtmlElement::new(HtmlTag::constant("li"))
+                .with_children(self.contained(item.body(), styles)?);
+            if let Some(number) = item.number(styles) {
+                if number != next {
+                    li = li.with_attr("value", eco_format!("{number}"));
+                }
+                next = number;
+            }
+            next += 1;
+            list.children.push(li.into());
+        }
+
+        Ok(list)
+    }
+
+    /// Convert a table, splitting it into its header, body, and footer.
+    fn table(
+        &mut self,
+        elem: &Packed<TableElem>,
+        styles: StyleChain,
+    ) -> SourceResult<HtmlElement> {
+        let grid = elem.cell_grid(self.engine, styles)?;
+        let header_end = grid.header_end().unwrap_or(0);
+        let footer_start = grid.footer_start().unwrap_or(grid.row_count());
+
+        let (mut head, mut body, mut foot) = (vec![], vec![], vec![]);
+        for y in 0..grid.row_count() {
+            if grid.is_gutter_track(y) {
+                continue;
+            }
+
+            let header = y < header_end;
+            let mut row = vec![];
+            for x in 0..grid.col_count() {
+                let Some(cell) = grid.cell(x, y) else { continue };
+                let tag = if header { "th" } else { "td" };
+                let mut element = HtmlElement::new(HtmlTag::constant(tag))
+                    .with_children(self.contained(&cell.body, styles)?);
+                if cell.colspan.get() > 1 {
+                    element =
+                        element.with_attr("colspan", eco_format!("{}", cell.colspan));
+                }
+                if cell.rowspan.get() > 1 {
+                    element =
+                        element.with_attr("rowspan", eco_format!("{}", cell.rowspan));
+                }
+                row.push(element.into());
+            }
+
+            let row = HtmlElement::new(HtmlTag::constant("tr")).with_children(row).into();
+            if header {
+                head.push(row);
+            } else if y >= footer_start {
+                foot.push(row);
+            } else {
+                body.push(row);
+            }
+        }
+
+        let mut table = HtmlElement::new(HtmlTag::constant("table"));
+        for (tag, rows) in [("thead", head), ("tbody", body), ("tfoot", foot)] {
+            if !rows.is_empty() {
+                table.children.push(
+                    HtmlElement::new(HtmlTag::constant(tag)).with_children(rows).into(),
+                );
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// Convert an inline equation, laying it out into a single frame whose
+    /// baseline is that of the equation.
+    fn inline_equation(
+        &mut self,
+        elem: &Packed<EquationElem>,
+        styles: StyleChain,
+    ) -> SourceResult<HtmlElement> {
+        // The MathML is generated with the styles in effect at the equation.
+        let outer = styles;
+
+        // Without leading, the frames cover their attachments completely
+        // instead of letting them overlap the surrounding lines.
+        let paged = TargetElem::set_target(Target::Paged).wrap();
+        let leading = ParElem::set_leading(Abs::zero().into()).wrap();
+        let styles = styles.chain(&paged);
+        let styles = styles.chain(&leading);
+        let pod = Regions::one(Size::new(self.width, Abs::inf()), Axes::splat(false));
+        let items = elem.layout_inline(self.engine, styles, pod)?;
+
+        let (mut width, mut ascent, mut descent) =
+            (Abs::zero(), Abs::zero(), Abs::zero());
+        for item in &items {
+            match item {
+                MathParItem::Space(space) => width += *space,
+                MathParItem::Frame(frame) => {
+                    width += frame.width();
+                    ascent.set_max(frame.ascent());
+                    descent.set_max(frame.descent());
+                }
+            }
+        }
+
+        let mut frame = Frame::soft(Size::new(width, ascent + descent));
+        frame.set_baseline(ascent);
+
+        let mut x = Abs::zero();
+        for item in items {
+            match item {
+                MathParItem::Space(space) => x += space,
+                MathParItem::Frame(item) => {
+                    let pos = Point::new(x, ascent - item.ascent());
+                    x += item.width();
+                    frame.push_frame(pos, item);
+                }
+            }
+        }
+
+        let equation = HtmlEquation { elem: elem.clone(), styles: outer.to_map(), frame };
+        Ok(HtmlElement::new(HtmlTag::constant("span"))
+            .with_attr("class", "math")
+            .with_children(vec![HtmlNode::Equation(equation)]))
+    }
+
+    /// Lay out content that has no HTML counterpart into a frame.
+    fn frame(&mut self, content: &Content, styles: StyleChain) -> SourceResult<Frame> {
+        let paged = TargetElem::set_target(Target::Paged).wrap();
+        let styles = styles.chain(&paged);
+        let pod = Regions::one(Size::new(self.width, Abs::inf()), Axes::splat(false));
+        Ok(content.layout(self.engine, styles, pod)?.into_frame())
+    }
+
+    /// Create a node for a located element and remember footnotes so that
+    /// their entries can be added at the end of the document.
+    fn tag(&mut self, elem: &Content) -> HtmlNode {
+        if let Some(note) = elem.to_packed::<FootnoteElem>() {
+            if !note.is_ref()
+                && !self.footnotes.iter().any(|other| other.location() == note.location())
+            {
+                self.footnotes.push(note.clone());
+            }
+        }
+
+        HtmlNode::Tag(elem.clone())
+    }
+
+    /// Remember the font that text with the given styles is set in.
+    fn font(&mut self, styles: StyleChain) {
+        let world = self.engine.world;
+        let variant = variant(styles);
+        let font = families(styles)
+            .find_map(|family| world.book().select(family, variant))
+            .and_then(|id| world.font(id));
+        if let Some(font) = font {
+            if !self.fonts.contains(&font) {
+                self.fonts.push(font);
+            }
+        }
+    }
+
+    /// Resolve a link destination into an `href` value.
+    fn href(&mut self, dest: &Destination) -> Option<EcoString> {
+        match dest {
+            Destination::Url(url) => Some(url.clone()),
+            Destination::Location(loc) => Some(eco_format!("#{}", self.id(*loc))),
+            Destination::Position(_) => None,
+        }
+    }
+
+    /// The `id` under which the element at a location can be linked to.
+    fn id(&mut self, loc: Location) -> EcoString {
+        let label = self
+            .engine
+            .introspector
+            .query_first(&Selector::Location(loc))
+            .and_then(|elem| elem.label());
+        match label {
+            Some(label) => label.as_str().into(),
+            None => {
+                let n = self.linked.len() + 1;
+                self.linked
+                    .entry(loc)
+                    .or_insert_with(|| eco_format!("loc-{n}"))
+                    .clone()
+            }
+        }
+    }
+}
+
+/// Flatten styled elements and sequences into leaves with their styles.
+fn collect_leaves<'a>(
+    arenas: &'a Arenas<'a>,
+    content: &'a Content,
+    styles: StyleChain<'a>,
+    out: &mut Vec<(&'a Content, StyleChain<'a>)>,
+) {
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        let outer = arenas.store(styles);
+        collect_leaves(arenas, &styled.child, outer.chain(&styled.styles), out);
+    } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        for child in &sequence.children {
+            collect_leaves(arenas, child, styles, out);
+        }
+    } else {
+        out.push((content, styles));
+    }
+}
+
+/// Give elements that are labelled or the target of a link an `id`.
+///
+/// The `id` is attached to the element that directly follows the element's
+/// tag. If there is none, an empty `span` is inserted to carry it.
+fn assign_ids(
+    nodes: &mut Vec<HtmlNode>,
+    linked: &HashMap<Location, EcoString>,
+    used: &mut HashSet<EcoString>,
+) {
+    let mut i = 0;
+    while i < nodes.len() {
+        let id = match &mut nodes[i] {
+            HtmlNode::Element(element) => {
+                assign_ids(&mut element.children, linked, used);
+                None
+            }
+            HtmlNode::Tag(elem) => match elem.label() {
+                Some(label) => Some(label.as_str().into()),
+                None => elem.location().and_then(|loc| linked.get(&loc)).cloned(),
+            },
+            _ => None,
+        };
+
+        i += 1;
+        let Some(id) = id.filter(|id| used.insert(id.clone())) else { continue };
+        match nodes[i..].iter_mut().find(|node| !matches!(node, HtmlNode::Tag(_))) {
+            Some(HtmlNode::Element(element)) if element.attrs.get("id").is_none() => {
+                element.attrs.push("id", id);
+            }
+            _ => nodes.insert(
+                i,
+                HtmlElement::new(HtmlTag::constant("span")).with_attr("id", id).into(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+
+use crate::foundations::{Content, Datetime, Packed, Smart, Styles};
+use crate::html::{HtmlAttrs, HtmlTag};
+use crate::introspection::Introspector;
+use crate::layout::Frame;
+use crate::math::EquationElem;
+use crate::text::{Font, Lang};
+use crate::visualize::Image;
+
+/// A document that was realized into a tree of HTML elements.
+#[derive(Debug, Default, Clone)]
+pub struct HtmlDocument {
+    /// The contents of the document's `<body>`.
+    pub body: Vec<HtmlNode>,
+    /// The document's title.
+    pub title: Option<EcoString>,
+    /// The document's author.
+    pub author: Vec<EcoString>,
+    /// The document's keywords.
+    pub keywords: Vec<EcoString>,
+    /// The document's creation date.
+    pub date: Smart<Option<Datetime>>,
+    /// The language of the document's text.
+    pub lang: Option<Lang>,
+    /// The outline of the document's headings, in document order.
+    pub outline: Vec<HtmlOutlineEntry>,
+    /// The fonts that the document's text is set in.
+    pub fonts: Vec<Font>,
+    /// Provides the ability to execute queries on the document.
+    pub introspector: Introspector,
+}
+
+/// A node in an HTML document.
+#[derive(Debug, Clone, Hash)]
+pub enum HtmlNode {
+    /// A located element that was realized. It has no visible output, but
+    /// makes the element available for introspection.
+    Tag(Content),
+    /// Plain text.
+    Text(EcoString),
+    /// An HTML element.
+    Element(HtmlElement),
+    /// An image, which is embedded as a separate resource or data URL.
+    Image(Image),
+    /// Content that has no HTML counterpart and was laid out into a frame
+    /// instead, like shapes.
+    Frame(Frame),
+    /// An equation, which is exported as MathML with its laid out frame as a
+    /// fallback.
+    Equation(HtmlEquation),
+}
+
+impl HtmlNode {
+    /// Create a text node.
+    pub fn text(text: impl Into<EcoString>) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+/// An equation in an HTML document.
+#[derive(Debug, Clone, Hash)]
+pub struct HtmlEquation {
+    /// The equation element.
+    pub elem: Packed<EquationElem>,
+    /// The styles in effect where the equation appears.
+    pub styles: Styles,
+    /// The equation laid out into a frame.
+    pub frame: Frame,
+}
+
+impl From<HtmlElement> for HtmlNode {
+    fn from(element: HtmlElement) -> Self {
+        Self::Element(element)
+    }
+}
+
+/// An HTML element with its attributes and children.
+#[derive(Debug, Clone, Hash)]
+pub struct HtmlElement {
+    /// The element's tag.
+    pub tag: HtmlTag,
+    /// The element's attributes.
+    pub attrs: HtmlAttrs,
+    /// The element's children.
+    pub children: Vec<HtmlNode>,
+}
+
+impl HtmlElement {
+    /// Create a new, empty element.
+    pub fn new(tag: HtmlTag) -> Self {
+        Self { tag, attrs: HtmlAttrs::default(), children: vec![] }
+    }
+
+    /// Set the element's children.
+    pub fn with_children(mut self, children: Vec<HtmlNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Add an attribute to the element.
+    pub fn with_attr(mut self, key: &str, value: impl Into<EcoString>) -> Self {
+        self.attrs.push(key, value);
+        self
+    }
+}
+
+/// An entry in the outline of an HTML document.
+#[derive(Debug, Clone, Hash)]
+pub struct HtmlOutlineEntry {
+    /// The nesting level of the entry, starting at one.
+    pub level: NonZeroUsize,
+    /// The plain text of the entry, including its numbering.
+    pub title: EcoString,
+    /// The `id` of the element the entry refers to.
+    pub id: EcoString,
+}
@@ -0,0 +1,192 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{cast, elem, Content, Dict, Str};
+
+/// An HTML element that can contain Typst content.
+///
+/// When exporting to HTML, the element is emitted with the given tag and
+/// attributes and its body is exported within it. For all other targets, the
+/// element is transparent: Only its body is shown. This makes it possible to
+/// enrich documents with semantic markup for the web without affecting their
+/// printed form. Use the [`target`] function to produce HTML elements only for
+/// HTML export.
+///
+/// # Example
+/// ```example
+/// #html.elem("aside", attrs: (class: "note"))[
+///   In HTML, this is an aside with
+///   the class `note`. Elsewhere, it
+///   is just its body.
+/// ]
+/// ```
+#[elem(name = "elem", title = "HTML Element")]
+pub struct HtmlElem {
+    /// The element's tag.
+    #[required]
+    pub tag: HtmlTag,
+
+    /// The element's HTML attributes.
+    #[borrowed]
+    pub attrs: HtmlAttrs,
+
+    /// The contents of the HTML element.
+    ///
+    /// Elements that can only contain text, like `span` or `a`, should only be
+    /// given inline content.
+    #[positional]
+    #[borrowed]
+    pub body: Option<Content>,
+}
+
+impl HtmlElem {
+    /// Add an attribute to the element.
+    pub fn with_attr(self, key: &str, value: impl Into<EcoString>) -> Self {
+        let mut attrs = self.attrs.clone().unwrap_or_default();
+        attrs.push(key, value);
+        self.with_attrs(attrs)
+    }
+}
+
+/// The tag of an HTML element.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HtmlTag(EcoString);
+
+impl HtmlTag {
+    /// Create a tag from a name that is known to be valid.
+    pub fn constant(name: &'static str) -> Self {
+        Self(name.into())
+    }
+
+    /// The name of the tag.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether elements with this tag are phrasing content, i.e. can appear
+    /// within a paragraph.
+    pub fn is_inline(&self) -> bool {
+        matches!(
+            self.as_str(),
+            "a" | "abbr"
+                | "b"
+                | "bdi"
+                | "bdo"
+                | "br"
+                | "cite"
+                | "code"
+                | "data"
+                | "del"
+                | "dfn"
+                | "em"
+                | "i"
+                | "img"
+                | "ins"
+                | "kbd"
+                | "label"
+                | "mark"
+                | "math"
+                | "q"
+                | "rp"
+                | "rt"
+                | "ruby"
+                | "s"
+                | "samp"
+                | "small"
+                | "span"
+                | "strong"
+                | "sub"
+                | "sup"
+                | "svg"
+                | "time"
+                | "u"
+                | "var"
+                | "wbr"
+        )
+    }
+
+    /// Whether elements with this tag have no contents and no end tag.
+    pub fn is_void(&self) -> bool {
+        matches!(
+            self.as_str(),
+            "area"
+                | "base"
+                | "br"
+                | "col"
+                | "embed"
+                | "hr"
+                | "img"
+                | "input"
+                | "link"
+                | "meta"
+                | "source"
+                | "track"
+                | "wbr"
+        )
+    }
+
+    /// Whether whitespace within elements with this tag is significant.
+    pub fn is_preformatted(&self) -> bool {
+        matches!(self.as_str(), "pre" | "textarea")
+    }
+}
+
+cast! {
+    HtmlTag,
+    self => self.0.into_value(),
+    v: Str => {
+        validate_name(&v, "tag")?;
+        Self(v.to_lowercase().into())
+    },
+}
+
+/// The attributes of an HTML element.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct HtmlAttrs(pub Vec<(EcoString, EcoString)>);
+
+impl HtmlAttrs {
+    /// Add an attribute, replacing an existing one with the same name.
+    pub fn push(&mut self, key: &str, value: impl Into<EcoString>) {
+        let value = value.into();
+        match self.0.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => self.0.push((key.into(), value)),
+        }
+    }
+
+    /// Get the value of an attribute.
+    pub fn get(&self, key: &str) -> Option<&EcoString> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+cast! {
+    HtmlAttrs,
+    self => self.0
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(key, value)| {
+            validate_name(&key, "attribute")?;
+            Ok((key.to_lowercase().into(), value.cast::<EcoString>()?))
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+/// Ensure that a tag or attribute name only consists of allowed characters.
+fn validate_name(name: &str, kind: &str) -> StrResult<()> {
+    if name.is_empty() {
+        bail!("{kind} name must not be empty");
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+    {
+        bail!("invalid {kind} name: `{name}`");
+    }
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! HTML output.
+
+mod convert;
+mod document;
+mod elem;
+
+pub use self::document::*;
+pub use self::elem::*;
+
+pub(crate) use self::convert::html_document;
+
+use crate::foundations::{Module, Scope};
+
+/// A module with HTML-specific definitions.
+pub fn module() -> Module {
+    let mut html = Scope::deduplicating();
+    html.define_elem::<HtmlElem>();
+    Module::new("html", html)
+}
@@ -15,7 +15,7 @@ use crate::foundations::{
 };
 use crate::introspection::{Introspector, Locatable, Location, Locator};
 use crate::layout::{Frame, FrameItem, PageElem};
-use crate::math::EquationElem;
+use crate::math::{EquationElem, NumberElem};
 use crate::model::{FigureElem, HeadingElem, Numbering, NumberingPattern};
 use crate::syntax::Span;
 use crate::utils::NonZeroExt;
@@ -340,6 +340,14 @@ impl Counter {
 
         if let CounterKey::Selector(key) = &self.0 {
             selector = Selector::Or(eco_vec![selector, key.clone()]);
+
+            // Individually numbered lines of equations share their counter.
+            if *key == Selector::Elem(EquationElem::elem(), None) {
+                selector = Selector::Or(eco_vec![
+                    selector,
+                    Selector::Elem(NumberElem::elem(), None)
+                ]);
+            }
         }
 
         selector
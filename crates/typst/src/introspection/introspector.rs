@@ -10,6 +10,7 @@ use smallvec::SmallVec;
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{Content, Label, Repr, Selector};
+use crate::html::{HtmlEquation, HtmlNode};
 use crate::introspection::Location;
 use crate::layout::{Frame, FrameItem, Page, Point, Position, Transform};
 use crate::model::Numbering;
@@ -61,26 +62,61 @@ impl Introspector {
                         .pre_concat(group.transform);
                     self.extract(&group.frame, page, ts);
                 }
-                FrameItem::Tag(elem)
-                    if !self.elems.contains_key(&elem.location().unwrap()) =>
-                {
-                    let pos = pos.transform(ts);
-                    let ret = self.elems.insert(
-                        elem.location().unwrap(),
-                        (elem.clone(), Position { page, point: pos }),
-                    );
-                    assert!(ret.is_none(), "duplicate locations");
-
-                    // Build the label cache.
-                    if let Some(label) = elem.label() {
-                        self.labels.entry(label).or_default().push(self.elems.len() - 1);
-                    }
+                FrameItem::Tag(elem) => {
+                    self.insert(elem, Position { page, point: pos.transform(ts) });
                 }
                 _ => {}
             }
         }
     }
 
+    /// Applies a new HTML document in-place, reusing the existing allocations.
+    ///
+    /// An HTML document has no pages, so all elements are considered to be on
+    /// the first page at the origin.
+    #[typst_macros::time(name = "introspect")]
+    pub fn rebuild_html(&mut self, nodes: &[HtmlNode]) {
+        self.pages = 1;
+        self.elems.clear();
+        self.labels.clear();
+        self.page_numberings.clear();
+        self.queries.clear();
+
+        self.extract_html(nodes);
+        self.page_numberings.push(None);
+    }
+
+    /// Extract metadata from HTML nodes.
+    fn extract_html(&mut self, nodes: &[HtmlNode]) {
+        let position = Position { page: NonZeroUsize::ONE, point: Point::zero() };
+        for node in nodes {
+            match node {
+                HtmlNode::Tag(elem) => self.insert(elem, position),
+                HtmlNode::Element(element) => self.extract_html(&element.children),
+                HtmlNode::Frame(frame)
+                | HtmlNode::Equation(HtmlEquation { frame, .. }) => {
+                    self.extract(frame, NonZeroUsize::ONE, Transform::identity())
+                }
+                HtmlNode::Text(_) | HtmlNode::Image(_) => {}
+            }
+        }
+    }
+
+    /// Register a located element, unless it is already known.
+    fn insert(&mut self, elem: &Content, position: Position) {
+        let loc = elem.location().unwrap();
+        if self.elems.contains_key(&loc) {
+            return;
+        }
+
+        self.elems.insert(loc, (elem.clone(), position));
+
+        // Build the label cache.
+        if let Some(label) = elem.label() {
+            self.labels.entry(label).or_default().push(self.elems.len() - 1);
+        }
+    }
+
     /// Iterate over all locatable elements.
     pub fn all(&self) -> impl Iterator<Item = &Content> + '_ {
         self.elems.values().map(|(c, _)| c)
@@ -12,12 +12,15 @@ use crate::foundations::{
     elem, Content, NativeElement, Packed, Resolve, Smart, StyleChain, StyledElem,
 };
 use crate::introspection::TagElem;
+use crate::layout::page::turn_counter_clockwise;
 use crate::layout::{
-    Abs, AlignElem, Axes, BlockElem, ColbreakElem, ColumnsElem, FixedAlignment, Fr,
-    Fragment, Frame, FrameItem, LayoutMultiple, LayoutSingle, PlaceElem, Point, Regions,
-    Rel, Size, Spacing, VElem,
+    Abs, AlignElem, Axes, BlockElem, ColbreakElem, ColumnsElem, Dir, FixedAlignment, Fr,
+    Fragment, Frame, FrameItem, GridElem, LayoutMultiple, LayoutSingle, PlaceElem, Point,
+    Regions, Rel, Size, Spacing, VElem,
 };
-use crate::model::{FootnoteElem, FootnoteEntry, ParElem};
+use crate::math::EquationElem;
+use crate::model::{FootnoteElem, FootnoteEntry, ParElem, TableElem};
+use crate::text::{TextDir, TextElem};
 use crate::utils::Numeric;
 
 /// Arranges spacing, paragraphs and block-level elements into a flow.
@@ -84,6 +87,14 @@ impl LayoutMultiple for Packed<FlowElem> {
                 }
             } else if let Some(elem) = child.to_packed::<ParElem>() {
                 layouter.layout_par(engine, elem, styles)?;
+            } else if TextElem::dir_in(styles) == Dir::TTB && is_upright(child) {
+                if !TextElem::vertical_page_in(styles) {
+                    bail!(
+                        child.span(), "vertical text must be enabled for the whole page";
+                        hint: "use `set text(dir: ttb)` at the start of the document"
+                    );
+                }
+                layouter.layout_upright(engine, child, styles)?;
             } else if let Some(layoutable) = child.with::<dyn LayoutSingle>() {
                 layouter.layout_single(engine, layoutable, styles)?;
             } else if let Some(layoutable) = child.with::<dyn LayoutMultiple>() {
@@ -356,6 +367,36 @@ impl<'a> FlowLayouter<'a> {
         Ok(())
     }
 
+    /// Layout non-text content in vertical text.
+    ///
+    /// The page turns everything clockwise, so the content is laid out
+    /// horizontally and turned counter-clockwise beforehand to stay upright.
+    fn layout_upright(
+        &mut self,
+        engine: &mut Engine,
+        child: &Content,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let align = AlignElem::alignment_in(styles).resolve(styles);
+        let sticky = BlockElem::sticky_in(styles);
+        let base = self.regions.base();
+        let pod = Regions::one(Size::new(base.y, base.x), Axes::splat(false));
+        let content = child
+            .clone()
+            .styled(TextElem::set_dir(TextDir(Smart::Auto)))
+            .styled(TextElem::set_vertical_page(false));
+        let mut frame = content.layout(engine, styles, pod)?.into_frame();
+        turn_counter_clockwise(&mut frame);
+        self.drain_tag(&mut frame);
+        frame.post_process(styles);
+        self.layout_item(
+            engine,
+            FlowItem::Frame { frame, align, sticky, movable: true },
+        )?;
+        self.last_was_par = false;
+        Ok(())
+    }
+
     /// Layout into multiple regions.
     fn layout_multiple(
         &mut self,
@@ -818,6 +859,15 @@ impl FlowLayouter<'_> {
     }
 }
 
+/// Whether a block-level child holds non-text content that should stay
+/// upright in vertical text.
+fn is_upright(child: &Content) -> bool {
+    child.can::<dyn LayoutSingle>()
+        || child.is::<EquationElem>()
+        || child.is::<GridElem>()
+        || child.is::<TableElem>()
+}
+
 /// Finds all footnotes in the frame.
 fn find_footnotes(notes: &mut Vec<Packed<FootnoteElem>>, frame: &Frame) {
     for (_, item) in frame.items() {
@@ -1175,6 +1175,26 @@ impl CellGrid {
         }
     }
 
+    /// The number of columns, including gutter columns.
+    pub fn col_count(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// The number of rows, including gutter rows.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The index after the last row of the header, if there is a header.
+    pub fn header_end(&self) -> Option<usize> {
+        self.header.as_ref().map(|header| header.unwrap().end)
+    }
+
+    /// The first row of the footer, if there is a footer.
+    pub fn footer_start(&self) -> Option<usize> {
+        self.footer.as_ref().map(|footer| footer.unwrap().start)
+    }
+
     /// Get the grid entry in column `x` and row `y`.
     ///
     /// Returns `None` if it's a gutter cell.
@@ -1201,7 +1221,7 @@ impl CellGrid {
     ///
     /// Returns `None` if it's a gutter cell or merged position.
     #[track_caller]
-    pub(super) fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
+    pub fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
         self.entry(x, y).and_then(Entry::as_cell)
     }
 
@@ -1268,7 +1288,7 @@ impl CellGrid {
     /// Checks if the track with the given index is gutter.
     /// Does not check if the index is a valid track.
     #[inline]
-    pub(super) fn is_gutter_track(&self, index: usize) -> bool {
+    pub fn is_gutter_track(&self, index: usize) -> bool {
         self.has_gutter && index % 2 == 1
     }
 
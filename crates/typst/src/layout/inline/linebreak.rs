@@ -6,9 +6,10 @@ use icu_provider_blob::BlobDataProvider;
 use icu_segmenter::LineSegmenter;
 use once_cell::sync::Lazy;
 
-use super::Preparation;
+use super::shaping::{BEGIN_PUNCT_PAT, END_PUNCT_PAT};
+use super::{Item, Preparation};
 use crate::syntax::link_prefix;
-use crate::text::{Lang, TextElem};
+use crate::text::{hyphenate, Lang, TextElem};
 
 /// The general line break segmenter.
 static SEGMENTER: Lazy<LineSegmenter> = Lazy::new(|| {
@@ -68,6 +69,18 @@ pub(super) fn breakpoints<'a>(
         _ => &SEGMENTER,
     };
 
+    // Never break within the base of a ruby annotation.
+    let mut f = |offset: usize, breakpoint: Breakpoint| {
+        if !p
+            .rubies
+            .iter()
+            .any(|ruby| ruby.range.start < offset && offset < ruby.range.end)
+            && (breakpoint == Breakpoint::Mandatory || !is_prohibited(p, offset))
+        {
+            f(offset, breakpoint);
+        }
+    };
+
     let mut last = 0;
     let mut iter = segmenter.segment_str(text).peekable();
 
@@ -119,18 +132,9 @@ pub(super) fn breakpoints<'a>(
                 break 'hyphenate;
             }
 
-            let end = last + word.len();
-            let mut offset = last;
-
-            // Determine the language to hyphenate this word in.
-            let Some(lang) = lang_at(p, last) else { break 'hyphenate };
-
-            for syllable in hypher::hyphenate(word, lang) {
-                // Don't hyphenate after the final syllable.
-                offset += syllable.len();
-                if offset == end {
-                    continue;
-                }
+            // Determine the hyphenation opportunities in this word.
+            for offset in hyphenate_word(p, last, word) {
+                let offset = last + offset;
 
                 // Filter out hyphenation opportunities where hyphenation was
                 // actually disabled.
@@ -140,7 +144,7 @@ pub(super) fn breakpoints<'a>(
 
                 // Filter out forbidden hyphenation opportunities.
                 if matches!(
-                    syllable.chars().next_back().map(|c| lb.get(c)),
+                    text[..offset].chars().next_back().map(|c| lb.get(c)),
                     Some(LineBreak::Glue | LineBreak::WordJoiner | LineBreak::ZWJ)
                 ) {
                     continue;
@@ -216,6 +220,26 @@ fn linebreak_link(link: &str, mut f: impl FnMut(usize)) {
     }
 }
 
+/// Whether breaking at the given offset is prohibited by the CJK line
+/// composition rules (kinsoku shori).
+///
+/// Closing punctuation may not start a line and opening punctuation may not
+/// end one. UAX #14 covers most of these cases, but allows breaks around
+/// quotation marks that are surrounded by East Asian characters and before
+/// some punctuation that follows a space. The rules apply to punctuation in
+/// Chinese or Japanese text runs, even if the rest of the paragraph is in
+/// another language.
+fn is_prohibited(p: &Preparation, offset: usize) -> bool {
+    let is_cj = |offset: usize| {
+        let lang = p.find(offset).and_then(Item::text).map(|shaped| shaped.lang);
+        matches!(lang.or(p.lang), Some(Lang::CHINESE | Lang::JAPANESE))
+    };
+
+    let (head, tail) = p.bidi.text.split_at(offset);
+    (head.ends_with(BEGIN_PUNCT_PAT) && is_cj(offset - 1))
+        || (tail.starts_with(END_PUNCT_PAT) && is_cj(offset))
+}
+
 /// Whether hyphenation is enabled at the given offset.
 fn hyphenate_at(p: &Preparation, offset: usize) -> bool {
     p.hyphenate
@@ -226,13 +250,10 @@ fn hyphenate_at(p: &Preparation, offset: usize) -> bool {
         .unwrap_or(false)
 }
 
-/// The text language at the given offset.
-fn lang_at(p: &Preparation, offset: usize) -> Option<hypher::Lang> {
-    let lang = p.lang.or_else(|| {
-        let shaped = p.find(offset)?.text()?;
-        Some(TextElem::lang_in(shaped.styles))
-    })?;
-
-    let bytes = lang.as_str().as_bytes().try_into().ok()?;
-    hypher::Lang::from_iso(bytes)
+/// The hyphenation opportunities in a word starting at the given offset,
+/// relative to the start of the word.
+fn hyphenate_word(p: &Preparation, offset: usize, word: &str) -> Vec<usize> {
+    let Some(shaped) = p.find(offset).and_then(Item::text) else { return vec![] };
+    let lang = p.lang.unwrap_or_else(|| TextElem::lang_in(shaped.styles));
+    hyphenate(word, lang, shaped.styles)
 }
@@ -1,20 +1,24 @@
 mod linebreak;
+mod ruby;
 mod shaping;
 
 use comemo::{Tracked, TrackedMut};
+use ecow::EcoString;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
 
 use self::linebreak::{breakpoints, Breakpoint};
+use self::ruby::{layout_rubies, Ruby, RubyGroup};
 use self::shaping::{
-    cjk_punct_style, is_of_cj_script, shape, ShapedGlyph, ShapedText, BEGIN_PUNCT_PAT,
-    END_PUNCT_PAT,
+    cjk_punct_style, cjk_punct_trim, is_of_cj_script, shape, vertical_axis, Orientation,
+    ShapedGlyph, ShapedText, BEGIN_PUNCT_PAT, END_PUNCT_PAT,
 };
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, warning, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
-use crate::foundations::{Content, Packed, Resolve, Smart, StyleChain, StyledElem};
+use crate::foundations::{Content, Packed, Repr, Resolve, Smart, StyleChain, StyledElem};
 use crate::introspection::{Introspector, Locator, TagElem};
+use crate::layout::page::turn_counter_clockwise;
 use crate::layout::{
     Abs, AlignElem, Axes, BoxElem, Dir, Em, FixedAlignment, Fr, Fragment, Frame,
     FrameItem, HElem, Point, Regions, Size, Sizing, Spacing,
@@ -23,7 +27,8 @@ use crate::math::{EquationElem, MathParItem};
 use crate::model::{Linebreaks, ParElem};
 use crate::syntax::Span;
 use crate::text::{
-    Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
+    CjkPunctuationTrim, Lang, LinebreakElem, RubyMarkElem, SmartQuoteElem, SmartQuoter,
+    SmartQuotes, SpaceElem, TextDir, TextElem,
 };
 use crate::utils::Numeric;
 use crate::World;
@@ -61,13 +66,22 @@ pub(crate) fn layout_inline(
         };
 
         // Collect all text into one string for BiDi analysis.
-        let (text, segments, spans) =
+        let (text, segments, spans, rubies) =
             collect(children, &mut engine, &styles, region, consecutive)?;
 
         // Perform BiDi analysis and then prepare paragraph layout by building a
         // representation on which we can do line breaking without layouting
         // each and every line from scratch.
-        let p = prepare(&mut engine, children, &text, segments, spans, styles, region)?;
+        let p = prepare(
+            &mut engine,
+            children,
+            &text,
+            segments,
+            spans,
+            rubies,
+            styles,
+            region,
+        )?;
 
         // Break the paragraph into lines.
         let lines = linebreak(&engine, &p, region.x - p.hang);
@@ -120,6 +134,8 @@ struct Preparation<'a> {
     bidi: BidiInfo<'a>,
     /// Text runs, spacing and layouted elements.
     items: Vec<Item<'a>>,
+    /// Ruby annotations over ranges of the text.
+    rubies: Vec<Ruby>,
     /// The span mapper.
     spans: SpanMapper,
     /// Whether to hyphenate if it's the same for all children.
@@ -356,6 +372,10 @@ struct Line<'a> {
     /// Whether the line ends with a hyphen or dash, either naturally or through
     /// hyphenation.
     dash: Option<Dash>,
+    /// How far the glyphs at the line's edges hang into the margins. Only
+    /// computed for justified paragraphs, where it is taken into account
+    /// during line breaking.
+    protrusion: Abs,
 }
 
 impl<'a> Line<'a> {
@@ -414,6 +434,14 @@ impl<'a> Line<'a> {
         self.items().filter_map(Item::text).map(|s| s.stretchability()).sum()
     }
 
+    /// How much of the line's stretchability stems from kashidas.
+    fn kashida_stretchability(&self) -> Abs {
+        self.items()
+            .filter_map(Item::text)
+            .map(|s| s.kashida_stretchability())
+            .sum()
+    }
+
     /// How much can the line shrink
     fn shrinkability(&self) -> Abs {
         self.items().filter_map(Item::text).map(|s| s.shrinkability()).sum()
@@ -439,11 +467,18 @@ fn collect<'a>(
     styles: &'a StyleChain<'a>,
     region: Size,
     consecutive: bool,
-) -> SourceResult<(String, Vec<(Segment<'a>, StyleChain<'a>)>, SpanMapper)> {
+) -> SourceResult<(
+    String,
+    Vec<(Segment<'a>, StyleChain<'a>)>,
+    SpanMapper,
+    Vec<RubyGroup<'a>>,
+)> {
     let mut full = String::new();
     let mut quoter = SmartQuoter::new();
     let mut segments = Vec::with_capacity(2 + children.len());
     let mut spans = SpanMapper::new();
+    let mut rubies = vec![];
+    let mut ruby = None;
     let mut iter = children.iter().peekable();
 
     let first_line_indent = ParElem::first_line_indent_in(*styles);
@@ -472,12 +507,39 @@ fn collect<'a>(
             styles = outer.chain(&styled.styles);
         }
 
+        // The parts of a ruby base are regular children of the paragraph.
+        // Their marks only record where they start and end.
+        if let Some(mark) = child.to_packed::<RubyMarkElem>() {
+            let elem = mark.ruby();
+            let index = *mark.index();
+            let annotations = &elem.annotation().0;
+            if index == 0 {
+                ruby = Some((RubyGroup { elem, styles, parts: vec![] }, full.len()));
+            } else if let Some((group, start)) = &mut ruby {
+                if group.parts.len() + 1 == index {
+                    group.parts.push((*start..full.len(), &annotations[index - 1]));
+                    *start = full.len();
+                }
+                if group.parts.len() == annotations.len() {
+                    rubies.extend(ruby.take().map(|(group, _)| group));
+                }
+            }
+            continue;
+        }
+
         let segment = if child.is::<SpaceElem>() {
             full.push(' ');
             Segment::Text(1)
         } else if let Some(elem) = child.to_packed::<TextElem>() {
             let prev = full.len();
             let dir = TextElem::dir_in(styles);
+            if dir == Dir::TTB && !TextElem::vertical_page_in(styles) {
+                bail!(
+                    elem.span(), "vertical text must be enabled for the whole page";
+                    hint: "use `set text(dir: ttb)` at the start of the document"
+                );
+            }
+
             if dir != outer_dir {
                 // Insert "Explicit Directional Embedding".
                 match dir {
@@ -488,7 +550,7 @@ fn collect<'a>(
             }
 
             if let Some(case) = TextElem::case_in(styles) {
-                full.push_str(&case.apply(elem.text()));
+                full.push_str(&case.apply(elem.text(), TextElem::lang_in(styles)));
             } else {
                 full.push_str(elem.text());
             }
@@ -547,12 +609,20 @@ fn collect<'a>(
             }
             Segment::Text(full.len() - prev)
         } else if let Some(elem) = child.to_packed::<EquationElem>() {
-            let pod = Regions::one(region, Axes::splat(false));
-            let mut items = elem.layout_inline(engine, styles, pod)?;
-            for item in &mut items {
-                let MathParItem::Frame(frame) = item else { continue };
-                frame.post_process(styles);
-            }
+            let items = if TextElem::dir_in(styles) == Dir::TTB {
+                // In vertical text, the equation is set as a single object.
+                let boxed = Packed::new(BoxElem::new().with_body(Some(child.clone())))
+                    .spanned(child.span());
+                vec![MathParItem::Frame(layout_upright(engine, &boxed, styles, region)?)]
+            } else {
+                let pod = Regions::one(region, Axes::splat(false));
+                let mut items = elem.layout_inline(engine, styles, pod)?;
+                for item in &mut items {
+                    let MathParItem::Frame(frame) = item else { continue };
+                    frame.post_process(styles);
+                }
+                items
+            };
             full.push(LTR_ISOLATE);
             full.extend(items.iter().map(MathParItem::text));
             full.push(POP_ISOLATE);
@@ -585,16 +655,18 @@ fn collect<'a>(
         segments.push((segment, styles));
     }
 
-    Ok((full, segments, spans))
+    Ok((full, segments, spans, rubies))
 }
 
 /// Prepare paragraph layout by shaping the whole paragraph.
+#[allow(clippy::too_many_arguments)]
 fn prepare<'a>(
     engine: &mut Engine,
     children: &'a [Content],
     text: &'a str,
     segments: Vec<(Segment<'a>, StyleChain<'a>)>,
     spans: SpanMapper,
+    rubies: Vec<RubyGroup<'a>>,
     styles: StyleChain<'a>,
     region: Size,
 ) -> SourceResult<Preparation<'a>> {
@@ -602,7 +674,7 @@ fn prepare<'a>(
     let bidi = BidiInfo::new(
         text,
         match dir {
-            Dir::LTR => Some(BidiLevel::ltr()),
+            Dir::LTR | Dir::TTB => Some(BidiLevel::ltr()),
             Dir::RTL => Some(BidiLevel::rtl()),
             _ => None,
         },
@@ -616,7 +688,9 @@ fn prepare<'a>(
         let end = cursor + segment.len();
         match segment {
             Segment::Text(_) => {
+                let start = items.len();
                 shape_range(&mut items, engine, &bidi, cursor..end, &spans, styles);
+                warn_uncovered(engine, &items[start..], styles);
             }
             Segment::Spacing(spacing) => match spacing {
                 Spacing::Rel(v) => {
@@ -644,9 +718,14 @@ fn prepare<'a>(
                 if let Sizing::Fr(v) = elem.width(styles) {
                     items.push(Item::Fractional(v, Some((elem, styles))));
                 } else {
-                    let pod = Regions::one(region, Axes::splat(false));
-                    let mut frame = elem.layout(engine, styles, pod)?;
-                    frame.post_process(styles);
+                    let mut frame = if TextElem::dir_in(styles) == Dir::TTB {
+                        layout_upright(engine, elem, styles, region)?
+                    } else {
+                        let pod = Regions::one(region, Axes::splat(false));
+                        let mut frame = elem.layout(engine, styles, pod)?;
+                        frame.post_process(styles);
+                        frame
+                    };
                     frame.translate(Point::with_y(TextElem::baseline_in(styles)));
                     items.push(Item::Frame(frame));
                 }
@@ -664,11 +743,14 @@ fn prepare<'a>(
         add_cjk_latin_spacing(&mut items);
     }
 
+    let rubies = layout_rubies(engine, text, &mut items, rubies, region)?;
+
     let costs = TextElem::costs_in(styles);
 
     Ok(Preparation {
         bidi,
         items,
+        rubies,
         spans,
         hyphenate: shared_get(styles, children, TextElem::hyphenate_in),
         costs,
@@ -684,6 +766,60 @@ fn prepare<'a>(
     })
 }
 
+/// Lay out an inline object in vertical text.
+///
+/// The page turns everything clockwise, so the object is laid out
+/// horizontally and turned counter-clockwise beforehand to stay upright. It is
+/// then centered on the line's axis like upright glyphs.
+fn layout_upright(
+    engine: &mut Engine,
+    elem: &Packed<BoxElem>,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Frame> {
+    let dir = TextElem::set_dir(TextDir(Smart::Auto)).wrap();
+    let page = TextElem::set_vertical_page(false).wrap();
+    let styles = styles.chain(&dir);
+    let styles = styles.chain(&page);
+    let pod = Regions::one(Size::new(region.y, region.x), Axes::splat(false));
+    let mut frame = elem.layout(engine, styles, pod)?;
+    frame.post_process(styles);
+    turn_counter_clockwise(&mut frame);
+    frame.set_baseline(frame.height() / 2.0 + vertical_axis(engine, styles));
+    Ok(frame)
+}
+
+/// Warn about characters that no font could provide glyphs for.
+fn warn_uncovered(engine: &mut Engine, items: &[Item], styles: StyleChain) {
+    let mut uncovered: Vec<_> = items
+        .iter()
+        .filter_map(Item::text)
+        .flat_map(|text| text.glyphs.iter())
+        .filter(|glyph| glyph.glyph_id == 0 && !glyph.c.is_whitespace())
+        .collect();
+
+    // Glyphs of right-to-left text are stored in visual order.
+    uncovered.sort_by_key(|glyph| glyph.range.start);
+
+    let Some(first) = uncovered.first() else { return };
+    let mut chars = EcoString::new();
+    for glyph in &uncovered {
+        if !chars.contains(glyph.c) {
+            chars.push(glyph.c);
+        }
+    }
+
+    let mut diag = warning!(
+        first.span.0,
+        "no font could be found that covers the characters {}",
+        chars.repr(),
+    );
+    if !TextElem::fallback_in(styles) {
+        diag.hint("font fallback is disabled");
+    }
+    engine.tracer.warn(diag);
+}
+
 /// Add some spacing between Han characters and western characters.
 /// See Requirements for Chinese Text Layout, Section 3.2.2 Mixed Text Composition in Horizontal
 /// Written Mode
@@ -758,33 +894,43 @@ fn shape_range<'a>(
         items.push(Item::Text(shaped));
     };
 
+    let vertical = TextElem::dir_in(styles) == Dir::TTB;
     let mut prev_level = BidiLevel::ltr();
     let mut prev_script = Script::Unknown;
+    let mut prev_orientation = Orientation::Natural;
     let mut cursor = range.start;
 
     // Group by embedding level and script.  If the text's script is explicitly
     // set (rather than inferred from the glyphs), we keep the script at an
     // unchanging `Script::Unknown` so that only level changes cause breaks.
+    // In vertical text, we additionally group by orientation.
     for i in range.clone() {
         if !bidi.text.is_char_boundary(i) {
             continue;
         }
 
         let level = bidi.levels[i];
+        let c = bidi.text[i..].chars().next();
         let curr_script = match script {
-            Smart::Auto => {
-                bidi.text[i..].chars().next().map_or(Script::Unknown, |c| c.script())
-            }
+            Smart::Auto => c.map_or(Script::Unknown, |c| c.script()),
             Smart::Custom(_) => Script::Unknown,
         };
+        let curr_orientation = match c {
+            Some(c) if vertical => Orientation::of_char(c),
+            _ => Orientation::Natural,
+        };
 
-        if level != prev_level || !is_compatible(curr_script, prev_script) {
+        if level != prev_level
+            || !is_compatible(curr_script, prev_script)
+            || curr_orientation != prev_orientation
+        {
             if cursor < i {
                 process(cursor..i, prev_level);
             }
             cursor = i;
             prev_level = level;
             prev_script = curr_script;
+            prev_orientation = curr_orientation;
         } else if is_generic_script(prev_script) {
             prev_script = curr_script;
         }
@@ -855,7 +1001,7 @@ fn linebreak_simple<'a>(
         // If the line doesn't fit anymore, we push the last fitting attempt
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
-        if !width.fits(attempt.width) {
+        if !width.fits(attempt.width - attempt.protrusion) {
             if let Some((last_attempt, last_end)) = last.take() {
                 lines.push(last_attempt);
                 start = last_end;
@@ -866,7 +1012,9 @@ fn linebreak_simple<'a>(
         // Finish the current line if there is a mandatory line break (i.e.
         // due to "\n") or if the line doesn't fit horizontally already
         // since then no shorter line will be possible.
-        if breakpoint == Breakpoint::Mandatory || !width.fits(attempt.width) {
+        if breakpoint == Breakpoint::Mandatory
+            || !width.fits(attempt.width - attempt.protrusion)
+        {
             lines.push(attempt);
             start = end;
             last = None;
@@ -917,12 +1065,14 @@ fn linebreak_optimized<'a>(
     // Cost parameters.
     const DEFAULT_HYPH_COST: Cost = 0.5;
     const DEFAULT_RUNT_COST: Cost = 0.5;
+    const DEFAULT_KASHIDA_COST: Cost = 0.3;
     const CONSECUTIVE_DASH_COST: Cost = 0.3;
     const MAX_COST: Cost = 1_000_000.0;
     const MIN_RATIO: f64 = -1.0;
 
     let hyph_cost = DEFAULT_HYPH_COST * p.costs.hyphenation().get();
     let runt_cost = DEFAULT_RUNT_COST * p.costs.runt().get();
+    let kashida_cost = DEFAULT_KASHIDA_COST * p.costs.kashida().get();
 
     // Dynamic programming table.
     let mut active = 0;
@@ -949,7 +1099,7 @@ fn linebreak_optimized<'a>(
 
             // Determine how much the line's spaces would need to be stretched
             // to make it the desired width.
-            let delta = width - attempt.width;
+            let delta = width - attempt.width + attempt.protrusion;
             // Determine how much stretch are permitted.
             let adjust = if delta >= Abs::zero() {
                 attempt.stretchability()
@@ -1016,6 +1166,15 @@ fn linebreak_optimized<'a>(
                 cost += hyph_cost;
             }
 
+            // Penalize elongation with kashidas in proportion to how much of
+            // the line's stretch they take up.
+            if p.justify && ratio > 0.0 && delta >= Abs::zero() {
+                let kashida = attempt.kashida_stretchability();
+                if kashida > Abs::zero() {
+                    cost += kashida_cost * ratio.min(1.0) * (kashida / adjust);
+                }
+            }
+
             // In Knuth paper, cost = (1 + 100|r|^3 + p)^2 + a,
             // where r is the ratio, p=50 is the penalty, and a=3000 is consecutive the penalty.
             // We divide the whole formula by 10, resulting (0.01 + |r|^3 + p)^2 + a,
@@ -1075,6 +1234,7 @@ fn line<'a>(
             width: Abs::zero(),
             justify,
             dash: None,
+            protrusion: Abs::zero(),
         };
     }
 
@@ -1114,6 +1274,8 @@ fn line<'a>(
 
         // Deal with CJK punctuation at line ends.
         let gb_style = cjk_punct_style(shaped.lang, shaped.region);
+        let trim_end = cjk_punct_trim(shaped.styles, shaped.lang, shaped.region)
+            .is_some_and(CjkPunctuationTrim::trims_end);
         let maybe_adjust_last_glyph = trimmed.ends_with(END_PUNCT_PAT)
             || (p.cjk_latin_spacing && trimmed.ends_with(is_of_cj_script));
 
@@ -1138,7 +1300,7 @@ fn line<'a>(
                 }
 
                 if let Some(last_glyph) = reshaped.glyphs.last() {
-                    if last_glyph.is_cjk_left_aligned_punctuation(gb_style) {
+                    if trim_end && last_glyph.is_cjk_left_aligned_punctuation(gb_style) {
                         // If the last glyph is a CJK punctuation, we want to shrink it.
                         // See Requirements for Chinese Text Layout, Section 3.1.6.3
                         // Compression of punctuation marks at line start or line end
@@ -1209,8 +1371,11 @@ fn line<'a>(
     if maybe_adjust_first_glyph {
         let reshaped = first.as_mut().or(last.as_mut()).and_then(Item::text_mut);
         if let Some(reshaped) = reshaped {
+            let trim_start =
+                cjk_punct_trim(reshaped.styles, reshaped.lang, reshaped.region)
+                    .is_some_and(CjkPunctuationTrim::trims_start);
             if let Some(first_glyph) = reshaped.glyphs.first() {
-                if first_glyph.is_cjk_right_aligned_punctuation() {
+                if trim_start && first_glyph.is_cjk_right_aligned_punctuation() {
                     // If the first glyph is a CJK punctuation, we want to shrink it.
                     let shrink_amount = first_glyph.shrinkability().0;
                     let glyph = reshaped.glyphs.to_mut().first_mut().unwrap();
@@ -1242,7 +1407,7 @@ fn line<'a>(
         width += item.width();
     }
 
-    Line {
+    let mut line = Line {
         bidi: &p.bidi,
         trimmed: range,
         end,
@@ -1252,7 +1417,16 @@ fn line<'a>(
         width,
         justify,
         dash,
+        protrusion: Abs::zero(),
+    };
+
+    // Account for glyphs hanging into the margins.
+    if p.justify {
+        let (left, right) = protrusion(&reorder(&line).0);
+        line.protrusion = left + right;
     }
+
+    line
 }
 
 /// Combine layouted lines into one frame per region.
@@ -1330,32 +1504,10 @@ fn commit(
         offset += p.hang;
     }
 
-    // Handle hanging punctuation to the left.
-    if let Some(Item::Text(text)) = reordered.first() {
-        if let Some(glyph) = text.glyphs.first() {
-            if !text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
-                offset -= amount;
-                remaining += amount;
-            }
-        }
-    }
-
-    // Handle hanging punctuation to the right.
-    if let Some(Item::Text(text)) = reordered.last() {
-        if let Some(glyph) = text.glyphs.last() {
-            if text.dir.is_positive()
-                && TextElem::overhang_in(text.styles)
-                && (reordered.len() > 1 || text.glyphs.len() > 1)
-            {
-                let amount = overhang(glyph.c) * glyph.x_advance.at(text.size);
-                remaining += amount;
-            }
-        }
-    }
+    // Handle glyphs hanging into the margins.
+    let (left, right) = protrusion(&reordered);
+    offset -= left;
+    remaining += left + right;
 
     // Determine how much additional space is needed.
     // The justification_ratio is for the first step justification,
@@ -1390,6 +1542,9 @@ fn commit(
     let mut top = Abs::zero();
     let mut bottom = Abs::zero();
 
+    // Where the bases of ruby annotations end up.
+    let mut extents: Vec<Option<(Abs, Abs)>> = vec![None; p.rubies.len()];
+
     // Build the frames and determine the height and baseline.
     let mut frames = vec![];
     for item in reordered {
@@ -1419,6 +1574,19 @@ fn commit(
                 }
             }
             Item::Text(shaped) => {
+                for (ruby, extent) in p.rubies.iter().zip(&mut extents) {
+                    let Some((start, end)) = shaped.extent(
+                        ruby.range.clone(),
+                        justification_ratio,
+                        extra_justification,
+                    ) else {
+                        continue;
+                    };
+                    let (start, end) = (offset + start, offset + end);
+                    let merged = extent.get_or_insert((start, end));
+                    *merged = (merged.0.min(start), merged.1.max(end));
+                }
+
                 let mut frame =
                     shaped.build(engine, justification_ratio, extra_justification);
                 frame.post_process(shaped.styles);
@@ -1441,6 +1609,19 @@ fn commit(
         remaining = Abs::zero();
     }
 
+    // Ruby annotations are set into the leading above the line. If they
+    // don't fit, the line grows.
+    let rubies: Vec<(&Ruby, (Abs, Abs))> = p
+        .rubies
+        .iter()
+        .zip(extents)
+        .filter_map(|(ruby, extent)| Some((ruby, extent?)))
+        .collect();
+    let ascent = top;
+    if let Some(height) = rubies.iter().map(|(ruby, _)| ruby.frame.height()).max() {
+        top += (height - p.leading).max(Abs::zero());
+    }
+
     let size = Size::new(width, top + bottom);
     let mut output = Frame::soft(size);
     output.set_baseline(top);
@@ -1452,6 +1633,14 @@ fn commit(
         output.push_frame(Point::new(x, y), frame);
     }
 
+    // Center the annotations over their bases.
+    for (ruby, (start, end)) in rubies {
+        let center = (start + ruby.padding.0 + end - ruby.padding.1) / 2.0;
+        let x = center - ruby.frame.width() / 2.0 + p.align.position(remaining);
+        let y = top - ascent - ruby.frame.height();
+        output.push_frame(Point::new(x, y), ruby.frame.clone());
+    }
+
     Ok(output)
 }
 
@@ -1495,7 +1684,48 @@ fn reorder<'a>(line: &'a Line<'a>) -> (Vec<&Item<'a>>, bool) {
     (reordered, starts_rtl)
 }
 
-/// How much a character should hang into the end margin.
+/// How far the glyphs at the visual edges of a line hang into the left and
+/// right margins.
+fn protrusion(reordered: &[&Item]) -> (Abs, Abs) {
+    let amount = |text: &ShapedText, glyph: &ShapedGlyph, start: bool| {
+        if !TextElem::overhang_in(text.styles)
+            || (reordered.len() == 1 && text.glyphs.len() == 1)
+        {
+            return Abs::zero();
+        }
+
+        let ratio = match TextElem::protrusion_in(text.styles).get(glyph.c) {
+            Some((amount, _)) if start => amount.get(),
+            Some((_, amount)) => amount.get(),
+            None if start => 0.0,
+            None => overhang(glyph.c),
+        };
+
+        ratio * glyph.x_advance.at(text.size)
+    };
+
+    // A glyph at the left edge is at the start of a left-to-right text, but at
+    // the end of a right-to-left one, and vice versa.
+    let left = match reordered.first() {
+        Some(Item::Text(text)) => text
+            .glyphs
+            .first()
+            .map(|glyph| amount(text, glyph, text.dir.is_positive())),
+        _ => None,
+    };
+
+    let right = match reordered.last() {
+        Some(Item::Text(text)) => text
+            .glyphs
+            .last()
+            .map(|glyph| amount(text, glyph, !text.dir.is_positive())),
+        _ => None,
+    };
+
+    (left.unwrap_or_default(), right.unwrap_or_default())
+}
+
+/// How much a character should hang into the end margin by default.
 ///
 /// For more discussion, see:
 /// <https://recoveringphysicist.com/21/>
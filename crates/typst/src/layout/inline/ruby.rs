@@ -0,0 +1,191 @@
+use unicode_script::{Script, UnicodeScript};
+
+use super::shaping::{BEGIN_PUNCT_PAT, END_PUNCT_PAT};
+use super::{Item, Range};
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{Content, Packed, Resolve, StyleChain};
+use crate::layout::{Abs, Axes, Em, Frame, LayoutMultiple, Regions, Size};
+use crate::text::{RubyElem, RubyPlacement, TextElem};
+use crate::utils::Numeric;
+
+/// A ruby element whose base was collected into the paragraph.
+pub(super) struct RubyGroup<'a> {
+    /// The ruby element.
+    pub elem: &'a Packed<RubyElem>,
+    /// The element's styles.
+    pub styles: StyleChain<'a>,
+    /// The text ranges of the base parts along with their annotations.
+    pub parts: Vec<(Range, &'a Content)>,
+}
+
+/// A laid out ruby annotation over a range of the paragraph's text.
+///
+/// Lines never break within the base of a ruby annotation.
+pub(super) struct Ruby {
+    /// The text range of the base.
+    pub range: Range,
+    /// The laid out annotation.
+    pub frame: Frame,
+    /// Space that was added before and after the base because the annotation
+    /// is wider than the base and may not overhang the adjacent text.
+    pub padding: (Abs, Abs),
+}
+
+/// Lay out the annotations of the ruby groups and add the space they need
+/// around their bases to the shaped items.
+pub(super) fn layout_rubies(
+    engine: &mut Engine,
+    text: &str,
+    items: &mut [Item],
+    groups: Vec<RubyGroup>,
+    region: Size,
+) -> SourceResult<Vec<Ruby>> {
+    let mut rubies = vec![];
+    for RubyGroup { elem, styles, parts } in groups {
+        let size = elem.size(styles);
+        let mut layout = |content: Content, tracking: Abs| -> SourceResult<Frame> {
+            let mut content = content.styled(TextElem::set_size(size));
+            if !tracking.is_zero() {
+                content = content.styled(TextElem::set_tracking(tracking.into()));
+            }
+            let pod = Regions::one(region, Axes::splat(false));
+            let mut frame = content.layout(engine, styles, pod)?.into_frame();
+            frame.post_process(styles);
+            Ok(frame)
+        };
+
+        let mut frames = parts
+            .iter()
+            .map(|&(_, annotation)| layout(annotation.clone(), Abs::zero()))
+            .collect::<SourceResult<Vec<_>>>()?;
+
+        let placement = elem.placement(styles);
+        let join = parts.len() > 1
+            && match placement {
+                RubyPlacement::Mono => false,
+                RubyPlacement::Group => true,
+                RubyPlacement::Jukugo => frames
+                    .iter()
+                    .zip(&parts)
+                    .any(|(frame, (range, _))| frame.width() > base_width(items, range)),
+            };
+
+        let mut entries: Vec<(Range, Frame)> = if join {
+            let range = parts[0].0.start..parts[parts.len() - 1].0.end;
+            let annotation = Content::sequence(
+                parts.iter().map(|&(_, annotation)| annotation.clone()),
+            );
+            let frame = layout(annotation, Abs::zero())?;
+            vec![(range, frame)]
+        } else {
+            parts
+                .iter()
+                .map(|(range, _)| range.clone())
+                .zip(frames.drain(..))
+                .collect()
+        };
+
+        // Spread grouped annotations over the whole base, distributing the
+        // free space between and around the characters in a 1:2:1 ratio.
+        if placement == RubyPlacement::Group {
+            for (range, frame) in &mut entries {
+                let base = base_width(items, range);
+                let annotation = Content::sequence(
+                    parts
+                        .iter()
+                        .filter(|(part, _)| {
+                            range.start <= part.start && part.end <= range.end
+                        })
+                        .map(|&(_, annotation)| annotation.clone()),
+                );
+                let count = annotation.plain_text().chars().count();
+                if frame.width() < base && count > 0 {
+                    let tracking = (base - frame.width()) / count as f64;
+                    *frame = layout(annotation, tracking)?;
+                }
+            }
+        }
+
+        let limit = size.0.resolve(styles);
+        let count = entries.len();
+        for (i, (range, frame)) in entries.into_iter().enumerate() {
+            let excess = frame.width() - base_width(items, &range);
+            let mut padding = (Abs::zero(), Abs::zero());
+            if excess > Abs::zero() {
+                let half = excess / 2.0;
+                let overhang = |c: Option<char>| {
+                    if elem.overhang(styles) && c.is_some_and(allows_overhang) {
+                        half.min(limit)
+                    } else {
+                        Abs::zero()
+                    }
+                };
+
+                // Only the outermost parts border on text that isn't ruby.
+                let before = (i == 0).then(|| text[..range.start].chars().next_back());
+                let after = (i + 1 == count).then(|| text[range.end..].chars().next());
+                padding =
+                    (half - overhang(before.flatten()), half - overhang(after.flatten()));
+                pad(items, &range, padding);
+            }
+
+            rubies.push(Ruby { range, frame, padding });
+        }
+    }
+
+    Ok(rubies)
+}
+
+/// Whether a ruby annotation may overhang a character.
+fn allows_overhang(c: char) -> bool {
+    matches!(c.script(), Script::Hiragana | Script::Katakana)
+        || BEGIN_PUNCT_PAT.contains(&c)
+        || END_PUNCT_PAT.contains(&c)
+}
+
+/// The natural width of the glyphs in a text range.
+fn base_width(items: &[Item], range: &Range) -> Abs {
+    items
+        .iter()
+        .filter_map(Item::text)
+        .flat_map(|shaped| {
+            shaped
+                .glyphs
+                .iter()
+                .filter(|glyph| range.contains(&glyph.range.start))
+                .map(|glyph| glyph.x_advance.at(shaped.size))
+        })
+        .sum()
+}
+
+/// Add space before the first and after the last glyph of a text range.
+fn pad(items: &mut [Item], range: &Range, (before, after): (Abs, Abs)) {
+    for shaped in items.iter_mut().filter_map(Item::text_mut) {
+        let size = shaped.size;
+        let within = |start: usize| range.contains(&start);
+        if !shaped.glyphs.iter().any(|glyph| within(glyph.range.start)) {
+            continue;
+        }
+
+        let glyphs = shaped.glyphs.to_mut();
+        if let Some(first) =
+            glyphs.iter_mut().find(|glyph| glyph.range.start == range.start)
+        {
+            let amount = Em::from_length(before, size);
+            first.x_offset += amount;
+            first.x_advance += amount;
+            shaped.width += before;
+        }
+
+        if let Some(last) = glyphs
+            .iter_mut()
+            .filter(|glyph| within(glyph.range.start))
+            .max_by_key(|glyph| glyph.range.start)
+            .filter(|glyph| glyph.range.end >= range.end)
+        {
+            last.x_advance += Em::from_length(after, size);
+            shaped.width += after;
+        }
+    }
+}
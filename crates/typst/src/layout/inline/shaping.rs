@@ -12,11 +12,14 @@ use unicode_script::{Script, UnicodeScript};
 use super::SpanMapper;
 use crate::engine::Engine;
 use crate::foundations::StyleChain;
-use crate::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Size};
+use crate::layout::{
+    Abs, Angle, Dir, Em, Frame, FrameItem, Point, Ratio, Size, Transform,
+};
+use crate::model::ParElem;
 use crate::syntax::Span;
 use crate::text::{
-    decorate, families, features, variant, Font, FontVariant, Glyph, Lang, Region,
-    TextElem, TextItem,
+    decorate, families, features, variant, variations, CjkPunctuationTrim, Font,
+    FontFallbacks, FontVariant, Glyph, Lang, Region, TextElem, TextItem,
 };
 use crate::utils::SliceExt;
 use crate::World;
@@ -43,12 +46,59 @@ pub(super) struct ShapedText<'a> {
     pub variant: FontVariant,
     /// The font size.
     pub size: Abs,
+    /// How the glyphs are oriented in vertical text.
+    pub orientation: Orientation,
     /// The width of the text's bounding box.
     pub width: Abs,
     /// The shaped glyphs.
     pub glyphs: Cow<'a, [ShapedGlyph]>,
 }
 
+/// How text is oriented in vertical writing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum Orientation {
+    /// The glyphs are set along the line just like in horizontal text. In
+    /// vertical text, this turns them sideways.
+    Natural,
+    /// Each glyph is set upright, using its vertical alternate and metrics.
+    Upright,
+    /// The glyphs are set upright as a horizontal unit that takes up one em
+    /// (tate-chū-yoko).
+    Combined,
+}
+
+impl Orientation {
+    /// Determine how a run of text is oriented.
+    ///
+    /// The text must have been split into runs of equal
+    /// [per-character orientation](Self::of_char) beforehand.
+    fn of(text: &str, styles: StyleChain) -> Self {
+        if TextElem::dir_in(styles) != Dir::TTB {
+            return Self::Natural;
+        }
+
+        match text.chars().next().map(Self::of_char) {
+            Some(Self::Upright) => Self::Upright,
+            Some(Self::Combined) if text.len() <= TextElem::upright_digits_in(styles) => {
+                Self::Combined
+            }
+            _ => Self::Natural,
+        }
+    }
+
+    /// Determine how a character is oriented in vertical text, assuming that
+    /// it's part of a run that is short enough to be combined if possible.
+    pub fn of_char(c: char) -> Self {
+        if is_upright(c) {
+            Self::Upright
+        } else if c.is_ascii_digit() {
+            Self::Combined
+        } else {
+            Self::Natural
+        }
+    }
+}
+
 /// A single glyph resulting from shaping.
 #[derive(Debug, Clone)]
 pub(super) struct ShapedGlyph {
@@ -174,6 +224,13 @@ impl ShapedGlyph {
         }
     }
 
+    /// Whether the glyph may be elongated with kashidas on its left side.
+    pub fn is_kashida(&self) -> bool {
+        self.script == Script::Arabic
+            && !self.is_space()
+            && self.adjustability.stretchability.0 > Em::zero()
+    }
+
     /// The stretchability of the character.
     pub fn stretchability(&self) -> (Em, Em) {
         self.adjustability.stretchability
@@ -240,67 +297,77 @@ impl<'a> ShapedText<'a> {
             }
 
             let pos = Point::new(offset, top + shift - y_offset.at(self.size));
-            let glyphs: Vec<Glyph> = group
-                .iter()
-                .map(|shaped: &ShapedGlyph| {
-                    let adjustability_left = if justification_ratio < 0.0 {
-                        shaped.shrinkability().0
-                    } else {
-                        shaped.stretchability().0
-                    };
-                    let adjustability_right = if justification_ratio < 0.0 {
-                        shaped.shrinkability().1
-                    } else {
-                        shaped.stretchability().1
-                    };
-
-                    let justification_left = adjustability_left * justification_ratio;
-                    let mut justification_right =
-                        adjustability_right * justification_ratio;
-                    if shaped.is_justifiable() {
-                        justification_right +=
-                            Em::from_length(extra_justification, self.size)
+            let mut glyphs = Vec::with_capacity(group.len());
+            for shaped in group {
+                let (mut justification_left, justification_right) =
+                    self.justification(shaped, justification_ratio, extra_justification);
+
+                frame.size_mut().x +=
+                    justification_left.at(self.size) + justification_right.at(self.size);
+
+                // We may not be able to reach the offset completely if
+                // it exceeds u16, but better to have a roughly correct
+                // span offset than nothing.
+                let mut span = shaped.span;
+                span.1 = span.1.saturating_add(span_offset.saturating_as());
+
+                let start = (shaped.range.start - range.start).saturating_as();
+                let end = (shaped.range.end - range.start).saturating_as();
+
+                // Fill the gap of an elongated Arabic letter with tatweels
+                // instead of leaving it blank. The tatweels slightly overlap
+                // each other to avoid seams and belong to the letter they
+                // extend.
+                if shaped.is_kashida() && justification_left > Em::zero() {
+                    let ttf = font.ttf();
+                    if let Some(id) = ttf.glyph_index(TATWEEL) {
+                        let width = font.advance(id.0).unwrap_or(Em::zero());
+                        let count = if width > Em::zero() {
+                            (justification_left / width).ceil().max(1.0) as usize
+                        } else {
+                            1
+                        };
+                        for _ in 0..count {
+                            glyphs.push(Glyph {
+                                id: id.0,
+                                x_advance: justification_left / count as f64,
+                                x_offset: Em::zero(),
+                                range: start..end,
+                                span,
+                            });
+                        }
+                        justification_left = Em::zero();
                     }
+                }
 
-                    frame.size_mut().x += justification_left.at(self.size)
-                        + justification_right.at(self.size);
-
-                    // We may not be able to reach the offset completely if
-                    // it exceeds u16, but better to have a roughly correct
-                    // span offset than nothing.
-                    let mut span = shaped.span;
-                    span.1 = span.1.saturating_add(span_offset.saturating_as());
-
-                    // |<---- a Glyph ---->|
-                    //  -->|ShapedGlyph|<--
-                    // +---+-----------+---+
-                    // |   |  *********|   |
-                    // |   |  *        |   |
-                    // |   |  *    ****|   |
-                    // |   |  *       *|   |
-                    // |   |  *********|   |
-                    // +---+--+--------+---+
-                    //   A   B     C     D
-                    // Note A, B, D could be positive, zero, or negative.
-                    // A: justification_left
-                    // B: ShapedGlyph's x_offset
-                    //    (though a small part of the glyph may go inside B)
-                    // B+C: ShapedGlyph's x_advance
-                    // D: justification_right
-                    // A+B: Glyph's x_offset
-                    // A+B+C+D: Glyph's x_advance
-                    Glyph {
-                        id: shaped.glyph_id,
-                        x_advance: shaped.x_advance
-                            + justification_left
-                            + justification_right,
-                        x_offset: shaped.x_offset + justification_left,
-                        range: (shaped.range.start - range.start).saturating_as()
-                            ..(shaped.range.end - range.start).saturating_as(),
-                        span,
-                    }
-                })
-                .collect();
+                // |<---- a Glyph ---->|
+                //  -->|ShapedGlyph|<--
+                // +---+-----------+---+
+                // |   |  *********|   |
+                // |   |  *        |   |
+                // |   |  *    ****|   |
+                // |   |  *       *|   |
+                // |   |  *********|   |
+                // +---+--+--------+---+
+                //   A   B     C     D
+                // Note A, B, D could be positive, zero, or negative.
+                // A: justification_left
+                // B: ShapedGlyph's x_offset
+                //    (though a small part of the glyph may go inside B)
+                // B+C: ShapedGlyph's x_advance
+                // D: justification_right
+                // A+B: Glyph's x_offset
+                // A+B+C+D: Glyph's x_advance
+                glyphs.push(Glyph {
+                    id: shaped.glyph_id,
+                    x_advance: shaped.x_advance
+                        + justification_left
+                        + justification_right,
+                    x_offset: shaped.x_offset + justification_left,
+                    range: start..end,
+                    span,
+                });
+            }
 
             let item = TextItem {
                 font,
@@ -314,7 +381,9 @@ impl<'a> ShapedText<'a> {
             };
 
             let width = item.width();
-            if decos.is_empty() {
+            if self.orientation != Orientation::Natural {
+                self.push_upright(&mut frame, pos, item);
+            } else if decos.is_empty() {
                 frame.push(pos, FrameItem::Text(item));
             } else {
                 // Apply line decorations.
@@ -330,6 +399,107 @@ impl<'a> ShapedText<'a> {
         frame
     }
 
+    /// The extra advance added to the left and right of a glyph by
+    /// justification.
+    fn justification(
+        &self,
+        glyph: &ShapedGlyph,
+        justification_ratio: f64,
+        extra_justification: Abs,
+    ) -> (Em, Em) {
+        let (adjustability_left, adjustability_right) = if justification_ratio < 0.0 {
+            glyph.shrinkability()
+        } else {
+            glyph.stretchability()
+        };
+
+        let left = adjustability_left * justification_ratio;
+        let mut right = adjustability_right * justification_ratio;
+        if glyph.is_justifiable() {
+            right += Em::from_length(extra_justification, self.size)
+        }
+
+        (left, right)
+    }
+
+    /// The horizontal extent of the glyphs in a text range within the frame
+    /// built with the given justification.
+    pub fn extent(
+        &self,
+        text_range: Range<usize>,
+        justification_ratio: f64,
+        extra_justification: Abs,
+    ) -> Option<(Abs, Abs)> {
+        let mut x = Abs::zero();
+        let mut extent: Option<(Abs, Abs)> = None;
+        for glyph in self.glyphs.iter() {
+            let (left, right) =
+                self.justification(glyph, justification_ratio, extra_justification);
+            let advance = (glyph.x_advance + left + right).at(self.size);
+            if text_range.contains(&glyph.range.start) {
+                let (start, end) = extent.get_or_insert((x, x));
+                *start = (*start).min(x);
+                *end = (*end).max(x + advance);
+            }
+            x += advance;
+        }
+        extent
+    }
+
+    /// Add glyphs that are set upright in vertical text to the frame.
+    ///
+    /// The glyphs are rotated counter-clockwise and centered on the central
+    /// axis of the line so that they end up upright once the page is turned
+    /// into vertical orientation.
+    fn push_upright(&self, frame: &mut Frame, pos: Point, item: TextItem) {
+        let metrics = item.font.metrics();
+        let axis = ((metrics.ascender + metrics.descender) / 2.0).at(self.size);
+        let center = |x: Abs, width: Abs, ts: Transform| {
+            Transform::translate(x + axis, pos.y - axis + width / 2.0)
+                .pre_concat(Transform::rotate(Angle::deg(-90.0)))
+                .pre_concat(ts)
+        };
+
+        let mut push = |ts: Transform, item: TextItem| {
+            let mut sub = Frame::soft(Size::zero());
+            sub.push(Point::zero(), FrameItem::Text(item));
+            sub.transform(ts);
+            frame.push_frame(Point::zero(), sub);
+        };
+
+        let horizontal = |glyph: &Glyph| Glyph {
+            x_advance: item.font.advance(glyph.id).unwrap_or_default(),
+            x_offset: Em::zero(),
+            ..glyph.clone()
+        };
+
+        if self.orientation == Orientation::Combined {
+            // Compress the run if it doesn't fit into the em square.
+            let cell = item.width();
+            let glyphs: Vec<Glyph> = item.glyphs.iter().map(horizontal).collect();
+            let width = glyphs.iter().map(|g| g.x_advance).sum::<Em>().at(self.size);
+            let scale = if width > self.size { self.size / width } else { 1.0 };
+            let ts = center(
+                pos.x + cell / 2.0,
+                width * scale,
+                Transform::scale(Ratio::new(scale), Ratio::one()),
+            );
+            push(ts, TextItem { glyphs, ..item });
+            return;
+        }
+
+        let mut x = pos.x;
+        for glyph in &item.glyphs {
+            let advance = vertical_advance(&item.font, glyph.id).at(self.size);
+            let single = horizontal(glyph);
+            let width = single.x_advance.at(self.size);
+            let offset = glyph.x_offset.at(self.size);
+            let ts = center(x + offset + advance / 2.0, width, Transform::identity());
+            push(ts, TextItem { glyphs: vec![single], ..item.clone() });
+            x += glyph.x_advance.at(self.size);
+        }
+    }
+
     /// Measure the top and bottom extent of this text.
     fn measure(&self, engine: &Engine) -> (Abs, Abs) {
         let mut top = Abs::zero();
@@ -348,12 +518,14 @@ impl<'a> ShapedText<'a> {
             // When there are no glyphs, we just use the vertical metrics of the
             // first available font.
             let world = engine.world;
+            let variations = variations(self.styles);
             for family in families(self.styles) {
                 if let Some(font) = world
                     .book()
-                    .select(family, self.variant)
+                    .select_sized(family, self.variant, self.size)
                     .and_then(|id| world.font(id))
                 {
+                    let font = font.instance(self.variant, self.size, &variations);
                     expand(&font, None);
                     break;
                 }
@@ -396,6 +568,16 @@ impl<'a> ShapedText<'a> {
             .at(self.size)
     }
 
+    /// The part of the stretchability that stems from kashidas.
+    pub fn kashida_stretchability(&self) -> Abs {
+        self.glyphs
+            .iter()
+            .filter(|g| g.is_kashida())
+            .map(|g| g.stretchability().0)
+            .sum::<Em>()
+            .at(self.size)
+    }
+
     /// The shrinkability of the text
     pub fn shrinkability(&self) -> Abs {
         self.glyphs
@@ -428,6 +610,7 @@ impl<'a> ShapedText<'a> {
                 styles: self.styles,
                 size: self.size,
                 variant: self.variant,
+                orientation: self.orientation,
                 width: glyphs.iter().map(|g| g.x_advance).sum::<Em>().at(self.size),
                 glyphs: Cow::Borrowed(glyphs),
             }
@@ -457,6 +640,7 @@ impl<'a> ShapedText<'a> {
 
     fn insert_hyphen(&mut self, engine: &Engine, fallback: bool, side: Side) {
         let world = engine.world;
+        let variations = variations(self.styles);
         let book = world.book();
         let fallback_func = if fallback {
             Some(|| book.select_fallback(None, self.variant, "-"))
@@ -464,12 +648,12 @@ impl<'a> ShapedText<'a> {
             None
         };
         let mut chain = families(self.styles)
-            .map(|family| book.select(family, self.variant))
+            .map(|family| book.select_sized(family, self.variant, self.size))
             .chain(fallback_func.iter().map(|f| f()))
             .flatten();
 
         chain.find_map(|id| {
-            let font = world.font(id)?;
+            let font = world.font(id)?.instance(self.variant, self.size, &variations);
             let ttf = font.ttf();
             let glyph_id = ttf.glyph_index('-')?;
             let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?);
@@ -598,7 +782,10 @@ struct ShapingContext<'a, 'v> {
     size: Abs,
     variant: FontVariant,
     features: Vec<rustybuzz::Feature>,
+    variations: Vec<(Tag, f32)>,
+    fallbacks: FontFallbacks,
     fallback: bool,
+    lang: Lang,
     dir: Dir,
 }
 
@@ -624,16 +811,32 @@ pub(super) fn shape<'a>(
         styles,
         variant: variant(styles),
         features: features(styles),
+        variations: variations(styles),
+        fallbacks: TextElem::fallbacks_in(styles),
         fallback: TextElem::fallback_in(styles),
+        lang,
         dir,
     };
 
+    // Use the vertical alternates of upright glyphs. These features come
+    // first so that they can be turned off through the `features` property.
+    let orientation = Orientation::of(text, styles);
+    if orientation == Orientation::Upright {
+        ctx.features.splice(
+            0..0,
+            [b"vert", b"vrt2"]
+                .map(|tag| rustybuzz::Feature::new(Tag::from_bytes(tag), 1, ..)),
+        );
+    }
+
     if !text.is_empty() {
         shape_segment(&mut ctx, base, text, families(styles));
     }
 
+    apply_vertical_metrics(&mut ctx, orientation);
     track_and_space(&mut ctx);
     calculate_adjustability(&mut ctx, lang, region);
+    add_kashidas(&mut ctx, base, text);
 
     #[cfg(debug_assertions)]
     assert_all_glyphs_in_range(&ctx.glyphs, text, base..(base + text.len()));
@@ -649,6 +852,7 @@ pub(super) fn shape<'a>(
         styles,
         variant: ctx.variant,
         size,
+        orientation,
         width: ctx.glyphs.iter().map(|g| g.x_advance).sum::<Em>().at(size),
         glyphs: Cow::Owned(ctx.glyphs),
     }
@@ -669,11 +873,18 @@ fn shape_segment<'a>(
     // Find the next available family.
     let world = ctx.engine.world;
     let book = world.book();
-    let mut selection = families.find_map(|family| {
-        book.select(family, ctx.variant)
+    let select = |family: &str| {
+        book.select_sized(family, ctx.variant, ctx.size)
             .and_then(|id| world.font(id))
+            .map(|font| font.instance(ctx.variant, ctx.size, &ctx.variations))
             .filter(|font| !ctx.used.contains(font))
-    });
+    };
+    let mut selection = families.find_map(select);
+
+    // Try the fallback families configured for the language and script.
+    if selection.is_none() {
+        selection = ctx.fallbacks.families(ctx.lang, text).find_map(select);
+    }
 
     // Do font fallback if the families are exhausted and fallback is enabled.
     if selection.is_none() && ctx.fallback {
@@ -681,6 +892,7 @@ fn shape_segment<'a>(
         selection = book
             .select_fallback(first, ctx.variant, text)
             .and_then(|id| world.font(id))
+            .map(|font| font.instance(ctx.variant, ctx.size, &ctx.variations))
             .filter(|font| !ctx.used.contains(font));
     }
 
@@ -865,6 +1077,55 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
     }
 }
 
+/// Replace the horizontal advances of glyphs that are set upright in vertical
+/// text with their advances along the vertical line.
+fn apply_vertical_metrics(ctx: &mut ShapingContext, orientation: Orientation) {
+    match orientation {
+        Orientation::Natural => {}
+        Orientation::Upright => {
+            for glyph in &mut ctx.glyphs {
+                glyph.x_advance = vertical_advance(&glyph.font, glyph.glyph_id);
+                glyph.x_offset = Em::zero();
+                glyph.y_offset = Em::zero();
+            }
+        }
+        Orientation::Combined => {
+            // The whole run takes up one em.
+            let count = ctx.glyphs.len() as f64;
+            for glyph in &mut ctx.glyphs {
+                glyph.x_advance = Em::one() / count;
+            }
+        }
+    }
+}
+
+/// The distance of the central axis of vertical text from the baseline, based
+/// on the first available font.
+pub(super) fn vertical_axis(engine: &Engine, styles: StyleChain) -> Abs {
+    let world = engine.world;
+    let size = TextElem::size_in(styles);
+    families(styles)
+        .find_map(|family| {
+            world
+                .book()
+                .select_sized(family, variant(styles), size)
+                .and_then(|id| world.font(id))
+        })
+        .map(|font| {
+            let metrics = font.metrics();
+            ((metrics.ascender + metrics.descender) / 2.0).at(size)
+        })
+        .unwrap_or_default()
+}
+
+/// The advance of a glyph in vertical text, falling back to one em if the
+/// font has no vertical metrics.
+fn vertical_advance(font: &Font, glyph_id: u16) -> Em {
+    font.ttf()
+        .glyph_ver_advance(ttf_parser::GlyphId(glyph_id))
+        .map_or(Em::one(), |advance| font.to_em(advance))
+}
+
 /// Apply tracking and spacing to the shaped glyphs.
 fn track_and_space(ctx: &mut ShapingContext) {
     let tracking = Em::from_length(TextElem::tracking_in(ctx.styles), ctx.size);
@@ -900,13 +1161,13 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
         glyph.adjustability = glyph.base_adjustability(style);
     }
 
+    // Without trimming, punctuation keeps its full width.
+    if cjk_punct_trim(ctx.styles, lang, region).is_none() {
+        return;
+    }
+
     let mut glyphs = ctx.glyphs.iter_mut().peekable();
     while let Some(glyph) = glyphs.next() {
-        // CNS style needs not further adjustment.
-        if glyph.is_cjk_punctuation() && matches!(style, CjkPunctStyle::Cns) {
-            continue;
-        }
-
         // Now we apply consecutive punctuation adjustment, specified in Chinese Layout
         // Requirements, section 3.1.6.1 Punctuation Adjustment Space, and Japanese Layout
         // Requirements, section 3.1 Line Composition Rules for Punctuation Marks
@@ -924,6 +1185,161 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
     }
 }
 
+/// Allow Arabic words to be elongated with kashidas when justifying.
+///
+/// Each word gets at most one kashida point, chosen by the priorities
+/// commonly used for Arabic justification: After a letter of the seen or sad
+/// families, before a final taa marbuta, haa or dal, before a final alef,
+/// before a final ra, waw, baa or yaa and, otherwise, between the last
+/// connected pair of letters. The kashida is inserted on the left side of the
+/// glyph preceding the point (which comes first in logical order).
+fn add_kashidas(ctx: &mut ShapingContext, base: usize, text: &str) {
+    let Some(limit) = ParElem::kashida_in(ctx.styles) else { return };
+    let limit = Em::from_length(limit, ctx.size);
+    if limit <= Em::zero() || !text.chars().any(|c| c.script() == Script::Arabic) {
+        return;
+    }
+
+    for word in text.split(char::is_whitespace) {
+        let start = word.as_ptr() as usize - text.as_ptr() as usize;
+        let letters: Vec<(usize, char)> = word
+            .char_indices()
+            .filter(|&(_, c)| joining(c) != Joining::Transparent)
+            .collect();
+
+        let mut best: Option<(u8, usize, usize)> = None;
+        for (k, pair) in letters.windows(2).enumerate() {
+            let [(i, c), (j, next)] = [pair[0], pair[1]];
+            if !joining(c).joins_left() || !joining(next).joins_right() {
+                continue;
+            }
+
+            // Lam and alef form a mandatory ligature.
+            if c == 'ل' && is_alef(next) {
+                continue;
+            }
+
+            let last = k + 2 == letters.len();
+            let priority = if matches!(c, 'س' | 'ش' | 'ص' | 'ض') {
+                1
+            } else if last && matches!(next, 'ة' | 'ه' | 'د' | 'ذ') {
+                2
+            } else if last && is_alef(next) {
+                3
+            } else if last && matches!(next, 'ر' | 'ز' | 'و' | 'ب' | 'ي' | 'ى') {
+                4
+            } else {
+                5
+            };
+
+            if best.map_or(true, |(p, _, _)| priority <= p) {
+                best = Some((priority, start + i, start + j));
+            }
+        }
+
+        let Some((_, i, j)) = best else { continue };
+        let (point, next) = (base + i, base + j);
+
+        // The kashida goes to the left of the leftmost glyph of the letter's
+        // cluster, unless the letter forms a ligature with the next one.
+        let Some(glyph) = ctx
+            .glyphs
+            .iter_mut()
+            .find(|g| g.range.contains(&point) && g.range.end <= next)
+        else {
+            continue;
+        };
+
+        if glyph.font.ttf().glyph_index(TATWEEL).is_some() {
+            glyph.adjustability.stretchability.0 = limit;
+        }
+    }
+}
+
+/// The tatweel character, which is used to fill kashidas.
+const TATWEEL: char = '\u{0640}';
+
+/// How an Arabic character joins with its neighbours.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Joining {
+    /// Joins on both sides.
+    Dual,
+    /// Only joins with the preceding character.
+    Right,
+    /// Doesn't affect joining, like combining marks.
+    Transparent,
+    /// Doesn't join.
+    None,
+}
+
+impl Joining {
+    /// Whether the character joins with the following character.
+    fn joins_left(self) -> bool {
+        self == Self::Dual
+    }
+
+    /// Whether the character joins with the preceding character.
+    fn joins_right(self) -> bool {
+        matches!(self, Self::Dual | Self::Right)
+    }
+}
+
+/// The joining type of an Arabic character, following `ArabicShaping.txt`.
+fn joining(c: char) -> Joining {
+    match c {
+        '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        | '\u{06D6}'..='\u{06DC}'
+        | '\u{06DF}'..='\u{06E4}'
+        | '\u{06E7}'
+        | '\u{06E8}'
+        | '\u{06EA}'..='\u{06ED}' => Joining::Transparent,
+        '\u{0622}'..='\u{0625}'
+        | '\u{0627}'
+        | '\u{0629}'
+        | '\u{062F}'..='\u{0632}'
+        | '\u{0648}'
+        | '\u{0671}'..='\u{0673}'
+        | '\u{0675}'..='\u{0677}'
+        | '\u{0688}'..='\u{0699}'
+        | '\u{06C0}'
+        | '\u{06C3}'..='\u{06CB}'
+        | '\u{06CD}'
+        | '\u{06CF}'
+        | '\u{06D2}'
+        | '\u{06D3}'
+        | '\u{06D5}'
+        | '\u{06EE}'
+        | '\u{06EF}' => Joining::Right,
+        '\u{0620}'
+        | '\u{0626}'
+        | '\u{0628}'
+        | '\u{062A}'..='\u{062E}'
+        | '\u{0633}'..='\u{0647}'
+        | '\u{0649}'
+        | '\u{064A}'
+        | '\u{066E}'
+        | '\u{066F}'
+        | '\u{0678}'..='\u{0687}'
+        | '\u{069A}'..='\u{06BF}'
+        | '\u{06C1}'
+        | '\u{06C2}'
+        | '\u{06CC}'
+        | '\u{06CE}'
+        | '\u{06D0}'
+        | '\u{06D1}'
+        | '\u{06FA}'..='\u{06FC}'
+        | '\u{06FF}' => Joining::Dual,
+        _ => Joining::None,
+    }
+}
+
+/// Whether a character is a form of alef.
+fn is_alef(c: char) -> bool {
+    matches!(c, 'ا' | 'أ' | 'إ' | 'آ' | 'ٱ')
+}
+
 /// Difference between non-breaking and normal space.
 fn nbsp_delta(font: &Font) -> Option<Em> {
     let space = font.ttf().glyph_index(' ')?.0;
@@ -1006,6 +1422,45 @@ pub(super) fn cjk_punct_style(lang: Lang, region: Option<Region>) -> CjkPunctSty
     }
 }
 
+/// How full-width punctuation is trimmed in text with the given styles,
+/// language and region.
+pub(super) fn cjk_punct_trim(
+    styles: StyleChain,
+    lang: Lang,
+    region: Option<Region>,
+) -> Option<CjkPunctuationTrim> {
+    TextElem::cjk_punctuation_trim_in(styles).unwrap_or_else(|| {
+        match cjk_punct_style(lang, region) {
+            CjkPunctStyle::Gb | CjkPunctStyle::Jis => Some(CjkPunctuationTrim::TrimBoth),
+            CjkPunctStyle::Cns => None,
+        }
+    })
+}
+
+/// Whether a character is set upright in vertical text.
+///
+/// This approximates the `U`, `Tu`, and `Tr` values of the Unicode
+/// `Vertical_Orientation` property (UAX #50).
+fn is_upright(c: char) -> bool {
+    matches!(c,
+        '\u{00A7}' | '\u{00A9}' | '\u{00AE}' | '\u{00B1}' | '\u{00BC}'..='\u{00BE}'
+        | '\u{00D7}' | '\u{00F7}'
+        | '\u{1100}'..='\u{11FF}'
+        | '\u{2460}'..='\u{24FF}'
+        | '\u{25A0}'..='\u{27BF}'
+        | '\u{2E80}'..='\u{A4CF}'
+        | '\u{A960}'..='\u{A97F}'
+        | '\u{AC00}'..='\u{D7FF}'
+        | '\u{E000}'..='\u{FAFF}'
+        | '\u{FE10}'..='\u{FE1F}'
+        | '\u{FE30}'..='\u{FE6F}'
+        | '\u{FF01}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE7}'
+        | '\u{1F000}'..='\u{1FAFF}'
+        | '\u{20000}'..='\u{3FFFD}'
+    )
+}
+
 /// Whether the glyph is a space.
 fn is_space(c: char) -> bool {
     matches!(c, ' ' | '\u{00A0}' | '　')
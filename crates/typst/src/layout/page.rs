@@ -14,13 +14,13 @@ use crate::foundations::{
 };
 use crate::introspection::{Counter, CounterDisplayElem, CounterKey, ManualPageCounter};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, ColumnsElem, Dir, Frame, HAlignment, LayoutMultiple,
-    Length, OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size, SpecificAlignment,
-    VAlignment,
+    Abs, AlignElem, Alignment, Angle, Axes, ColumnsElem, Dir, Frame, HAlignment,
+    LayoutMultiple, Length, OuterVAlignment, Point, Ratio, Regions, Rel, Sides, Size,
+    SpecificAlignment, Transform, VAlignment,
 };
 
 use crate::model::Numbering;
-use crate::text::TextElem;
+use crate::text::{TextDir, TextElem};
 use crate::utils::{NonZeroExt, Numeric, Scalar};
 use crate::visualize::Paint;
 
@@ -339,6 +339,32 @@ pub struct PageElem {
 }
 
 impl Packed<PageElem> {
+    /// The width of the area within the page's margins. This is infinite if
+    /// the page fits its content horizontally.
+    pub fn content_width(&self, styles: StyleChain) -> Abs {
+        let width = self.width(styles).unwrap_or(Abs::inf());
+        let height = self.height(styles).unwrap_or(Abs::inf());
+        let mut size = Size::new(width, height);
+        if self.flipped(styles) {
+            std::mem::swap(&mut size.x, &mut size.y);
+        }
+
+        let mut min = width.min(height);
+        if !min.is_finite() {
+            min = Paper::A4.width();
+        }
+
+        let default = Rel::<Length>::from((2.5 / 21.0) * min);
+        let margin = self
+            .margin(styles)
+            .sides
+            .map(|side| side.and_then(Smart::custom).unwrap_or(default))
+            .resolve(styles)
+            .relative_to(size);
+
+        size.x - margin.left - margin.right
+    }
+
     /// A document can consist of multiple `PageElem`s, one per run of pages
     /// with equal properties (not one per actual output page!). The `number` is
     /// the physical page number of the first page of this run. It is mutated
@@ -395,12 +421,22 @@ impl Packed<PageElem> {
                 .spanned(self.span());
         }
 
+        // In vertical text, lines run from top to bottom and stack from right
+        // to left. We lay out the child with swapped axes and then turn the
+        // resulting frames clockwise.
+        let vertical = TextElem::dir_in(styles) == Dir::TTB;
         let area = size - margin.sum_by_axis();
-        let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
+        let pod = if vertical { Size::new(area.y, area.x) } else { area };
+        let mut regions = Regions::repeat(pod, pod.map(Abs::is_finite));
         regions.root = true;
 
         // Layout the child.
-        let mut frames = child.layout(engine, styles, regions)?.into_frames();
+        let local = TextElem::set_vertical_page(vertical).wrap();
+        let mut frames =
+            child.layout(engine, styles.chain(&local), regions)?.into_frames();
+        if vertical {
+            frames.iter_mut().for_each(turn_clockwise);
+        }
 
         // Align the child to the pagebreak's parity.
         // Check for page count after adding the pending frames
@@ -501,11 +537,12 @@ impl Packed<PageElem> {
                 };
 
                 let pod = Regions::one(area, Axes::splat(true));
-                let sub = content
-                    .clone()
-                    .styled(AlignElem::set_alignment(align))
-                    .layout(engine, styles, pod)?
-                    .into_frame();
+                let mut content = content.clone().styled(AlignElem::set_alignment(align));
+                if vertical {
+                    // Marginals are set horizontally.
+                    content = content.styled(TextElem::set_dir(TextDir(Smart::Auto)));
+                }
+                let sub = content.layout(engine, styles, pod)?.into_frame();
 
                 if ptr::eq(marginal, header) || ptr::eq(marginal, background) {
                     frame.prepend_frame(pos, sub);
@@ -532,6 +569,27 @@ impl Packed<PageElem> {
     }
 }
 
+/// Turn a frame laid out with swapped axes for vertical text clockwise.
+fn turn_clockwise(frame: &mut Frame) {
+    let size = Size::new(frame.height(), frame.width());
+    frame.transform(
+        Transform::translate(size.x, Abs::zero())
+            .pre_concat(Transform::rotate(Angle::deg(90.0))),
+    );
+    frame.set_size(size);
+}
+
+/// Turn a horizontally laid out frame counter-clockwise so that it ends up
+/// upright on a page with vertical text.
+pub(super) fn turn_counter_clockwise(frame: &mut Frame) {
+    let size = Size::new(frame.height(), frame.width());
+    frame.transform(
+        Transform::translate(Abs::zero(), size.y)
+            .pre_concat(Transform::rotate(Angle::deg(-90.0))),
+    );
+    frame.set_size(size);
+}
+
 /// A finished page.
 #[derive(Debug, Clone)]
 pub struct Page {
@@ -41,6 +41,7 @@ pub mod diag;
 pub mod engine;
 pub mod eval;
 pub mod foundations;
+pub mod html;
 pub mod introspection;
 pub mod layout;
 pub mod loading;
@@ -67,8 +68,10 @@ use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{
-    Array, Bytes, Content, Datetime, Dict, Module, Scope, StyleChain, Styles, Value,
+    Array, Bytes, Datetime, Dict, Module, Scope, StyleChain, Styles, Target, TargetElem,
+    Value,
 };
+use crate::html::HtmlDocument;
 use crate::introspection::{Introspector, Locator};
 use crate::layout::{Alignment, Dir, LayoutRoot};
 use crate::model::Document;
@@ -101,24 +104,76 @@ pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document>
     .map_err(deduplicate)?;
 
     // Typeset the module's content, relayouting until convergence.
-    typeset(world, tracer, &module.content()).map_err(deduplicate)
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+    let content = module.content();
+    converge(
+        world,
+        tracer,
+        |document: &Document| &document.introspector,
+        |engine| {
+            let mut document = content.layout_root(engine, styles)?;
+            document.introspector.rebuild(&document.pages);
+            Ok(document)
+        },
+    )
+    .map_err(deduplicate)
+}
+
+/// Compile a source file into an HTML document.
+///
+/// Instead of being laid out into pages, the content is realized into a tree
+/// of semantic HTML elements. Content without an HTML counterpart, like math,
+/// is laid out into frames.
+#[typst_macros::time(name = "compile html")]
+pub fn compile_html(
+    world: &dyn World,
+    tracer: &mut Tracer,
+) -> SourceResult<HtmlDocument> {
+    // Call `track` on the world just once to keep comemo's ID stable.
+    let world = world.track();
+
+    // Try to evaluate the source file into a module.
+    let module = crate::eval::eval(
+        world,
+        Route::default().track(),
+        tracer.track_mut(),
+        &world.main(),
+    )
+    .map_err(deduplicate)?;
+
+    // Realize the module's content, repeating until convergence.
+    let library = world.library();
+    let target = TargetElem::set_target(Target::Html).wrap();
+    let base = StyleChain::new(&library.styles);
+    let styles = base.chain(&target);
+    let content = module.content();
+    converge(
+        world,
+        tracer,
+        |document: &HtmlDocument| &document.introspector,
+        |engine| {
+            let mut document = html::html_document(engine, &content, styles)?;
+            document.introspector.rebuild_html(&document.body);
+            Ok(document)
+        },
+    )
+    .map_err(deduplicate)
 }
 
-/// Relayout until introspection converges.
-fn typeset(
+/// Run a compilation step until introspection converges.
+fn converge<T: Default>(
     world: Tracked<dyn World + '_>,
     tracer: &mut Tracer,
-    content: &Content,
-) -> SourceResult<Document> {
+    introspector: fn(&T) -> &Introspector,
+    mut run: impl FnMut(&mut Engine) -> SourceResult<T>,
+) -> SourceResult<T> {
     // The name of the iterations for timing scopes.
     const ITER_NAMES: &[&str] =
         &["typeset (1)", "typeset (2)", "typeset (3)", "typeset (4)", "typeset (5)"];
 
-    let library = world.library();
-    let styles = StyleChain::new(&library.styles);
-
     let mut iter = 0;
-    let mut document = Document::default();
+    let mut output = T::default();
 
     // Relayout until all introspections stabilize.
     // If that doesn't happen within five attempts, we give up.
@@ -135,15 +190,14 @@ fn typeset(
             route: Route::default(),
             tracer: tracer.track_mut(),
             locator: &mut locator,
-            introspector: document.introspector.track_with(&constraint),
+            introspector: introspector(&output).track_with(&constraint),
         };
 
         // Layout!
-        document = content.layout_root(&mut engine, styles)?;
-        document.introspector.rebuild(&document.pages);
+        output = run(&mut engine)?;
         iter += 1;
 
-        if timed!("check stabilized", document.introspector.validate(&constraint)) {
+        if timed!("check stabilized", introspector(&output).validate(&constraint)) {
             break;
         }
 
@@ -162,7 +216,7 @@ fn typeset(
         return Err(delayed);
     }
 
-    Ok(document)
+    Ok(output)
 }
 
 /// Deduplicate diagnostics.
@@ -350,6 +404,7 @@ fn global(math: Module, inputs: Dict) -> Module {
     self::text::define(&mut global);
     global.reset_category();
     global.define_module(math);
+    global.define_module(html::module());
     self::layout::define(&mut global);
     self::visualize::define(&mut global);
     self::introspection::define(&mut global);
@@ -132,6 +132,11 @@ impl Accent {
     pub fn new(c: char) -> Self {
         Self(Symbol::combining_accent(c).unwrap_or(c))
     }
+
+    /// The accent's character, which is a combining character if one exists.
+    pub fn get(self) -> char {
+        self.0
+    }
 }
 
 cast! {
@@ -0,0 +1,106 @@
+use unicode_math_class::MathClass;
+
+use crate::diag::SourceResult;
+use crate::foundations::{elem, Content, Packed, StyleChain};
+use crate::layout::{Abs, Em, FixedAlignment};
+use crate::math::{
+    scaled_font_size, stack, style_for_subscript, style_for_superscript, FrameFragment,
+    GlyphFragment, LayoutMath, LeftRightAlternator, MathContext, MathFragment,
+};
+use crate::syntax::Span;
+
+/// The minimum width of an extensible arrow.
+const ARROW_WIDTH: Em = Em::new(2.0);
+
+/// The space on each side of an arrow's annotations.
+const ARROW_PADDING: Em = Em::new(0.3);
+
+/// The gap between an arrow and its annotations.
+const ARROW_GAP: Em = Em::new(0.1);
+
+/// An arrow that stretches to fit the annotations above and below it.
+///
+/// If the math font can't stretch the arrow, it is drawn with lines instead.
+///
+/// ```example
+/// $ A xarrow("f") B xarrow(sym: arrow.l, below: g) C $
+/// $ X xarrow(sym: arrow.r.double, "a long annotation", below: n -> oo) Y $
+/// ```
+#[elem(name = "xarrow", title = "Extensible Arrow", LayoutMath)]
+pub struct XArrowElem {
+    /// The annotation above the arrow.
+    #[positional]
+    pub body: Option<Content>,
+
+    /// The annotation below the arrow.
+    pub below: Option<Content>,
+
+    /// The arrow symbol to stretch.
+    ///
+    /// ```example
+    /// $ A xarrow(sym: arrow.l.r, "both") B
+    ///     xarrow(sym: harpoons.rtlb, "eq") C $
+    /// ```
+    #[default('→')]
+    pub sym: char,
+}
+
+impl LayoutMath for Packed<XArrowElem> {
+    #[typst_macros::time(name = "math.xarrow", span = self.span())]
+    fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
+        layout_arrow(
+            ctx,
+            styles,
+            self.sym(styles),
+            self.body(styles),
+            self.below(styles),
+            self.span(),
+        )
+    }
+}
+
+/// Lay out an arrow that stretches to fit its annotations.
+fn layout_arrow(
+    ctx: &mut MathContext,
+    styles: StyleChain,
+    c: char,
+    above: Option<Content>,
+    below: Option<Content>,
+    span: Span,
+) -> SourceResult<()> {
+    let font_size = scaled_font_size(ctx, styles);
+    let above_style = style_for_superscript(styles);
+    let below_style = style_for_subscript(styles);
+    let above = above
+        .map(|above| ctx.layout_into_run(&above, styles.chain(&above_style)))
+        .transpose()?;
+    let below = below
+        .map(|below| ctx.layout_into_run(&below, styles.chain(&below_style)))
+        .transpose()?;
+
+    let width = above
+        .iter()
+        .chain(&below)
+        .map(|run| run.iter().map(MathFragment::width).sum::<Abs>())
+        .map(|width| width + 2.0 * ARROW_PADDING.at(font_size))
+        .fold(ARROW_WIDTH.at(font_size), |a, b| a.max(b));
+
+    let glyph = GlyphFragment::new(ctx, styles, c, span);
+    let arrow = glyph.stretch_horizontal(ctx, width, Default::default());
+
+    let mut rows = vec![];
+    let mut baseline = 0;
+    if let Some(above) = above {
+        rows.push(above);
+        baseline = 1;
+    }
+    rows.push(arrow.into());
+    rows.extend(below);
+
+    let gap = ARROW_GAP.at(font_size);
+    let frame =
+        stack(rows, FixedAlignment::Center, gap, baseline, LeftRightAlternator::Right);
+    ctx.push(FrameFragment::new(ctx, styles, frame).with_class(MathClass::Relation));
+
+    Ok(())
+}
@@ -0,0 +1,352 @@
+use ecow::EcoString;
+use unicode_math_class::MathClass;
+use unscanny::Scanner;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::foundations::{elem, Content, NativeElement, Packed, Smart, StyleChain};
+use crate::math::{
+    AttachElem, ClassElem, EquationElem, FracElem, LayoutMath, MathContext, XArrowElem,
+};
+use crate::syntax::Span;
+use crate::text::{SpaceElem, TextElem};
+
+/// A chemical formula or reaction.
+///
+/// The formula is given as a string in a notation similar to that of LaTeX's
+/// `mhchem` package:
+///
+/// - Element symbols are set upright and digits following them become
+///   subscripts, as in `{"H2SO4"}`. A leading number is a stoichiometric
+///   coefficient, as in `{"2H2O"}` or `{"1/2O2"}`.
+/// - Charges follow a caret, as in `{"SO4^2-"}`, or are given by a trailing
+///   `+` or `-`, as in `{"Na+"}`.
+/// - Isotopes are written with a leading caret and underscore, as in
+///   `{"^227_90Th"}`.
+/// - Parentheses and brackets group atoms, as in `{"(NH4)2S"}`. States like
+///   `{"(aq)"}` are written the same way.
+/// - A `*` or `.` inside a formula joins an adduct, as in `{"CuSO4*5H2O"}`.
+/// - Within a formula, `-`, `=`, and `#` between atoms are single, double,
+///   and triple bonds.
+/// - Separated by spaces, `+` is a plus sign, `^` and `v` mark a gas or a
+///   precipitate, and `->`, `<-`, `<->`, and `<=>` are reaction arrows. An
+///   arrow can be followed by text above and below it in square brackets, as
+///   in `{"->[heat][cat.]"}`.
+///
+/// # Example
+/// ```example
+/// $ ce("2H2 + O2 -> 2H2O") $
+/// $ ce("CO2 + C ->[heat] 2CO") $
+/// $ ce("Fe^3+ + 3OH- <=> Fe(OH)3 v") $
+/// ```
+#[elem(title = "Chemical Formula", LayoutMath)]
+pub struct CeElem {
+    /// The formula in `mhchem`-like notation.
+    #[required]
+    pub text: EcoString,
+}
+
+impl LayoutMath for Packed<CeElem> {
+    #[typst_macros::time(name = "math.ce", span = self.span())]
+    fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
+        let expanded = self.expand().at(self.span())?;
+        let fragments = ctx.layout_into_fragments(&expanded, styles)?;
+        ctx.extend(fragments);
+        Ok(())
+    }
+}
+
+impl Packed<CeElem> {
+    /// Expand the formula into the regular math content it stands for, with
+    /// reaction arrows as [extensible arrows](XArrowElem).
+    pub fn expand(&self) -> StrResult<Content> {
+        let span = self.span();
+        let items = parse(self.text(), span)?.into_iter().map(|item| match item {
+            Item::Formula(content) => content,
+            Item::Arrow(c, above, below) => XArrowElem::new()
+                .with_body(above)
+                .with_below(below)
+                .with_sym(c)
+                .pack()
+                .spanned(span),
+        });
+        Ok(Content::sequence(items)
+            .styled(EquationElem::set_italic(Smart::Custom(false)))
+            .spanned(span))
+    }
+}
+
+/// A top-level item of a chemical formula.
+enum Item {
+    /// A formula laid out with the regular math machinery.
+    Formula(Content),
+    /// A reaction arrow with its annotations.
+    Arrow(char, Option<Content>, Option<Content>),
+}
+
+/// The reaction arrows, longest first.
+const ARROWS: &[(&str, char)] = &[("<=>", '⇌'), ("<->", '↔'), ("->", '→'), ("<-", '←')];
+
+/// Parse a formula into its top-level items.
+fn parse(text: &str, span: Span) -> StrResult<Vec<Item>> {
+    let mut s = Scanner::new(text);
+    let mut items = vec![];
+    loop {
+        s.eat_whitespace();
+        if s.done() {
+            break;
+        }
+
+        if let Some(&(_, c)) = ARROWS.iter().find(|(arrow, _)| s.eat_if(*arrow)) {
+            let above = annotation(&mut s, span)?;
+            let below = if above.is_some() { annotation(&mut s, span)? } else { None };
+            items.push(Item::Arrow(c, above, below));
+            continue;
+        }
+
+        let token = s.eat_until(char::is_whitespace);
+        let content = match token {
+            "+" => TextElem::packed('+'),
+            "^" => TextElem::packed('↑'),
+            "v" => TextElem::packed('↓'),
+            _ => species(token, span)?,
+        };
+        items.push(Item::Formula(content.spanned(span)));
+    }
+
+    Ok(items)
+}
+
+/// Parse the annotation of an arrow in square brackets, if any.
+fn annotation(s: &mut Scanner, span: Span) -> StrResult<Option<Content>> {
+    if !s.eat_if('[') {
+        return Ok(None);
+    }
+
+    let start = s.cursor();
+    let mut depth = 1;
+    while depth > 0 {
+        match s.eat() {
+            Some('[') => depth += 1,
+            Some(']') => depth -= 1,
+            Some(_) => {}
+            None => bail!("unclosed arrow annotation"),
+        }
+    }
+
+    let text = s.from(start).strip_suffix(']').unwrap_or_default();
+    let mut seq = vec![];
+    for item in parse(text, span)? {
+        match item {
+            // Keep words in the annotation apart.
+            Item::Formula(content) => {
+                if !seq.is_empty() {
+                    seq.push(SpaceElem::new().pack());
+                }
+                seq.push(content)
+            }
+            Item::Arrow(..) => bail!("arrow annotations cannot contain arrows"),
+        }
+    }
+
+    Ok(Some(Content::sequence(seq)))
+}
+
+/// An atom or group of a formula together with its scripts.
+#[derive(Default)]
+struct Piece {
+    base: Content,
+    sub: Option<Content>,
+    sup: Option<Content>,
+    pre_sub: Option<Content>,
+    pre_sup: Option<Content>,
+}
+
+impl Piece {
+    fn new(base: Content) -> Self {
+        Self { base, ..Default::default() }
+    }
+
+    fn pack(self) -> Content {
+        if self.sub.is_none()
+            && self.sup.is_none()
+            && self.pre_sub.is_none()
+            && self.pre_sup.is_none()
+        {
+            return self.base;
+        }
+
+        AttachElem::new(self.base)
+            .with_b(self.sub)
+            .with_t(self.sup)
+            .with_bl(self.pre_sub)
+            .with_tl(self.pre_sup)
+            .pack()
+    }
+}
+
+/// Parse a single species, like `2H2O` or `[Fe(CN)6]^3-`.
+fn species(text: &str, span: Span) -> StrResult<Content> {
+    let mut s = Scanner::new(text);
+    let mut pieces: Vec<Piece> = vec![];
+    let mut pre = None;
+
+    coefficient(&mut s, &mut pieces);
+
+    while let Some(c) = s.peek() {
+        match c {
+            'A'..='Z' => {
+                let start = s.cursor();
+                s.eat();
+                s.eat_while(char::is_lowercase);
+                let mut piece = Piece::new(TextElem::packed(s.from(start)));
+                if let Some((sup, sub)) = pre.take() {
+                    piece.pre_sup = sup;
+                    piece.pre_sub = sub;
+                }
+                pieces.push(piece);
+            }
+            'a'..='z' => {
+                let word = s.eat_while(char::is_lowercase);
+                pieces.push(Piece::new(TextElem::packed(word)));
+            }
+            '0'..='9' => {
+                let digits = s.eat_while(|c: char| c.is_ascii_digit());
+                match pieces.last_mut() {
+                    Some(piece) if piece.sub.is_none() => {
+                        piece.sub = Some(TextElem::packed(digits));
+                    }
+                    _ => pieces.push(Piece::new(TextElem::packed(digits))),
+                }
+            }
+            '(' | '[' => {
+                s.eat();
+                let close = if c == '(' { ')' } else { ']' };
+                let start = s.cursor();
+                let mut depth = 1;
+                while depth > 0 {
+                    match s.eat() {
+                        Some(d) if d == c => depth += 1,
+                        Some(d) if d == close => depth -= 1,
+                        Some(_) => {}
+                        None => bail!("unclosed group in chemical formula"),
+                    }
+                }
+                let inner = s.from(start).strip_suffix(close).unwrap_or_default();
+                pieces.push(Piece::new(TextElem::packed(c)));
+                pieces.push(Piece::new(species(inner, span)?));
+                pieces.push(Piece::new(TextElem::packed(close)));
+            }
+            '^' => {
+                s.eat();
+                let script = script(&mut s, true);
+                match pieces.last_mut() {
+                    // A caret at the start introduces an isotope's mass number.
+                    None => {
+                        let sub = if s.eat_if('_') {
+                            Some(TextElem::packed(script_text(&mut s, false)))
+                        } else {
+                            None
+                        };
+                        pre = Some((Some(script), sub));
+                    }
+                    Some(piece) => piece.sup = Some(script),
+                }
+            }
+            '_' => {
+                s.eat();
+                let script = script(&mut s, false);
+                match pieces.last_mut() {
+                    None => pre = Some((None, Some(script))),
+                    Some(piece) => piece.sub = Some(script),
+                }
+            }
+            '+' | '-' if is_charge(&s) => {
+                let charge = s.eat_while(['+', '-']);
+                if let Some(piece) = pieces.last_mut() {
+                    piece.sup = Some(TextElem::packed(charge.replace('-', "−")));
+                }
+            }
+            '-' | '=' | '#' => {
+                s.eat();
+                let bond = match c {
+                    '-' => '−',
+                    '=' => '=',
+                    _ => '≡',
+                };
+                let bond = ClassElem::new(MathClass::Normal, TextElem::packed(bond));
+                pieces.push(Piece::new(bond.pack()));
+            }
+            '*' | '.' => {
+                s.eat();
+                pieces.push(Piece::new(TextElem::packed('·')));
+                coefficient(&mut s, &mut pieces);
+            }
+            _ => {
+                s.eat();
+                pieces.push(Piece::new(TextElem::packed(c)));
+            }
+        }
+    }
+
+    if pre.is_some() {
+        bail!("isotope is missing its element");
+    }
+
+    Ok(Content::sequence(pieces.into_iter().map(Piece::pack)).spanned(span))
+}
+
+/// Parse a leading stoichiometric coefficient, like `2` or `1/2`.
+fn coefficient(s: &mut Scanner, pieces: &mut Vec<Piece>) {
+    let start = s.cursor();
+    let num = s.eat_while(|c: char| c.is_ascii_digit());
+    if num.is_empty() {
+        return;
+    }
+
+    let mut denom = "";
+    if s.eat_if('/') {
+        denom = s.eat_while(|c: char| c.is_ascii_digit());
+    }
+
+    // A number on its own is not a coefficient.
+    if denom.is_empty() && s.done() || s.at('/') {
+        s.jump(start);
+        return;
+    }
+
+    let coefficient = if denom.is_empty() {
+        TextElem::packed(num)
+    } else {
+        FracElem::new(TextElem::packed(num), TextElem::packed(denom)).pack()
+    };
+    pieces.push(Piece::new(coefficient));
+}
+
+/// Parse a script after a caret or underscore.
+fn script(s: &mut Scanner, charge: bool) -> Content {
+    TextElem::packed(script_text(s, charge))
+}
+
+/// Parse the text of a script, either in braces or up to the next atom.
+fn script_text(s: &mut Scanner, charge: bool) -> EcoString {
+    let text = if s.eat_if('{') {
+        let text = s.eat_until('}');
+        s.eat_if('}');
+        text
+    } else if charge {
+        let digits = s.eat_while(|c: char| c.is_ascii_digit());
+        let start = s.cursor() - digits.len();
+        s.eat_while(['+', '-']);
+        s.from(start)
+    } else {
+        s.eat_while(|c: char| c.is_ascii_digit())
+    };
+
+    text.replace('-', "−").into()
+}
+
+/// Whether the `+` or `-` at the cursor is a charge rather than a bond.
+fn is_charge(s: &Scanner) -> bool {
+    let rest = s.after().trim_start_matches(['+', '-']);
+    rest.is_empty() || rest.starts_with([')', ']', '*', '.'])
+}
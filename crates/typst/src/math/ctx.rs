@@ -1,5 +1,6 @@
 use std::f64::consts::SQRT_2;
 
+use comemo::Tracked;
 use ecow::EcoString;
 use rustybuzz::Feature;
 use ttf_parser::gsub::{AlternateSubstitution, SingleSubstitution, SubstitutionSubtable};
@@ -11,18 +12,19 @@ use unicode_segmentation::UnicodeSegmentation;
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{Content, Packed, StyleChain};
+use crate::foundations::{Content, Packed, Smart, StyleChain};
 use crate::layout::{Abs, Axes, BoxElem, Em, Frame, LayoutMultiple, Regions, Size};
 use crate::math::{
-    scaled_font_size, styled_char, EquationElem, FrameFragment, GlyphFragment,
-    LayoutMath, MathFragment, MathRun, MathSize, THICK,
+    scaled_font_size, styled_char, unstyled_char, EquationElem, FrameFragment,
+    GlyphFragment, LayoutMath, MathFragment, MathRun, MathSize, THICK,
 };
 use crate::model::ParElem;
 use crate::syntax::{is_newline, Span};
 use crate::text::{
-    features, BottomEdge, BottomEdgeMetric, Font, TextElem, TextSize, TopEdge,
-    TopEdgeMetric,
+    features, variant, BottomEdge, BottomEdgeMetric, Font, FontFamily, FontStyle,
+    FontVariant, FontWeight, TextElem, TextSize, TopEdge, TopEdgeMetric,
 };
+use crate::World;
 
 macro_rules! scaled {
     ($ctx:expr, $styles:expr, text: $text:ident, display: $display:ident $(,)?) => {
@@ -69,29 +71,9 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
         font: &'a Font,
     ) -> Self {
         let math_table = font.ttf().tables().math.unwrap();
-        let gsub_table = font.ttf().tables().gsub;
         let constants = math_table.constants.unwrap();
-
-        let ssty_table = gsub_table
-            .and_then(|gsub| {
-                gsub.features
-                    .find(ttf_parser::Tag::from_bytes(b"ssty"))
-                    .and_then(|feature| feature.lookup_indices.get(0))
-                    .and_then(|index| gsub.lookups.get(index))
-            })
-            .and_then(|ssty| ssty.subtables.get::<SubstitutionSubtable>(0))
-            .and_then(|ssty| match ssty {
-                SubstitutionSubtable::Alternate(alt_glyphs) => Some(alt_glyphs),
-                _ => None,
-            });
-
-        let features = features(styles);
-        let glyphwise_tables = gsub_table.map(|gsub| {
-            features
-                .into_iter()
-                .filter_map(|feature| GlyphwiseSubsts::new(gsub, feature))
-                .collect()
-        });
+        let ssty_table = ssty_table(font);
+        let glyphwise_tables = glyphwise_tables(font, styles);
 
         let ttf = font.ttf();
         let space_width = ttf
@@ -114,6 +96,65 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
         }
     }
 
+    /// Select the font for a character, following the equation's font
+    /// settings.
+    ///
+    /// Returns the font and the character to use with it. The character
+    /// differs from the given one if a styled letter is taken from a bold or
+    /// italic face of the alphabet font. Returns `None` if no suitable font
+    /// has the character.
+    pub fn select_font(&self, styles: StyleChain, c: char) -> Option<(Font, char)> {
+        let alphabet = EquationElem::alphabet_font_in(styles);
+        let ranges = EquationElem::font_ranges_in(styles);
+        let fallback = EquationElem::fallback_font_in(styles);
+
+        let world = self.engine.world;
+        let variant = variant(styles);
+
+        // Text fonts usually lack the mathematical alphanumeric symbols, so
+        // for styled letters, we also try their bold and italic faces.
+        let unstyled = unstyled_char(c).map(|(base, bold, italic)| {
+            let variant = FontVariant {
+                style: if italic { FontStyle::Italic } else { FontStyle::Normal },
+                weight: if bold { FontWeight::BOLD } else { variant.weight },
+                ..variant
+            };
+            (base, variant)
+        });
+
+        if let Some(font) = find_font(world, ranges.families(c), c, variant) {
+            return Some((font, c));
+        }
+
+        if let Some((base, variant)) = unstyled {
+            if let Some(font) = find_font(world, ranges.families(base), base, variant) {
+                return Some((font, base));
+            }
+        }
+
+        if let Smart::Custom(list) = &alphabet {
+            if c.is_alphanumeric() {
+                let families = list.into_iter().map(FontFamily::as_str);
+                if let Some(font) = find_font(world, families.clone(), c, variant) {
+                    return Some((font, c));
+                }
+
+                if let Some((base, variant)) = unstyled {
+                    if let Some(font) = find_font(world, families, base, variant) {
+                        return Some((font, base));
+                    }
+                }
+            }
+        }
+
+        if self.ttf.glyph_index(c).is_some() {
+            return Some((self.font.clone(), c));
+        }
+
+        let families = fallback.into_iter().map(FontFamily::as_str);
+        find_font(world, families, c, variant).map(|font| (font, c))
+    }
+
     pub fn push(&mut self, fragment: impl Into<MathFragment>) {
         self.fragments.push(fragment.into());
     }
@@ -249,6 +290,14 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
 
             // Anything else is handled by Typst's standard text layout.
             let styles = styles.chain(&local);
+            let font_style;
+            let styles = match EquationElem::alphabet_font_in(styles) {
+                Smart::Custom(list) => {
+                    font_style = TextElem::set_font(list).wrap();
+                    styles.chain(&font_style)
+                }
+                Smart::Auto => styles,
+            };
             let text: EcoString =
                 text.chars().map(|c| styled_char(styles, c, false)).collect();
             if text.contains(is_newline) {
@@ -299,6 +348,54 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
     }
 }
 
+/// Find the first of the families that has a glyph for the character.
+fn find_font<'f>(
+    world: Tracked<dyn World + '_>,
+    families: impl IntoIterator<Item = &'f str>,
+    c: char,
+    variant: FontVariant,
+) -> Option<Font> {
+    families.into_iter().find_map(|family| {
+        let id = world.book().select(family, variant)?;
+        let font = world.font(id)?;
+        font.ttf().glyph_index(c)?;
+        Some(font)
+    })
+}
+
+/// Look up the table with the script and scriptscript alternates of a font.
+pub(super) fn ssty_table(font: &Font) -> Option<AlternateSubstitution<'_>> {
+    font.ttf()
+        .tables()
+        .gsub
+        .and_then(|gsub| {
+            gsub.features
+                .find(ttf_parser::Tag::from_bytes(b"ssty"))
+                .and_then(|feature| feature.lookup_indices.get(0))
+                .and_then(|index| gsub.lookups.get(index))
+        })
+        .and_then(|ssty| ssty.subtables.get::<SubstitutionSubtable>(0))
+        .and_then(|ssty| match ssty {
+            SubstitutionSubtable::Alternate(alt_glyphs) => Some(alt_glyphs),
+            _ => None,
+        })
+}
+
+/// Look up the glyph-wise substitution tables of a font for the active
+/// features.
+pub(super) fn glyphwise_tables<'a>(
+    font: &'a Font,
+    styles: StyleChain,
+) -> Option<Vec<GlyphwiseSubsts<'a>>> {
+    let features = features(styles);
+    font.ttf().tables().gsub.map(|gsub| {
+        features
+            .into_iter()
+            .filter_map(|feature| GlyphwiseSubsts::new(gsub, feature))
+            .collect()
+    })
+}
+
 pub(super) trait Scaled {
     fn scaled(self, ctx: &MathContext, font_size: Abs) -> Abs;
 }
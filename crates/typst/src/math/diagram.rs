@@ -0,0 +1,662 @@
+use ecow::EcoString;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::foundations::{
+    cast, elem, Array, Cast, Content, Packed, SequenceElem, Str, StyleChain, StyledElem,
+    Value,
+};
+use crate::layout::{Abs, Angle, Em, Frame, FrameItem, Length, Point, Rel, Size};
+use crate::math::{
+    scaled_font_size, style_for_superscript, FrameFragment, LayoutMath, MathContext,
+    Scaled,
+};
+use crate::syntax::Spanned;
+use crate::text::TextElem;
+use crate::visualize::{
+    FixedStroke, Geometry, LineCap, LineJoin, Path, PathItem, Shape, Stroke,
+};
+
+const DEFAULT_ROW_GAP: Em = Em::new(2.0);
+const DEFAULT_COL_GAP: Em = Em::new(3.0);
+const NODE_PADDING: Em = Em::new(0.25);
+const LABEL_GAP: Em = Em::new(0.2);
+const TIP_LENGTH: Em = Em::new(0.3);
+const TIP_ANGLE_DEG: f64 = 40.0;
+
+/// A commutative diagram.
+///
+/// The nodes of a diagram are arranged in a grid. Like for a
+/// [matrix]($math.mat), the nodes of a row are separated by commas and the
+/// rows by semicolons. Arrows between nodes are added with
+/// [`edge`]($math.edge) in the node they start from.
+///
+/// # Example
+/// ```example
+/// $ diagram(
+///   A edge("r", f) edge("d", g, swap: #true), B edge("d", h);
+///   C edge("r", k), D;
+/// ) $
+/// ```
+#[elem(LayoutMath)]
+pub struct DiagramElem {
+    /// The gap between rows and columns.
+    ///
+    /// ```example
+    /// #set math.diagram(gap: 1.5em)
+    /// $ diagram(A edge("r"), B) $
+    /// ```
+    #[external]
+    pub gap: Rel<Length>,
+
+    /// The gap between rows. Takes precedence over `gap`.
+    ///
+    /// ```example
+    /// #set math.diagram(row-gap: 1em)
+    /// $ diagram(A edge("d"); B) $
+    /// ```
+    #[resolve]
+    #[parse(
+        let gap = args.named("gap")?;
+        args.named("row-gap")?.or(gap)
+    )]
+    #[default(DEFAULT_ROW_GAP.into())]
+    pub row_gap: Rel<Length>,
+
+    /// The gap between columns. Takes precedence over `gap`.
+    ///
+    /// ```example
+    /// #set math.diagram(column-gap: 5em)
+    /// $ diagram(A edge("r", f), B) $
+    /// ```
+    #[resolve]
+    #[parse(args.named("column-gap")?.or(gap))]
+    #[default(DEFAULT_COL_GAP.into())]
+    pub column_gap: Rel<Length>,
+
+    /// An array of arrays with the rows of the diagram.
+    ///
+    /// Rows may have different lengths. Missing nodes at the end of a row are
+    /// left empty.
+    ///
+    /// ```example
+    /// #let rows = (($A$, $B$), ($C$,))
+    /// $ diagram(..rows) $
+    /// ```
+    #[variadic]
+    #[parse(
+        let mut rows = vec![];
+        let mut width = 0;
+
+        let values = args.all::<Spanned<Value>>()?;
+        if values.iter().any(|spanned| matches!(spanned.v, Value::Array(_))) {
+            for Spanned { v, span } in values {
+                let array = v.cast::<Array>().at(span)?;
+                let row: Vec<_> = array.into_iter().map(Value::display).collect();
+                width = width.max(row.len());
+                rows.push(row);
+            }
+        } else {
+            rows = vec![values.into_iter().map(|spanned| spanned.v.display()).collect()];
+        }
+
+        for row in &mut rows {
+            if row.len() < width {
+                row.resize(width, Content::empty());
+            }
+        }
+
+        rows
+    )]
+    pub rows: Vec<Vec<Content>>,
+}
+
+impl LayoutMath for Packed<DiagramElem> {
+    #[typst_macros::time(name = "math.diagram", span = self.span())]
+    fn layout_math(&self, ctx: &mut MathContext, styles: StyleChain) -> SourceResult<()> {
+        let rows = self.rows();
+        let nrows = rows.len();
+        let ncols = rows.first().map_or(0, |row| row.len());
+
+        let base = ctx.regions.base();
+        let row_gap = self.row_gap(styles).relative_to(base.y);
+        let column_gap = self.column_gap(styles).relative_to(base.x);
+
+        let font_size = scaled_font_size(ctx, styles);
+        let axis = scaled!(ctx, styles, axis_height);
+        let padding = NODE_PADDING.at(font_size);
+
+        // Lay out the nodes and measure the rows and columns.
+        let mut nodes = vec![];
+        let mut heights = vec![(Abs::zero(), Abs::zero()); nrows];
+        let mut widths = vec![Abs::zero(); ncols];
+        for (row, (ascent, descent)) in rows.iter().zip(&mut heights) {
+            for (cell, width) in row.iter().zip(&mut widths) {
+                let frame = ctx.layout_into_frame(cell, styles)?;
+                ascent.set_max(frame.ascent());
+                descent.set_max(frame.descent());
+                width.set_max(frame.width());
+                nodes.push(frame);
+            }
+        }
+
+        let mut frame = Frame::soft(Size::zero());
+        let mut anchors = vec![];
+        let mut bounds = vec![];
+
+        let mut y = Abs::zero();
+        let mut nodes = nodes.into_iter();
+        for &(ascent, descent) in &heights {
+            let baseline = y + ascent;
+            let mut x = Abs::zero();
+            for &width in &widths {
+                let node = nodes.next().unwrap();
+                let center = x + width / 2.0;
+                let pos =
+                    Point::new(center - node.width() / 2.0, baseline - node.ascent());
+
+                // Arrows point to the math axis of a node so that arrows
+                // within a row are horizontal.
+                let anchor = Point::new(center, baseline - axis);
+                let min = pos.min(anchor) + Point::splat(-padding);
+                let max =
+                    (pos + node.size().to_point()).max(anchor) + Point::splat(padding);
+
+                anchors.push(anchor);
+                bounds.push((min, max));
+                frame.push_frame(pos, node);
+                x += width + column_gap;
+            }
+            y += ascent + descent + row_gap;
+        }
+
+        let grid = Size::new(
+            widths.iter().sum::<Abs>() + column_gap * ncols.saturating_sub(1) as f64,
+            y - row_gap.min(y),
+        );
+        let mut min = Point::zero();
+        let mut max = grid.to_point();
+
+        // Draw the arrows.
+        for (i, cell) in rows.iter().flatten().enumerate() {
+            let mut edges = vec![];
+            collect_edges(cell, &mut edges);
+            for edge in &edges {
+                let offset = edge.direction();
+                let row = (i / ncols) as isize + offset.rows;
+                let col = (i % ncols) as isize + offset.cols;
+                if row < 0 || row >= nrows as isize || col < 0 || col >= ncols as isize {
+                    bail!(edge.span(), "edge points outside of the diagram");
+                }
+
+                let j = row as usize * ncols + col as usize;
+                for (pos, item) in layout_edge(
+                    ctx,
+                    styles,
+                    edge,
+                    (anchors[i], bounds[i]),
+                    (anchors[j], bounds[j]),
+                    font_size,
+                )? {
+                    let (item_min, item_max) = item_bounds(pos, &item);
+                    min = min.min(item_min);
+                    max = max.max(item_max);
+                    match item {
+                        EdgeItem::Shape(shape) => {
+                            frame.push(pos, FrameItem::Shape(shape, edge.span()))
+                        }
+                        EdgeItem::Frame(label) => frame.push_frame(pos, label),
+                    }
+                }
+            }
+        }
+
+        frame.translate(-min);
+        *frame.size_mut() = (max + -min).to_size();
+
+        // Center the nodes rather than the arrows on the axis, so that labels
+        // and bent arrows don't move the diagram.
+        frame.set_baseline(grid.y / 2.0 + axis - min.y);
+        ctx.push(FrameFragment::new(ctx, styles, frame));
+
+        Ok(())
+    }
+}
+
+/// An arrow in a [commutative diagram]($math.diagram).
+///
+/// An edge is placed in the node that the arrow starts from. It only has an
+/// effect within a diagram.
+///
+/// # Example
+/// ```example
+/// $ diagram(
+///   A edge("r", f, bend: #30deg)
+///     edge("r", g, bend: #(-30deg), swap: #true), B
+/// ) $
+/// ```
+#[elem(title = "Diagram Edge", LayoutMath)]
+pub struct EdgeElem {
+    /// The node that the arrow points to, relative to the node it starts
+    /// from.
+    ///
+    /// The direction is given as a string of the letters `u`, `d`, `l` and
+    /// `r` for up, down, left and right, respectively. For example, `{"rr"}`
+    /// points to the node two columns to the right and `{"dl"}` to the node
+    /// one row down and one column to the left.
+    #[required]
+    pub direction: EdgeDirection,
+
+    /// The label of the arrow, placed next to its middle.
+    #[positional]
+    pub body: Option<Content>,
+
+    /// Whether to place the label on the right side of the arrow instead of
+    /// the left side, as seen in the arrow's direction.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   A edge("r", f) edge("d", g, swap: #true), B;
+    ///   C
+    /// ) $
+    /// ```
+    #[default(false)]
+    pub swap: bool,
+
+    /// How much to bend the arrow. Positive angles bend it to the left, as
+    /// seen in the arrow's direction.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   A edge("r", f, bend: #40deg), B
+    ///     edge("l", g, bend: #40deg)
+    /// ) $
+    /// ```
+    pub bend: Angle,
+
+    /// How far to move the arrow sideways. Positive lengths move it to the
+    /// left, as seen in the arrow's direction.
+    ///
+    /// This is useful for parallel arrows between the same nodes.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   A edge("r", f, shift: #0.2em)
+    ///     edge("r", g, shift: #(-0.2em), swap: #true), B
+    /// ) $
+    /// ```
+    #[resolve]
+    pub shift: Length,
+
+    /// The tip at the end of the arrow.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   A edge("r", head: "double"), B
+    ///     edge("r", head: "harpoon"), C
+    ///     edge("r", head: #none), D
+    /// ) $
+    /// ```
+    #[default(Some(ArrowTip::Arrow))]
+    pub head: Option<ArrowTip>,
+
+    /// The tip at the start of the arrow.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   A edge("r", tail: "hook"), B
+    ///     edge("r", tail: "bar"), C
+    /// ) $
+    /// ```
+    pub tail: Option<ArrowTip>,
+
+    /// How to [stroke]($stroke) the arrow.
+    ///
+    /// By default, the arrow has the thickness of a fraction line. The tips
+    /// are never dashed.
+    ///
+    /// ```example
+    /// $ diagram(
+    ///   A edge("r", stroke: #(dash: "dashed")), B
+    ///     edge("r", stroke: #blue), C
+    /// ) $
+    /// ```
+    #[resolve]
+    #[fold]
+    pub stroke: Stroke,
+}
+
+impl LayoutMath for Packed<EdgeElem> {
+    fn layout_math(&self, _: &mut MathContext, _: StyleChain) -> SourceResult<()> {
+        // Edges are drawn by the diagram that contains them.
+        Ok(())
+    }
+}
+
+/// The node an edge points to, relative to the node it starts from.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EdgeDirection {
+    /// How many rows to go down.
+    pub rows: isize,
+    /// How many columns to go right.
+    pub cols: isize,
+    /// The letters the direction was created from.
+    text: EcoString,
+}
+
+impl EdgeDirection {
+    /// Parse a direction from a string of `u`, `d`, `l` and `r`.
+    fn parse(text: &str) -> StrResult<Self> {
+        let (mut rows, mut cols) = (0, 0);
+        for c in text.chars() {
+            match c {
+                'u' => rows -= 1,
+                'd' => rows += 1,
+                'l' => cols -= 1,
+                'r' => cols += 1,
+                _ => bail!("expected a direction made of `u`, `d`, `l`, and `r`"),
+            }
+        }
+
+        if rows == 0 && cols == 0 {
+            bail!("direction must point to a different node");
+        }
+
+        Ok(Self { rows, cols, text: text.into() })
+    }
+}
+
+cast! {
+    EdgeDirection,
+    self => self.text.into_value(),
+    v: Str => Self::parse(&v)?,
+    v: Content => match v.to_packed::<TextElem>() {
+        Some(elem) => Self::parse(elem.text())?,
+        None => bail!("expected a direction made of `u`, `d`, `l`, and `r`"),
+    },
+}
+
+/// A tip at the start or end of an arrow in a diagram.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ArrowTip {
+    /// An arrowhead, like in `→`.
+    Arrow,
+    /// Two arrowheads, like in `↠`.
+    Double,
+    /// A single barb on the left side, like in `⇀`.
+    Harpoon,
+    /// A perpendicular bar, like in `↦`.
+    Bar,
+    /// A hook curling to the left side, like in `↪`.
+    Hook,
+}
+
+/// A shape or label of an edge.
+enum EdgeItem {
+    Shape(Shape),
+    Frame(Frame),
+}
+
+/// Collect the edges that start from a node. Edges within nested diagrams
+/// belong to those diagrams.
+fn collect_edges<'a>(content: &'a Content, out: &mut Vec<&'a Packed<EdgeElem>>) {
+    if let Some(edge) = content.to_packed::<EdgeElem>() {
+        out.push(edge);
+    } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        for child in &sequence.children {
+            collect_edges(child, out);
+        }
+    } else if let Some(styled) = content.to_packed::<StyledElem>() {
+        collect_edges(&styled.child, out);
+    }
+}
+
+/// Lay out the arrow and label of an edge between two nodes.
+///
+/// Each node is given by its anchor and its padded bounding box. The arrow
+/// runs between the anchors, but starts and ends where it leaves and enters
+/// the bounding boxes.
+fn layout_edge(
+    ctx: &mut MathContext,
+    styles: StyleChain,
+    edge: &Packed<EdgeElem>,
+    (from, start_bounds): (Point, (Point, Point)),
+    (to, end_bounds): (Point, (Point, Point)),
+    font_size: Abs,
+) -> SourceResult<Vec<(Point, EdgeItem)>> {
+    let stroke = edge.stroke(styles).unwrap_or(FixedStroke {
+        paint: TextElem::fill_in(styles).as_decoration(),
+        thickness: scaled!(ctx, styles, fraction_rule_thickness),
+        ..Default::default()
+    });
+
+    // The curve through both anchors, bent and shifted to the left.
+    let chord = to + -from;
+    let bend = edge.bend(styles);
+    let shift = left(chord, edge.shift(styles));
+    let curve = Cubic([
+        from + shift,
+        from + shift + rotate(chord, -bend) * 0.4,
+        to + shift + -(rotate(chord, bend) * 0.4),
+        to + shift,
+    ]);
+
+    let t0 = clip(&curve, start_bounds, true);
+    let t1 = clip(&curve, end_bounds, false);
+    let curve = curve.split(t0, t1);
+    let Cubic([p0, p1, p2, p3]) = curve;
+
+    let mut items = vec![];
+    let mut path = Path::new();
+    path.move_to(p0);
+    path.cubic_to(p1, p2, p3);
+    items.push((
+        Point::zero(),
+        EdgeItem::Shape(Geometry::Path(path).stroked(stroke.clone())),
+    ));
+
+    // Tips are drawn with a solid stroke and rounded ends.
+    let tip_stroke = FixedStroke {
+        cap: LineCap::Round,
+        join: LineJoin::Round,
+        dash: None,
+        ..stroke
+    };
+
+    let tip_length = TIP_LENGTH.at(font_size);
+    let tips = [
+        (edge.head(styles), p3, curve.tangent(1.0)),
+        (edge.tail(styles), p0, -curve.tangent(0.0)),
+    ];
+    for (tip, point, direction) in tips {
+        if let Some(tip) = tip {
+            let path = layout_tip(tip, point, direction, tip_length);
+            items.push((
+                Point::zero(),
+                EdgeItem::Shape(Geometry::Path(path).stroked(tip_stroke.clone())),
+            ));
+        }
+    }
+
+    // Place the label next to the middle of the arrow.
+    if let Some(body) = edge.body(styles) {
+        let script_style = style_for_superscript(styles);
+        let label = ctx.layout_into_frame(&body, styles.chain(&script_style))?;
+        let mut normal = left(curve.tangent(0.5), Abs::pt(1.0));
+        if edge.swap(styles) {
+            normal = -normal;
+        }
+
+        // Move the label's center away from the arrow until its bounding box
+        // is clear of it.
+        let size = label.size();
+        let (nx, ny) = (normal.x.to_pt(), normal.y.to_pt());
+        let distance =
+            LABEL_GAP.at(font_size) + size.x / 2.0 * nx.abs() + size.y / 2.0 * ny.abs();
+        let center = curve.at(0.5) + Point::new(distance * nx, distance * ny);
+        let pos = center + -(size.to_point() / 2.0);
+        items.push((pos, EdgeItem::Frame(label)));
+    }
+
+    Ok(items)
+}
+
+/// Build the path of an arrow tip at the given point, pointing in the given
+/// direction.
+fn layout_tip(tip: ArrowTip, point: Point, direction: Point, length: Abs) -> Path {
+    let angle = Angle::deg(TIP_ANGLE_DEG);
+    let back = -(direction * (length * angle.cos() / direction.hypot()));
+    let side = left(direction, length * angle.sin());
+
+    let mut path = Path::new();
+    match tip {
+        ArrowTip::Arrow | ArrowTip::Double => {
+            path.move_to(point + back + side);
+            path.line_to(point);
+            path.line_to(point + back + -side);
+            if tip == ArrowTip::Double {
+                let point = point + back * 0.6;
+                path.move_to(point + back + side);
+                path.line_to(point);
+                path.line_to(point + back + -side);
+            }
+        }
+        ArrowTip::Harpoon => {
+            path.move_to(point + back + side);
+            path.line_to(point);
+        }
+        ArrowTip::Bar => {
+            path.move_to(point + side);
+            path.line_to(point + -side);
+        }
+        ArrowTip::Hook => {
+            // The hook curls to the left of the arrow's direction of travel,
+            // which is opposite to the tip's direction at the start.
+            let across = side * -2.0;
+            let bulge = -back * (4.0 / 3.0 * angle.tan());
+            path.move_to(point);
+            path.cubic_to(point + bulge, point + across + bulge, point + across);
+        }
+    }
+
+    path
+}
+
+/// A cubic Bézier curve.
+#[derive(Copy, Clone)]
+struct Cubic([Point; 4]);
+
+impl Cubic {
+    /// The point at parameter `t`.
+    fn at(&self, t: f64) -> Point {
+        let Self([p0, p1, p2, p3]) = *self;
+        let s = 1.0 - t;
+        p0 * (s * s * s)
+            + p1 * (3.0 * s * s * t)
+            + p2 * (3.0 * s * t * t)
+            + p3 * (t * t * t)
+    }
+
+    /// The direction of the curve at parameter `t`.
+    fn tangent(&self, t: f64) -> Point {
+        let Self([p0, p1, p2, p3]) = *self;
+        let s = 1.0 - t;
+        let tangent =
+            (p1 + -p0) * (s * s) + (p2 + -p1) * (2.0 * s * t) + (p3 + -p2) * (t * t);
+        if tangent.hypot().approx_eq(Abs::zero()) {
+            p3 + -p0
+        } else {
+            tangent
+        }
+    }
+
+    /// The part of the curve between the parameters `t0` and `t1`.
+    fn split(&self, t0: f64, t1: f64) -> Self {
+        let tail = self.subdivide(t0).1;
+        let t = if t0 < 1.0 { (t1 - t0) / (1.0 - t0) } else { 1.0 };
+        tail.subdivide(t).0
+    }
+
+    /// Split the curve into two at parameter `t`.
+    fn subdivide(&self, t: f64) -> (Self, Self) {
+        let Self([p0, p1, p2, p3]) = *self;
+        let lerp = |a: Point, b: Point| a + (b + -a) * t;
+        let (q0, q1, q2) = (lerp(p0, p1), lerp(p1, p2), lerp(p2, p3));
+        let (r0, r1) = (lerp(q0, q1), lerp(q1, q2));
+        let s = lerp(r0, r1);
+        (Self([p0, q0, r0, s]), Self([s, r1, q2, p3]))
+    }
+}
+
+/// Find the parameter at which the curve leaves (at its start) or enters (at
+/// its end) the given bounding box.
+fn clip(curve: &Cubic, (min, max): (Point, Point), start: bool) -> f64 {
+    let contains = |t: f64| {
+        let p = curve.at(if start { t } else { 1.0 - t });
+        p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y
+    };
+
+    // If the boxes overlap, there is nothing sensible to clip.
+    if !contains(0.0) || contains(0.5) {
+        return if start { 0.0 } else { 1.0 };
+    }
+
+    let (mut inside, mut outside) = (0.0, 0.5);
+    for _ in 0..24 {
+        let mid = (inside + outside) / 2.0;
+        if contains(mid) {
+            inside = mid;
+        } else {
+            outside = mid;
+        }
+    }
+
+    if start {
+        outside
+    } else {
+        1.0 - outside
+    }
+}
+
+/// The bounding box of an item of an edge.
+fn item_bounds(pos: Point, item: &EdgeItem) -> (Point, Point) {
+    match item {
+        EdgeItem::Frame(frame) => (pos, pos + frame.size().to_point()),
+        EdgeItem::Shape(shape) => {
+            let Geometry::Path(path) = &shape.geometry else {
+                return (pos, pos);
+            };
+
+            // The control points of a Bézier curve enclose it.
+            let pad = shape.stroke.as_ref().map_or(Abs::zero(), |s| s.thickness / 2.0);
+            let mut min = Point::splat(Abs::inf());
+            let mut max = Point::splat(-Abs::inf());
+            for item in &path.0 {
+                let points = match *item {
+                    PathItem::MoveTo(p) | PathItem::LineTo(p) => vec![p],
+                    PathItem::CubicTo(p1, p2, p3) => vec![p1, p2, p3],
+                    PathItem::ClosePath => vec![],
+                };
+                for point in points {
+                    min = min.min(point);
+                    max = max.max(point);
+                }
+            }
+            (pos + min + Point::splat(-pad), pos + max + Point::splat(pad))
+        }
+    }
+}
+
+/// Rotate a vector by the given angle, clockwise on the page.
+fn rotate(vector: Point, angle: Angle) -> Point {
+    let (sin, cos) = (angle.sin(), angle.cos());
+    Point::new(vector.x * cos - vector.y * sin, vector.x * sin + vector.y * cos)
+}
+
+/// A vector of the given length pointing to the left of the direction.
+fn left(direction: Point, length: Abs) -> Point {
+    let hypot = direction.hypot();
+    if hypot.approx_eq(Abs::zero()) {
+        return Point::zero();
+    }
+    Point::new(direction.y, -direction.x) * (length / hypot)
+}
@@ -1,25 +1,30 @@
 use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+
+use ecow::eco_format;
 
 use unicode_math_class::MathClass;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Content, NativeElement, Packed, Resolve, ShowSet, Smart, StyleChain, Styles,
-    Synthesize,
+    cast, elem, Content, Dict, Fold, Label, NativeElement, Packed, Repr, Resolve,
+    Selector, ShowSet, Smart, StyleChain, Styles, Synthesize,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
+use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{
-    Abs, AlignElem, Alignment, Axes, Em, FixedAlignment, Frame, LayoutMultiple,
-    LayoutSingle, OuterHAlignment, Point, Regions, Size, SpecificAlignment, VAlignment,
+    Abs, AlignElem, Alignment, Axes, Em, FixedAlignment, Fragment, Frame, LayoutMultiple,
+    Length, OuterHAlignment, Point, Regions, Size, SpecificAlignment, VAlignment,
 };
 use crate::math::{
-    scaled_font_size, LayoutMath, MathContext, MathRunFrameBuilder, MathSize, MathVariant,
+    scaled_font_size, LayoutMath, MathContext, MathFragment, MathRunFrameBuilder,
+    MathSize, MathVariant,
 };
 use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
 use crate::syntax::Span;
 use crate::text::{
-    families, variant, Font, FontFamily, FontList, FontWeight, LocalName, TextElem,
+    families, variant, Font, FontFamily, FontList, FontWeight, LinebreakElem, LocalName,
+    SpaceElem, TextElem,
 };
 use crate::utils::{NonZeroExt, Numeric};
 use crate::World;
@@ -51,7 +56,7 @@ use crate::World;
     Locatable,
     Synthesize,
     ShowSet,
-    LayoutSingle,
+    LayoutMultiple,
     LayoutMath,
     Count,
     LocalName,
@@ -112,6 +117,197 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// Whether to number each line of a multi-line block equation on its own
+    /// instead of the equation as a whole.
+    ///
+    /// Use [`math.number`]($math.number) to label the number of a single line
+    /// so that it can be referenced, or to exclude a line from numbering. The
+    /// equation as a whole then has no number of its own.
+    ///
+    /// ```example
+    /// #set math.equation(
+    ///   numbering: "(1)",
+    ///   number-lines: true,
+    /// )
+    ///
+    /// $ a &= b + c #math.number(<first>) \
+    ///     &= c + b #math.number(numbered: false) \
+    ///     &= d $
+    ///
+    /// We start with @first.
+    /// ```
+    #[default(false)]
+    pub number_lines: bool,
+
+    /// Whether individually numbered lines share the number of their equation
+    /// and are told apart by a sub-number, as in (3a) and (3b).
+    ///
+    /// The [numbering]($math.equation.numbering) then receives two numbers
+    /// for each line: the equation's and the line's. A reference to the
+    /// equation itself shows just the equation's number, and so does an
+    /// equation with just one line.
+    ///
+    /// ```example
+    /// #set math.equation(
+    ///   numbering: "(1a)",
+    ///   number-lines: true,
+    ///   sub-numbering: true,
+    /// )
+    ///
+    /// $ x = y $
+    /// $ a &= b #math.number(<inner>) \
+    ///   c &= d $ <system>
+    ///
+    /// See @system and @inner.
+    /// ```
+    #[default(false)]
+    pub sub_numbering: bool,
+
+    /// Whether to automatically break block equations that are too wide for
+    /// the available space into multiple lines.
+    ///
+    /// Lines are broken in front of relations (like `=`) and binary operators
+    /// (like `+`), but never within a fraction, a delimited group, or another
+    /// nested structure. In a multi-line equation, each line is broken on its
+    /// own.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(line-breaking: true)
+    ///
+    /// $ f(x) = a_0 + a_1 x + a_2 x^2
+    ///   + a_3 x^3 + a_4 x^4 $
+    /// ```
+    #[default(false)]
+    pub line_breaking: bool,
+
+    /// How far continuation lines of an automatically broken equation are
+    /// indented.
+    ///
+    /// The indent is measured from the position determined by
+    /// [`continuation-align`]($math.equation.continuation-align).
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(
+    ///   line-breaking: true,
+    ///   continuation-indent: 2em,
+    /// )
+    ///
+    /// $ f(x) = a_0 + a_1 x + a_2 x^2
+    ///   + a_3 x^3 + a_4 x^4 $
+    /// ```
+    #[resolve]
+    #[default(Em::new(1.0).into())]
+    pub continuation_indent: Length,
+
+    /// How to align continuation lines of an automatically broken equation.
+    ///
+    /// - `{auto}`: Continuation lines are indented from the first relation of
+    ///   the first line. If there is no such relation or it is too far to the
+    ///   end, they are indented from the start instead.
+    /// - `{start}` or `{left}`: Continuation lines are indented from the start
+    ///   of the first line.
+    /// - `{end}` or `{right}`: Continuation lines are placed at the end of the
+    ///   available space, indented from there.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set math.equation(
+    ///   line-breaking: true,
+    ///   continuation-align: end,
+    /// )
+    ///
+    /// $ f(x) = a_0 + a_1 x + a_2 x^2
+    ///   + a_3 x^3 + a_4 x^4 $
+    /// ```
+    pub continuation_align: Smart<OuterHAlignment>,
+
+    /// Whether a block equation may break across pages or columns.
+    ///
+    /// When enabled, the lines of a multi-line equation (including those
+    /// created by automatic line breaking) are distributed across regions as
+    /// needed. This is useful for long derivations. If the equation is
+    /// numbered, the number is placed with the first or last part, depending
+    /// on [`number-align`]($math.equation.number-align).
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #set math.equation(breakable: true)
+    ///
+    /// $ x &= (a + b)^2 \
+    ///     &= (a + b)(a + b) \
+    ///     &= a^2 + a b + b a + b^2 \
+    ///     &= a^2 + 2 a b + b^2 $
+    /// ```
+    #[default(false)]
+    pub breakable: bool,
+
+    /// The font for letters and digits.
+    ///
+    /// If set to a text font, its bold and italic faces are used for bold and
+    /// italic letters. Letters and digits missing from these fonts are taken
+    /// from the [math font]($math.equation.operator-font). Multi-letter text,
+    /// like operator names, is set in this font, too.
+    ///
+    /// ```example
+    /// #set math.equation(alphabet-font: "Linux Libertine")
+    ///
+    /// $ f(x) = alpha x^2 + bold(v) dot 3 $
+    /// ```
+    pub alphabet_font: Smart<FontList>,
+
+    /// The math font, used for operators, delimiters, and all other symbols.
+    ///
+    /// The first font in this list with an OpenType MATH table is used. The
+    /// table also determines the sizes and spacing of the equation's parts.
+    /// If set to `{auto}`, the math font is chosen from the
+    /// [text font]($text.font) list instead.
+    ///
+    /// ```example
+    /// #set text(font: "Linux Libertine")
+    /// #set math.equation(
+    ///   operator-font: "New Computer Modern Math",
+    /// )
+    ///
+    /// $ sum_(i=1)^n (a_i + b_i)^2 $
+    /// ```
+    #[borrowed]
+    pub operator_font: Smart<FontList>,
+
+    /// Fonts for glyphs that are missing from the math font.
+    ///
+    /// These fonts are tried in order. If there is no math font otherwise,
+    /// the first one of them with an OpenType MATH table becomes the math
+    /// font.
+    ///
+    /// ```example
+    /// #set math.equation(fallback-font: "DejaVu Sans Mono")
+    ///
+    /// $ x ☃ y $
+    /// ```
+    #[borrowed]
+    pub fallback_font: FontList,
+
+    /// Fonts for specific characters, taking precedence over the other font
+    /// settings.
+    ///
+    /// Maps characters or ranges of characters to font families. A key can
+    /// be a single character like `{"∑"}`, a range of characters like
+    /// `{"α-ω"}`, or a range of code points like `{"U+2200-U+22FF"}`.
+    /// Ranges of letters also apply to their bold and italic forms.
+    ///
+    /// ```example
+    /// #set math.equation(font-ranges: (
+    ///   "α-ω": "Linux Libertine",
+    ///   "+": "DejaVu Sans Mono",
+    /// ))
+    ///
+    /// $ alpha + beta = gamma $
+    /// ```
+    #[fold]
+    pub font_ranges: MathFontRanges,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
@@ -148,6 +344,13 @@ pub struct EquationElem {
     #[internal]
     #[ghost]
     pub class: Option<MathClass>,
+
+    /// Whether the lines of the surrounding equation are numbered
+    /// individually.
+    #[internal]
+    #[default(false)]
+    #[ghost]
+    pub line_numbering: bool,
 }
 
 impl Synthesize for Packed<EquationElem> {
@@ -165,6 +368,12 @@ impl Synthesize for Packed<EquationElem> {
         };
 
         self.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
+
+        if self.numbers_lines(styles) {
+            let body = mark_lines(self.body());
+            self.push_body(body);
+        }
+
         Ok(())
     }
 }
@@ -178,6 +387,7 @@ impl ShowSet for Packed<EquationElem> {
         } else {
             out.set(EquationElem::set_size(MathSize::Text));
         }
+        out.set(EquationElem::set_line_numbering(self.numbers_lines(styles)));
         out.set(TextElem::set_weight(FontWeight::from_number(450)));
         out.set(TextElem::set_font(FontList(vec![FontFamily::new(
             "New Computer Modern Math",
@@ -186,6 +396,122 @@ impl ShowSet for Packed<EquationElem> {
     }
 }
 
+/// Configures the number of a line in an equation whose lines are numbered
+/// individually.
+///
+/// When [`number-lines`]($math.equation.number-lines) is enabled, each line of
+/// a numbered block equation gets its own number. Put this function anywhere
+/// in a line to label that line's number so that it can be referenced, or to
+/// exclude the line from numbering. It has no effect in other equations.
+///
+/// # Example
+/// ```example
+/// #set math.equation(
+///   numbering: "(1)",
+///   number-lines: true,
+/// )
+///
+/// $ sin^2 x + cos^2 x &= 1 #math.number(<pythagoras>) \
+///   tan x &= (sin x) / (cos x) \
+///   cot x &= 1 / (tan x) #math.number(numbered: false) $
+///
+/// By @pythagoras, ...
+/// ```
+#[elem(title = "Line Number", Locatable, Synthesize, LayoutMath, Count, Refable)]
+pub struct NumberElem {
+    /// A label for the line's number, under which it can be referenced.
+    #[positional]
+    pub name: Option<Label>,
+
+    /// Whether the line is numbered.
+    #[default(true)]
+    pub numbered: bool,
+
+    /// The numbering of the line, if it is numbered.
+    #[synthesized]
+    pub numbering: Option<Numbering>,
+
+    /// The counter level stepped by the line.
+    #[internal]
+    #[synthesized]
+    pub level: NonZeroUsize,
+
+    /// The supplement for references to the line.
+    #[synthesized]
+    pub supplement: Content,
+}
+
+impl Synthesize for Packed<NumberElem> {
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let numbered = self.numbered(styles);
+        let numbering = if EquationElem::line_numbering_in(styles) && numbered {
+            EquationElem::numbering_in(styles).clone()
+        } else {
+            None
+        };
+
+        let level = if EquationElem::sub_numbering_in(styles) {
+            NonZeroUsize::new(2).unwrap()
+        } else {
+            NonZeroUsize::ONE
+        };
+
+        let supplement = match EquationElem::supplement_in(styles) {
+            Smart::Auto => {
+                TextElem::packed(Packed::<EquationElem>::local_name_in(styles))
+            }
+            Smart::Custom(None) => Content::empty(),
+            Smart::Custom(Some(supplement)) => {
+                supplement.resolve(engine, styles, [self.clone().pack()])?
+            }
+        };
+
+        if let Some(label) = self.name(styles) {
+            self.set_label(label);
+        }
+
+        self.push_numbered(numbered);
+        self.push_numbering(numbering);
+        self.push_level(level);
+        self.push_supplement(supplement);
+        Ok(())
+    }
+}
+
+impl LayoutMath for Packed<NumberElem> {
+    fn layout_math(&self, ctx: &mut MathContext, _: StyleChain) -> SourceResult<()> {
+        if let (Some(Some(_)), Some(location)) = ((**self).numbering(), self.location()) {
+            ctx.push(MathFragment::Number(location));
+        }
+        Ok(())
+    }
+}
+
+impl Count for Packed<NumberElem> {
+    fn update(&self) -> Option<CounterUpdate> {
+        let level = (**self).level().copied().unwrap_or(NonZeroUsize::ONE);
+        matches!((**self).numbering(), Some(Some(_))).then(|| CounterUpdate::Step(level))
+    }
+}
+
+impl Refable for Packed<NumberElem> {
+    fn supplement(&self) -> Content {
+        (**self).supplement().cloned().unwrap_or_else(Content::empty)
+    }
+
+    fn counter(&self) -> Counter {
+        Counter::of(EquationElem::elem())
+    }
+
+    fn numbering(&self) -> Option<&Numbering> {
+        (**self).numbering().and_then(Option::as_ref)
+    }
+}
+
 /// Layouted items suitable for placing in a paragraph.
 #[derive(Debug, Clone)]
 pub enum MathParItem {
@@ -204,6 +530,28 @@ impl MathParItem {
 }
 
 impl Packed<EquationElem> {
+    /// Whether the equation as a whole steps the counter. This is not the
+    /// case if its lines are numbered individually without sub-numbering.
+    fn has_own_number(&self) -> bool {
+        let styles = StyleChain::default();
+        (**self).numbering(styles).is_some()
+            && (!self.number_lines(styles) || self.sub_numbering(styles))
+    }
+
+    /// Whether the lines of this equation are numbered individually. With
+    /// sub-numbering, an equation with just one line keeps its own number.
+    fn numbers_lines(&self, styles: StyleChain) -> bool {
+        self.block(styles)
+            && self.number_lines(styles)
+            && (**self).numbering(styles).is_some()
+            && (!self.sub_numbering(styles)
+                || split_lines(self.body())
+                    .iter()
+                    .filter(|line| !is_empty_line(line))
+                    .nth(1)
+                    .is_some())
+    }
+
     pub fn layout_inline(
         &self,
         engine: &mut Engine<'_>,
@@ -248,60 +596,154 @@ impl Packed<EquationElem> {
     }
 }
 
-impl LayoutSingle for Packed<EquationElem> {
+impl LayoutMultiple for Packed<EquationElem> {
     #[typst_macros::time(name = "math.equation", span = self.span())]
     fn layout(
         &self,
         engine: &mut Engine,
         styles: StyleChain,
         regions: Regions,
-    ) -> SourceResult<Frame> {
+    ) -> SourceResult<Fragment> {
         assert!(self.block(styles));
 
         let span = self.span();
         let font = find_math_font(engine, styles, span)?;
 
-        let mut ctx = MathContext::new(engine, styles, regions, &font);
-        let equation_builder = ctx
-            .layout_into_run(self, styles)?
-            .multiline_frame_builder(&ctx, styles);
-
-        let Some(numbering) = (**self).numbering(styles) else {
-            return Ok(equation_builder.build());
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let numbers_lines = self.numbers_lines(styles);
+        let number = match (**self).numbering(styles) {
+            Some(numbering) if !numbers_lines => Some(layout_number(
+                engine,
+                self.location().unwrap(),
+                numbering,
+                styles,
+                span,
+                pod,
+            )?),
+            _ => None,
         };
 
-        let pod = Regions::one(regions.base(), Axes::splat(false));
-        let number = Counter::of(EquationElem::elem())
-            .display_at_loc(engine, self.location().unwrap(), styles, numbering)?
-            .spanned(span)
-            .layout(engine, styles, pod)?
-            .into_frame();
+        let mut ctx = MathContext::new(engine, styles, pod, &font);
+        let run = ctx.layout_into_run(self, styles)?;
 
-        static NUMBER_GUTTER: Em = Em::new(0.5);
-        let full_number_width = number.width() + NUMBER_GUTTER.resolve(styles);
+        // Lay out the numbers of individually numbered lines.
+        let mut line_numbers = vec![];
+        if let Some(numbering) =
+            (**self).numbering(styles).as_ref().filter(|_| numbers_lines)
+        {
+            for fragment in run.iter() {
+                if let MathFragment::Number(location) = *fragment {
+                    let number = layout_number(
+                        ctx.engine, location, numbering, styles, span, pod,
+                    )?;
+                    line_numbers.push((location, number));
+                }
+            }
+        }
 
+        static NUMBER_GUTTER: Em = Em::new(0.5);
+        let full_number_width = number
+            .iter()
+            .chain(line_numbers.iter().map(|(_, number)| number))
+            .map(Frame::width)
+            .max()
+            .map_or(Abs::zero(), |width| width + NUMBER_GUTTER.resolve(styles));
+        let equation_align = AlignElem::alignment_in(styles).resolve(styles).x;
         let number_align = match self.number_align(styles) {
             SpecificAlignment::H(h) => SpecificAlignment::Both(h, VAlignment::Horizon),
             SpecificAlignment::V(v) => SpecificAlignment::Both(OuterHAlignment::End, v),
             SpecificAlignment::Both(h, v) => SpecificAlignment::Both(h, v),
+        }
+        .resolve(styles);
+
+        let mut equation_builder =
+            if self.line_breaking(styles) && regions.size.x.is_finite() {
+                // Keep the equation clear of the number on both sides if it is
+                // centered.
+                let reserved = match equation_align {
+                    FixedAlignment::Center => 2.0 * full_number_width,
+                    _ => full_number_width,
+                };
+                run.broken_frame_builder(&ctx, styles, regions.size.x - reserved)
+            } else {
+                run.multiline_frame_builder(&ctx, styles)
+            };
+
+        let width = if regions.size.x.is_finite() {
+            regions.size.x
+        } else {
+            equation_builder.size.x + 2.0 * full_number_width
         };
 
+        if numbers_lines {
+            add_line_numbers(
+                &mut equation_builder,
+                line_numbers,
+                number_align,
+                equation_align,
+                width,
+                full_number_width,
+            );
+        }
+
+        let mut builders = if self.breakable(styles) {
+            split_equation(equation_builder, regions)
+        } else {
+            vec![equation_builder]
+        };
+
+        let Some(number) = number else {
+            let frames = builders
+                .into_iter()
+                .map(|builder| {
+                    let mut frame = builder.build();
+                    if numbers_lines {
+                        frame.resize(
+                            Size::new(width, frame.height()),
+                            Axes::new(equation_align, FixedAlignment::Start),
+                        );
+                        frame.translate(Point::with_x(number_shift(
+                            equation_align,
+                            number_align.x,
+                            full_number_width,
+                        )));
+                    }
+                    frame
+                })
+                .collect();
+            return Ok(Fragment::frames(frames));
+        };
+
+        // The number goes with the part of a split equation that it is
+        // vertically aligned to. Regions that stay empty because not even the
+        // first line fits into them don't count.
+        let filled = |builder: &MathRunFrameBuilder| !builder.frames.is_empty();
+        let numbered = match number_align.y {
+            FixedAlignment::End => builders.iter().rposition(filled),
+            _ => builders.iter().position(filled),
+        }
+        .unwrap_or(0);
+
         let frame = add_equation_number(
-            equation_builder,
+            builders.remove(numbered),
             number,
-            number_align.resolve(styles),
-            AlignElem::alignment_in(styles).resolve(styles).x,
+            number_align,
+            equation_align,
             regions.size.x,
             full_number_width,
         );
 
-        Ok(frame)
+        let mut frames: Vec<_> =
+            builders.into_iter().map(MathRunFrameBuilder::build).collect();
+        frames.insert(numbered, frame);
+
+        Ok(Fragment::frames(frames))
     }
 }
 
 impl Count for Packed<EquationElem> {
     fn update(&self) -> Option<CounterUpdate> {
-        (self.block(StyleChain::default()) && self.numbering().is_some())
+        (self.block(StyleChain::default()) && self.has_own_number())
             .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
     }
 }
@@ -324,7 +766,10 @@ impl Refable for Packed<EquationElem> {
     }
 
     fn numbering(&self) -> Option<&Numbering> {
-        (**self).numbering(StyleChain::default()).as_ref()
+        (**self)
+            .numbering(StyleChain::default())
+            .as_ref()
+            .filter(|_| self.has_own_number())
     }
 }
 
@@ -376,7 +821,15 @@ fn find_math_font(
 ) -> SourceResult<Font> {
     let variant = variant(styles);
     let world = engine.world;
-    let Some(font) = families(styles).find_map(|family| {
+    let primary: Vec<&str> = match EquationElem::operator_font_in(styles) {
+        Smart::Custom(list) => list.into_iter().map(FontFamily::as_str).collect(),
+        Smart::Auto => families(styles).collect(),
+    };
+    let fallback = EquationElem::fallback_font_in(styles);
+    let mut candidates = primary
+        .into_iter()
+        .chain(fallback.into_iter().map(FontFamily::as_str));
+    let Some(font) = candidates.find_map(|family| {
         let id = world.book().select(family, variant)?;
         let font = world.font(id)?;
         let _ = font.ttf().tables().math?.constants?;
@@ -387,6 +840,144 @@ fn find_math_font(
     Ok(font)
 }
 
+/// Lay out the number of an equation or of one of its lines.
+fn layout_number(
+    engine: &mut Engine,
+    location: Location,
+    numbering: &Numbering,
+    styles: StyleChain,
+    span: Span,
+    pod: Regions,
+) -> SourceResult<Frame> {
+    Ok(Counter::of(EquationElem::elem())
+        .display_at_loc(engine, location, styles, numbering)?
+        .spanned(span)
+        .layout(engine, styles, pod)?
+        .into_frame())
+}
+
+/// Add a number marker to each non-empty line of an equation's body that
+/// doesn't have one yet.
+fn mark_lines(body: &Content) -> Content {
+    let selector = Selector::Elem(NumberElem::elem(), None);
+    let mut seq = vec![];
+    for mut line in split_lines(body) {
+        let marked =
+            line.iter().any(|child| child.query_first(selector.clone()).is_some());
+        if !is_empty_line(&line) && !marked {
+            let end = line.len()
+                - line.last().is_some_and(|c| c.is::<LinebreakElem>()) as usize;
+            line.insert(end, NumberElem::new().pack().spanned(body.span()));
+        }
+        seq.extend(line);
+    }
+
+    Content::sequence(seq)
+}
+
+/// Split an equation's body into its lines, each with its line break.
+fn split_lines(body: &Content) -> Vec<Vec<Content>> {
+    let mut lines = vec![vec![]];
+    body.sequence_recursive_for_each(&mut |child| {
+        lines.last_mut().unwrap().push(child.clone());
+        if child.is::<LinebreakElem>() {
+            lines.push(vec![]);
+        }
+    });
+    lines
+}
+
+/// Whether a line of an equation's body has no content besides spaces and
+/// its line break.
+fn is_empty_line(line: &[Content]) -> bool {
+    line.iter()
+        .all(|child| child.is::<SpaceElem>() || child.is::<LinebreakElem>())
+}
+
+/// Place the numbers of individually numbered lines next to their lines.
+///
+/// The numbers are positioned relative to the equation resized to the given
+/// width.
+fn add_line_numbers(
+    builder: &mut MathRunFrameBuilder,
+    mut numbers: Vec<(Location, Frame)>,
+    number_align: Axes<FixedAlignment>,
+    equation_align: FixedAlignment,
+    width: Abs,
+    full_number_width: Abs,
+) {
+    let offset = equation_align.position(width - builder.size.x)
+        + number_shift(equation_align, number_align.x, full_number_width);
+
+    for (lines, location) in std::mem::take(&mut builder.numbers) {
+        let Some(i) = numbers.iter().position(|(loc, _)| *loc == location) else {
+            continue;
+        };
+        let (_, number) = numbers.swap_remove(i);
+
+        // A row that was broken into multiple lines gets its number on the
+        // last line if numbers are bottom-aligned and on the first otherwise.
+        let index = match number_align.y {
+            FixedAlignment::End => lines.end - 1,
+            _ => lines.start,
+        };
+
+        let (line, pos) = &mut builder.frames[index];
+        let x = match number_align.x {
+            FixedAlignment::Start => Abs::zero(),
+            _ => width - number.width(),
+        };
+        let y = line.baseline() - number.baseline();
+        line.push_frame(Point::new(x - offset - pos.x, y), number);
+    }
+}
+
+/// How far an equation is moved to keep it clear of its number when both
+/// are aligned to the same side.
+fn number_shift(
+    equation_align: FixedAlignment,
+    number_align_x: FixedAlignment,
+    full_number_width: Abs,
+) -> Abs {
+    match (equation_align, number_align_x) {
+        (FixedAlignment::Start, FixedAlignment::Start) => full_number_width,
+        (FixedAlignment::End, FixedAlignment::End) => -full_number_width,
+        _ => Abs::zero(),
+    }
+}
+
+/// Distribute the lines of an equation across the given regions.
+fn split_equation(
+    builder: MathRunFrameBuilder,
+    mut regions: Regions,
+) -> Vec<MathRunFrameBuilder> {
+    let empty = || MathRunFrameBuilder {
+        size: Size::with_x(builder.size.x),
+        frames: vec![],
+        numbers: vec![],
+    };
+
+    let mut builders = vec![];
+    let mut current = empty();
+    let mut offset = Abs::zero();
+    for (sub, pos) in builder.frames {
+        let bottom = pos.y + sub.height();
+        if !regions.size.y.fits(bottom - offset - current.size.y) && !regions.in_last() {
+            // If not even the first line fits, the current region stays empty.
+            builders.push(std::mem::replace(&mut current, empty()));
+            regions.next();
+            offset = pos.y;
+        }
+
+        regions.size.y -= bottom - offset - current.size.y;
+        current.size.y = bottom - offset;
+        current.frames.push((sub, pos - Point::with_y(offset)));
+    }
+
+    builders.push(current);
+    builders
+}
+
 fn add_equation_number(
     equation_builder: MathRunFrameBuilder,
     number: Frame,
@@ -424,11 +1015,11 @@ fn add_equation_number(
         is_multiline,
         [first, last],
     );
-    equation.translate(Point::with_x(match (equation_align, number_align.x) {
-        (FixedAlignment::Start, FixedAlignment::Start) => full_number_width,
-        (FixedAlignment::End, FixedAlignment::End) => -full_number_width,
-        _ => Abs::zero(),
-    }));
+    equation.translate(Point::with_x(number_shift(
+        equation_align,
+        number_align.x,
+        full_number_width,
+    )));
 
     let x = match number_align.x {
         FixedAlignment::Start => Abs::zero(),
@@ -498,3 +1089,74 @@ fn resize_equation(
     equation.translate(Point::with_y(excess_above));
     resizing_offset + Point::with_y(excess_above)
 }
+
+/// Fonts for specific ranges of characters in equations.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct MathFontRanges(pub Vec<(RangeInclusive<char>, FontList)>);
+
+impl MathFontRanges {
+    /// The families defined for the given character, in order of priority.
+    pub fn families(&self, c: char) -> impl Iterator<Item = &str> + Clone {
+        self.0
+            .iter()
+            .rev()
+            .filter(move |(range, _)| range.contains(&c))
+            .flat_map(|(_, list)| list)
+            .map(FontFamily::as_str)
+    }
+}
+
+cast! {
+    MathFontRanges,
+    self => self.0
+        .into_iter()
+        .map(|(range, list)| {
+            let key = if range.start() == range.end() {
+                eco_format!("{}", range.start())
+            } else {
+                eco_format!("{}-{}", range.start(), range.end())
+            };
+            (key.into(), list.into_value())
+        })
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| Ok((parse_char_range(&k)?, v.cast()?)))
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for MathFontRanges {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
+/// Parse a character range like `a`, `a-z`, or `U+2200-U+22FF`.
+fn parse_char_range(key: &str) -> StrResult<RangeInclusive<char>> {
+    let single = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => s
+                .strip_prefix("U+")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32),
+        }
+    };
+
+    if let Some(c) = single(key) {
+        return Ok(c..=c);
+    }
+
+    for (i, _) in key.match_indices('-') {
+        if let (Some(start), Some(end)) = (single(&key[..i]), single(&key[i + 1..])) {
+            if start > end {
+                bail!("character range {} is reversed", key.repr());
+            }
+            return Ok(start..=end);
+        }
+    }
+
+    bail!("expected a character or a range of characters, found {}", key.repr())
+}
@@ -1,14 +1,16 @@
 use std::fmt::{self, Debug, Formatter};
 
 use smallvec::SmallVec;
-use ttf_parser::gsub::AlternateSet;
+use ttf_parser::gsub::AlternateSubstitution;
 use ttf_parser::{GlyphId, Rect};
 use unicode_math_class::MathClass;
 
 use crate::foundations::StyleChain;
+use crate::introspection::Location;
 use crate::layout::{Abs, Corner, Em, Frame, FrameItem, HideElem, Point, Size};
 use crate::math::{
-    scaled_font_size, EquationElem, Limits, MathContext, MathSize, Scaled,
+    glyphwise_tables, scaled_font_size, ssty_table, EquationElem, GlyphwiseSubsts,
+    Limits, MathContext, MathSize, Scaled,
 };
 use crate::model::{Destination, LinkElem};
 use crate::syntax::Span;
@@ -24,6 +26,7 @@ pub enum MathFragment {
     Space(Abs),
     Linebreak,
     Align,
+    Number(Location),
 }
 
 impl MathFragment {
@@ -78,6 +81,7 @@ impl MathFragment {
             Self::Space(_) => MathClass::Space,
             Self::Linebreak => MathClass::Space,
             Self::Align => MathClass::Special,
+            Self::Number(_) => MathClass::Special,
         }
     }
 
@@ -225,9 +229,11 @@ pub struct GlyphFragment {
 
 impl GlyphFragment {
     pub fn new(ctx: &MathContext, styles: StyleChain, c: char, span: Span) -> Self {
-        let id = ctx.ttf.glyph_index(c).unwrap_or_default();
-        let id = Self::adjust_glyph_index(ctx, id);
-        Self::with_id(ctx, styles, c, id, span)
+        let (font, c) =
+            ctx.select_font(styles, c).unwrap_or_else(|| (ctx.font.clone(), c));
+        let id = font.ttf().glyph_index(c).unwrap_or_default();
+        let id = Self::adjust_glyph_index(ctx, styles, &font, id);
+        Self::with_id(ctx, styles, font, c, id, span)
     }
 
     pub fn try_new(
@@ -236,14 +242,16 @@ impl GlyphFragment {
         c: char,
         span: Span,
     ) -> Option<Self> {
-        let id = ctx.ttf.glyph_index(c)?;
-        let id = Self::adjust_glyph_index(ctx, id);
-        Some(Self::with_id(ctx, styles, c, id, span))
+        let (font, c) = ctx.select_font(styles, c)?;
+        let id = font.ttf().glyph_index(c)?;
+        let id = Self::adjust_glyph_index(ctx, styles, &font, id);
+        Some(Self::with_id(ctx, styles, font, c, id, span))
     }
 
     pub fn with_id(
         ctx: &MathContext,
         styles: StyleChain,
+        font: Font,
         c: char,
         id: GlyphId,
         span: Span,
@@ -259,7 +267,7 @@ impl GlyphFragment {
         let mut fragment = Self {
             id,
             c,
-            font: ctx.font.clone(),
+            font,
             lang: TextElem::lang_in(styles),
             region: TextElem::region_in(styles),
             fill: TextElem::fill_in(styles).as_decoration(),
@@ -282,38 +290,52 @@ impl GlyphFragment {
     }
 
     /// Apply GSUB substitutions.
-    fn adjust_glyph_index(ctx: &MathContext, id: GlyphId) -> GlyphId {
-        if let Some(glyphwise_tables) = &ctx.glyphwise_tables {
-            glyphwise_tables.iter().fold(id, |id, table| table.apply(id))
+    fn adjust_glyph_index(
+        ctx: &MathContext,
+        styles: StyleChain,
+        font: &Font,
+        id: GlyphId,
+    ) -> GlyphId {
+        let apply = |tables: &[GlyphwiseSubsts]| {
+            tables.iter().fold(id, |id, table| table.apply(id))
+        };
+        if is_math_font(ctx, font) {
+            ctx.glyphwise_tables.as_deref().map_or(id, apply)
         } else {
-            id
+            glyphwise_tables(font, styles).as_deref().map_or(id, apply)
         }
     }
 
     /// Sets element id and boxes in appropriate way without changing other
     /// styles. This is used to replace the glyph with a stretch variant.
-    pub fn set_id(&mut self, ctx: &MathContext, id: GlyphId) {
-        let advance = ctx.ttf.glyph_hor_advance(id).unwrap_or_default();
-        let italics = italics_correction(ctx, id, self.font_size).unwrap_or_default();
-        let bbox = ctx.ttf.glyph_bounding_box(id).unwrap_or(Rect {
+    pub fn set_id(&mut self, _: &MathContext, id: GlyphId) {
+        let font = &self.font;
+        let ttf = font.ttf();
+        let scaled = |units: i16| font.to_em(units).at(self.font_size);
+        let advance = ttf.glyph_hor_advance(id).unwrap_or_default();
+        let italics = italics_correction(font, id, self.font_size).unwrap_or_default();
+        let bbox = ttf.glyph_bounding_box(id).unwrap_or(Rect {
             x_min: 0,
             y_min: 0,
             x_max: 0,
             y_max: 0,
         });
 
-        let mut width = advance.scaled(ctx, self.font_size);
+        let mut width = font.to_em(advance).at(self.font_size);
         let accent_attach =
-            accent_attach(ctx, id, self.font_size).unwrap_or((width + italics) / 2.0);
+            accent_attach(font, id, self.font_size).unwrap_or((width + italics) / 2.0);
 
-        if !is_extended_shape(ctx, id) {
+        if !is_extended_shape(font, id) {
             width += italics;
         }
 
+        let ascent = scaled(bbox.y_max);
+        let descent = -scaled(bbox.y_min);
+
         self.id = id;
         self.width = width;
-        self.ascent = bbox.y_max.scaled(ctx, self.font_size);
-        self.descent = -bbox.y_min.scaled(ctx, self.font_size);
+        self.ascent = ascent;
+        self.descent = descent;
         self.italics_correction = italics;
         self.accent_attach = accent_attach;
     }
@@ -364,20 +386,13 @@ impl GlyphFragment {
     }
 
     pub fn make_scriptsize(&mut self, ctx: &MathContext) {
-        let alt_id =
-            script_alternatives(ctx, self.id).and_then(|alts| alts.alternates.get(0));
-
-        if let Some(alt_id) = alt_id {
+        if let Some(alt_id) = script_alternative(ctx, &self.font, self.id, false) {
             self.set_id(ctx, alt_id);
         }
     }
 
     pub fn make_scriptscriptsize(&mut self, ctx: &MathContext) {
-        let alts = script_alternatives(ctx, self.id);
-        let alt_id = alts
-            .and_then(|alts| alts.alternates.get(1).or_else(|| alts.alternates.get(0)));
-
-        if let Some(alt_id) = alt_id {
+        if let Some(alt_id) = script_alternative(ctx, &self.font, self.id, true) {
             self.set_id(ctx, alt_id);
         }
     }
@@ -488,42 +503,59 @@ pub struct SpacingFragment {
     pub weak: bool,
 }
 
+/// Whether the font is the math font of the context.
+fn is_math_font(ctx: &MathContext, font: &Font) -> bool {
+    std::ptr::eq(font.ttf(), ctx.ttf)
+}
+
 /// Look up the italics correction for a glyph.
-fn italics_correction(ctx: &MathContext, id: GlyphId, font_size: Abs) -> Option<Abs> {
-    Some(
-        ctx.table
-            .glyph_info?
-            .italic_corrections?
-            .get(id)?
-            .scaled(ctx, font_size),
-    )
+fn italics_correction(font: &Font, id: GlyphId, font_size: Abs) -> Option<Abs> {
+    let value = font.ttf().tables().math?.glyph_info?.italic_corrections?.get(id)?;
+    Some(font.to_em(value.value).at(font_size))
 }
 
 /// Loop up the top accent attachment position for a glyph.
-fn accent_attach(ctx: &MathContext, id: GlyphId, font_size: Abs) -> Option<Abs> {
-    Some(
-        ctx.table
-            .glyph_info?
-            .top_accent_attachments?
-            .get(id)?
-            .scaled(ctx, font_size),
-    )
+fn accent_attach(font: &Font, id: GlyphId, font_size: Abs) -> Option<Abs> {
+    let value = font
+        .ttf()
+        .tables()
+        .math?
+        .glyph_info?
+        .top_accent_attachments?
+        .get(id)?;
+    Some(font.to_em(value.value).at(font_size))
 }
 
-/// Look up the script/scriptscript alternates for a glyph
-fn script_alternatives<'a>(
-    ctx: &MathContext<'a, '_, '_>,
+/// Look up the script or scriptscript alternate for a glyph.
+fn script_alternative(
+    ctx: &MathContext,
+    font: &Font,
     id: GlyphId,
-) -> Option<AlternateSet<'a>> {
-    ctx.ssty_table.and_then(|ssty| {
-        ssty.coverage.get(id).and_then(|index| ssty.alternate_sets.get(index))
-    })
+    scriptscript: bool,
+) -> Option<GlyphId> {
+    let lookup = |ssty: AlternateSubstitution| {
+        let index = ssty.coverage.get(id)?;
+        let alts = ssty.alternate_sets.get(index)?;
+        if scriptscript {
+            alts.alternates.get(1).or_else(|| alts.alternates.get(0))
+        } else {
+            alts.alternates.get(0)
+        }
+    };
+
+    if is_math_font(ctx, font) {
+        ctx.ssty_table.and_then(lookup)
+    } else {
+        ssty_table(font).and_then(lookup)
+    }
 }
 
 /// Look up whether a glyph is an extended shape.
-fn is_extended_shape(ctx: &MathContext, id: GlyphId) -> bool {
-    ctx.table
-        .glyph_info
+fn is_extended_shape(font: &Font, id: GlyphId) -> bool {
+    font.ttf()
+        .tables()
+        .math
+        .and_then(|math| math.glyph_info)
         .and_then(|info| info.extended_shapes)
         .and_then(|info| info.get(id))
         .is_some()
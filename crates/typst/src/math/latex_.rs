@@ -0,0 +1,1132 @@
+use ecow::{eco_format, EcoString};
+use unscanny::Scanner;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::eval::{eval_string, EvalMode};
+use crate::foundations::{func, Content, Scope};
+use crate::math::EquationElem;
+use crate::syntax::Spanned;
+
+/// Converts a LaTeX math formula into math content.
+///
+/// Supports the commands and environments of LaTeX and `amsmath` that are
+/// commonly used in formulas: fractions, roots, scripts, accents, alphabets,
+/// operators, delimiters, matrices, cases, and aligned equations. A formula
+/// with an unsupported command results in an error.
+///
+/// ```example
+/// #math.latex("\\frac{1}{2} \\sum_{i=0}^n x_i^2")
+///
+/// $ #math.latex(
+///   "\\mathbb{E}[X] = \\begin{pmatrix} a & b \\\\ c & d \\end{pmatrix}"
+/// ) $
+/// ```
+#[func(title = "LaTeX")]
+pub fn latex(
+    /// The engine.
+    engine: &mut Engine,
+    /// The LaTeX source of the formula, without the surrounding dollar signs.
+    source: Spanned<EcoString>,
+) -> SourceResult<Content> {
+    let Spanned { v: text, span } = source;
+    let converted = latex_to_typst(&text).at(span)?;
+    let content =
+        eval_string(engine.world, &converted, span, EvalMode::Math, Scope::new())?
+            .display();
+    Ok(match content.to_packed::<EquationElem>() {
+        Some(equation) => equation.body().clone(),
+        None => content,
+    })
+}
+
+/// Translates a LaTeX math formula into Typst math syntax.
+pub fn latex_to_typst(source: &str) -> StrResult<EcoString> {
+    let mut parser = Parser { s: Scanner::new(source) };
+    let converted = parser.sequence(Stop::End)?;
+    Ok(converted.into())
+}
+
+/// Where a sequence of LaTeX tokens ends.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Stop {
+    /// At the end of the formula.
+    End,
+    /// At a closing brace.
+    Brace,
+    /// At a closing bracket of an optional argument.
+    Bracket,
+    /// At a `\right` delimiter.
+    Right,
+    /// At an alignment point, row break or end of an environment.
+    Cell,
+}
+
+/// A converted part of a formula that scripts can attach to.
+struct Atom {
+    /// The Typst syntax for the atom.
+    text: String,
+    /// Whether the atom consists of multiple parts, so that scripts can't be
+    /// attached with `^` and `_`.
+    compound: bool,
+}
+
+impl Atom {
+    fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), compound: false }
+    }
+}
+
+/// Converts LaTeX to Typst syntax.
+struct Parser<'s> {
+    s: Scanner<'s>,
+}
+
+impl<'s> Parser<'s> {
+    /// Convert atoms until the given stop is reached.
+    fn sequence(&mut self, stop: Stop) -> StrResult<String> {
+        Ok(join(self.parts(stop)?))
+    }
+
+    /// Convert atoms until the given stop is reached, keeping them separate.
+    fn parts(&mut self, stop: Stop) -> StrResult<Vec<String>> {
+        let mut parts: Vec<String> = vec![];
+        loop {
+            self.skip_trivia();
+            if self.s.done() {
+                match stop {
+                    Stop::End => break,
+                    Stop::Brace => bail!("unclosed group in LaTeX formula"),
+                    Stop::Bracket => bail!("unclosed optional argument in LaTeX formula"),
+                    Stop::Right => bail!("missing `\\right` for `\\left`"),
+                    Stop::Cell => bail!("unclosed environment in LaTeX formula"),
+                }
+            }
+
+            match stop {
+                Stop::Brace if self.s.eat_if('}') => break,
+                Stop::Bracket if self.s.eat_if(']') => break,
+                Stop::Right if self.at_command("right") => break,
+                Stop::Cell
+                    if self.s.at('&') || self.s.at("\\\\") || self.at_command("end") =>
+                {
+                    break
+                }
+                _ => {}
+            }
+
+            // Style switches apply to the rest of the group.
+            if let Some(func) = self.style_switch() {
+                let rest = self.sequence(stop)?;
+                parts.push(format!("{func}({})", nonempty(rest)));
+                break;
+            }
+
+            let atom = self.atom(true)?;
+            let converted = self.scripts(atom)?;
+            if !converted.is_empty() {
+                parts.push(converted);
+            }
+        }
+
+        Ok(parts)
+    }
+
+    /// Convert the next atom. If `number` is false, only a single digit is
+    /// taken, as for arguments like in `\frac12`.
+    fn atom(&mut self, number: bool) -> StrResult<Atom> {
+        let Some(c) = self.s.eat() else {
+            bail!("unexpected end of LaTeX formula");
+        };
+
+        Ok(match c {
+            '{' => {
+                let parts = self.parts(Stop::Brace)?;
+                Atom { compound: parts.len() > 1, text: join(parts) }
+            }
+            '}' => bail!("unexpected closing brace in LaTeX formula"),
+            '\\' => return self.command(),
+            '0'..='9' if number => {
+                let start = self.s.cursor() - 1;
+                loop {
+                    self.s.eat_while(char::is_ascii_digit);
+                    if self.s.at('.')
+                        && self.s.after()[1..].starts_with(|c: char| c.is_ascii_digit())
+                    {
+                        self.s.eat();
+                        continue;
+                    }
+                    break;
+                }
+                Atom::new(self.s.from(start))
+            }
+            '^' | '_' => {
+                // A script without a base.
+                self.s.uneat();
+                Atom::new("")
+            }
+            '\'' => Atom::new("prime"),
+            '~' => Atom::new("space.nobreak"),
+            '&' => Atom::new("&"),
+            '#' | '$' => bail!("unexpected `{c}` in LaTeX formula"),
+            c => Atom::new(escape_char(c)),
+        })
+    }
+
+    /// Convert the scripts and primes attached to an atom.
+    fn scripts(&mut self, atom: Atom) -> StrResult<String> {
+        let mut sup = None;
+        let mut sub = None;
+        let mut primes = 0;
+        let mut limits = None;
+
+        loop {
+            self.skip_trivia();
+            if self.s.eat_if('^') {
+                if sup.is_some() {
+                    bail!("double superscript in LaTeX formula");
+                }
+                sup = Some(self.argument("^")?);
+            } else if self.s.eat_if('_') {
+                if sub.is_some() {
+                    bail!("double subscript in LaTeX formula");
+                }
+                sub = Some(self.argument("_")?);
+            } else if self.s.eat_if('\'') {
+                primes += 1;
+            } else if self.eat_command("limits") {
+                limits = Some(true);
+            } else if self.eat_command("nolimits") {
+                limits = Some(false);
+            } else {
+                break;
+            }
+        }
+
+        let mut base = atom.text;
+        let mut compound = atom.compound;
+        match limits {
+            Some(true) => base = format!("limits({})", nonempty(base)),
+            Some(false) => base = format!("scripts({})", nonempty(base)),
+            None => {}
+        }
+
+        if sup.is_none() && sub.is_none() && primes == 0 {
+            return Ok(base);
+        }
+
+        if base.is_empty() {
+            base = "\"\"".into();
+            compound = false;
+        }
+
+        if compound && limits.is_none() {
+            let mut top = vec!["prime"; primes].join(" ");
+            if let Some(sup) = sup {
+                top = if top.is_empty() { sup } else { format!("{top} {sup}") };
+            }
+            let mut args = vec![base];
+            if !top.is_empty() {
+                args.push(format!("t: {top}"));
+            }
+            if let Some(sub) = sub {
+                args.push(format!("b: {}", nonempty(sub)));
+            }
+            return Ok(format!("attach({})", args.join(", ")));
+        }
+
+        base.push_str(&"'".repeat(primes));
+        if let Some(sub) = sub {
+            base.push_str(&format!("_({sub})"));
+        }
+        if let Some(sup) = sup {
+            base.push_str(&format!("^({sup})"));
+        }
+        Ok(base)
+    }
+
+    /// Convert a command. The backslash is already eaten.
+    fn command(&mut self) -> StrResult<Atom> {
+        let name = self.command_name();
+        if let Some(symbol) = symbol(name) {
+            return Ok(Atom::new(symbol));
+        }
+
+        if let Some(op) = operator(name) {
+            return Ok(Atom::new(op));
+        }
+
+        if let Some(accent) = accent(name) {
+            let body = self.argument(name)?;
+            return Ok(Atom::new(format!("{accent}({})", nonempty(body))));
+        }
+
+        if let Some(funcs) = alphabet(name) {
+            let body = nonempty(self.argument(name)?);
+            let styled =
+                funcs.iter().rev().fold(body, |body, func| format!("{func}({body})"));
+            return Ok(Atom::new(styled));
+        }
+
+        if let Some(func) = text_style(name) {
+            let text = string(self.raw_argument(name)?);
+            return Ok(Atom::new(match func {
+                "" => text,
+                func => format!("{func}({text})"),
+            }));
+        }
+
+        if let Some(size) = big(name) {
+            let delim = self.delimiter(name)?;
+            return Ok(Atom::new(format!("lr({}, size: #{size})", nonempty(delim))));
+        }
+
+        Ok(Atom::new(match name {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let num = self.argument(name)?;
+                let denom = self.argument(name)?;
+                let frac = format!("frac({}, {})", nonempty(num), nonempty(denom));
+                match name {
+                    "dfrac" | "cfrac" => format!("display({frac})"),
+                    "tfrac" => format!("inline({frac})"),
+                    _ => frac,
+                }
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let n = self.argument(name)?;
+                let k = self.argument(name)?;
+                let binom = format!("binom({}, {})", nonempty(n), nonempty(k));
+                match name {
+                    "dbinom" => format!("display({binom})"),
+                    "tbinom" => format!("inline({binom})"),
+                    _ => binom,
+                }
+            }
+            "sqrt" => {
+                let index = self.optional()?;
+                let radicand = nonempty(self.argument(name)?);
+                match index {
+                    Some(index) => format!("root({}, {radicand})", nonempty(index)),
+                    None => format!("sqrt({radicand})"),
+                }
+            }
+            "overbrace" | "underbrace" | "overbracket" | "underbracket" => {
+                let body = nonempty(self.argument(name)?);
+                self.skip_trivia();
+                let script = if name.starts_with("over") { '^' } else { '_' };
+                if self.s.eat_if(script) {
+                    let annotation = self.argument(name)?;
+                    format!("{name}({body}, {})", nonempty(annotation))
+                } else {
+                    format!("{name}({body})")
+                }
+            }
+            "overset" | "underset" | "stackrel" => {
+                let annotation = self.argument(name)?;
+                let base = nonempty(self.argument(name)?);
+                let script = if name == "underset" { '_' } else { '^' };
+                format!("limits({base}){script}({annotation})")
+            }
+            "xrightarrow" | "xleftarrow" | "xleftrightarrow" | "xRightarrow"
+            | "xLeftarrow" | "xLeftrightarrow" | "xmapsto" => {
+                let below = self.optional()?;
+                let above = self.argument(name)?;
+                let sym = match name {
+                    "xleftarrow" => "arrow.l",
+                    "xleftrightarrow" => "arrow.l.r",
+                    "xRightarrow" => "arrow.r.double",
+                    "xLeftarrow" => "arrow.l.double",
+                    "xLeftrightarrow" => "arrow.l.r.double",
+                    "xmapsto" => "arrow.r.bar",
+                    _ => "arrow.r",
+                };
+                let mut args = format!("sym: {sym}");
+                if !above.trim().is_empty() {
+                    args.push_str(&format!(", {above}"));
+                }
+                if let Some(below) = below.filter(|below| !below.trim().is_empty()) {
+                    args.push_str(&format!(", below: {below}"));
+                }
+                format!("xarrow({args})")
+            }
+            "cancel" | "bcancel" | "xcancel" => {
+                let body = nonempty(self.argument(name)?);
+                match name {
+                    "bcancel" => format!("cancel({body}, inverted: #true)"),
+                    "xcancel" => format!("cancel({body}, cross: #true)"),
+                    _ => format!("cancel({body})"),
+                }
+            }
+            "operatorname" => {
+                let limits = self.s.eat_if('*');
+                let text = string(self.raw_argument(name)?);
+                if limits {
+                    format!("op({text}, limits: #true)")
+                } else {
+                    format!("op({text})")
+                }
+            }
+            "pmod" => {
+                let body = self.argument(name)?;
+                format!("quad \\( mod {body} \\)")
+            }
+            "mod" => "quad mod".into(),
+            "bmod" => "mod".into(),
+            "not" => {
+                let atom = self.atom(false)?;
+                match negated(&atom.text) {
+                    Some(symbol) => symbol.into(),
+                    None => bail!("cannot negate `{}` in LaTeX formula", atom.text),
+                }
+            }
+            "left" => {
+                let open = self.delimiter(name)?;
+                let body = self.sequence(Stop::Right)?;
+                self.eat_command("right");
+                let close = self.delimiter("right")?;
+                let parts = [open, body, close]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect();
+                format!("lr({})", nonempty(join(parts)))
+            }
+            "middle" => {
+                let delim = self.delimiter(name)?;
+                format!("mid({})", nonempty(delim))
+            }
+            "right" => bail!("missing `\\left` for `\\right`"),
+            "begin" => return self.environment(),
+            "end" => {
+                let env = self.raw_argument(name)?;
+                bail!("unexpected `\\end{{{env}}}` in LaTeX formula");
+            }
+            "hspace" => {
+                let length = self.raw_argument(name)?;
+                format!("#h({})", length_arg(length.trim())?)
+            }
+            "!" => "#h(-1em/6)".into(),
+            "\\" => "\\".into(),
+            "label" | "tag" => {
+                self.s.eat_if('*');
+                self.raw_argument(name)?;
+                String::new()
+            }
+            "nonumber" | "notag" | "hline" | "limits" | "nolimits" => String::new(),
+            "" => bail!("unexpected backslash at end of LaTeX formula"),
+            _ => bail!("unsupported LaTeX command `\\{name}`"),
+        }))
+    }
+
+    /// Convert the body of an environment. `\begin` is already eaten.
+    fn environment(&mut self) -> StrResult<Atom> {
+        let name = self.raw_argument("begin")?;
+        let delim = match name {
+            "matrix" | "smallmatrix" | "array" => Some("#none"),
+            "pmatrix" => Some("\"(\""),
+            "bmatrix" => Some("\"[\""),
+            "Bmatrix" => Some("\"{\""),
+            "vmatrix" => Some("\"|\""),
+            "Vmatrix" => Some("\"‖\""),
+            "cases" | "dcases" | "aligned" | "align" | "align*" | "alignedat"
+            | "alignat" | "alignat*" | "gathered" | "gather" | "gather*" | "split"
+            | "equation" | "equation*" | "multline" | "multline*" => None,
+            _ => bail!("unsupported LaTeX environment `{name}`"),
+        };
+
+        // Skip column specifications and alignment counts.
+        if matches!(name, "array" | "alignedat" | "alignat" | "alignat*") {
+            self.raw_argument(name)?;
+        }
+
+        let mut rows: Vec<Vec<String>> = vec![vec![]];
+        loop {
+            let cell = self.sequence(Stop::Cell)?;
+            rows.last_mut().unwrap().push(cell);
+            if self.s.eat_if('&') {
+                continue;
+            } else if self.s.eat_if("\\\\") {
+                // Skip the optional vertical space of a row break.
+                self.skip_trivia();
+                if self.s.at('[') {
+                    self.optional()?;
+                }
+                rows.push(vec![]);
+            } else {
+                self.eat_command("end");
+                let end = self.raw_argument("end")?;
+                if end != name {
+                    bail!("expected `\\end{{{name}}}`, found `\\end{{{end}}}`");
+                }
+                break;
+            }
+        }
+
+        // A trailing row break doesn't start a new row.
+        if rows.len() > 1 && rows.last().is_some_and(|row| row.concat().is_empty()) {
+            rows.pop();
+        }
+
+        let joined = |rows: Vec<Vec<String>>, cells: &str, lines: &str| {
+            rows.into_iter()
+                .map(|row| row.join(cells))
+                .collect::<Vec<_>>()
+                .join(lines)
+        };
+
+        let text = match (name, delim) {
+            (_, Some(delim)) => {
+                let rows = rows
+                    .into_iter()
+                    .map(|row| row.into_iter().map(nonempty).collect())
+                    .collect();
+                let mat = format!("mat(delim: {delim}, {})", joined(rows, ", ", "; "));
+                if name == "smallmatrix" {
+                    format!("inline({mat})")
+                } else {
+                    mat
+                }
+            }
+            ("cases" | "dcases", _) => {
+                let cases = format!("cases({})", joined(rows, " & ", ", "));
+                if name == "dcases" {
+                    format!("display({cases})")
+                } else {
+                    cases
+                }
+            }
+            _ => joined(rows, " & ", " \\ "),
+        };
+
+        Ok(Atom { text, compound: true })
+    }
+
+    /// Convert a required argument of a command.
+    fn argument(&mut self, command: &str) -> StrResult<String> {
+        self.skip_trivia();
+        if self.s.done() || self.s.at('}') {
+            bail!("missing argument for `{}`", display_command(command));
+        }
+        let atom = self.atom(false)?;
+        Ok(atom.text)
+    }
+
+    /// Convert an optional argument in brackets, if there is one.
+    fn optional(&mut self) -> StrResult<Option<String>> {
+        self.skip_trivia();
+        if !self.s.eat_if('[') {
+            return Ok(None);
+        }
+        self.sequence(Stop::Bracket).map(Some)
+    }
+
+    /// Take the unconverted text of a braced argument.
+    fn raw_argument(&mut self, command: &str) -> StrResult<&'s str> {
+        self.skip_trivia();
+        if !self.s.eat_if('{') {
+            bail!("missing argument for `{}`", display_command(command));
+        }
+
+        let start = self.s.cursor();
+        let mut depth = 0;
+        loop {
+            match self.s.eat() {
+                Some('\\') => {
+                    self.s.eat();
+                }
+                Some('{') => depth += 1,
+                Some('}') if depth == 0 => break,
+                Some('}') => depth -= 1,
+                Some(_) => {}
+                None => bail!("unclosed group in LaTeX formula"),
+            }
+        }
+
+        Ok(self.s.from(start).strip_suffix('}').unwrap_or_default())
+    }
+
+    /// Convert the delimiter after `\left`, `\right`, `\middle`, or `\big`.
+    fn delimiter(&mut self, command: &str) -> StrResult<String> {
+        self.skip_trivia();
+        let delim = match self.s.eat() {
+            Some('.') => String::new(),
+            Some('\\') => {
+                let name = self.command_name();
+                match symbol(name) {
+                    Some(symbol) => symbol.into(),
+                    None => bail!("unsupported delimiter `\\{name}`"),
+                }
+            }
+            Some(c) if !c.is_alphanumeric() && !matches!(c, '{' | '}') => escape_char(c),
+            _ => bail!("missing delimiter for `{}`", display_command(command)),
+        };
+        Ok(delim)
+    }
+
+    /// Convert a style switch like `\displaystyle` to the function that
+    /// applies the same style.
+    fn style_switch(&mut self) -> Option<&'static str> {
+        let func = [
+            ("displaystyle", "display"),
+            ("textstyle", "inline"),
+            ("scriptstyle", "script"),
+            ("scriptscriptstyle", "sscript"),
+            ("rm", "upright"),
+            ("it", "italic"),
+            ("bf", "bold"),
+            ("sf", "sans"),
+            ("tt", "mono"),
+            ("cal", "cal"),
+        ]
+        .into_iter()
+        .find(|&(name, _)| self.at_command(name))?
+        .1;
+        self.s.eat_if('\\');
+        self.command_name();
+        Some(func)
+    }
+
+    /// Eat the name of a command after the backslash.
+    fn command_name(&mut self) -> &'s str {
+        let start = self.s.cursor();
+        if self.s.eat_while(char::is_ascii_alphabetic).is_empty() {
+            self.s.eat();
+        }
+        self.s.from(start)
+    }
+
+    /// Whether the scanner is at the given command.
+    fn at_command(&self, name: &str) -> bool {
+        let rest = self.s.after();
+        rest.strip_prefix('\\')
+            .and_then(|rest| rest.strip_prefix(name))
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+    }
+
+    /// Eat the given command if the scanner is at it.
+    fn eat_command(&mut self, name: &str) -> bool {
+        let at = self.at_command(name);
+        if at {
+            self.s.jump(self.s.cursor() + 1 + name.len());
+        }
+        at
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            self.s.eat_whitespace();
+            if self.s.eat_if('%') {
+                self.s.eat_until('\n');
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// Join converted parts with spaces.
+///
+/// Typst keeps spaces next to fences like `|`, so these are joined without
+/// spaces to match LaTeX's spacing.
+fn join(parts: Vec<String>) -> String {
+    let mut joined = String::new();
+    for part in parts {
+        let fenced = joined.ends_with(['|', '‖'])
+            || part.starts_with("\\|")
+            || part.starts_with('‖');
+        if !joined.is_empty() && !fenced {
+            joined.push(' ');
+        }
+        joined.push_str(&part);
+    }
+    joined
+}
+
+/// Wrap empty content so that it can be passed as an argument.
+fn nonempty(text: String) -> String {
+    if text.is_empty() {
+        "\"\"".into()
+    } else {
+        text
+    }
+}
+
+/// Display a command name for an error message.
+fn display_command(command: &str) -> EcoString {
+    if command.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        eco_format!("\\{command}")
+    } else {
+        command.into()
+    }
+}
+
+/// Convert a single character that has no special meaning in LaTeX.
+fn escape_char(c: char) -> String {
+    match c {
+        '<' | '>' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '/' | ',' | ';' | '@'
+        | '`' | '"' | '\\' | '_' | '^' | '&' | '#' | '$' | '\'' | '~' => {
+            format!("\\{c}")
+        }
+        c => c.into(),
+    }
+}
+
+/// Convert text to a Typst string literal.
+fn string(text: &str) -> String {
+    let mut s = Scanner::new(text);
+    let mut string = String::from("\"");
+    while let Some(c) = s.eat() {
+        match c {
+            '{' | '}' => {}
+            '\\' => match s.eat() {
+                Some(c) if c.is_ascii_alphabetic() => {
+                    // Commands inside of text are dropped, but not their
+                    // arguments.
+                    s.eat_while(char::is_ascii_alphabetic);
+                    s.eat_whitespace();
+                }
+                Some('\\' | '"') => {}
+                Some(c) => string.push(c),
+                None => {}
+            },
+            '~' => string.push('\u{A0}'),
+            '"' => string.push_str("\\\""),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
+
+/// Convert a LaTeX length to a Typst length.
+fn length_arg(length: &str) -> StrResult<String> {
+    let unit = length
+        .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'));
+    let value = &length[..length.len() - unit.len()];
+    if value.parse::<f64>().is_err() || !matches!(unit, "pt" | "mm" | "cm" | "in" | "em")
+    {
+        bail!("unsupported length `{length}` in LaTeX formula");
+    }
+    Ok(format!("{value}{unit}"))
+}
+
+/// The Typst symbol for a LaTeX symbol command.
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        // Greek letters.
+        "alpha" => "alpha",
+        "beta" => "beta",
+        "gamma" => "gamma",
+        "delta" => "delta",
+        "epsilon" => "epsilon.alt",
+        "varepsilon" => "epsilon",
+        "zeta" => "zeta",
+        "eta" => "eta",
+        "theta" => "theta",
+        "vartheta" => "theta.alt",
+        "iota" => "iota",
+        "kappa" => "kappa",
+        "varkappa" => "kappa.alt",
+        "lambda" => "lambda",
+        "mu" => "mu",
+        "nu" => "nu",
+        "xi" => "xi",
+        "omicron" => "omicron",
+        "pi" => "pi",
+        "varpi" => "pi.alt",
+        "rho" => "rho",
+        "varrho" => "rho.alt",
+        "sigma" => "sigma",
+        "varsigma" => "sigma.alt",
+        "tau" => "tau",
+        "upsilon" => "upsilon",
+        "phi" => "phi.alt",
+        "varphi" => "phi",
+        "chi" => "chi",
+        "psi" => "psi",
+        "omega" => "omega",
+        "Gamma" => "Gamma",
+        "Delta" => "Delta",
+        "Theta" => "Theta",
+        "Lambda" => "Lambda",
+        "Xi" => "Xi",
+        "Pi" => "Pi",
+        "Sigma" => "Sigma",
+        "Upsilon" => "Upsilon",
+        "Phi" => "Phi",
+        "Psi" => "Psi",
+        "Omega" => "Omega",
+        "aleph" => "alef",
+        "beth" => "beth",
+        "gimel" => "gimel",
+        "daleth" => "daleth",
+
+        // Binary operators.
+        "pm" => "plus.minus",
+        "mp" => "minus.plus",
+        "times" => "times",
+        "div" => "div",
+        "cdot" => "dot.op",
+        "ast" => "ast.op",
+        "star" => "star.op",
+        "circ" => "compose",
+        "bullet" => "bullet",
+        "oplus" => "plus.circle",
+        "ominus" => "minus.circle",
+        "otimes" => "times.circle",
+        "odot" => "dot.circle",
+        "cap" => "sect",
+        "cup" => "union",
+        "sqcap" => "sect.sq",
+        "sqcup" => "union.sq",
+        "uplus" => "union.plus",
+        "wedge" | "land" => "and",
+        "vee" | "lor" => "or",
+        "setminus" | "smallsetminus" => "without",
+        "wr" => "wreath",
+        "amalg" => "product.co",
+        "dagger" => "dagger",
+        "ddagger" => "dagger.double",
+
+        // Relations.
+        "le" | "leq" => "lt.eq",
+        "ge" | "geq" => "gt.eq",
+        "leqslant" => "lt.eq.slant",
+        "geqslant" => "gt.eq.slant",
+        "ne" | "neq" => "eq.not",
+        "ll" => "lt.double",
+        "gg" => "gt.double",
+        "lesssim" => "lt.tilde",
+        "gtrsim" => "gt.tilde",
+        "equiv" => "equiv",
+        "approx" => "approx",
+        "sim" => "tilde.op",
+        "simeq" => "tilde.eq",
+        "cong" => "tilde.equiv",
+        "doteq" => "≐",
+        "triangleq" => "eq.delta",
+        "coloneqq" => "colon.eq",
+        "propto" => "prop",
+        "prec" => "prec",
+        "succ" => "succ",
+        "preceq" => "prec.eq",
+        "succeq" => "succ.eq",
+        "subset" => "subset",
+        "supset" => "supset",
+        "subseteq" => "subset.eq",
+        "supseteq" => "supset.eq",
+        "subsetneq" => "subset.neq",
+        "supsetneq" => "supset.neq",
+        "in" => "in",
+        "ni" => "in.rev",
+        "notin" => "in.not",
+        "mid" => "divides",
+        "nmid" => "divides.not",
+        "parallel" => "parallel",
+        "perp" => "perp",
+        "models" => "models",
+        "vdash" => "tack.r",
+        "dashv" => "tack.l",
+        "bowtie" => "join",
+
+        // Arrows.
+        "to" | "rightarrow" => "arrow.r",
+        "gets" | "leftarrow" => "arrow.l",
+        "leftrightarrow" => "arrow.l.r",
+        "Rightarrow" => "arrow.r.double",
+        "Leftarrow" => "arrow.l.double",
+        "Leftrightarrow" => "arrow.l.r.double",
+        "longrightarrow" => "arrow.r.long",
+        "longleftarrow" => "arrow.l.long",
+        "longleftrightarrow" => "arrow.l.r.long",
+        "Longrightarrow" | "implies" => "arrow.r.double.long",
+        "Longleftarrow" | "impliedby" => "arrow.l.double.long",
+        "Longleftrightarrow" | "iff" => "arrow.l.r.double.long",
+        "mapsto" => "arrow.r.bar",
+        "longmapsto" => "arrow.r.long.bar",
+        "hookrightarrow" => "arrow.r.hook",
+        "hookleftarrow" => "arrow.l.hook",
+        "twoheadrightarrow" => "arrow.r.twohead",
+        "leadsto" => "arrow.r.squiggly",
+        "uparrow" => "arrow.t",
+        "downarrow" => "arrow.b",
+        "updownarrow" => "arrow.t.b",
+        "Uparrow" => "arrow.t.double",
+        "Downarrow" => "arrow.b.double",
+        "Updownarrow" => "arrow.t.b.double",
+        "nearrow" => "arrow.tr",
+        "searrow" => "arrow.br",
+        "swarrow" => "arrow.bl",
+        "nwarrow" => "arrow.tl",
+        "rightharpoonup" => "harpoon.rt",
+        "rightharpoondown" => "harpoon.rb",
+        "leftharpoonup" => "harpoon.lt",
+        "leftharpoondown" => "harpoon.lb",
+        "rightleftharpoons" => "harpoons.rtlb",
+        "rightrightarrows" => "arrows.rr",
+        "leftleftarrows" => "arrows.ll",
+
+        // Large operators.
+        "sum" => "sum",
+        "prod" => "product",
+        "coprod" => "product.co",
+        "int" => "integral",
+        "iint" => "integral.double",
+        "iiint" => "integral.triple",
+        "oint" => "integral.cont",
+        "bigcup" => "union.big",
+        "bigcap" => "sect.big",
+        "bigsqcup" => "union.sq.big",
+        "biguplus" => "union.plus.big",
+        "bigoplus" => "plus.circle.big",
+        "bigotimes" => "times.circle.big",
+        "bigodot" => "dot.circle.big",
+        "bigvee" => "or.big",
+        "bigwedge" => "and.big",
+
+        // Delimiters.
+        "{" | "lbrace" => "\\{",
+        "}" | "rbrace" => "\\}",
+        "lbrack" => "\\[",
+        "rbrack" => "\\]",
+        "langle" => "angle.l",
+        "rangle" => "angle.r",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "vert" | "lvert" | "rvert" => "\\|",
+        "|" | "Vert" | "lVert" | "rVert" => "‖",
+        "backslash" => "backslash",
+
+        // Miscellaneous symbols.
+        "infty" => "infinity",
+        "partial" => "diff",
+        "nabla" => "nabla",
+        "forall" => "forall",
+        "exists" => "exists",
+        "nexists" => "exists.not",
+        "emptyset" | "varnothing" => "nothing",
+        "neg" | "lnot" => "not",
+        "top" => "top",
+        "bot" => "bot",
+        "angle" => "angle",
+        "ell" => "ell",
+        "hbar" | "hslash" => "planck.reduce",
+        "Re" => "Re",
+        "Im" => "Im",
+        "prime" => "prime",
+        "degree" => "degree",
+        "dots" | "ldots" => "dots.h",
+        "cdots" => "dots.h.c",
+        "vdots" => "dots.v",
+        "ddots" => "dots.down",
+        "therefore" => "therefore",
+        "because" => "because",
+        "square" | "Box" => "square.stroked",
+        "blacksquare" => "square.filled",
+        "triangle" => "triangle.stroked.t",
+        "clubsuit" => "suit.club",
+        "diamondsuit" => "suit.diamond",
+        "heartsuit" => "suit.heart",
+        "spadesuit" => "suit.spade",
+        "colon" => "colon",
+        "#" => "\\#",
+        "$" => "\\$",
+        "%" => "%",
+        "&" => "\\&",
+        "_" => "\\_",
+
+        // Spacing.
+        "," | "thinspace" => "thin",
+        ":" | ">" | "medspace" => "med",
+        ";" | "thickspace" => "thick",
+        "quad" => "quad",
+        "qquad" => "wide",
+        " " => "space",
+        "enspace" => "space.en",
+        _ => return None,
+    })
+}
+
+/// The Typst operator for a LaTeX operator command.
+fn operator(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "arccos" => "arccos",
+        "arcsin" => "arcsin",
+        "arctan" => "arctan",
+        "arg" => "arg",
+        "cos" => "cos",
+        "cosh" => "cosh",
+        "cot" => "cot",
+        "coth" => "coth",
+        "csc" => "csc",
+        "deg" => "deg",
+        "det" => "det",
+        "dim" => "dim",
+        "exp" => "exp",
+        "gcd" => "gcd",
+        "hom" => "hom",
+        "inf" => "inf",
+        "ker" => "ker",
+        "lg" => "lg",
+        "lim" => "lim",
+        "liminf" => "liminf",
+        "limsup" => "limsup",
+        "ln" => "ln",
+        "log" => "log",
+        "max" => "max",
+        "min" => "min",
+        "Pr" => "Pr",
+        "sec" => "sec",
+        "sin" => "sin",
+        "sinh" => "sinh",
+        "sup" => "sup",
+        "tan" => "tan",
+        "tanh" => "tanh",
+        _ => return None,
+    })
+}
+
+/// The Typst accent for a LaTeX accent command.
+fn accent(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "hat" | "widehat" => "hat",
+        "tilde" | "widetilde" => "tilde",
+        "bar" => "macron",
+        "vec" | "overrightarrow" => "arrow",
+        "overleftarrow" => "arrow.l",
+        "overleftrightarrow" => "arrow.l.r",
+        "dot" => "dot",
+        "ddot" => "dot.double",
+        "dddot" => "dot.triple",
+        "acute" => "acute",
+        "grave" => "grave",
+        "breve" => "breve",
+        "check" => "caron",
+        "mathring" => "circle",
+        "overline" => "overline",
+        "underline" => "underline",
+        _ => return None,
+    })
+}
+
+/// The Typst functions for a LaTeX alphabet command, from outermost to
+/// innermost.
+fn alphabet(name: &str) -> Option<&'static [&'static str]> {
+    Some(match name {
+        "mathbb" => &["bb"],
+        "mathbf" => &["upright", "bold"],
+        "mathit" => &["italic"],
+        "mathrm" => &["upright"],
+        "mathsf" => &["upright", "sans"],
+        "mathtt" => &["mono"],
+        "mathcal" | "mathscr" => &["cal"],
+        "mathfrak" => &["frak"],
+        "boldsymbol" | "bm" => &["bold"],
+        _ => return None,
+    })
+}
+
+/// The Typst function for a LaTeX text command. Returns an empty string if
+/// the text is unstyled.
+fn text_style(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "text" | "textrm" | "textup" | "textnormal" | "mbox" | "hbox" => "",
+        "textbf" => "bold",
+        "textit" | "emph" => "italic",
+        "textsf" => "sans",
+        "texttt" => "mono",
+        _ => return None,
+    })
+}
+
+/// The size of the delimiter for a LaTeX `\big` command.
+fn big(name: &str) -> Option<&'static str> {
+    Some(match name.trim_end_matches(['l', 'r', 'm']) {
+        "big" => "1.2em",
+        "Big" => "1.8em",
+        "bigg" => "2.4em",
+        "Bigg" => "3em",
+        _ => return None,
+    })
+}
+
+/// The negated Typst symbol for a symbol preceded by LaTeX's `\not`.
+fn negated(symbol: &str) -> Option<&'static str> {
+    Some(match symbol {
+        "=" => "eq.not",
+        "\\<" => "lt.not",
+        "\\>" => "gt.not",
+        "lt.eq" => "lt.eq.not",
+        "gt.eq" => "gt.eq.not",
+        "equiv" => "equiv.not",
+        "approx" => "approx.not",
+        "tilde.op" => "tilde.not",
+        "tilde.equiv" => "tilde.equiv.not",
+        "in" => "in.not",
+        "in.rev" => "in.rev.not",
+        "subset" => "subset.not",
+        "supset" => "supset.not",
+        "subset.eq" => "subset.eq.not",
+        "supset.eq" => "supset.eq.not",
+        "divides" => "divides.not",
+        "parallel" => "parallel.not",
+        "exists" => "exists.not",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn test(latex: &str, typst: &str) {
+        assert_eq!(latex_to_typst(latex).unwrap(), typst);
+    }
+
+    #[test]
+    fn test_latex_to_typst() {
+        test("ab + 12.5", "a b + 12.5");
+        test("\\frac12", "frac(1, 2)");
+        test("\\frac{a+b}{c}", "frac(a + b, c)");
+        test("\\sum_{i=0}^n x_i^2", "sum_(i = 0)^(n) x_(i)^(2)");
+        test("f'(x)", "f' \\( x \\)");
+        test("{ab}^2", "attach(a b, t: 2)");
+        test("\\sqrt[3]{x}", "root(3, x)");
+        test("\\mathbf{v} \\cdot \\mathbb{R}", "upright(bold(v)) dot.op bb(R)");
+        test("\\left( \\frac{a}{b} \\right.", "lr(\\( frac(a, b))");
+        test("\\text{if } x < 0", "\"if \" x \\< 0");
+        test("\\operatorname*{argmax}_x", "op(\"argmax\", limits: #true)_(x)");
+        test("\\not\\in", "in.not");
+        test("\\xrightarrow{f}", "xarrow(sym: arrow.r, f)");
+        test("\\xLeftarrow[n]{}", "xarrow(sym: arrow.l.double, below: n)");
+        test("a,b", "a \\, b");
+        test("|x|_2 + \\|y\\|", "\\|x\\|_(2) +‖y‖");
+        test(
+            "\\begin{pmatrix} a & b \\\\ c & d \\\\ \\end{pmatrix}",
+            "mat(delim: \"(\", a, b; c, d)",
+        );
+        test(
+            "\\begin{cases} 1 & x > 0 \\\\ 0 & \\text{else} \\end{cases}",
+            "cases(1 & x \\> 0, 0 & \"else\")",
+        );
+        test("x &= 1 \\\\ y &= 2", "x & = 1 \\ y & = 2");
+        test("\\displaystyle \\frac{1}{2}", "display(frac(1, 2))");
+    }
+
+    #[test]
+    fn test_latex_to_typst_errors() {
+        let error = |latex| latex_to_typst(latex).unwrap_err();
+        assert_eq!(error("\\foo"), "unsupported LaTeX command `\\foo`");
+        assert_eq!(error("{a"), "unclosed group in LaTeX formula");
+        assert_eq!(error("\\frac{a}"), "missing argument for `\\frac`");
+        assert_eq!(error("\\left( a"), "missing `\\right` for `\\left`");
+        assert_eq!(error("x^1^2"), "double superscript in LaTeX formula");
+        assert_eq!(
+            error("\\begin{matrix} a \\end{array}"),
+            "expected `\\end{matrix}`, found `\\end{array}`",
+        );
+    }
+}
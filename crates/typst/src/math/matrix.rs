@@ -413,12 +413,12 @@ impl DelimiterPair {
     };
 
     /// The delimiter's opening character.
-    fn open(self) -> Option<char> {
+    pub fn open(self) -> Option<char> {
         self.open.get()
     }
 
     /// The delimiter's closing character.
-    fn close(self) -> Option<char> {
+    pub fn close(self) -> Option<char> {
         self.close.get()
     }
 }
@@ -4,13 +4,17 @@
 mod ctx;
 mod accent;
 mod align;
+mod arrow;
 mod attach;
 mod cancel;
+mod chem;
 #[path = "class.rs"]
 mod class_;
+mod diagram;
 mod equation;
 mod frac;
 mod fragment;
+mod latex_;
 mod lr;
 mod matrix;
 mod op;
@@ -23,11 +27,15 @@ mod underover;
 
 pub use self::accent::*;
 pub use self::align::*;
+pub use self::arrow::*;
 pub use self::attach::*;
 pub use self::cancel::*;
+pub use self::chem::*;
 pub use self::class_::*;
+pub use self::diagram::*;
 pub use self::equation::*;
 pub use self::frac::*;
+pub use self::latex_::*;
 pub use self::lr::*;
 pub use self::matrix::*;
 pub use self::op::*;
@@ -160,6 +168,7 @@ pub fn module() -> Module {
     let mut math = Scope::deduplicating();
     math.category(MATH);
     math.define_elem::<EquationElem>();
+    math.define_elem::<NumberElem>();
     math.define_elem::<TextElem>();
     math.define_elem::<LrElem>();
     math.define_elem::<MidElem>();
@@ -174,11 +183,15 @@ pub fn module() -> Module {
     math.define_elem::<UnderbracketElem>();
     math.define_elem::<OverbracketElem>();
     math.define_elem::<CancelElem>();
+    math.define_elem::<CeElem>();
+    math.define_elem::<XArrowElem>();
     math.define_elem::<FracElem>();
     math.define_elem::<BinomElem>();
     math.define_elem::<VecElem>();
     math.define_elem::<MatElem>();
     math.define_elem::<CasesElem>();
+    math.define_elem::<DiagramElem>();
+    math.define_elem::<EdgeElem>();
     math.define_elem::<RootElem>();
     math.define_elem::<ClassElem>();
     math.define_elem::<OpElem>();
@@ -202,6 +215,7 @@ pub fn module() -> Module {
     math.define_func::<inline>();
     math.define_func::<script>();
     math.define_func::<sscript>();
+    math.define_func::<latex>();
 
     // Text operators, spacings, and symbols.
     op::define(&mut math);
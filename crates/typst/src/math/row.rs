@@ -1,9 +1,11 @@
 use std::iter::once;
+use std::ops::Range;
 
 use unicode_math_class::MathClass;
 
-use crate::foundations::{Resolve, StyleChain};
-use crate::layout::{Abs, AlignElem, Em, Frame, Point, Size};
+use crate::foundations::{Resolve, Smart, StyleChain};
+use crate::introspection::Location;
+use crate::layout::{Abs, AlignElem, Em, FixedAlignment, Frame, Point, Size};
 use crate::math::{
     alignments, scaled_font_size, spacing, EquationElem, FrameFragment, MathContext,
     MathFragment, MathParItem, MathSize,
@@ -44,8 +46,8 @@ impl MathRun {
                     continue;
                 }
 
-                // Alignment points are resolved later.
-                MathFragment::Align => {
+                // Alignment points and line numbers are resolved later.
+                MathFragment::Align | MathFragment::Number(_) => {
                     resolved.push(fragment);
                     continue;
                 }
@@ -161,6 +163,27 @@ impl MathRun {
         self,
         ctx: &MathContext,
         styles: StyleChain,
+    ) -> MathRunFrameBuilder {
+        self.frame_builder(ctx, styles, None)
+    }
+
+    /// Like [`multiline_frame_builder`](Self::multiline_frame_builder), but
+    /// additionally breaks rows that don't fit into the given width before
+    /// relation and binary operators.
+    pub fn broken_frame_builder(
+        self,
+        ctx: &MathContext,
+        styles: StyleChain,
+        width: Abs,
+    ) -> MathRunFrameBuilder {
+        self.frame_builder(ctx, styles, Some(width))
+    }
+
+    fn frame_builder(
+        self,
+        ctx: &MathContext,
+        styles: StyleChain,
+        width: Option<Abs>,
     ) -> MathRunFrameBuilder {
         let rows: Vec<_> = self.rows();
         let row_count = rows.len();
@@ -173,29 +196,66 @@ impl MathRun {
             TIGHT_LEADING.at(font_size)
         };
 
-        let align = AlignElem::alignment_in(styles).resolve(styles).x;
-        let mut frames: Vec<(Frame, Point)> = vec![];
-        let mut size = Size::zero();
+        // Lay out each row into one or more lines, along with the width of the
+        // block spanned by them and the row's number marker.
+        let mut blocks = vec![];
         for (i, row) in rows.into_iter().enumerate() {
             if i == row_count - 1 && row.0.is_empty() {
                 continue;
             }
 
-            let sub = row.into_line_frame(&alignments.points, LeftRightAlternator::Right);
-            if i > 0 {
-                size.y += leading;
-            }
+            let number = row.iter().find_map(|fragment| match fragment {
+                MathFragment::Number(location) => Some(*location),
+                _ => None,
+            });
+
+            let lines = match width {
+                Some(width) => row.into_broken_lines(&alignments.points, width, styles),
+                None => {
+                    let sub = row
+                        .into_line_frame(&alignments.points, LeftRightAlternator::Right);
+                    vec![(sub, Abs::zero())]
+                }
+            };
+
+            let block_width = lines
+                .iter()
+                .map(|(sub, offset)| *offset + sub.width())
+                .max()
+                .unwrap_or_default();
+            blocks.push((lines, block_width, number));
+        }
 
-            let mut pos = Point::with_y(size.y);
+        let align = AlignElem::alignment_in(styles).resolve(styles).x;
+        let total_width =
+            blocks.iter().map(|&(_, width, _)| width).max().unwrap_or_default();
+        let mut frames: Vec<(Frame, Point)> = vec![];
+        let mut numbers = vec![];
+        let mut size = Size::zero();
+        for (lines, block_width, number) in blocks {
+            let mut x = Abs::zero();
             if alignments.points.is_empty() {
-                pos.x = align.position(alignments.width - sub.width());
+                x = align.position(total_width - block_width);
+            }
+
+            let start = frames.len();
+            for (sub, offset) in lines {
+                if !frames.is_empty() {
+                    size.y += leading;
+                }
+
+                let pos = Point::new(x + offset, size.y);
+                size.x.set_max(pos.x + sub.width());
+                size.y += sub.height();
+                frames.push((sub, pos));
+            }
+
+            if let Some(location) = number {
+                numbers.push((start..frames.len(), location));
             }
-            size.x.set_max(sub.width());
-            size.y += sub.height();
-            frames.push((sub, pos));
         }
 
-        MathRunFrameBuilder { size, frames }
+        MathRunFrameBuilder { size, frames, numbers }
     }
 
     /// Lay out [`MathFragment`]s into a one-row [`Frame`], using the
@@ -203,12 +263,148 @@ impl MathRun {
     pub fn into_line_frame(
         self,
         points: &[Abs],
-        mut alternator: LeftRightAlternator,
+        alternator: LeftRightAlternator,
     ) -> Frame {
-        let ascent = self.ascent();
-        let mut frame = Frame::soft(Size::new(Abs::zero(), ascent + self.descent()));
-        frame.set_baseline(ascent);
+        let (xs, width) = self.positions(points, alternator);
+        positioned_frame(self.0.into_iter().zip(xs).collect(), width)
+    }
 
+    /// Lay out [`MathFragment`]s into one or more lines, breaking the row
+    /// before relation and binary operators if it doesn't fit into the given
+    /// width. Returns the lines along with their horizontal offsets.
+    fn into_broken_lines(
+        self,
+        points: &[Abs],
+        width: Abs,
+        styles: StyleChain,
+    ) -> Vec<(Frame, Abs)> {
+        let (xs, end) = self.positions(points, LeftRightAlternator::Right);
+        let fragments = &self.0;
+
+        let is_space = |fragment: &MathFragment| {
+            matches!(fragment, MathFragment::Space(_) | MathFragment::Spacing(_))
+        };
+
+        // Breaking is allowed before binary operators and before the first of
+        // a sequence of relations, but not within delimiters.
+        let mut depth = 0usize;
+        let mut prev = None;
+        let breakable: Vec<bool> = fragments
+            .iter()
+            .map(|fragment| {
+                let class = fragment.class();
+                let allowed = depth == 0
+                    && match class {
+                        MathClass::Binary => true,
+                        MathClass::Relation => prev != Some(MathClass::Relation),
+                        _ => false,
+                    };
+                match class {
+                    MathClass::Opening => depth += 1,
+                    MathClass::Closing => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                if !is_space(fragment) {
+                    prev = Some(class);
+                }
+                allowed
+            })
+            .collect();
+
+        // Find the last break opportunity before the line starting at `start`
+        // overflows the available width.
+        let next_break = |start: usize, available: Abs| {
+            let origin = if start == 0 { Abs::zero() } else { xs[start] };
+            let mut candidate = None;
+            let mut has_content = false;
+            for (i, fragment) in fragments.iter().enumerate().skip(start) {
+                if has_content && breakable[i] {
+                    candidate = Some(i);
+                }
+
+                if !available.fits(xs[i] + fragment.width() - origin)
+                    && candidate.is_some()
+                {
+                    return candidate;
+                }
+
+                has_content |= !is_space(fragment)
+                    && !matches!(fragment, MathFragment::Align | MathFragment::Number(_));
+            }
+            None
+        };
+
+        let Some(first) = next_break(0, width) else {
+            return vec![(
+                positioned_frame(self.0.into_iter().zip(xs).collect(), end),
+                Abs::zero(),
+            )];
+        };
+
+        let indent = EquationElem::continuation_indent_in(styles);
+        let align = EquationElem::continuation_align_in(styles)
+            .map(|align| align.resolve(styles));
+        let anchor = match align {
+            Smart::Auto => fragments[..first]
+                .iter()
+                .position(|fragment| fragment.class() == MathClass::Relation)
+                .map(|i| xs[i])
+                .filter(|&x| x < width / 2.0)
+                .unwrap_or_default(),
+            Smart::Custom(_) => Abs::zero(),
+        };
+
+        let mut breaks = vec![first];
+        while let Some(next) =
+            next_break(breaks[breaks.len() - 1], width - anchor - indent)
+        {
+            breaks.push(next);
+        }
+        breaks.push(fragments.len());
+
+        let mut lines = vec![];
+        let mut iter = self.0.into_iter().zip(xs);
+        let mut start = 0;
+        for (k, end) in breaks.into_iter().enumerate() {
+            let mut line: Vec<_> = iter.by_ref().take(end - start).collect();
+            start = end;
+
+            // Spacing in front of the operator we broke at is not needed.
+            while line.last().is_some_and(|(fragment, _)| is_space(fragment)) {
+                line.pop();
+            }
+
+            let origin = match line.first() {
+                Some(&(_, x)) if k > 0 => x,
+                _ => Abs::zero(),
+            };
+            let line_end =
+                line.last().map_or(origin, |(fragment, x)| *x + fragment.width());
+            for (_, x) in &mut line {
+                *x -= origin;
+            }
+
+            let sub = positioned_frame(line, line_end - origin);
+            let offset = match align {
+                _ if k == 0 => Abs::zero(),
+                Smart::Custom(FixedAlignment::End) => {
+                    (width - indent - sub.width()).max(Abs::zero())
+                }
+                _ => anchor + indent,
+            };
+            lines.push((sub, offset));
+        }
+
+        lines
+    }
+
+    /// Compute the horizontal position of each fragment when laid out into one
+    /// row using the given alignment points, along with the row's width.
+    fn positions(
+        &self,
+        points: &[Abs],
+        mut alternator: LeftRightAlternator,
+    ) -> (Vec<Abs>, Abs) {
         let mut next_x = {
             let widths: Vec<Abs> = if points.is_empty() {
                 vec![]
@@ -235,20 +431,16 @@ impl MathRun {
         };
         let mut x = next_x().unwrap_or_default();
 
-        for fragment in self.0.into_iter() {
+        let mut xs = Vec::with_capacity(self.0.len());
+        for fragment in self.iter() {
             if matches!(fragment, MathFragment::Align) {
                 x = next_x().unwrap_or(x);
-                continue;
             }
-
-            let y = ascent - fragment.ascent();
-            let pos = Point::new(x, y);
+            xs.push(x);
             x += fragment.width();
-            frame.push_frame(pos, fragment.into_frame());
         }
 
-        frame.size_mut().x = x;
-        frame
+        (xs, x)
     }
 
     pub fn into_par_items(self) -> Vec<MathParItem> {
@@ -338,6 +530,26 @@ impl MathRun {
     }
 }
 
+/// Lay out positioned [`MathFragment`]s into a one-row [`Frame`] of the given
+/// width.
+fn positioned_frame(fragments: Vec<(MathFragment, Abs)>, width: Abs) -> Frame {
+    let ascent = fragments.iter().map(|(f, _)| f.ascent()).max().unwrap_or_default();
+    let descent = fragments.iter().map(|(f, _)| f.descent()).max().unwrap_or_default();
+    let mut frame = Frame::soft(Size::new(width, ascent + descent));
+    frame.set_baseline(ascent);
+
+    for (fragment, x) in fragments {
+        if matches!(fragment, MathFragment::Align | MathFragment::Number(_)) {
+            continue;
+        }
+
+        let y = ascent - fragment.ascent();
+        frame.push_frame(Point::new(x, y), fragment.into_frame());
+    }
+
+    frame
+}
+
 impl<T: Into<MathFragment>> From<T> for MathRun {
     fn from(fragment: T) -> Self {
         Self(vec![fragment.into()])
@@ -374,6 +586,9 @@ pub struct MathRunFrameBuilder {
     /// Each row's frame, and the position where the frame should
     /// be pushed into the resulting frame.
     pub frames: Vec<(Frame, Point)>,
+    /// For each row with a number marker, the range of frames the row was
+    /// laid out into and the location of the marker.
+    pub numbers: Vec<(Range<usize>, Location)>,
 }
 
 impl MathRunFrameBuilder {
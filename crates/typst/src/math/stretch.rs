@@ -1,8 +1,9 @@
 use ttf_parser::math::{GlyphAssembly, GlyphConstruction, GlyphPart};
 use ttf_parser::LazyArray16;
 
-use crate::layout::{Abs, Frame, Point, Size};
+use crate::layout::{Abs, Em, Frame, FrameItem, Point, Size};
 use crate::math::{GlyphFragment, MathContext, Scaled, VariantFragment};
+use crate::visualize::{FixedStroke, Geometry, LineCap, LineJoin, Path};
 
 /// Maximum number of times extenders can be repeated.
 const MAX_REPEATS: usize = 1024;
@@ -41,11 +42,14 @@ fn stretch_glyph(
 ) -> VariantFragment {
     let short_target = target - short_fall;
     let mut min_overlap = Abs::zero();
-    let construction = ctx
-        .table
-        .variants
+    let font = base.font.clone();
+    let construction = font
+        .ttf()
+        .tables()
+        .math
+        .and_then(|math| math.variants)
         .and_then(|variants| {
-            min_overlap = variants.min_connector_overlap.scaled(ctx, base.font_size);
+            min_overlap = font.to_em(variants.min_connector_overlap).at(base.font_size);
             if horizontal {
                 variants.horizontal_constructions
             } else {
@@ -72,8 +76,14 @@ fn stretch_glyph(
         }
     }
 
-    // This is either good or the best we've got.
+    // This is either good or the best we've got, unless we can draw the
+    // glyph ourselves.
     if short_target <= best_advance || construction.assembly.is_none() {
+        if short_target > best_advance {
+            if let Some(drawn) = draw(ctx, &base, target, horizontal) {
+                return drawn;
+            }
+        }
         base.set_id(ctx, best_id);
         return base.into_variant();
     }
@@ -106,12 +116,12 @@ fn assemble(
         let mut growable = Abs::zero();
 
         while let Some(part) = parts.next() {
-            let mut advance = part.full_advance.scaled(ctx, base.font_size);
+            let mut advance = base.font.to_em(part.full_advance).at(base.font_size);
             if let Some(next) = parts.peek() {
-                let max_overlap = part
-                    .end_connector_length
-                    .min(next.start_connector_length)
-                    .scaled(ctx, base.font_size);
+                let max_overlap = base
+                    .font
+                    .to_em(part.end_connector_length.min(next.start_connector_length))
+                    .at(base.font_size);
 
                 advance -= max_overlap;
                 growable += max_overlap - min_overlap;
@@ -136,12 +146,12 @@ fn assemble(
     let mut selected = vec![];
     let mut parts = parts(assembly, repeat).peekable();
     while let Some(part) = parts.next() {
-        let mut advance = part.full_advance.scaled(ctx, base.font_size);
+        let mut advance = base.font.to_em(part.full_advance).at(base.font_size);
         if let Some(next) = parts.peek() {
-            let max_overlap = part
-                .end_connector_length
-                .min(next.start_connector_length)
-                .scaled(ctx, base.font_size);
+            let max_overlap = base
+                .font
+                .to_em(part.end_connector_length.min(next.start_connector_length))
+                .at(base.font_size);
             advance -= max_overlap;
             advance += ratio * (max_overlap - min_overlap);
         }
@@ -204,3 +214,256 @@ fn parts(assembly: GlyphAssembly, repeat: usize) -> impl Iterator<Item = GlyphPa
         std::iter::repeat(part).take(count)
     })
 }
+
+/// Draw a stretched delimiter or arrow with lines, for glyphs that the font
+/// can't stretch far enough.
+fn draw(
+    ctx: &MathContext,
+    base: &GlyphFragment,
+    target: Abs,
+    horizontal: bool,
+) -> Option<VariantFragment> {
+    let em = |v: f64| Em::new(v).at(base.font_size);
+    let thickness = ctx.constants.fraction_rule_thickness().scaled(ctx, base.font_size);
+    let axis = ctx.constants.axis_height().scaled(ctx, base.font_size);
+
+    let (size, baseline, lines, cap) = if horizontal {
+        // Arrows are drawn around the math axis.
+        let lines = arrow_lines(base.c, target, &em)?;
+        let reach = em(0.3) + thickness;
+        let ascent = base.ascent.max(axis + reach);
+        let descent = base.descent.max(reach - axis);
+        let lines = lines
+            .into_iter()
+            .map(|line| {
+                line.into_iter().map(|p| p + Point::with_y(ascent - axis)).collect()
+            })
+            .collect();
+        (Size::new(target, ascent + descent), ascent, lines, LineCap::Round)
+    } else {
+        let (width, lines) = delimiter_lines(base.c, target, thickness, &em)?;
+        (Size::new(width, target), target / 2.0 + axis, lines, LineCap::Butt)
+    };
+
+    let stroke = FixedStroke {
+        paint: base.fill.clone(),
+        thickness,
+        cap,
+        join: if cap == LineCap::Round { LineJoin::Round } else { LineJoin::Miter },
+        ..FixedStroke::default()
+    };
+
+    let mut frame = Frame::soft(size);
+    frame.set_baseline(baseline);
+    for line in lines {
+        let mut path = Path::new();
+        let mut points = line.into_iter();
+        path.move_to(points.next()?);
+        for point in points {
+            path.line_to(point);
+        }
+        let shape = Geometry::Path(path).stroked(stroke.clone());
+        frame.push(Point::zero(), FrameItem::Shape(shape, base.span));
+    }
+    frame.post_process_raw(base.dests.clone(), base.hidden);
+
+    let accent_attach = if horizontal { size.x / 2.0 } else { base.accent_attach };
+
+    Some(VariantFragment {
+        c: base.c,
+        id: None,
+        frame,
+        font_size: base.font_size,
+        italics_correction: Abs::zero(),
+        accent_attach,
+        class: base.class,
+        math_size: base.math_size,
+        span: base.span,
+        limits: base.limits,
+        mid_stretched: None,
+    })
+}
+
+/// The lines of a vertical delimiter with the given height, along with its
+/// width.
+fn delimiter_lines(
+    c: char,
+    height: Abs,
+    thickness: Abs,
+    em: &dyn Fn(f64) -> Abs,
+) -> Option<(Abs, Vec<Vec<Point>>)> {
+    let bearing = em(0.1);
+    let top = thickness / 2.0;
+    let bottom = height - thickness / 2.0;
+    let point = |x: Abs, y: Abs| Point::new(bearing + x, y);
+
+    // Opening delimiters; closing ones are mirrored.
+    let (arm, lines) = match c {
+        '[' | ']' => {
+            let arm = em(0.25);
+            (
+                arm,
+                vec![vec![
+                    point(arm, top),
+                    point(Abs::zero(), top),
+                    point(Abs::zero(), bottom),
+                    point(arm, bottom),
+                ]],
+            )
+        }
+        '⟦' | '⟧' => {
+            let arm = em(0.3);
+            let inner = em(0.12);
+            (
+                arm,
+                vec![
+                    vec![
+                        point(arm, top),
+                        point(Abs::zero(), top),
+                        point(Abs::zero(), bottom),
+                        point(arm, bottom),
+                    ],
+                    vec![point(inner, top), point(inner, bottom)],
+                ],
+            )
+        }
+        '⌈' | '⌉' => {
+            let arm = em(0.25);
+            (
+                arm,
+                vec![vec![
+                    point(arm, top),
+                    point(Abs::zero(), top),
+                    point(Abs::zero(), height),
+                ]],
+            )
+        }
+        '⌊' | '⌋' => {
+            let arm = em(0.25);
+            (
+                arm,
+                vec![vec![
+                    point(Abs::zero(), Abs::zero()),
+                    point(Abs::zero(), bottom),
+                    point(arm, bottom),
+                ]],
+            )
+        }
+        '⟨' | '⟩' => {
+            let arm = (height * 0.12).clamp(em(0.3), em(0.6));
+            (
+                arm,
+                vec![vec![
+                    point(arm, Abs::zero()),
+                    point(Abs::zero(), height / 2.0),
+                    point(arm, height),
+                ]],
+            )
+        }
+        '|' => (
+            Abs::zero(),
+            vec![vec![point(Abs::zero(), Abs::zero()), point(Abs::zero(), height)]],
+        ),
+        '‖' => {
+            let gap = em(0.15);
+            (
+                gap,
+                vec![
+                    vec![point(Abs::zero(), Abs::zero()), point(Abs::zero(), height)],
+                    vec![point(gap, Abs::zero()), point(gap, height)],
+                ],
+            )
+        }
+        _ => return None,
+    };
+
+    let width = arm + 2.0 * bearing;
+    let lines = if matches!(c, ']' | '⟧' | '⌉' | '⌋' | '⟩') {
+        lines
+            .into_iter()
+            .map(|line| line.into_iter().map(|p| Point::new(width + -p.x, p.y)).collect())
+            .collect()
+    } else {
+        lines
+    };
+
+    Some((width, lines))
+}
+
+/// The lines of a horizontal arrow with the given width, relative to the
+/// math axis.
+fn arrow_lines(c: char, width: Abs, em: &dyn Fn(f64) -> Abs) -> Option<Vec<Vec<Point>>> {
+    let (double, left, right, bar) = match c {
+        '→' | '⟶' => (false, false, true, false),
+        '←' | '⟵' => (false, true, false, false),
+        '↔' | '⟷' => (false, true, true, false),
+        '↦' | '⟼' => (false, false, true, true),
+        '⇒' | '⟹' => (true, false, true, false),
+        '⇐' | '⟸' => (true, true, false, false),
+        '⇔' | '⟺' => (true, true, true, false),
+        '⇌' | '⇋' => return Some(harpoon_lines(c == '⇋', width, em)),
+        _ => return None,
+    };
+
+    let pad = em(0.05);
+    let (start, end) = (pad, width + -pad);
+    let (length, spread) = if double { (em(0.35), em(0.3)) } else { (em(0.3), em(0.2)) };
+    let head = |tip: Point, dir: f64| {
+        vec![
+            Point::new(tip.x + -length * dir, -spread),
+            tip,
+            Point::new(tip.x + -length * dir, spread),
+        ]
+    };
+
+    let mut lines = vec![];
+    if double {
+        // The shafts end where they meet the heads.
+        let offset = em(0.1);
+        let inset = length * (offset / spread);
+        let from = if left { start + inset } else { start };
+        let to = if right { end + -inset } else { end };
+        for y in [-offset, offset] {
+            lines.push(vec![Point::new(from, y), Point::new(to, y)]);
+        }
+    } else {
+        lines.push(vec![Point::new(start, Abs::zero()), Point::new(end, Abs::zero())]);
+    }
+
+    if left {
+        lines.push(head(Point::with_x(start), -1.0));
+    }
+    if right {
+        lines.push(head(Point::with_x(end), 1.0));
+    }
+    if bar {
+        lines.push(vec![Point::new(start, -spread), Point::new(start, spread)]);
+    }
+
+    Some(lines)
+}
+
+/// The lines of a pair of harpoons with the given width, relative to the math
+/// axis.
+fn harpoon_lines(reversed: bool, width: Abs, em: &dyn Fn(f64) -> Abs) -> Vec<Vec<Point>> {
+    let pad = em(0.05);
+    let (start, end) = (pad, width + -pad);
+    let (length, spread, offset) = (em(0.3), em(0.2), em(0.1));
+
+    // The upper harpoon points right and the lower one left, unless
+    // reversed.
+    let (upper, lower) = if reversed { (start, end) } else { (end, start) };
+    let dir = if reversed { -1.0 } else { 1.0 };
+    vec![
+        vec![
+            Point::new(lower, -offset),
+            Point::new(upper, -offset),
+            Point::new(upper + -length * dir, -offset + -spread),
+        ],
+        vec![
+            Point::new(upper, offset),
+            Point::new(lower, offset),
+            Point::new(lower + length * dir, offset + spread),
+        ],
+    ]
+}
@@ -407,6 +407,42 @@ pub fn styled_char(styles: StyleChain, c: char, auto_italic: bool) -> char {
     std::char::from_u32(start + (c as u32 - base as u32)).unwrap()
 }
 
+/// Map a bold or italic serif math letter or digit back to its basic
+/// character.
+///
+/// Returns the basic character and whether the letter is bold and italic.
+/// This is used to typeset styled letters with the bold and italic faces of a
+/// text font that doesn't have the mathematical alphanumeric symbols.
+pub fn unstyled_char(c: char) -> Option<(char, bool, bool)> {
+    if c == 'ℎ' {
+        return Some(('h', false, true));
+    }
+
+    // The start of each block, its basic character, and its style.
+    const BLOCKS: &[(u32, char, u32, bool, bool)] = &[
+        (0x1D400, 'A', 26, true, false),
+        (0x1D41A, 'a', 26, true, false),
+        (0x1D434, 'A', 26, false, true),
+        (0x1D44E, 'a', 26, false, true),
+        (0x1D468, 'A', 26, true, true),
+        (0x1D482, 'a', 26, true, true),
+        (0x1D6A8, 'Α', 25, true, false),
+        (0x1D6C2, 'α', 25, true, false),
+        (0x1D6E2, 'Α', 25, false, true),
+        (0x1D6FC, 'α', 25, false, true),
+        (0x1D71C, 'Α', 25, true, true),
+        (0x1D736, 'α', 25, true, true),
+        (0x1D7CE, '0', 10, true, false),
+    ];
+
+    let code = c as u32;
+    BLOCKS.iter().find_map(|&(start, base, len, bold, italic)| {
+        let offset = code.checked_sub(start).filter(|&offset| offset < len)?;
+        let c = std::char::from_u32(base as u32 + offset)?;
+        Some((c, bold, italic))
+    })
+}
+
 fn basic_exception(c: char) -> Option<char> {
     Some(match c {
         '〈' => '⟨',
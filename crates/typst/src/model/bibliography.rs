@@ -4,10 +4,12 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use biblatex::ChunksExt;
 use comemo::Tracked;
-use ecow::{eco_format, EcoString, EcoVec};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use hayagriva::archive::ArchivedStyle;
 use hayagriva::io::BibLaTeXError;
 use hayagriva::{
@@ -23,9 +25,9 @@ use crate::diag::{bail, error, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::eval::{eval_string, EvalMode};
 use crate::foundations::{
-    cast, elem, ty, Args, Array, Bytes, CastInfo, Content, FromValue, IntoValue, Label,
-    NativeElement, Packed, Reflect, Repr, Scope, Show, ShowSet, Smart, Str, StyleChain,
-    Styles, Synthesize, Type, Value,
+    cast, elem, ty, Args, Array, Bytes, Cast, CastInfo, Content, FromValue, IntoValue,
+    Label, NativeElement, Packed, Reflect, Repr, Scope, Selector, Show, ShowSet, Smart,
+    Str, StyleChain, Styles, Synthesize, Type, Value,
 };
 use crate::introspection::{Introspector, Locatable, Location};
 use crate::layout::{
@@ -38,7 +40,8 @@ use crate::model::{
 
 use crate::syntax::{Span, Spanned};
 use crate::text::{
-    FontStyle, Lang, LocalName, Region, SubElem, SuperElem, TextElem, WeightDelta,
+    FontStyle, Lang, LocalName, Region, SpaceElem, SubElem, SuperElem, TextElem,
+    WeightDelta,
 };
 use crate::utils::{LazyHash, NonZeroExt, PicoStr};
 use crate::World;
@@ -85,6 +88,26 @@ use crate::World;
 ///
 /// #bibliography("works.bib")
 /// ```
+///
+/// # Multiple bibliographies
+/// A document can contain more than one bibliography. With the
+/// [`scope`]($bibliography.scope) parameter, you can add a reference list to
+/// each chapter. The [`types`]($bibliography.types) and
+/// [`keywords`]($bibliography.keywords) parameters split the works into
+/// several lists, for example one for primary and one for secondary sources.
+///
+/// ```example
+/// #set heading(numbering: "1.")
+/// #show bibliography: set heading(level: 2)
+///
+/// = Pirates
+/// Pirates were well organized. @arrgh
+/// #bibliography("works.bib", scope: "section")
+///
+/// = Networks
+/// The net-work is a creature of its own. @netwok
+/// #bibliography("works.bib", scope: "section")
+/// ```
 #[elem(Locatable, Synthesize, Show, ShowSet, LocalName)]
 pub struct BibliographyElem {
     /// Path(s) to Hayagriva `.yml` and/or BibLaTeX `.bib` files.
@@ -115,6 +138,55 @@ pub struct BibliographyElem {
     #[default(false)]
     pub full: bool,
 
+    /// Which citations the bibliography is responsible for.
+    ///
+    /// Each citation is numbered and linked according to the first
+    /// bibliography with `{"section"}` scope that follows it and can list the
+    /// cited work. If there is no such bibliography, the first bibliography
+    /// with `{"document"}` scope that can list the work is used instead.
+    ///
+    /// A bibliography with `{"document"}` scope lists all cited works it can
+    /// list, even those that are also listed in a section bibliography. This
+    /// way, you can combine per-chapter reference lists with a full one at
+    /// the end of the document.
+    ///
+    /// The scope is only taken into account when it is passed directly to the
+    /// bibliography. A set rule for it has no effect.
+    #[default(BibliographyScope::Document)]
+    pub scope: BibliographyScope,
+
+    /// Restricts the bibliography to works of the given types.
+    ///
+    /// The types are the ones of the
+    /// [Hayagriva file format](https://github.com/typst/hayagriva/blob/main/docs/file-format.md#entry-type),
+    /// for example `{"article"}`, `{"book"}`, or `{"web"}`. BibLaTeX entry
+    /// types are mapped to them when loading a `.bib` file.
+    ///
+    /// ```example
+    /// @arrgh makes a point that
+    /// @netwok disagrees with.
+    ///
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: [Articles],
+    ///   types: "article",
+    /// )
+    /// #bibliography(
+    ///   "works.bib",
+    ///   title: [Books],
+    ///   types: ("book", "anthology"),
+    /// )
+    /// ```
+    pub types: Option<BibliographyTypes>,
+
+    /// Restricts the bibliography to works with at least one of the given
+    /// keywords.
+    ///
+    /// The keywords are read from the `keywords` field of the entries. In
+    /// BibLaTeX files, it holds a comma-separated list. In Hayagriva files, it
+    /// can also be a list of strings.
+    pub keywords: Option<BibliographyKeywords>,
+
     /// The bibliography style.
     ///
     /// Should be either one of the built-in styles (see below) or a path to
@@ -153,22 +225,59 @@ cast! {
     v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
 }
 
-impl BibliographyElem {
-    /// Find the document's bibliography.
-    pub fn find(introspector: Tracked<Introspector>) -> StrResult<Packed<Self>> {
-        let query = introspector.query(&Self::elem().select());
-        let mut iter = query.iter();
-        let Some(elem) = iter.next() else {
-            bail!("the document does not contain a bibliography");
-        };
+/// Which citations a bibliography is responsible for.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BibliographyScope {
+    /// All citations in the document.
+    Document,
+    /// The citations before this bibliography that are not yet claimed by an
+    /// earlier section bibliography. A citation of a work that an earlier
+    /// section bibliography cannot list is claimed by a later one.
+    Section,
+}
 
-        if iter.next().is_some() {
-            bail!("multiple bibliographies are not yet supported");
+/// A list of entry types to restrict a bibliography to.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct BibliographyTypes(Vec<EcoString>);
+
+impl BibliographyTypes {
+    /// Check that all types are valid.
+    fn new(types: Vec<EcoString>) -> StrResult<Self> {
+        for ty in &types {
+            if hayagriva::types::EntryType::from_str(ty).is_err() {
+                bail!("unknown entry type: `{ty}`");
+            }
         }
+        Ok(Self(types))
+    }
 
-        Ok(elem.to_packed::<Self>().unwrap().clone())
+    /// Whether the given type is in the list.
+    fn contains(&self, ty: &hayagriva::types::EntryType) -> bool {
+        self.0.iter().any(|name| {
+            hayagriva::types::EntryType::from_str(name).ok().as_ref() == Some(ty)
+        })
     }
+}
 
+cast! {
+    BibliographyTypes,
+    self => self.0.into_value(),
+    v: EcoString => Self::new(vec![v])?,
+    v: Array => Self::new(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?)?,
+}
+
+/// A list of keywords to restrict a bibliography to.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct BibliographyKeywords(Vec<EcoString>);
+
+cast! {
+    BibliographyKeywords,
+    self => self.0.into_value(),
+    v: EcoString => Self(vec![v]),
+    v: Array => Self(v.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
+}
+
+impl BibliographyElem {
     /// Whether the bibliography contains the given key.
     pub fn has(engine: &Engine, key: impl Into<PicoStr>) -> bool {
         let key = key.into();
@@ -194,6 +303,28 @@ impl BibliographyElem {
         }
         vec
     }
+
+    /// Whether the bibliography can list the work with the given key, that
+    /// is, whether it contains the key and the work passes the filters.
+    fn accepts(&self, key: PicoStr) -> bool {
+        let database = self.bibliography();
+        let Some(entry) = database.map.get(&key) else { return false };
+
+        if let Some(types) = self.types(StyleChain::default()) {
+            if !types.contains(entry.entry_type()) {
+                return false;
+            }
+        }
+
+        if let Some(keywords) = self.keywords(StyleChain::default()) {
+            let Some(own) = database.keywords.get(&key) else { return false };
+            if !keywords.0.iter().any(|keyword| own.contains(keyword)) {
+                return false;
+            }
+        }
+
+        true
+    }
 }
 
 impl Synthesize for Packed<BibliographyElem> {
@@ -225,8 +356,11 @@ impl Show for Packed<BibliographyElem> {
 
         let span = self.span();
         let works = Works::generate(engine.world, engine.introspector).at(span)?;
-        let references = works
-            .references
+        let Some(own) = works.references.get(&self.location().unwrap()) else {
+            bail!(span, "failed to generate the bibliography");
+        };
+        let references = own
+            .items
             .as_ref()
             .ok_or("CSL style is not suitable for bibliographies")
             .at(span)?;
@@ -261,7 +395,7 @@ impl Show for Packed<BibliographyElem> {
         }
 
         let mut content = Content::sequence(seq);
-        if works.hanging_indent {
+        if own.hanging_indent {
             content = content.styled(ParElem::set_hanging_indent(INDENT.into()));
         }
 
@@ -287,6 +421,7 @@ impl LocalName for Packed<BibliographyElem> {
 #[derive(Clone, PartialEq)]
 pub struct Bibliography {
     map: Arc<IndexMap<PicoStr, hayagriva::Entry>>,
+    keywords: Arc<HashMap<PicoStr, Vec<EcoString>>>,
     hash: u128,
 }
 
@@ -320,6 +455,7 @@ impl Bibliography {
     #[typst_macros::time(name = "load bibliography")]
     fn load(paths: &BibliographyPaths, data: &[Bytes]) -> StrResult<Bibliography> {
         let mut map = IndexMap::new();
+        let mut keywords = HashMap::new();
         let mut duplicates = Vec::<EcoString>::new();
 
         // We might have multiple bib/yaml files
@@ -332,10 +468,18 @@ impl Bibliography {
                 .unwrap_or_default();
 
             let library = match ext.to_lowercase().as_str() {
-                "yml" | "yaml" => hayagriva::io::from_yaml_str(src)
-                    .map_err(|err| eco_format!("failed to parse YAML ({err})"))?,
-                "bib" => hayagriva::io::from_biblatex_str(src)
-                    .map_err(|errors| format_biblatex_error(path, src, errors))?,
+                "yml" | "yaml" => {
+                    let library = hayagriva::io::from_yaml_str(src)
+                        .map_err(|err| eco_format!("failed to parse YAML ({err})"))?;
+                    read_yaml_keywords(src, &mut keywords);
+                    library
+                }
+                "bib" => {
+                    let library = hayagriva::io::from_biblatex_str(src)
+                        .map_err(|errors| format_biblatex_error(path, src, errors))?;
+                    read_biblatex_keywords(src, &mut keywords);
+                    library
+                }
                 _ => bail!("unknown bibliography format (must be .yml/.yaml or .bib)"),
             };
 
@@ -357,6 +501,7 @@ impl Bibliography {
 
         Ok(Bibliography {
             map: Arc::new(map),
+            keywords: Arc::new(keywords),
             hash: crate::utils::hash128(data),
         })
     }
@@ -382,6 +527,50 @@ impl Hash for Bibliography {
     }
 }
 
+/// Read the `keywords` fields of BibLaTeX entries, which Hayagriva does not
+/// retain.
+fn read_biblatex_keywords(src: &str, keywords: &mut HashMap<PicoStr, Vec<EcoString>>) {
+    let Ok(bibliography) = biblatex::Bibliography::parse(src) else { return };
+    for entry in bibliography.iter() {
+        let Ok(chunks) = entry.keywords() else { continue };
+        let list = chunks
+            .format_verbatim()
+            .split(',')
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+            .map(EcoString::from)
+            .collect();
+        keywords.insert(entry.key.as_str().into(), list);
+    }
+}
+
+/// Read the `keywords` fields of Hayagriva entries, which Hayagriva does not
+/// retain. The field can hold a list or a comma-separated string.
+fn read_yaml_keywords(src: &str, keywords: &mut HashMap<PicoStr, Vec<EcoString>>) {
+    let Ok(entries) = serde_yaml::from_str::<IndexMap<String, serde_yaml::Value>>(src)
+    else {
+        return;
+    };
+
+    for (key, entry) in entries {
+        let list = match entry.get("keywords") {
+            Some(serde_yaml::Value::String(string)) => string
+                .split(',')
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(EcoString::from)
+                .collect(),
+            Some(serde_yaml::Value::Sequence(seq)) => seq
+                .iter()
+                .filter_map(serde_yaml::Value::as_str)
+                .map(|keyword| keyword.trim().into())
+                .collect(),
+            _ => continue,
+        };
+        keywords.insert(key.as_str().into(), list);
+    }
+}
+
 /// Format a BibLaTeX loading error.
 fn format_biblatex_error(path: &str, src: &str, errors: Vec<BibLaTeXError>) -> EcoString {
     let Some(error) = errors.first() else {
@@ -541,9 +730,15 @@ impl Repr for CslStyle {
 pub(super) struct Works {
     /// Maps from the location of a citation group to its rendered content.
     pub citations: HashMap<Location, SourceResult<Content>>,
+    /// Maps from the location of a bibliography to its rendered references.
+    pub references: HashMap<Location, References>,
+}
+
+/// The rendered references of a single bibliography.
+pub(super) struct References {
     /// Lists all references in the bibliography, with optional prefix, or
     /// `None` if the citation style can't be used for bibliographies.
-    pub references: Option<Vec<(Option<Content>, Content)>>,
+    pub items: Option<Vec<(Option<Content>, Content)>>,
     /// Whether the bibliography should have hanging indent.
     pub hanging_indent: bool,
 }
@@ -555,26 +750,155 @@ impl Works {
         world: Tracked<dyn World + '_>,
         introspector: Tracked<Introspector>,
     ) -> StrResult<Arc<Works>> {
-        let mut generator = Generator::new(world, introspector)?;
-        let rendered = generator.drive();
-        let works = generator.display(&rendered)?;
-        Ok(Arc::new(works))
+        let elems = introspector.query(&Selector::Or(eco_vec![
+            CiteGroup::elem().select(),
+            BibliographyElem::elem().select(),
+        ]));
+
+        let bibliographies: Vec<_> = elems
+            .iter()
+            .filter_map(|elem| elem.to_packed::<BibliographyElem>())
+            .collect();
+        if bibliographies.is_empty() {
+            bail!("the document does not contain a bibliography");
+        }
+
+        let mut citations = HashMap::new();
+        let owners = assign_owners(&elems, &bibliographies, &mut citations);
+
+        // Each bibliography renders the citations it owns. Groups whose
+        // citations belong to different bibliographies are assembled from
+        // several parts.
+        let mut parts = HashMap::<Location, Vec<(usize, Content)>>::new();
+        let mut references = HashMap::new();
+        for (index, bibliography) in bibliographies.iter().enumerate() {
+            let mut generator =
+                Generator::new(world, bibliography, index, &elems, &owners);
+            let rendered = generator.drive();
+            generator.display_citations(&rendered, &mut parts);
+            references.insert(
+                bibliography.location().unwrap(),
+                References {
+                    items: generator.display_references(&rendered),
+                    hanging_indent: rendered
+                        .bibliography
+                        .as_ref()
+                        .is_some_and(|b| b.hanging_indent),
+                },
+            );
+        }
+
+        for (location, mut group) in parts {
+            group.sort_by_key(|&(i, _)| i);
+            let mut seq = vec![];
+            for (_, content) in group {
+                if !seq.is_empty() {
+                    seq.push(SpaceElem::new().pack());
+                }
+                seq.push(content);
+            }
+            citations.insert(location, Ok(Content::sequence(seq)));
+        }
+
+        Ok(Arc::new(Works { citations, references }))
     }
 }
 
-/// Context for generating the bibliography.
+/// Determines which bibliography each citation belongs to.
+///
+/// A citation belongs to the first section bibliography after it that can
+/// list the cited work. If there is none, it belongs to the first document
+/// bibliography that can list the work and, failing that, to the first
+/// bibliography that can list it at all. Citation groups with unresolved keys
+/// are recorded as failures.
+fn assign_owners(
+    elems: &[Content],
+    bibliographies: &[&Packed<BibliographyElem>],
+    failures: &mut HashMap<Location, SourceResult<Content>>,
+) -> HashMap<Location, SmallVec<[usize; 1]>> {
+    let mut owners = HashMap::new();
+    let mut pending: Vec<(Location, usize, PicoStr)> = vec![];
+    let mut index = 0;
+
+    for elem in elems {
+        if let Some(bibliography) = elem.to_packed::<BibliographyElem>() {
+            if bibliography.scope(StyleChain::default()) == BibliographyScope::Section {
+                pending.retain(|&(location, i, key)| {
+                    if !bibliography.accepts(key) {
+                        return true;
+                    }
+                    let group: &mut SmallVec<[usize; 1]> =
+                        owners.get_mut(&location).unwrap();
+                    group[i] = index;
+                    false
+                });
+            }
+            index += 1;
+            continue;
+        }
+
+        let group = elem.to_packed::<CiteGroup>().unwrap();
+        let location = elem.location().unwrap();
+        let mut errors = EcoVec::new();
+        let mut fallbacks = SmallVec::new();
+
+        for child in group.children() {
+            let key = child.key().into_inner();
+            let fallback = bibliographies
+                .iter()
+                .position(|b| {
+                    b.scope(StyleChain::default()) == BibliographyScope::Document
+                        && b.accepts(key)
+                })
+                .or_else(|| bibliographies.iter().position(|b| b.accepts(key)));
+
+            match fallback {
+                Some(fallback) => fallbacks.push(fallback),
+                None if bibliographies.iter().any(|b| b.bibliography().has(key)) => {
+                    errors.push(error!(
+                        child.span(),
+                        "key `{}` is excluded from all bibliographies",
+                        child.key().as_str()
+                    ));
+                }
+                None => errors.push(error!(
+                    child.span(),
+                    "key `{}` does not exist in the bibliography",
+                    child.key().as_str()
+                )),
+            }
+        }
+
+        if !errors.is_empty() {
+            failures.insert(location, Err(errors));
+            continue;
+        }
+
+        for (i, child) in group.children().iter().enumerate() {
+            pending.push((location, i, child.key().into_inner()));
+        }
+        owners.insert(location, fallbacks);
+    }
+
+    owners
+}
+
+/// Context for generating a single bibliography.
 struct Generator<'a> {
     /// The world that is used to evaluate mathematical material in citations.
     world: Tracked<'a, dyn World + 'a>,
-    /// The document's bibliography.
-    bibliography: Packed<BibliographyElem>,
-    /// The document's citation groups.
-    groups: EcoVec<Content>,
+    /// The bibliography to generate.
+    bibliography: &'a Packed<BibliographyElem>,
+    /// The bibliography's index among all bibliographies in the document.
+    index: usize,
+    /// The document's citation groups and bibliographies.
+    elems: &'a [Content],
+    /// For each citation in each group, the index of the bibliography it
+    /// belongs to.
+    owners: &'a HashMap<Location, SmallVec<[usize; 1]>>,
     /// Details about each group that are accumulated while driving hayagriva's
     /// bibliography driver and needed when processing hayagriva's output.
     infos: Vec<GroupInfo>,
-    /// Citations with unresolved keys.
-    failures: HashMap<Location, SourceResult<Content>>,
 }
 
 /// Details about a group of merged citations. All citations are put into groups
@@ -585,6 +909,10 @@ struct GroupInfo {
     location: Location,
     /// The group's span.
     span: Span,
+    /// The index of the first citation in the group that belongs to the
+    /// bibliography, or `None` if the group's citations were only added to
+    /// list them.
+    first: Option<usize>,
     /// Whether the group should be displayed in a footnote.
     footnote: bool,
     /// Details about the groups citations.
@@ -597,6 +925,8 @@ struct CiteInfo {
     key: Label,
     /// The citation's supplement.
     supplement: Option<Content>,
+    /// Content to display before and after the cited item.
+    affixes: (Option<Content>, Option<Content>),
     /// Whether this citation was hidden.
     hidden: bool,
 }
@@ -605,18 +935,19 @@ impl<'a> Generator<'a> {
     /// Create a new generator.
     fn new(
         world: Tracked<'a, dyn World + 'a>,
-        introspector: Tracked<Introspector>,
-    ) -> StrResult<Self> {
-        let bibliography = BibliographyElem::find(introspector)?;
-        let groups = introspector.query(&CiteGroup::elem().select());
-        let infos = Vec::with_capacity(groups.len());
-        Ok(Self {
+        bibliography: &'a Packed<BibliographyElem>,
+        index: usize,
+        elems: &'a [Content],
+        owners: &'a HashMap<Location, SmallVec<[usize; 1]>>,
+    ) -> Self {
+        Self {
             world,
             bibliography,
-            groups,
-            infos,
-            failures: HashMap::new(),
-        })
+            index,
+            elems,
+            owners,
+            infos: vec![],
+        }
     }
 
     /// Drives hayagriva's citation driver.
@@ -626,44 +957,58 @@ impl<'a> Generator<'a> {
 
         let database = self.bibliography.bibliography();
         let bibliography_style = self.bibliography.style(StyleChain::default());
+        let document =
+            self.bibliography.scope(StyleChain::default()) == BibliographyScope::Document;
         let styles = Arena::new();
+        let uncollapsed_styles = Arena::new();
+        let strings = Arena::<EcoString>::new();
 
         // Process all citation groups.
         let mut driver = BibliographyDriver::new();
-        for elem in &self.groups {
-            let group = elem.to_packed::<CiteGroup>().unwrap();
+        for elem in self.elems {
+            let Some(group) = elem.to_packed::<CiteGroup>() else { continue };
             let location = elem.location().unwrap();
+            let Some(owners) = self.owners.get(&location) else { continue };
             let children = group.children();
 
-            // Groups should never be empty.
-            let Some(first) = children.first() else { continue };
-
             let mut subinfos = SmallVec::with_capacity(children.len());
             let mut items = Vec::with_capacity(children.len());
-            let mut errors = EcoVec::new();
+            let mut first = None;
             let mut normal = true;
 
-            // Create infos and items for each child in the group.
-            for child in children {
+            // Create infos and items for each child in the group. Citations
+            // that belong to other bibliographies are hidden, but a document
+            // bibliography still lists them.
+            for (i, (child, &owner)) in children.iter().zip(owners).enumerate() {
                 let key = *child.key();
-                let Some(entry) = database.map.get(&key.into_inner()) else {
-                    errors.push(error!(
-                        child.span(),
-                        "key `{}` does not exist in the bibliography",
-                        key.as_str()
-                    ));
+                let owned = owner == self.index;
+                let listed = document && self.bibliography.accepts(key.into_inner());
+                if !owned && !listed {
                     continue;
-                };
+                }
 
+                let Some(entry) = database.map.get(&key.into_inner()) else { continue };
                 let supplement = child.supplement(StyleChain::default());
-                let locator = supplement.as_ref().map(|_| {
-                    SpecificLocator(
-                        citationberg::taxonomy::Locator::Custom,
-                        hayagriva::LocatorPayload::Transparent,
-                    )
-                });
+                let locator = match child.locator(StyleChain::default()) {
+                    Some(locator) => Some(SpecificLocator(
+                        locator.kind(),
+                        hayagriva::LocatorPayload::Str(
+                            strings.alloc(locator.value().clone()),
+                        ),
+                    )),
+                    None => supplement.as_ref().map(|_| {
+                        SpecificLocator(
+                            citationberg::taxonomy::Locator::Custom,
+                            hayagriva::LocatorPayload::Transparent,
+                        )
+                    }),
+                };
+                let affixes = (
+                    child.prefix(StyleChain::default()),
+                    child.suffix(StyleChain::default()),
+                );
 
-                let mut hidden = false;
+                let mut hidden = !owned;
                 let special_form = match child.form(StyleChain::default()) {
                     None => {
                         hidden = true;
@@ -676,25 +1021,43 @@ impl<'a> Generator<'a> {
                     Some(CitationForm::Year) => Some(hayagriva::CitePurpose::Year),
                 };
 
-                normal &= special_form.is_none();
-                subinfos.push(CiteInfo { key, supplement, hidden });
+                if owned {
+                    first.get_or_insert(i);
+                    normal &= special_form.is_none();
+                }
+
+                subinfos.push(CiteInfo { key, supplement, affixes, hidden });
                 items.push(CitationItem::new(entry, locator, None, hidden, special_form));
             }
 
-            if !errors.is_empty() {
-                self.failures.insert(location, Err(errors));
+            if items.is_empty() {
                 continue;
             }
 
-            let style = match first.style(StyleChain::default()) {
-                Smart::Auto => &bibliography_style.style,
-                Smart::Custom(style) => styles.alloc(style.style),
-            };
+            let first_child = &children[first.unwrap_or_default()];
+            let mut style: &citationberg::IndependentStyle =
+                match first_child.style(StyleChain::default()) {
+                    Smart::Auto => &bibliography_style.style,
+                    Smart::Custom(style) => styles.alloc(style.style),
+                };
+
+            // Hayagriva has no per-item affixes, so we attach them to the
+            // rendered items ourselves. Collapsing would hide some of these
+            // items and lose their affixes, so we don't collapse such groups.
+            let affixed = subinfos.iter().any(|sub: &CiteInfo| {
+                !sub.hidden && (sub.affixes.0.is_some() || sub.affixes.1.is_some())
+            });
+            if affixed && style.citation.collapse.is_some() {
+                let mut uncollapsed = style.clone();
+                uncollapsed.citation.collapse = None;
+                style = uncollapsed_styles.alloc(uncollapsed);
+            }
 
             self.infos.push(GroupInfo {
                 location,
                 subinfos,
-                span: first.span(),
+                first,
+                span: first_child.span(),
                 footnote: normal
                     && style.settings.class == citationberg::StyleClass::Note,
             });
@@ -703,8 +1066,8 @@ impl<'a> Generator<'a> {
                 items,
                 style,
                 Some(locale(
-                    first.lang().copied().unwrap_or(Lang::ENGLISH),
-                    first.region().copied().flatten(),
+                    first_child.lang().copied().unwrap_or(Lang::ENGLISH),
+                    first_child.region().copied().flatten(),
                 )),
                 &LOCALES,
                 None,
@@ -719,7 +1082,11 @@ impl<'a> Generator<'a> {
         // Add hidden items for everything if we should print the whole
         // bibliography.
         if self.bibliography.full(StyleChain::default()) {
-            for entry in database.map.values() {
+            for (key, entry) in database.map.iter() {
+                if !self.bibliography.accepts(*key) {
+                    continue;
+                }
+
                 driver.citation(CitationRequest::new(
                     vec![CitationItem::new(entry, None, None, true, None)],
                     bibliography_style.get(),
@@ -737,20 +1104,14 @@ impl<'a> Generator<'a> {
         })
     }
 
-    /// Displays hayagriva's output as content for the citations and references.
-    fn display(&mut self, rendered: &hayagriva::Rendered) -> StrResult<Works> {
-        let citations = self.display_citations(rendered);
-        let references = self.display_references(rendered);
-        let hanging_indent =
-            rendered.bibliography.as_ref().is_some_and(|b| b.hanging_indent);
-        Ok(Works { citations, references, hanging_indent })
-    }
-
-    /// Display the citation groups.
+    /// Display the citation groups that belong to this bibliography. The
+    /// rendered content is added to the parts of each group together with the
+    /// index of the group's first citation it covers.
     fn display_citations(
-        &mut self,
+        &self,
         rendered: &hayagriva::Rendered,
-    ) -> HashMap<Location, SourceResult<Content>> {
+        parts: &mut HashMap<Location, Vec<(usize, Content)>>,
+    ) {
         // Determine for each citation key where in the bibliography it is,
         // so that we can link there.
         let mut links = HashMap::new();
@@ -761,15 +1122,22 @@ impl<'a> Generator<'a> {
             }
         }
 
-        let mut output = std::mem::take(&mut self.failures);
         for (info, citation) in self.infos.iter().zip(&rendered.citations) {
+            let Some(first) = info.first else { continue };
             let supplement = |i: usize| info.subinfos.get(i)?.supplement.clone();
+            let affixes = |i: usize| {
+                info.subinfos
+                    .get(i)
+                    .map(|sub| sub.affixes.clone())
+                    .unwrap_or_default()
+            };
             let link = |i: usize| links.get(info.subinfos.get(i)?.key.as_str()).copied();
 
             let renderer = ElemRenderer {
                 world: self.world,
                 span: info.span,
                 supplement: &supplement,
+                affixes: &affixes,
                 link: &link,
             };
 
@@ -786,10 +1154,8 @@ impl<'a> Generator<'a> {
                 content
             };
 
-            output.insert(info.location, Ok(content));
+            parts.entry(info.location).or_default().push((first, content));
         }
-
-        output
     }
 
     /// Display the bibliography references.
@@ -818,6 +1184,7 @@ impl<'a> Generator<'a> {
                 world: self.world,
                 span: self.bibliography.span(),
                 supplement: &|_| None,
+                affixes: &|_| (None, None),
                 link: &|_| None,
             };
 
@@ -856,6 +1223,8 @@ struct ElemRenderer<'a> {
     span: Span,
     /// Resolves the supplement of i-th citation in the request.
     supplement: &'a dyn Fn(usize) -> Option<Content>,
+    /// Resolves the prefix and suffix of the i-th citation in the request.
+    affixes: &'a dyn Fn(usize) -> (Option<Content>, Option<Content>),
     /// Resolves where the i-th citation in the request should link to.
     link: &'a dyn Fn(usize) -> Option<Location>,
 }
@@ -944,6 +1313,14 @@ impl ElemRenderer<'_> {
                 let dest = Destination::Location(location);
                 content = content.linked(dest);
             }
+
+            let (prefix, suffix) = (self.affixes)(i);
+            if let Some(prefix) = prefix {
+                content = prefix + content;
+            }
+            if let Some(suffix) = suffix {
+                content += suffix;
+            }
         }
 
         content
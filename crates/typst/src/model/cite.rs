@@ -1,7 +1,13 @@
+use std::str::FromStr;
+
+use ecow::{eco_format, EcoString};
+use hayagriva::citationberg::taxonomy::Locator;
+
 use crate::diag::{bail, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Cast, Content, Label, Packed, Show, Smart, StyleChain, Synthesize,
+    cast, elem, Cast, Content, Dict, Label, Packed, Show, Smart, StyleChain, Synthesize,
+    Value,
 };
 use crate::introspection::Locatable;
 use crate::model::bibliography::Works;
@@ -36,6 +42,22 @@ use crate::text::{Lang, Region, TextElem};
 /// >>> #bibliography("works.bib")
 /// ```
 ///
+/// # Multiple citations
+/// Adjacent citations that are only separated by spaces are merged into a
+/// single group. Depending on the style, consecutive numbers in the group are
+/// then compressed into a range.
+///
+/// ```example
+/// #set page(width: 200pt)
+/// Several works @netwok @arrgh
+/// @quark @distress discuss this,
+/// #cite(<glacier-melt>, prefix: [see ])
+/// in particular.
+///
+/// >>> #set text(0pt)
+/// >>> #bibliography("works.bib")
+/// ```
+///
 /// # Syntax
 /// This function indirectly has dedicated syntax. [References]($ref) can be
 /// used to cite works from the bibliography. The label then corresponds to the
@@ -67,6 +89,47 @@ pub struct CiteElem {
     /// ```
     pub supplement: Option<Content>,
 
+    /// A structured locator that specifies which part of the work is cited.
+    ///
+    /// The locator is given as a dictionary with a single pair. Its key is the
+    /// kind of locator and its value the location. Unlike with a
+    /// [supplement]($cite.supplement), the style takes care of formatting the
+    /// locator, for example by adding "p." or "pp." for pages. A citation
+    /// can't have both a supplement and a locator.
+    ///
+    /// The supported kinds are those of CSL: `{"act"}`, `{"appendix"}`,
+    /// `{"article-locator"}`, `{"book"}`, `{"canon"}`, `{"chapter"}`,
+    /// `{"column"}`, `{"elocation"}`, `{"equation"}`, `{"figure"}`,
+    /// `{"folio"}`, `{"issue"}`, `{"line"}`, `{"note"}`, `{"opus"}`,
+    /// `{"page"}`, `{"paragraph"}`, `{"part"}`, `{"rule"}`, `{"scene"}`,
+    /// `{"section"}`, `{"sub-verbo"}`, `{"supplement"}`, `{"table"}`,
+    /// `{"timestamp"}`, `{"title"}`, `{"title-locator"}`, `{"verse"}`, and
+    /// `{"volume"}`.
+    ///
+    /// ```example
+    /// This has been proven
+    /// #cite(<distress>, locator: (page: "7-9")).
+    ///
+    /// #bibliography("works.bib", style: "apa")
+    /// ```
+    pub locator: Option<CiteLocator>,
+
+    /// Content to display before the cited item, within the citation's
+    /// brackets or parentheses.
+    ///
+    /// ```example
+    /// #cite(<netwok>, prefix: [see ])
+    /// #cite(<arrgh>, suffix: [, for example])
+    ///
+    /// >>> #set text(0pt)
+    /// #bibliography("works.bib", style: "apa")
+    /// ```
+    pub prefix: Option<Content>,
+
+    /// Content to display after the cited item, within the citation's
+    /// brackets or parentheses.
+    pub suffix: Option<Content>,
+
     /// The kind of citation to produce. Different forms are useful in different
     /// scenarios: A normal citation is useful as a source at the end of a
     /// sentence, while a "prose" citation is more suitable for inclusion in the
@@ -110,6 +173,10 @@ pub struct CiteElem {
 
 impl Synthesize for Packed<CiteElem> {
     fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        if self.supplement(styles).is_some() && self.locator(styles).is_some() {
+            bail!(self.span(), "a citation cannot have both a supplement and a locator");
+        }
+
         let elem = self.as_mut();
         elem.push_lang(TextElem::lang_in(styles));
         elem.push_region(TextElem::region_in(styles));
@@ -122,6 +189,46 @@ cast! {
     v: Content => v.unpack::<Self>().map_err(|_| "expected citation")?,
 }
 
+/// A structured locator in a cited work, like a page or a chapter.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CiteLocator {
+    /// The CSL name of the locator kind.
+    kind: EcoString,
+    /// The location in the work.
+    value: EcoString,
+}
+
+impl CiteLocator {
+    /// The kind of the locator.
+    pub fn kind(&self) -> Locator {
+        Locator::from_str(&self.kind).unwrap()
+    }
+
+    /// The location in the work.
+    pub fn value(&self) -> &EcoString {
+        &self.value
+    }
+}
+
+cast! {
+    CiteLocator,
+    self => Dict::from_iter([(self.kind.into(), self.value.into_value())]).into_value(),
+    v: Dict => {
+        let mut pairs = v.into_iter();
+        let (Some((kind, value)), None) = (pairs.next(), pairs.next()) else {
+            bail!("locator must have exactly one entry");
+        };
+        if Locator::from_str(&kind).is_err() {
+            bail!("unknown locator kind: `{kind}`");
+        }
+        let value = match value {
+            Value::Int(number) => eco_format!("{number}"),
+            value => value.cast::<EcoString>()?,
+        };
+        Self { kind: kind.into(), value }
+    },
+}
+
 /// The form of the citation.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum CitationForm {
@@ -118,7 +118,7 @@ impl LayoutRoot for Packed<DocumentElem> {
 
 /// A list of authors.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
-pub struct Author(Vec<EcoString>);
+pub struct Author(pub Vec<EcoString>);
 
 cast! {
     Author,
@@ -129,7 +129,7 @@ cast! {
 
 /// A list of keywords.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
-pub struct Keywords(Vec<EcoString>);
+pub struct Keywords(pub Vec<EcoString>);
 
 cast! {
     Keywords,
@@ -1,6 +1,9 @@
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{elem, Content, Packed, Show, StyleChain};
+use crate::foundations::{
+    elem, Content, NativeElement, Packed, Show, StyleChain, TargetElem,
+};
+use crate::html::{HtmlElem, HtmlTag};
 use crate::text::{ItalicToggle, TextElem};
 
 /// Emphasizes content by toggling italics.
@@ -35,7 +38,16 @@ pub struct EmphElem {
 
 impl Show for Packed<EmphElem> {
     #[typst_macros::time(name = "emph", span = self.span())]
-    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
-        Ok(self.body().clone().styled(TextElem::set_emph(ItalicToggle(true))))
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let body = self.body().clone().styled(TextElem::set_emph(ItalicToggle(true)));
+
+        // The style is kept in HTML so that the italic font is embedded.
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(HtmlElem::new(HtmlTag::constant("em"))
+                .with_body(Some(body))
+                .pack());
+        }
+
+        Ok(body)
     }
 }
@@ -8,8 +8,9 @@ use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, select_where, Content, Element, NativeElement, Packed, Selector,
-    Show, ShowSet, Smart, StyleChain, Styles, Synthesize,
+    Show, ShowSet, Smart, StyleChain, Styles, Synthesize, TargetElem,
 };
+use crate::html::{HtmlElem, HtmlTag};
 use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
@@ -307,6 +308,34 @@ impl Show for Packed<FigureElem> {
     fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let mut realized = self.body().clone();
 
+        // In HTML, the figure is a `figure` element with its kind as the class
+        // and it never floats.
+        if TargetElem::target_in(styles).is_html() {
+            if let Some(caption) = self.caption(styles) {
+                let position = caption.position(styles);
+                let caption = HtmlElem::new(HtmlTag::constant("figcaption"))
+                    .with_body(Some(caption.pack()))
+                    .pack();
+                realized = match position {
+                    OuterVAlignment::Top => caption + realized,
+                    OuterVAlignment::Bottom => realized + caption,
+                };
+            }
+
+            let mut elem = HtmlElem::new(HtmlTag::constant("figure"));
+            if let Smart::Custom(kind) = self.kind(styles) {
+                elem = elem.with_attr(
+                    "class",
+                    match kind {
+                        FigureKind::Elem(elem) => elem.name().into(),
+                        FigureKind::Name(name) => name,
+                    },
+                );
+            }
+
+            return Ok(elem.with_body(Some(realized)).pack().spanned(self.span()));
+        }
+
         // Build the caption, if any.
         if let Some(caption) = self.caption(styles) {
             let v = VElem::weak(self.gap(styles).into()).pack();
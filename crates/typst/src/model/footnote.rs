@@ -5,12 +5,13 @@ use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, Content, Label, NativeElement, Packed, Show, ShowSet, Smart,
-    StyleChain, Styles,
+    StyleChain, Styles, TargetElem,
 };
+use crate::html::{HtmlElem, HtmlTag};
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{Abs, Em, HElem, Length, Ratio};
 use crate::model::{Destination, Numbering, NumberingPattern, ParElem};
-use crate::text::{SuperElem, TextElem, TextSize};
+use crate::text::{SpaceElem, SuperElem, TextElem, TextSize};
 use crate::utils::NonZeroExt;
 use crate::visualize::{LineElem, Stroke};
 
@@ -131,8 +132,16 @@ impl Show for Packed<FootnoteElem> {
         let numbering = self.numbering(styles);
         let counter = Counter::of(FootnoteElem::elem());
         let num = counter.display_at_loc(engine, loc, styles, numbering)?;
-        let sup = SuperElem::new(num).pack().spanned(span);
         let loc = loc.variant(1);
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(HtmlElem::new(HtmlTag::constant("sup"))
+                .with_attr("class", "footnote-ref")
+                .with_body(Some(num.linked(Destination::Location(loc))))
+                .pack()
+                .spanned(span));
+        }
+
+        let sup = SuperElem::new(num).pack().spanned(span);
         // Add zero-width weak spacing to make the footnote "sticky".
         Ok(HElem::hole().pack() + sup.linked(Destination::Location(loc)))
     }
@@ -282,6 +291,21 @@ impl Show for Packed<FootnoteEntry> {
         };
 
         let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        if TargetElem::target_in(styles).is_html() {
+            let sup = HtmlElem::new(HtmlTag::constant("sup"))
+                .with_body(Some(num.linked(Destination::Location(loc))))
+                .pack()
+                .spanned(span)
+                .backlinked(loc.variant(1));
+            return Ok(HtmlElem::new(HtmlTag::constant("div"))
+                .with_attr("class", "footnote")
+                .with_body(Some(
+                    sup + SpaceElem::new().pack() + note.body_content().unwrap().clone(),
+                ))
+                .pack()
+                .spanned(span));
+        }
+
         let sup = SuperElem::new(num)
             .pack()
             .spanned(span)
@@ -0,0 +1,352 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+
+use crate::diag::{bail, At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, scope, select_where, Cast, Content, Label, NativeElement, Packed, Selector,
+    SequenceElem, Show, ShowSet, Smart, StyleChain, StyledElem, Styles,
+};
+use crate::introspection::Locatable;
+use crate::model::{
+    page_list, Destination, HeadingElem, ParbreakElem, TermItem, TermsElem,
+};
+use crate::text::{Collator, LocalName, TextElem};
+use crate::utils::NonZeroExt;
+
+/// A glossary of terms and acronyms.
+///
+/// Terms are defined with [`glossary.entry`]($glossary.entry) and a label. A
+/// [reference]($ref) to the label then displays the term: On its first use,
+/// the long form is shown together with the short form, afterwards only the
+/// short form is used. The `glossary` function itself prints a list of all
+/// defined terms, sorted alphabetically according to the
+/// [text language]($text.lang), with links to the pages where each term is
+/// used.
+///
+/// # Example
+/// ```example
+/// #glossary.entry(
+///   [API],
+///   long: [Application Programming Interface],
+/// ) <api>
+///
+/// Every @api needs documentation.
+/// A good @api is easy to use.
+///
+/// #glossary()
+/// ```
+///
+/// # Plurals and capitalization
+/// For more control over how a term is displayed, use
+/// [`glossary.ref`]($glossary.ref) instead of the `@label` syntax.
+///
+/// ```example
+/// #glossary.entry(
+///   [GPU],
+///   long: [graphics processing unit],
+/// ) <gpu>
+///
+/// #glossary.ref(<gpu>, capitalize: true, form: "long")
+/// clusters consist of many
+/// #glossary.ref(<gpu>, plural: true).
+/// ```
+#[elem(scope, Show, ShowSet, LocalName)]
+pub struct GlossaryElem {
+    /// The title of the glossary.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the glossary will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+
+    /// Whether to list the pages on which each term is used.
+    #[default(true)]
+    pub backrefs: bool,
+}
+
+#[scope]
+impl GlossaryElem {
+    #[elem]
+    type GlossaryEntry;
+
+    #[elem]
+    type GlossaryRef;
+}
+
+impl Show for Packed<GlossaryElem> {
+    #[typst_macros::time(name = "glossary", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles).unwrap_or_else(|| {
+            Some(TextElem::packed(Self::local_name_in(styles)).spanned(self.span()))
+        }) {
+            seq.push(
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(self.span()),
+            );
+        }
+
+        let collator = Collator::new(TextElem::lang_in(styles));
+        let mut entries: Vec<_> = engine
+            .introspector
+            .query(&GlossaryEntry::elem().select())
+            .into_iter()
+            .map(|elem| {
+                let key = elem.to_packed::<GlossaryEntry>().unwrap().short().plain_text();
+                (key, elem)
+            })
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| collator.compare(a, b));
+
+        let mut items = vec![];
+        for (_, elem) in &entries {
+            let entry = elem.to_packed::<GlossaryEntry>().unwrap();
+            let loc = entry.location().unwrap();
+
+            let mut parts = vec![];
+            if let Some(long) = entry.long(styles) {
+                parts.push(long);
+            }
+            if let Some(description) = entry.description(styles) {
+                if !parts.is_empty() {
+                    parts.push(TextElem::packed(": "));
+                }
+                parts.push(description);
+            }
+
+            if let Some(label) = entry.label().filter(|_| self.backrefs(styles)) {
+                let selector = select_where!(GlossaryRef, Target => label);
+                let locations: Vec<_> = engine
+                    .introspector
+                    .query(&selector)
+                    .iter()
+                    .filter_map(Content::location)
+                    .collect();
+                let pages = page_list(engine, &locations, styles)?;
+                if !pages.is_empty() {
+                    if !parts.is_empty() {
+                        parts.push(TextElem::packed(", "));
+                    }
+                    parts.push(pages);
+                }
+            }
+
+            let term = entry.short().clone().backlinked(loc.variant(1));
+            items.push(Packed::new(TermItem::new(term, Content::sequence(parts))));
+        }
+
+        seq.push(TermsElem::new(items).pack().spanned(self.span()));
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ShowSet for Packed<GlossaryElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::set_outlined(false));
+        out.set(HeadingElem::set_numbering(None));
+        out
+    }
+}
+
+impl LocalName for Packed<GlossaryElem> {
+    const KEY: &'static str = "glossary";
+}
+
+/// Defines a term or acronym for the [glossary].
+///
+/// The definition itself is invisible. Attach a label to it to be able to
+/// reference the term.
+///
+/// ```example
+/// #glossary.entry(
+///   [PDF],
+///   long: [Portable Document Format],
+///   description: [A file format for documents.],
+/// ) <pdf>
+///
+/// Typst exports to @pdf.
+/// ```
+#[elem(name = "entry", title = "Glossary Entry", Locatable, Show)]
+pub struct GlossaryEntry {
+    /// The short form of the term, for example an acronym.
+    #[required]
+    pub short: Content,
+
+    /// The long form of the term, for example the expansion of an acronym.
+    pub long: Option<Content>,
+
+    /// A description of the term for the glossary listing.
+    pub description: Option<Content>,
+
+    /// The plural of the short form. Defaults to the short form followed by
+    /// an "s".
+    pub plural: Option<Content>,
+
+    /// The plural of the long form. Defaults to the long form followed by an
+    /// "s".
+    pub long_plural: Option<Content>,
+}
+
+impl Show for Packed<GlossaryEntry> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// A reference to a [glossary entry]($glossary.entry).
+///
+/// A [reference]($ref) to the label of a glossary entry produces this element
+/// with default settings.
+///
+/// ```example
+/// #glossary.entry(
+///   [CPU],
+///   long: [central processing unit],
+///   long-plural: [central processing units],
+/// ) <cpu>
+///
+/// #glossary.ref(<cpu>, plural: true)
+/// are fast. A @cpu has cores.
+/// #glossary.ref(<cpu>, form: "full")
+/// ```
+#[elem(name = "ref", title = "Glossary Reference", Locatable, Show)]
+pub struct GlossaryRef {
+    /// The label of the referenced glossary entry.
+    #[required]
+    pub target: Label,
+
+    /// Which form of the term to display.
+    ///
+    /// When `{auto}`, the full form is used on the first reference to the
+    /// entry in the document and the short form afterwards.
+    ///
+    /// The first reference is determined by document order: Every reference
+    /// to the entry counts as a use, including references in hidden content
+    /// or in repeated content like page headers. Set the form explicitly in
+    /// such places to avoid consuming the first use.
+    pub form: Smart<GlossaryForm>,
+
+    /// Whether to display the plural of the term.
+    #[default(false)]
+    pub plural: bool,
+
+    /// Whether to capitalize the first letter of the term, for example at the
+    /// start of a sentence.
+    #[default(false)]
+    pub capitalize: bool,
+
+    /// Content to display instead of the term, for example an inflected
+    /// form. The reference still links to the glossary and counts as a use
+    /// of the term.
+    ///
+    /// This is set by a supplement in reference syntax: `[@api[APIs]]`.
+    pub supplement: Option<Content>,
+}
+
+impl Show for Packed<GlossaryRef> {
+    #[typst_macros::time(name = "glossary.ref", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let span = self.span();
+        let target = *self.target();
+        let elem = engine.introspector.query_label(target).at(span)?;
+        let Some(entry) = elem.to_packed::<GlossaryEntry>() else {
+            bail!(span, "label does not refer to a glossary entry");
+        };
+
+        let form = self.form(styles).unwrap_or_else(|| {
+            let first = self.location().map_or(true, |loc| {
+                let selector = select_where!(GlossaryRef, Target => target);
+                engine.introspector.query_count_before(&selector, loc) <= 1
+            });
+            if first {
+                GlossaryForm::Full
+            } else {
+                GlossaryForm::Short
+            }
+        });
+
+        let plural = self.plural(styles);
+        let short = if plural {
+            entry
+                .plural(styles)
+                .unwrap_or_else(|| entry.short().clone() + TextElem::packed("s"))
+        } else {
+            entry.short().clone()
+        };
+
+        let long = entry.long(styles).map(|long| {
+            if plural {
+                entry
+                    .long_plural(styles)
+                    .unwrap_or_else(|| long + TextElem::packed("s"))
+            } else {
+                long
+            }
+        });
+
+        let mut body = match (self.supplement(styles), form, long) {
+            (Some(supplement), _, _) => supplement,
+            (None, GlossaryForm::Long, Some(long)) => long,
+            (None, GlossaryForm::Full, Some(long)) => {
+                long + TextElem::packed(" (") + short + TextElem::packed(")")
+            }
+            _ => short,
+        };
+
+        if self.capitalize(styles) {
+            body = capitalize(body);
+        }
+
+        // The term only has a target if the glossary is printed.
+        let loc = entry.location().unwrap().variant(1);
+        if engine.introspector.query_first(&Selector::Location(loc)).is_none() {
+            return Ok(body);
+        }
+
+        Ok(body.linked(Destination::Location(loc)))
+    }
+}
+
+/// How a glossary term is displayed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum GlossaryForm {
+    /// Only the short form, e.g. "API".
+    Short,
+    /// Only the long form, e.g. "Application Programming Interface".
+    Long,
+    /// The long form followed by the short form in parentheses.
+    Full,
+}
+
+/// Uppercase the first letter of some content.
+fn capitalize(content: Content) -> Content {
+    if let Some(text) = content.to_packed::<TextElem>() {
+        let mut chars = text.text().chars();
+        let Some(first) = chars.next() else { return content };
+        let capitalized: EcoString = first.to_uppercase().chain(chars).collect();
+        return TextElem::packed(capitalized).spanned(content.span());
+    }
+
+    if let Some(sequence) = content.to_packed::<SequenceElem>() {
+        let mut children = sequence.children().clone();
+        if let Some(first) = children.iter_mut().find(|child| !child.is_empty()) {
+            *first = capitalize(first.clone());
+        }
+        return Content::sequence(children);
+    }
+
+    if let Some(styled) = content.to_packed::<StyledElem>() {
+        return capitalize(styled.child().clone())
+            .styled_with_map(styled.styles().clone());
+    }
+
+    content
+}
@@ -4,8 +4,9 @@ use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
     elem, Content, NativeElement, Packed, Resolve, Show, ShowSet, Smart, StyleChain,
-    Styles, Synthesize,
+    Styles, Synthesize, TargetElem,
 };
+use crate::html::{HtmlElem, HtmlTag};
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
 use crate::layout::{
     Abs, Axes, BlockElem, Em, HElem, LayoutMultiple, Length, Regions, VElem,
@@ -220,6 +221,30 @@ impl Show for Packed<HeadingElem> {
         let span = self.span();
         let mut realized = self.body().clone();
 
+        if TargetElem::target_in(styles).is_html() {
+            if let Some(numbering) = (**self).numbering(styles).as_ref() {
+                realized = Counter::of(HeadingElem::elem())
+                    .display_at_loc(engine, self.location().unwrap(), styles, numbering)?
+                    .spanned(span)
+                    + SpaceElem::new().pack()
+                    + realized;
+            }
+
+            let tag = match self.resolve_level(styles).get() {
+                1 => "h1",
+                2 => "h2",
+                3 => "h3",
+                4 => "h4",
+                5 => "h5",
+                _ => "h6",
+            };
+
+            return Ok(HtmlElem::new(HtmlTag::constant(tag))
+                .with_body(Some(realized))
+                .pack()
+                .spanned(span));
+        }
+
         let hanging_indent = self.hanging_indent(styles);
 
         let mut indent = match hanging_indent {
@@ -0,0 +1,429 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, scope, Content, NativeElement, Packed, Show, ShowSet, Smart, Str, StyleChain,
+    Styles,
+};
+use crate::introspection::{Counter, CounterKey, Locatable, Location};
+use crate::layout::{Em, HElem, Length};
+use crate::model::{Destination, HeadingElem, NumberingPattern, ParElem, ParbreakElem};
+use crate::text::{localized_str, Collator, LinebreakElem, LocalName, TextElem};
+use crate::utils::NonZeroExt;
+
+/// A back-of-book index.
+///
+/// This function collects all [index markers]($index.marker) in the document
+/// and lists their terms in alphabetical order, together with the pages on
+/// which they occur. Terms are sorted according to the
+/// [text language]($text.lang) of the index, so that, for instance, `ö`
+/// sorts after `z` in a Swedish document. Consecutive pages are merged into
+/// ranges and each page number links to the marked location.
+///
+/// # Example
+/// ```example
+/// #set page(height: 100pt)
+///
+/// Typst#index.marker[Typst] is a
+/// markup-based typesetting system.
+/// Its compiler#index.marker(sub: [compiler])[Typst]
+/// is written in Rust.
+///
+/// #pagebreak()
+/// #index()
+/// ```
+///
+/// # Cross references
+/// A marker can point the reader to another term with the `see` and
+/// `see-also` parameters. A marker with a `see` reference does not add a page
+/// number to its term.
+///
+/// ```example
+/// #index.marker(see: [Typst])[Typesetting]
+/// #index.marker(see-also: [LaTeX])[TeX]
+/// TeX is old.
+///
+/// #index()
+/// ```
+#[elem(scope, keywords = ["Back-of-book index"], Show, ShowSet, LocalName)]
+pub struct IndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+
+    /// Whether to group the terms by their initial letter, with a level two
+    /// heading before each group.
+    ///
+    /// ```example
+    /// #index(grouped: false)
+    /// #index.marker[Apple]
+    /// #index.marker[Banana]
+    /// ```
+    #[default(true)]
+    pub grouped: bool,
+
+    /// How far to indent sub-terms below their main term.
+    #[default(Em::new(1.0).into())]
+    pub indent: Length,
+}
+
+#[scope]
+impl IndexElem {
+    #[elem]
+    type IndexMarker;
+
+    #[elem]
+    type IndexEntry;
+}
+
+impl Show for Packed<IndexElem> {
+    #[typst_macros::time(name = "index", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles).unwrap_or_else(|| {
+            Some(TextElem::packed(Self::local_name_in(styles)).spanned(self.span()))
+        }) {
+            seq.push(
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(self.span()),
+            );
+        }
+
+        let collator = Collator::new(TextElem::lang_in(styles));
+        let markers = engine.introspector.query(&IndexMarker::elem().select());
+
+        let mut terms: Vec<IndexTerm> = vec![];
+        for marker in &markers {
+            let marker = marker.to_packed::<IndexMarker>().unwrap();
+            let key = marker.sort_key(styles);
+            let term = match terms.iter().position(|term| term.key == key) {
+                Some(i) => &mut terms[i],
+                None => {
+                    terms.push(IndexTerm::new(key, marker.term().clone()));
+                    terms.last_mut().unwrap()
+                }
+            };
+
+            let term = match marker.sub(styles) {
+                Some(sub) => {
+                    let key: EcoString = sub.plain_text();
+                    match term.subs.iter().position(|term| term.key == key) {
+                        Some(i) => &mut term.subs[i],
+                        None => {
+                            term.subs.push(IndexTerm::new(key, sub));
+                            term.subs.last_mut().unwrap()
+                        }
+                    }
+                }
+                None => term,
+            };
+
+            term.add(marker, styles);
+        }
+
+        terms.sort_by(|a, b| collator.compare(&a.key, &b.key));
+        for term in &mut terms {
+            term.subs.sort_by(|a, b| collator.compare(&a.key, &b.key));
+        }
+
+        let mut group: Option<Option<EcoString>> = None;
+        let indent = self.indent(styles);
+        for term in &terms {
+            if self.grouped(styles) {
+                let initial = collator.initial(&term.key);
+                if group.as_ref() != Some(&initial) {
+                    let letter = initial.clone().unwrap_or_else(|| "#".into());
+                    seq.push(
+                        HeadingElem::new(TextElem::packed(letter))
+                            .with_depth(NonZeroUsize::new(2).unwrap())
+                            .pack()
+                            .spanned(self.span()),
+                    );
+                    group = Some(initial);
+                }
+            }
+
+            let entry = term.entry(engine, NonZeroUsize::ONE, styles)?;
+            seq.push(entry.pack().spanned(self.span()));
+            seq.push(LinebreakElem::new().pack());
+
+            for sub in &term.subs {
+                let entry = sub.entry(engine, NonZeroUsize::new(2).unwrap(), styles)?;
+                seq.push(HElem::new(indent.into()).pack());
+                seq.push(entry.pack().spanned(self.span()));
+                seq.push(LinebreakElem::new().pack());
+            }
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ShowSet for Packed<IndexElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::set_outlined(false));
+        out.set(HeadingElem::set_numbering(None));
+        out.set(ParElem::set_first_line_indent(Em::new(0.0).into()));
+        out
+    }
+}
+
+impl LocalName for Packed<IndexElem> {
+    const KEY: &'static str = "index";
+}
+
+/// Marks a term for inclusion in the [index].
+///
+/// The marker itself is invisible. The index lists the page on which the
+/// marker ends up next to its term, so it is best placed directly after the
+/// word it refers to.
+///
+/// ```example
+/// Rust#index.marker[Rust] is a
+/// systems programming language.
+///
+/// #index(title: none)
+/// ```
+#[elem(name = "marker", title = "Index Marker", Locatable, Show)]
+pub struct IndexMarker {
+    /// The term to list in the index.
+    #[required]
+    pub term: Content,
+
+    /// A sub-term that is listed indented below the term.
+    ///
+    /// ```example
+    /// #index.marker(sub: [lions])[Cats]
+    /// #index.marker(sub: [tigers])[Cats]
+    ///
+    /// #index(title: none)
+    /// ```
+    pub sub: Option<Content>,
+
+    /// The text by which the term is sorted. Defaults to the plain text of the
+    /// term.
+    ///
+    /// ```example
+    /// #index.marker(key: "Twenty")[20th century]
+    /// #index.marker[Nineties]
+    ///
+    /// #index(title: none, grouped: false)
+    /// ```
+    pub key: Option<Str>,
+
+    /// Another term to refer to instead of listing a page number.
+    pub see: Option<Content>,
+
+    /// Another term to refer to in addition to the page numbers.
+    pub see_also: Option<Content>,
+}
+
+impl Packed<IndexMarker> {
+    /// The text by which the main term is sorted and merged with others.
+    fn sort_key(&self, styles: StyleChain) -> EcoString {
+        match self.key(styles) {
+            Some(key) => key.into(),
+            None => self.term().plain_text(),
+        }
+    }
+}
+
+impl Show for Packed<IndexMarker> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Represents an entry line in an index, consisting of a term and its page
+/// numbers and cross references.
+///
+/// This element is intended for use with show rules to control the appearance
+/// of indexes.
+///
+/// ```example
+/// #show index.entry.where(level: 1): strong
+///
+/// Term#index.marker[Term]
+/// #index.marker(sub: [Sub-term])[Term]
+///
+/// #index(title: none, grouped: false)
+/// ```
+#[elem(name = "entry", title = "Index Entry", Show)]
+pub struct IndexEntry {
+    /// The nesting level of this entry. Main terms are at level `{1}` and
+    /// sub-terms at level `{2}`.
+    #[required]
+    pub level: NonZeroUsize,
+
+    /// The indexed term.
+    #[required]
+    pub term: Content,
+
+    /// The linked page numbers and cross references of the term.
+    #[required]
+    pub pages: Content,
+}
+
+impl Show for Packed<IndexEntry> {
+    #[typst_macros::time(name = "index.entry", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![self.term().clone()];
+        if !self.pages().is_empty() {
+            seq.push(TextElem::packed(", "));
+            seq.push(self.pages().clone());
+        }
+        Ok(Content::sequence(seq))
+    }
+}
+
+/// A term collected from the markers in the document.
+struct IndexTerm {
+    /// The text by which the term is sorted.
+    key: EcoString,
+    /// The displayed term.
+    body: Content,
+    /// The marked locations.
+    pages: Vec<Location>,
+    /// Terms referred to instead of pages.
+    see: Vec<Content>,
+    /// Terms referred to in addition to pages.
+    see_also: Vec<Content>,
+    /// The sub-terms of this term.
+    subs: Vec<IndexTerm>,
+}
+
+impl IndexTerm {
+    fn new(key: EcoString, body: Content) -> Self {
+        Self {
+            key,
+            body,
+            pages: vec![],
+            see: vec![],
+            see_also: vec![],
+            subs: vec![],
+        }
+    }
+
+    /// Record the page and cross references of a marker.
+    fn add(&mut self, marker: &Packed<IndexMarker>, styles: StyleChain) {
+        if let Some(see) = marker.see(styles) {
+            self.see.push(see);
+        } else {
+            self.pages.push(marker.location().unwrap());
+        }
+
+        if let Some(see_also) = marker.see_also(styles) {
+            self.see_also.push(see_also);
+        }
+    }
+
+    /// Create the displayed entry for this term.
+    fn entry(
+        &self,
+        engine: &mut Engine,
+        level: NonZeroUsize,
+        styles: StyleChain,
+    ) -> SourceResult<Packed<IndexEntry>> {
+        let mut seq = vec![];
+        let pages = page_list(engine, &self.pages, styles)?;
+        if !pages.is_empty() {
+            seq.push(pages);
+        }
+
+        let lang = TextElem::lang_in(styles);
+        let region = TextElem::region_in(styles);
+        for (refs, key, sep) in
+            [(&self.see, "index-see", ", "), (&self.see_also, "index-see-also", "; ")]
+        {
+            if refs.is_empty() {
+                continue;
+            }
+
+            if !seq.is_empty() {
+                seq.push(TextElem::packed(sep));
+            }
+
+            seq.push(TextElem::packed(localized_str(lang, region, key)).emph());
+            seq.push(TextElem::packed(" "));
+            for (k, term) in refs.iter().enumerate() {
+                if k > 0 {
+                    seq.push(TextElem::packed(", "));
+                }
+                seq.push(term.clone());
+            }
+        }
+
+        Ok(Packed::new(IndexEntry::new(level, self.body.clone(), Content::sequence(seq))))
+    }
+}
+
+/// Display the page numbers of a list of locations, linked to the locations.
+///
+/// Each page is only listed once and consecutive pages are merged into ranges.
+pub(crate) fn page_list(
+    engine: &mut Engine,
+    locations: &[Location],
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let mut pages: Vec<_> = locations
+        .iter()
+        .map(|&loc| (engine.introspector.page(loc), loc))
+        .collect();
+    pages.sort_by_key(|&(page, _)| page);
+    pages.dedup_by_key(|&mut (page, _)| page);
+
+    let mut seq = vec![];
+    let mut i = 0;
+    while i < pages.len() {
+        // Find the end of a range of consecutive pages.
+        let mut j = i;
+        while j + 1 < pages.len() && pages[j + 1].0.get() == pages[j].0.get() + 1 {
+            j += 1;
+        }
+
+        if !seq.is_empty() {
+            seq.push(TextElem::packed(", "));
+        }
+
+        seq.push(page_number(engine, pages[i].1, styles)?);
+        if j > i {
+            seq.push(TextElem::packed("–"));
+            seq.push(page_number(engine, pages[j].1, styles)?);
+        }
+
+        i = j + 1;
+    }
+
+    Ok(Content::sequence(seq))
+}
+
+/// Display the page number of a location, linked to the location.
+fn page_number(
+    engine: &mut Engine,
+    location: Location,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let numbering = engine
+        .introspector
+        .page_numbering(location)
+        .cloned()
+        .unwrap_or_else(|| NumberingPattern::from_str("1").unwrap().into());
+
+    let page = Counter::new(CounterKey::Page)
+        .display_at_loc(engine, location, styles, &numbering)?;
+
+    Ok(page.linked(Destination::Location(location)))
+}
@@ -8,7 +8,9 @@ mod emph;
 mod enum_;
 mod figure;
 mod footnote;
+mod glossary;
 mod heading;
+mod index;
 mod link;
 mod list;
 #[path = "numbering.rs"]
@@ -28,7 +30,9 @@ pub use self::emph::*;
 pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
+pub use self::glossary::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::list::*;
 pub use self::numbering_::*;
@@ -57,6 +61,8 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<RefElem>();
     global.define_elem::<LinkElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<IndexElem>();
+    global.define_elem::<GlossaryElem>();
     global.define_elem::<HeadingElem>();
     global.define_elem::<FigureElem>();
     global.define_elem::<FootnoteElem>();
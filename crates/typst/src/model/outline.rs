@@ -7,7 +7,7 @@ use crate::diag::{bail, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, select_where, Content, Context, Func, LocatableSelector,
-    NativeElement, Packed, Show, ShowSet, Smart, StyleChain, Styles,
+    NativeElement, Packed, Show, ShowSet, Smart, StyleChain, Styles, TargetElem,
 };
 use crate::introspection::{Counter, CounterKey, Locatable};
 use crate::layout::{BoxElem, Em, Fr, HElem, HideElem, Length, Rel, RepeatElem, Spacing};
@@ -483,7 +483,7 @@ impl OutlineEntry {
 
 impl Show for Packed<OutlineEntry> {
     #[typst_macros::time(name = "outline.entry", span = self.span())]
-    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let mut seq = vec![];
         let elem = self.element();
 
@@ -500,7 +500,14 @@ impl Show for Packed<OutlineEntry> {
         };
 
         // The body text remains overridable.
-        seq.push(self.body().clone().linked(Destination::Location(location)));
+        let body = self.body().clone().linked(Destination::Location(location));
+
+        // There are no pages to refer to in HTML.
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(body);
+        }
+
+        seq.push(body);
 
         // Add filler symbols between the section name and page number.
         if let Some(filler) = self.fill() {
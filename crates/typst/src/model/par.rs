@@ -54,6 +54,31 @@ pub struct ParElem {
     #[default(false)]
     pub justify: bool,
 
+    /// How much words in Arabic script may be elongated with kashidas when
+    /// justifying text.
+    ///
+    /// Instead of only widening the spaces between words, justified Arabic
+    /// text is traditionally stretched by extending the connections between
+    /// letters with _kashidas_ (also known as _tatweels_). If this is set to a
+    /// length, each word may be elongated by up to that amount at one point,
+    /// which is chosen according to the script's rules: Preferably after a
+    /// letter of the seen and sad families, otherwise before the last letter
+    /// of the word. Fonts that lack a tatweel glyph are never elongated.
+    ///
+    /// With [optimized line breaks]($par.linebreaks), the `kashida` entry of
+    /// the text's [`costs`]($text.costs) configures how strongly line breaks
+    /// that need a lot of elongation are avoided.
+    ///
+    /// ```example
+    /// #set par(justify: true, kashida: 0.5em)
+    /// #set text(lang: "ar")
+    /// هذا نص عربي مضبوط يستخدم الكشيدة
+    /// لتوزيع المسافات بين الكلمات.
+    /// ```
+    #[ghost]
+    #[resolve]
+    pub kashida: Option<Length>,
+
     /// How to determine line breaks.
     ///
     /// When this property is set to `{auto}`, its default value, optimized line
@@ -8,9 +8,10 @@ use crate::foundations::{
     Smart, StyleChain, Synthesize,
 };
 use crate::introspection::{Counter, Locatable};
-use crate::math::EquationElem;
+use crate::math::{EquationElem, NumberElem};
 use crate::model::{
-    BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, Numbering,
+    BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, GlossaryEntry,
+    GlossaryRef, Numbering,
 };
 use crate::text::TextElem;
 
@@ -22,7 +23,8 @@ use crate::text::TextElem;
 /// element. Reference syntax can also be used to [cite] from a bibliography.
 ///
 /// Referenceable elements include [headings]($heading), [figures]($figure),
-/// [equations]($math.equation), and [footnotes]($footnote). To create a custom
+/// [equations]($math.equation), [footnotes]($footnote), and
+/// [glossary entries]($glossary.entry). To create a custom
 /// referenceable element like a theorem, you can create a figure of a custom
 /// [`kind`]($figure.kind) and write a show rule for it. In the future, there
 /// might be a more direct way to define a custom referenceable element.
@@ -104,6 +106,7 @@ pub struct RefElem {
     ///
     /// For references to headings or figures, this is added before the
     /// referenced number. For citations, this can be used to add a page number.
+    /// For glossary entries, it is displayed instead of the term.
     ///
     /// If a function is specified, it is passed the referenced element and
     /// should return content.
@@ -181,6 +184,19 @@ impl Show for Packed<RefElem> {
             return Ok(FootnoteElem::with_label(target).pack().spanned(span));
         }
 
+        if elem.func() == GlossaryEntry::elem() {
+            let supplement = match self.supplement(styles).as_ref() {
+                Smart::Custom(Some(supplement)) => {
+                    Some(supplement.resolve(engine, styles, [elem.clone()])?)
+                }
+                _ => None,
+            };
+            return Ok(GlossaryRef::new(target)
+                .with_supplement(supplement)
+                .pack()
+                .spanned(span));
+        }
+
         let elem = elem.clone();
         let refable = elem
             .with::<dyn Refable>()
@@ -196,20 +212,33 @@ impl Show for Packed<RefElem> {
             })
             .at(span)?;
 
-        let numbering = refable
-            .numbering()
-            .ok_or_else(|| {
-                eco_format!("cannot reference {} without numbering", elem.func().name())
-            })
-            .hint(eco_format!(
+        let hint = if elem
+            .to_packed::<NumberElem>()
+            .is_some_and(|number| !number.numbered(StyleChain::default()))
+        {
+            "this line was excluded from numbering with \
+             `math.number(numbered: false)`"
+                .into()
+        } else {
+            eco_format!(
                 "you can enable {} numbering with `#set {}(numbering: \"1.\")`",
                 elem.func().name(),
-                if elem.func() == EquationElem::elem() {
+                if elem.func() == EquationElem::elem()
+                    || elem.func() == NumberElem::elem()
+                {
                     "math.equation"
                 } else {
                     elem.func().name()
                 }
-            ))
+            )
+        };
+
+        let numbering = refable
+            .numbering()
+            .ok_or_else(|| {
+                eco_format!("cannot reference {} without numbering", elem.func().name())
+            })
+            .hint(hint)
             .at(span)?;
 
         let loc = elem.location().unwrap();
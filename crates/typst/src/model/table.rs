@@ -6,7 +6,7 @@ use ecow::{eco_format, EcoString};
 use crate::diag::{bail, SourceResult, StrResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Fold, Packed, Show, Smart, StyleChain,
+    cast, elem, scope, Content, Fold, Packed, Show, Smart, StyleChain, TargetElem,
 };
 use crate::layout::{
     show_grid_cell, Abs, Alignment, Axes, Cell, CellGrid, Celled, Dir, Fragment,
@@ -268,6 +268,19 @@ impl LayoutMultiple for Packed<TableElem> {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let grid = self.cell_grid(engine, styles)?;
+        let layouter = GridLayouter::new(&grid, regions, styles, self.span());
+        layouter.layout(engine)
+    }
+}
+
+impl Packed<TableElem> {
+    /// Resolve the table's children into a grid of cells.
+    pub fn cell_grid(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<CellGrid> {
         let inset = self.inset(styles);
         let align = self.align(styles);
         let columns = self.columns(styles);
@@ -297,7 +310,7 @@ impl LayoutMultiple for Packed<TableElem> {
                 ResolvableGridChild::Item(item.to_resolvable(styles))
             }
         });
-        let grid = CellGrid::resolve(
+        CellGrid::resolve(
             tracks,
             gutter,
             children,
@@ -309,10 +322,7 @@ impl LayoutMultiple for Packed<TableElem> {
             styles,
             self.span(),
         )
-        .trace(engine.world, tracepoint, self.span())?;
-
-        let layouter = GridLayouter::new(&grid, regions, styles, self.span());
-        layouter.layout(engine)
+        .trace(engine.world, tracepoint, self.span())
     }
 }
 
@@ -880,6 +890,11 @@ impl ResolvableCell for Packed<TableCell> {
 
 impl Show for Packed<TableCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        // In HTML, insets and alignment are left to CSS.
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(self.body().clone());
+        }
+
         show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
     }
 }
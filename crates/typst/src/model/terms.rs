@@ -4,7 +4,7 @@ use crate::foundations::{
     cast, elem, scope, Array, Content, NativeElement, Packed, Smart, StyleChain,
 };
 use crate::layout::{
-    BlockElem, Dir, Em, Fragment, HElem, LayoutMultiple, Length, Regions, Sides, Spacing,
+    BlockElem, Em, Fragment, HElem, LayoutMultiple, Length, Regions, Sides, Spacing,
     StackChild, StackElem,
 };
 use crate::model::ParElem;
@@ -142,7 +142,7 @@ impl LayoutMultiple for Packed<TermsElem> {
         }
 
         let mut padding = Sides::default();
-        if TextElem::dir_in(styles) == Dir::LTR {
+        if TextElem::dir_in(styles).is_positive() {
             padding.left = pad.into();
         } else {
             padding.right = pad.into();
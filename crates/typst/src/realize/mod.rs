@@ -20,7 +20,9 @@ use crate::diag::{bail, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::foundations::{
     Content, NativeElement, Packed, SequenceElem, StyleChain, StyledElem, Styles,
+    TargetElem,
 };
+use crate::html::HtmlElem;
 use crate::introspection::TagElem;
 use crate::layout::{
     AlignElem, BlockElem, BoxElem, ColbreakElem, FlowElem, HElem, LayoutMultiple,
@@ -32,7 +34,7 @@ use crate::model::{
     ParbreakElem, TermItem, TermsElem,
 };
 use crate::syntax::Span;
-use crate::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use crate::text::{LinebreakElem, RubyMarkElem, SmartQuoteElem, SpaceElem, TextElem};
 
 /// Realize into a `DocumentElem`, an element that is capable of root-level
 /// layout.
@@ -131,6 +133,16 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
             return Ok(());
         }
 
+        // HTML elements are transparent unless exporting to HTML.
+        if let Some(elem) = content.to_packed::<HtmlElem>() {
+            if !TargetElem::target_in(styles).is_html() {
+                if let Some(body) = elem.body(styles) {
+                    self.accept(self.arenas.store(body.clone()), styles)?;
+                }
+                return Ok(());
+            }
+        }
+
         // Try to merge `content` with an element under construction
 
         if self.cites.accept(content, styles) {
@@ -381,6 +393,7 @@ impl<'a> FlowBuilder<'a> {
             || content.is::<ColbreakElem>()
             || content.is::<TagElem>()
             || content.is::<PlaceElem>()
+            || content.is::<HtmlElem>()
         {
             self.0.push(content, styles);
             return true;
@@ -455,6 +468,10 @@ impl<'a> ParBuilder<'a> {
                 .to_packed::<EquationElem>()
                 .is_some_and(|elem| !elem.block(styles))
             || content.is::<BoxElem>()
+            || content.is::<RubyMarkElem>()
+            || content
+                .to_packed::<HtmlElem>()
+                .is_some_and(|elem| elem.tag.is_inline())
         {
             self.0.push(content, styles);
             return true;
@@ -1,46 +1,75 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::diag::{Hint, HintedStrResult};
 use crate::foundations::{cast, func, Cast, Content, Str};
-use crate::text::TextElem;
+use crate::text::{Lang, TextElem};
 
 /// Converts a string or content to lowercase.
 ///
+/// Content is converted according to the rules of the
+/// [text language]($text.lang), so that, for instance, a Turkish `I` becomes a
+/// dotless `ı`. For strings, the language can be specified explicitly.
+///
 /// # Example
 /// ```example
 /// #lower("ABC") \
 /// #lower[*My Text*] \
-/// #lower[already low]
+/// #lower[already low] \
+/// #lower("ISPARTA", lang: "tr")
 /// ```
 #[func(title = "Lowercase")]
 pub fn lower(
     /// The text to convert to lowercase.
     text: Caseable,
-) -> Caseable {
-    case(text, Case::Lower)
+    /// The language whose casing rules to apply to a string. If omitted, the
+    /// language-independent Unicode rules are used.
+    #[named]
+    lang: Option<Lang>,
+) -> HintedStrResult<Caseable> {
+    case(text, Case::Lower, lang)
 }
 
 /// Converts a string or content to uppercase.
 ///
+/// Content is converted according to the rules of the
+/// [text language]($text.lang), so that, for instance, a Turkish `i` keeps its
+/// dot and Greek accents are dropped. For strings, the language can be
+/// specified explicitly.
+///
 /// # Example
 /// ```example
 /// #upper("abc") \
 /// #upper[*my text*] \
-/// #upper[ALREADY HIGH]
+/// #upper[ALREADY HIGH] \
+/// #upper("istanbul", lang: "tr")
 /// ```
 #[func(title = "Uppercase")]
 pub fn upper(
     /// The text to convert to uppercase.
     text: Caseable,
-) -> Caseable {
-    case(text, Case::Upper)
+    /// The language whose casing rules to apply to a string. If omitted, the
+    /// language-independent Unicode rules are used.
+    #[named]
+    lang: Option<Lang>,
+) -> HintedStrResult<Caseable> {
+    case(text, Case::Upper, lang)
 }
 
 /// Change the case of text.
-fn case(text: Caseable, case: Case) -> Caseable {
-    match text {
-        Caseable::Str(v) => Caseable::Str(case.apply(&v).into()),
+fn case(text: Caseable, case: Case, lang: Option<Lang>) -> HintedStrResult<Caseable> {
+    Ok(match text {
+        Caseable::Str(v) => {
+            Caseable::Str(case.apply(&v, lang.unwrap_or(Lang::ENGLISH)).into())
+        }
         Caseable::Content(v) => {
+            if lang.is_some() {
+                return Err("the language can only be specified for strings")
+                    .hint("content is converted according to the text language");
+            }
             Caseable::Content(v.styled(TextElem::set_case(Some(case))))
         }
-    }
+    })
 }
 
 /// A value whose case can be changed.
@@ -69,11 +98,97 @@ pub enum Case {
 }
 
 impl Case {
-    /// Apply the case to a string.
-    pub fn apply(self, text: &str) -> String {
-        match self {
-            Self::Lower => text.to_lowercase(),
-            Self::Upper => text.to_uppercase(),
+    /// Apply the case to a string, following the casing rules of the given
+    /// language.
+    pub fn apply(self, text: &str, lang: Lang) -> String {
+        match (self, lang.as_str()) {
+            (Self::Lower, "tr" | "az") => lower_turkic(text),
+            (Self::Upper, "tr" | "az") => upper_turkic(text),
+            (Self::Upper, "el") => upper_greek(text),
+            (Self::Lower, _) => text.to_lowercase(),
+            (Self::Upper, _) => text.to_uppercase(),
         }
     }
 }
+
+/// Lowercase text in Turkish or Azerbaijani, where the dotless `I` becomes a
+/// dotless `ı` and the dotted `İ` becomes an `i`.
+fn lower_turkic(text: &str) -> String {
+    let mut lower = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // A decomposed dotted capital I.
+            'I' if chars.next_if_eq(&'\u{0307}').is_some() => lower.push('i'),
+            'I' => lower.push('ı'),
+            'İ' => lower.push('i'),
+            _ => lower.extend(c.to_lowercase()),
+        }
+    }
+    lower
+}
+
+/// Uppercase text in Turkish or Azerbaijani, where the dotted `i` becomes a
+/// dotted `İ`.
+fn upper_turkic(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            'i' => 'İ'.to_uppercase(),
+            _ => c.to_uppercase(),
+        })
+        .collect()
+}
+
+/// Uppercase text in Greek, which drops the accents and breathing marks, but
+/// keeps the diaeresis.
+///
+/// An accent on the first of two vowels that would otherwise form a diphthong
+/// shows that they are pronounced separately. Without the accent, this is
+/// marked by a diaeresis on the second vowel instead.
+fn upper_greek(text: &str) -> String {
+    let mut upper = String::with_capacity(text.len());
+    let mut chars = text.nfd().peekable();
+    let mut accented = None;
+
+    while let Some(base) = chars.next() {
+        let mut marks = vec![];
+        while let Some(mark) = chars.next_if(|&c| is_combining_mark(c)) {
+            marks.push(mark);
+        }
+
+        let separate = accented
+            .take()
+            .is_some_and(|first| marks.is_empty() && is_greek_diphthong(first, base));
+
+        upper.extend(base.to_uppercase());
+        for &mark in &marks {
+            if !matches!(mark, '\u{0313}' | '\u{0314}') && !is_greek_accent(mark) {
+                upper.extend(mark.to_uppercase());
+            }
+        }
+
+        if separate {
+            upper.push('\u{0308}');
+        }
+
+        if marks.iter().any(|&mark| is_greek_accent(mark)) {
+            accented = Some(base);
+        }
+    }
+
+    upper.nfc().collect()
+}
+
+/// Whether a combining mark is a Greek accent.
+fn is_greek_accent(mark: char) -> bool {
+    matches!(mark, '\u{0300}' | '\u{0301}' | '\u{0342}')
+}
+
+/// Whether two vowels form a diphthong when written without accents.
+fn is_greek_diphthong(first: char, second: char) -> bool {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    matches!(
+        (lower(first), lower(second)),
+        ('α' | 'ε' | 'ο' | 'υ', 'ι') | ('α' | 'ε' | 'η' | 'ο', 'υ')
+    )
+}
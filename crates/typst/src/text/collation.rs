@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+
+use ecow::EcoString;
+use icu_collator::{CollatorOptions, Strength};
+use icu_provider::DataLocale;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::text::Lang;
+
+/// Compares strings according to the alphabetical order of a language.
+///
+/// The order follows the collation rules of the Unicode CLDR. First, base
+/// letters are compared (taking language-specific letters like the Swedish
+/// `å` into account). Ties are then broken by diacritics and finally by case,
+/// with lowercase letters sorting before uppercase ones. Languages without
+/// specific rules use the CLDR's default order, which covers all scripts.
+#[derive(Debug)]
+pub struct Collator {
+    lang: Lang,
+    /// Compares strings at all levels.
+    full: icu_collator::Collator,
+    /// Compares only the base letters of strings.
+    primary: icu_collator::Collator,
+}
+
+impl Collator {
+    /// Create a collator for the given language.
+    pub fn new(lang: Lang) -> Self {
+        let locale = lang.as_str().parse::<DataLocale>().unwrap_or_default();
+        let collator = |strength| {
+            let mut options = CollatorOptions::new();
+            options.strength = Some(strength);
+            icu_collator::Collator::try_new(&locale, options)
+                .or_else(|_| {
+                    icu_collator::Collator::try_new(&DataLocale::default(), options)
+                })
+                .unwrap()
+        };
+
+        Self {
+            lang,
+            full: collator(Strength::Tertiary),
+            primary: collator(Strength::Primary),
+        }
+    }
+
+    /// Compare two strings.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.full.compare(a, b).then_with(|| a.cmp(b))
+    }
+
+    /// The letter under which a string is grouped in alphabetical listings,
+    /// in uppercase.
+    ///
+    /// Returns `None` if the string does not start with a letter.
+    pub fn initial(&self, text: &str) -> Option<EcoString> {
+        let text: String = text.trim_start().nfc().collect();
+        let first = text.chars().next()?;
+        if !first.is_alphabetic() {
+            return None;
+        }
+
+        // Letters that are written with multiple characters, like the Czech
+        // `ch`, sort after all strings that start with their first part.
+        let ends: Vec<usize> =
+            text.char_indices().map(|(i, c)| i + c.len_utf8()).take(3).collect();
+        for n in (2..=ends.len()).rev() {
+            let (part, letter) = (&text[..ends[n - 2]], &text[..ends[n - 1]]);
+            if !letter[part.len()..].chars().all(char::is_alphabetic) {
+                continue;
+            }
+
+            if self.primary.compare(letter, &format!("{part}\u{FFFF}")).is_gt() {
+                let mut initial = EcoString::new();
+                self.push_upper(&mut initial, first);
+                initial.extend(
+                    letter[first.len_utf8()..].chars().flat_map(char::to_lowercase),
+                );
+                return Some(initial);
+            }
+        }
+
+        // A letter with diacritics is grouped under its base letter unless it
+        // is a letter of its own in the language.
+        let letter = first.to_string();
+        let base = self
+            .lower(first)
+            .chars()
+            .flat_map(|c| std::iter::once(c).nfd())
+            .find(|&c| !is_combining_mark(c))
+            .filter(|base| self.primary.compare(&letter, &base.to_string()).is_eq())
+            .unwrap_or(first);
+
+        let mut initial = EcoString::new();
+        self.push_upper(&mut initial, base);
+        Some(initial)
+    }
+
+    /// Lowercase a character, respecting the dotted and dotless i of Turkic
+    /// languages.
+    fn lower(&self, c: char) -> EcoString {
+        match (self.lang.as_str(), c) {
+            ("tr" | "az", 'I') => 'ı'.into(),
+            ("tr" | "az", 'İ') => 'i'.into(),
+            _ => c.to_lowercase().collect(),
+        }
+    }
+
+    /// Uppercase a character, respecting the dotted and dotless i of Turkic
+    /// languages.
+    fn push_upper(&self, buf: &mut EcoString, c: char) {
+        match (self.lang.as_str(), c) {
+            ("tr" | "az", 'i') => buf.push('İ'),
+            _ => c.to_uppercase().for_each(|u| buf.push(u)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn sorted(lang: &str, mut words: Vec<&'static str>) -> Vec<&'static str> {
+        let collator = Collator::new(Lang::from_str(lang).unwrap());
+        words.sort_by(|a, b| collator.compare(a, b));
+        words
+    }
+
+    #[test]
+    fn test_collation_default() {
+        assert_eq!(
+            sorted("en", vec!["zebra", "Apple", "apple", "Äpfel", "banana", "10"]),
+            ["10", "Äpfel", "apple", "Apple", "banana", "zebra"],
+        );
+        assert_eq!(
+            sorted("de", vec!["Straße", "Strasse", "Strand"]),
+            ["Strand", "Strasse", "Straße"]
+        );
+    }
+
+    #[test]
+    fn test_collation_tailored() {
+        assert_eq!(
+            sorted("sv", vec!["Öl", "Ål", "Zebra", "Äpple", "Apa"]),
+            ["Apa", "Zebra", "Ål", "Äpple", "Öl"]
+        );
+        assert_eq!(
+            sorted("cs", vec!["chata", "hrad", "ihned", "cukr"]),
+            ["cukr", "hrad", "chata", "ihned"]
+        );
+        assert_eq!(sorted("es", vec!["ñu", "nube", "oso"]), ["nube", "ñu", "oso"]);
+    }
+
+    #[test]
+    fn test_collation_initial() {
+        let sv = Collator::new(Lang::SWEDISH);
+        let de = Collator::new(Lang::GERMAN);
+        let tr = Collator::new(Lang::TURKISH);
+        assert_eq!(sv.initial("ärlig").as_deref(), Some("Ä"));
+        assert_eq!(de.initial("ärgerlich").as_deref(), Some("A"));
+        assert_eq!(tr.initial("ilginç").as_deref(), Some("İ"));
+        assert_eq!(de.initial("42").as_deref(), None);
+        let cs = Collator::new(Lang::from_str("cs").unwrap());
+        assert_eq!(cs.initial("chata").as_deref(), Some("Ch"));
+        assert_eq!(cs.initial("cukr").as_deref(), Some("C"));
+        assert_eq!(cs.initial("čaj").as_deref(), Some("Č"));
+        let hu = Collator::new(Lang::from_str("hu").unwrap());
+        assert_eq!(hu.initial("dzsungel").as_deref(), Some("Dzs"));
+    }
+}
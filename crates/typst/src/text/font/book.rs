@@ -7,6 +7,7 @@ use ttf_parser::{name_id, PlatformId, Tag};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::exceptions::find_exception;
+use crate::layout::Abs;
 use crate::text::{Font, FontStretch, FontStyle, FontVariant, FontWeight};
 
 /// Metadata about a collection of fonts.
@@ -42,6 +43,16 @@ impl FontBook {
     pub fn push(&mut self, info: FontInfo) {
         let index = self.infos.len();
         let family = info.family.to_lowercase();
+
+        // Fonts that are cut for a specific size range can also be selected
+        // through the family shared by all their cuts.
+        if info.optical_size.is_some() {
+            let shared = optical_family(&family);
+            if shared != family {
+                self.families.entry(shared.into()).or_default().push(index);
+            }
+        }
+
         self.families.entry(family).or_default().push(index);
         self.infos.push(info);
     }
@@ -62,9 +73,12 @@ impl FontBook {
         &self,
     ) -> impl Iterator<Item = (&str, impl Iterator<Item = &FontInfo>)> + '_ {
         // Since the keys are lowercased, we instead use the family field of the
-        // first face's info.
-        self.families.values().map(|ids| {
-            let family = self.infos[ids[0]].family.as_str();
+        // first face's info, trimmed to the shared family of optical cuts.
+        self.families.iter().map(|(key, ids)| {
+            let mut family = self.infos[ids[0]].family.as_str();
+            if family.to_lowercase() != *key {
+                family = optical_family(family);
+            }
             let infos = ids.iter().map(|&id| &self.infos[id]);
             (family, infos)
         })
@@ -76,7 +90,22 @@ impl FontBook {
     /// The `family` should be all lowercase.
     pub fn select(&self, family: &str, variant: FontVariant) -> Option<usize> {
         let ids = self.families.get(family)?;
-        self.find_best_variant(None, variant, ids.iter().copied())
+        self.find_best_variant(None, variant, None, ids.iter().copied())
+    }
+
+    /// Try to find a font from the given `family` that matches the given
+    /// `variant` as closely as possible and whose optical size range is
+    /// closest to the given font `size`.
+    ///
+    /// The `family` should be all lowercase.
+    pub fn select_sized(
+        &self,
+        family: &str,
+        variant: FontVariant,
+        size: Abs,
+    ) -> Option<usize> {
+        let ids = self.families.get(family)?;
+        self.find_best_variant(None, variant, Some(size), ids.iter().copied())
     }
 
     /// Iterate over all variants of a family.
@@ -109,12 +138,13 @@ impl FontBook {
             .map(|(index, _)| index);
 
         // ... and find the best variant among them.
-        self.find_best_variant(like, variant, ids)
+        self.find_best_variant(like, variant, None, ids)
     }
 
     /// Find the font in the passed iterator that
     /// - is closest to the font `like` (if any)
     /// - is closest to the given `variant`
+    /// - is designed for the given font `size` (if any)
     ///
     /// To do that we compute a key for all variants and select the one with the
     /// minimal key. This key prioritizes:
@@ -133,10 +163,12 @@ impl FontBook {
     ///   normal.
     /// - The absolute distance to the target stretch.
     /// - The absolute distance to the target weight.
+    /// - The distance of the font size to the optical size range.
     fn find_best_variant(
         &self,
         like: Option<&FontInfo>,
         variant: FontVariant,
+        size: Option<Abs>,
         ids: impl IntoIterator<Item = usize>,
     ) -> Option<usize> {
         let mut best = None;
@@ -158,6 +190,8 @@ impl FontBook {
                 current.variant.style.distance(variant.style),
                 current.variant.stretch.distance(variant.stretch),
                 current.variant.weight.distance(variant.weight),
+                size.zip(current.optical_size)
+                    .map_or(0, |(size, range)| optical_size_distance(size, range)),
             );
 
             if best_key.map_or(true, |b| key < b) {
@@ -182,6 +216,13 @@ pub struct FontInfo {
     pub flags: FontFlags,
     /// The unicode coverage of the font.
     pub coverage: Coverage,
+    /// The range of font sizes that the font is designed for, in twentieths
+    /// of a point. The lower bound is inclusive and the upper bound is
+    /// exclusive.
+    ///
+    /// This is read from the `OS/2` table or from the `opsz` axis of a
+    /// variable font.
+    pub optical_size: Option<(u16, u16)>,
 }
 
 bitflags::bitflags! {
@@ -194,6 +235,8 @@ bitflags::bitflags! {
         const MONOSPACE = 1 << 0;
         /// Glyphs have short strokes at their stems.
         const SERIF = 1 << 1;
+        /// The font has variation axes whose coordinates can be set freely.
+        const VARIABLE = 1 << 2;
     }
 }
 
@@ -272,6 +315,7 @@ impl FontInfo {
 
         let mut flags = FontFlags::empty();
         flags.set(FontFlags::MONOSPACE, ttf.is_monospaced());
+        flags.set(FontFlags::VARIABLE, ttf.is_variable());
 
         // Determine whether this is a serif or sans-serif font.
         if let Some(panose) = ttf
@@ -289,6 +333,7 @@ impl FontInfo {
             variant,
             flags,
             coverage: Coverage::from_vec(codepoints),
+            optical_size: optical_size(ttf),
         })
     }
 }
@@ -398,6 +443,67 @@ fn typographic_family(mut family: &str) -> &str {
     family
 }
 
+/// Trim an optical size suffix like "Caption" or "Display" from a family
+/// name.
+fn optical_family(family: &str) -> &str {
+    // Optical size suffixes.
+    #[rustfmt::skip]
+    const SUFFIXES: &[&str] = &[
+        "caption", "capt", "small text", "smtext", "text", "subhead", "subh",
+        "display", "disp", "micro", "fine", "deck", "poster", "banner",
+    ];
+
+    let lower = family.to_ascii_lowercase();
+    SUFFIXES
+        .iter()
+        .find_map(|s| lower.strip_suffix(s)?.strip_suffix([' ', '-', '_']))
+        .map_or(family, |trimmed| &family[..trimmed.len()])
+}
+
+/// Determine the range of font sizes a font is designed for, in twentieths of
+/// a point.
+fn optical_size(ttf: &ttf_parser::Face) -> Option<(u16, u16)> {
+    let explicit = declared_optical_size(ttf);
+    if explicit.is_some() {
+        return explicit;
+    }
+
+    // Variable fonts can cover a range of sizes with an axis.
+    let axis = ttf
+        .variation_axes()
+        .into_iter()
+        .find(|axis| axis.tag == Tag::from_bytes(b"opsz"))?;
+    let to_twips =
+        |value: f32| (value * 20.0).round().clamp(0.0, f32::from(u16::MAX)) as u16;
+    Some((to_twips(axis.min_value), to_twips(axis.max_value).saturating_add(1)))
+}
+
+/// The range of font sizes that a font's default instance is designed for, in
+/// twentieths of a point, if the font specifies it explicitly.
+pub(super) fn declared_optical_size(ttf: &ttf_parser::Face) -> Option<(u16, u16)> {
+    // Version 5 of the OS/2 table specifies the range explicitly.
+    let os2 = ttf.raw_face().table(Tag::from_bytes(b"OS/2"))?;
+    let read = |offset: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(os2.get(offset..offset + 2)?.try_into().ok()?))
+    };
+
+    let (lower, upper) = (read(96)?, read(98)?);
+    (read(0)? >= 5 && lower < upper).then_some((lower, upper))
+}
+
+/// How far a font size is outside of an optical size range, in twentieths
+/// of a point.
+pub(super) fn optical_size_distance(size: Abs, (lower, upper): (u16, u16)) -> u32 {
+    let twips = (size.to_pt() * 20.0).round().clamp(0.0, f64::from(u16::MAX)) as u16;
+    if twips < lower {
+        u32::from(lower - twips)
+    } else if twips >= upper {
+        u32::from(twips - upper) + 1
+    } else {
+        0
+    }
+}
+
 /// How many words the two strings share in their prefix.
 fn shared_prefix_words(left: &str, right: &str) -> usize {
     left.unicode_words()
@@ -506,6 +612,46 @@ mod tests {
         assert_eq!(typographic_family("Font Ultra Bold"), "Font");
     }
 
+    #[test]
+    fn test_optical_size() {
+        assert_eq!(optical_family("Source Serif 4 Caption"), "Source Serif 4");
+        assert_eq!(optical_family("Minion Pro Disp"), "Minion Pro");
+        assert_eq!(optical_family("Context"), "Context");
+        assert_eq!(optical_size_distance(Abs::pt(10.0), (120, 480)), 0);
+        assert_eq!(optical_size_distance(Abs::pt(5.0), (120, 480)), 20);
+        assert_eq!(optical_size_distance(Abs::pt(24.0), (120, 480)), 1);
+    }
+
+    #[test]
+    fn test_optical_size_selection() {
+        let info = |family: &str, optical_size| FontInfo {
+            family: family.into(),
+            variant: FontVariant::default(),
+            flags: FontFlags::empty(),
+            coverage: Coverage::from_vec(vec![]),
+            optical_size: Some(optical_size),
+        };
+
+        let book = FontBook::from_infos([
+            info("Serif Caption", (0, 180)),
+            info("Serif", (180, 360)),
+            info("Serif Display", (360, u16::MAX)),
+        ]);
+
+        let select = |family, size| {
+            book.select_sized(family, FontVariant::default(), Abs::pt(size))
+        };
+
+        assert_eq!(select("serif", 6.0), Some(0));
+        assert_eq!(select("serif", 12.0), Some(1));
+        assert_eq!(select("serif", 30.0), Some(2));
+        assert_eq!(select("serif caption", 30.0), Some(0));
+        assert_eq!(select("serif display", 6.0), Some(2));
+
+        let families: Vec<_> = book.families().map(|(family, _)| family).collect();
+        assert_eq!(families, ["Serif", "Serif Caption", "Serif Display"]);
+    }
+
     #[test]
     fn test_coverage() {
         #[track_caller]
@@ -1,15 +1,23 @@
 //! Utilities for color font handling
 
+use std::f64::consts::{PI, TAU};
 use std::io::Read;
+use std::sync::Arc;
 
 use ecow::EcoString;
-use ttf_parser::GlyphId;
+use ttf_parser::{GlyphId, OutlineBuilder, Tag};
 use usvg::{TreeParsing, TreeWriting};
 
-use crate::layout::{Abs, Axes, Em, Frame, FrameItem, Point, Size};
+use crate::foundations::Smart;
+use crate::layout::{
+    Abs, Angle, Axes, Em, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
+};
 use crate::syntax::Span;
 use crate::text::{Font, Glyph, Lang, TextItem};
-use crate::visualize::{Color, Image, Paint, Rgb};
+use crate::visualize::{
+    Color, ColorSpace, ConicGradient, Geometry, Gradient, Image, LinearGradient, Paint,
+    Path, PathItem, RadialGradient, RelativeTo, Rgb,
+};
 
 /// Tells if a glyph is a color glyph or not in a given font.
 pub fn is_color_glyph(font: &Font, g: &Glyph) -> bool {
@@ -17,15 +25,31 @@ pub fn is_color_glyph(font: &Font, g: &Glyph) -> bool {
     let glyph_id = GlyphId(g.id);
     ttf.glyph_raster_image(glyph_id, 160).is_some()
         || ttf.glyph_svg_image(glyph_id).is_some()
-        || ttf.is_color_glyph(glyph_id)
+        || is_colr_glyph(font, g.id)
+}
+
+/// Tells if a glyph is drawn from the font's `COLR` table.
+pub fn is_colr_glyph(font: &Font, glyph_id: u16) -> bool {
+    font.ttf().is_color_glyph(GlyphId(glyph_id))
+        || Colr::new(font, Color::BLACK).is_some_and(|colr| colr.contains(glyph_id))
 }
 
 /// Returns a frame with the glyph drawn inside.
 ///
 /// The glyphs are sized in font units, [`text.item.size`] is not taken into
-/// account.
+/// account. Parts of `COLR` glyphs that use the foreground color are drawn
+/// with the text's fill if it is a solid color and black otherwise.
+pub fn frame_for_glyph(font: &Font, glyph_id: u16, fill: &Paint) -> Frame {
+    let foreground = match fill {
+        Paint::Solid(color) => *color,
+        Paint::Gradient(_) | Paint::Pattern(_) => Color::BLACK,
+    };
+    draw_glyph(font, glyph_id, foreground)
+}
+
+/// Draws a glyph with the given foreground color into a frame.
 #[comemo::memoize]
-pub fn frame_for_glyph(font: &Font, glyph_id: u16) -> Frame {
+fn draw_glyph(font: &Font, glyph_id: u16, foreground: Color) -> Frame {
     let ttf = font.ttf();
     let upem = Abs::pt(ttf.units_per_em() as f64);
     let glyph_id = GlyphId(glyph_id);
@@ -36,8 +60,12 @@ pub fn frame_for_glyph(font: &Font, glyph_id: u16) -> Frame {
         draw_raster_glyph(&mut frame, font, upem, raster_image);
     } else if ttf.glyph_svg_image(glyph_id).is_some() {
         draw_svg_glyph(&mut frame, upem, font, glyph_id);
+    } else if let Some(colr) =
+        Colr::new(font, foreground).filter(|colr| colr.contains(glyph_id.0))
+    {
+        colr.draw(&mut frame, glyph_id.0);
     } else if ttf.is_color_glyph(glyph_id) {
-        draw_colr_glyph(&mut frame, font, glyph_id);
+        draw_colr_glyph(&mut frame, font, glyph_id, foreground);
     }
 
     frame
@@ -76,8 +104,8 @@ fn draw_raster_glyph(
 }
 
 /// Draws a COLR glyph in a frame.
-fn draw_colr_glyph(frame: &mut Frame, font: &Font, glyph_id: GlyphId) {
-    let mut painter = ColrPainter { font, current_glyph: glyph_id, frame };
+fn draw_colr_glyph(frame: &mut Frame, font: &Font, glyph_id: GlyphId, foreground: Color) {
+    let mut painter = ColrPainter { font, current_glyph: glyph_id, foreground, frame };
     font.ttf().paint_color_glyph(glyph_id, 0, &mut painter);
 }
 
@@ -89,6 +117,8 @@ struct ColrPainter<'f, 't> {
     font: &'t Font,
     /// The glyph that will be drawn the next time `ColrPainter::paint` is called.
     current_glyph: GlyphId,
+    /// The color used where the font asks for the text color.
+    foreground: Color,
 }
 
 impl<'f, 't> ColrPainter<'f, 't> {
@@ -126,8 +156,7 @@ impl<'f, 't> ttf_parser::colr::Painter for ColrPainter<'f, 't> {
     }
 
     fn paint_foreground(&mut self) {
-        // Default to black if no color was specified
-        self.paint(Paint::Solid(Color::BLACK))
+        self.paint(Paint::Solid(self.foreground))
     }
 
     fn paint_color(&mut self, color: ttf_parser::RgbaColor) {
@@ -141,6 +170,1021 @@ impl<'f, 't> ttf_parser::colr::Painter for ColrPainter<'f, 't> {
     }
 }
 
+/// The offsets into a `COLR` table of version 1.
+///
+/// They are read once when the font is parsed, so that checking whether a
+/// glyph is a color glyph doesn't need to look at the table's header again.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct ColrHeader {
+    /// The offset and count of the version 0 base glyph records.
+    base_glyph_records: (usize, usize),
+    /// The offset of the version 0 layer records.
+    layer_records: usize,
+    /// The offset of the base glyph paint list.
+    base_glyph_list: usize,
+    /// The offset of the layer paint list.
+    layer_list: usize,
+    /// The offset of the clip box list.
+    clip_list: usize,
+}
+
+impl ColrHeader {
+    /// Read the header of a font's `COLR` table, if it has one of version 1.
+    pub(super) fn from_ttf(ttf: &ttf_parser::Face) -> Option<Self> {
+        let data = ttf.raw_face().table(Tag::from_bytes(b"COLR"))?;
+        let u16 =
+            |at: usize| Some(u16::from_be_bytes([*data.get(at)?, *data.get(at + 1)?]));
+        let u32 = |at: usize| {
+            Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?) as usize)
+        };
+
+        if u16(0)? != 1 {
+            return None;
+        }
+
+        Some(Self {
+            base_glyph_records: (u32(4)?, u16(2)? as usize),
+            layer_records: u32(8)?,
+            base_glyph_list: u32(14)?,
+            layer_list: u32(18)?,
+            clip_list: u32(22)?,
+        })
+    }
+}
+
+/// A `COLR` table of version 1, which describes color glyphs as graphs of
+/// paint operations with gradients, transforms and compositing.
+///
+/// Version 0 tables are read by `ttf-parser`, but it rejects version 1 tables
+/// entirely, so this also covers the version 0 layers that version 1 tables
+/// contain for backwards compatibility.
+///
+/// Variable fonts are drawn at their default instance. Compositing modes that
+/// keep only parts of the source or backdrop are approximated by clipping
+/// with the outlines of the other layer and blending modes are approximated
+/// by drawing the source over the backdrop, as frames have no notion of
+/// blending.
+struct Colr<'a> {
+    /// The font the table belongs to.
+    font: &'a Font,
+    /// The color used where the font asks for the text color.
+    foreground: Color,
+    /// The raw table data.
+    data: &'a [u8],
+    /// The raw `CPAL` table data, which is empty if the font has none.
+    palettes: &'a [u8],
+    /// The offset and count of the version 0 base glyph records.
+    base_glyph_records: (usize, usize),
+    /// The offset of the version 0 layer records.
+    layer_records: usize,
+    /// The offset of the base glyph paint list.
+    base_glyph_list: usize,
+    /// The offset of the layer paint list.
+    layer_list: usize,
+    /// The offset of the clip box list.
+    clip_list: usize,
+}
+
+/// The maximum nesting depth of paint graphs, which protects against cycles.
+const MAX_PAINT_DEPTH: usize = 64;
+
+/// The side length of the square that gradients are drawn into before it is
+/// scaled to its actual size. Gradients are laid out in font units, which
+/// would otherwise make raster exporters sample huge gradient textures.
+const GRADIENT_SIZE: f64 = 64.0;
+
+impl<'a> Colr<'a> {
+    /// Read the `COLR` table of a font, if it has one of version 1.
+    fn new(font: &'a Font, foreground: Color) -> Option<Self> {
+        let ColrHeader {
+            base_glyph_records,
+            layer_records,
+            base_glyph_list,
+            layer_list,
+            clip_list,
+        } = font.colr()?;
+
+        let face = font.ttf().raw_face();
+        Some(Self {
+            font,
+            foreground,
+            data: face.table(Tag::from_bytes(b"COLR"))?,
+            palettes: face.table(Tag::from_bytes(b"CPAL")).unwrap_or_default(),
+            base_glyph_records,
+            layer_records,
+            base_glyph_list,
+            layer_list,
+            clip_list,
+        })
+    }
+
+    /// Whether the table describes the glyph.
+    fn contains(&self, glyph: u16) -> bool {
+        self.base_paint(glyph).is_some() || self.base_layers(glyph).is_some()
+    }
+
+    /// Draw a glyph into a frame that is sized in font units.
+    fn draw(&self, frame: &mut Frame, glyph: u16) {
+        let bounds = self
+            .clip_box(glyph)
+            .or_else(|| {
+                let rect = self.font.ttf().glyph_bounding_box(GlyphId(glyph))?;
+                Some(Bounds::new(
+                    point(rect.x_min as f64, rect.y_min as f64),
+                    point(rect.x_max as f64, rect.y_max as f64),
+                ))
+            })
+            .unwrap_or_else(|| {
+                let upem = self.font.units_per_em();
+                Bounds::new(point(0.0, 0.0), point(upem, upem))
+            });
+
+        let mut inner = self.layer();
+        if let Some(paint) = self.base_paint(glyph) {
+            self.paint(&mut inner, paint, bounds, 0);
+        } else if let Some((first, count)) = self.base_layers(glyph) {
+            for i in first..first + count {
+                let record = self.layer_records + 4 * i;
+                let (Some(id), Some(index)) = (self.u16(record), self.u16(record + 2))
+                else {
+                    break;
+                };
+                if let (Some(outline), Some(color)) =
+                    (self.outline(id), self.color(index, 1.0))
+                {
+                    inner.push(
+                        Point::zero(),
+                        FrameItem::Shape(
+                            Geometry::Path(outline.path).filled(Paint::Solid(color)),
+                            Span::detached(),
+                        ),
+                    );
+                }
+            }
+        }
+
+        // The paint graph lives in font units with the Y axis going upwards,
+        // with the baseline at the bottom of the frame.
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::translate(Abs::zero(), frame.height())
+            .pre_concat(Transform::scale(Ratio::one(), -Ratio::one()));
+        frame.push(Point::zero(), FrameItem::Group(group));
+    }
+
+    /// Draw the paint at the given offset into a frame.
+    ///
+    /// The bounds are the area in the frame's coordinates that fills need to
+    /// cover. Malformed paints are skipped.
+    fn paint(
+        &self,
+        frame: &mut Frame,
+        offset: usize,
+        bounds: Bounds,
+        depth: usize,
+    ) -> Option<()> {
+        if depth > MAX_PAINT_DEPTH {
+            return None;
+        }
+
+        let depth = depth + 1;
+        let format = self.u8(offset)?;
+        let child = |at: usize| Some(offset + self.u24(offset + at)? as usize);
+        let center = |at: usize| {
+            Some(point(self.fword(offset + at)?, self.fword(offset + at + 2)?))
+        };
+
+        match format {
+            // PaintColrLayers
+            1 => {
+                let count = self.u8(offset + 1)? as usize;
+                let first = self.u32(offset + 2)? as usize;
+                for i in first..first + count {
+                    let paint =
+                        self.layer_list + self.u32(self.layer_list + 4 + 4 * i)? as usize;
+                    self.paint(frame, paint, bounds, depth);
+                }
+            }
+            // PaintSolid
+            2 | 3 => {
+                let color =
+                    self.color(self.u16(offset + 1)?, self.f2dot14(offset + 3)?)?;
+                frame.push(
+                    bounds.min,
+                    FrameItem::Shape(
+                        Geometry::Rect(bounds.size()).filled(Paint::Solid(color)),
+                        Span::detached(),
+                    ),
+                );
+            }
+            // PaintLinearGradient, PaintRadialGradient and PaintSweepGradient
+            4..=9 => {
+                let line = self.color_line(child(1)?, format % 2 == 1)?;
+                self.fill_gradient(frame, offset, format, &line, bounds)?;
+            }
+            // PaintGlyph
+            10 => {
+                let outline = self.outline(self.u16(offset + 4)?)?;
+                let paint = child(1)?;
+
+                // Fill the outline directly if possible.
+                if let Some(2 | 3) = self.u8(paint) {
+                    let color =
+                        self.color(self.u16(paint + 1)?, self.f2dot14(paint + 3)?)?;
+                    frame.push(
+                        Point::zero(),
+                        FrameItem::Shape(
+                            Geometry::Path(outline.path).filled(Paint::Solid(color)),
+                            Span::detached(),
+                        ),
+                    );
+                    return Some(());
+                }
+
+                let mut inner = self.layer();
+                self.paint(&mut inner, paint, outline.bounds, depth);
+                let mut group = GroupItem::new(inner);
+                group.clip_path = Some(outline.path);
+                frame.push(Point::zero(), FrameItem::Group(group));
+            }
+            // PaintColrGlyph
+            11 => {
+                let paint = self.base_paint(self.u16(offset + 1)?)?;
+                self.paint(frame, paint, bounds, depth);
+            }
+            // PaintTransform
+            12 | 13 => {
+                let at = child(4)?;
+                let ts = Transform {
+                    sx: Ratio::new(self.fixed(at)?),
+                    ky: Ratio::new(self.fixed(at + 4)?),
+                    kx: Ratio::new(self.fixed(at + 8)?),
+                    sy: Ratio::new(self.fixed(at + 12)?),
+                    tx: Abs::pt(self.fixed(at + 16)?),
+                    ty: Abs::pt(self.fixed(at + 20)?),
+                };
+                self.transformed(frame, child(1)?, ts, bounds, depth);
+            }
+            // PaintTranslate
+            14 | 15 => {
+                let delta = center(4)?;
+                let ts = Transform::translate(delta.x, delta.y);
+                self.transformed(frame, child(1)?, ts, bounds, depth);
+            }
+            // PaintScale, PaintScaleAroundCenter, PaintScaleUniform and
+            // PaintScaleUniformAroundCenter
+            16..=23 => {
+                let uniform = format >= 20;
+                let sx = self.f2dot14(offset + 4)?;
+                let sy = if uniform { sx } else { self.f2dot14(offset + 6)? };
+                let ts = Transform::scale(Ratio::new(sx), Ratio::new(sy));
+                let ts = match format {
+                    18 | 19 => around(ts, center(8)?),
+                    22 | 23 => around(ts, center(6)?),
+                    _ => ts,
+                };
+                self.transformed(frame, child(1)?, ts, bounds, depth);
+            }
+            // PaintRotate and PaintRotateAroundCenter
+            24..=27 => {
+                let ts = Transform::rotate(Angle::rad(self.f2dot14(offset + 4)? * PI));
+                let ts = if format >= 26 { around(ts, center(6)?) } else { ts };
+                self.transformed(frame, child(1)?, ts, bounds, depth);
+            }
+            // PaintSkew and PaintSkewAroundCenter
+            28..=31 => {
+                let ts = Transform {
+                    kx: Ratio::new((-self.f2dot14(offset + 4)? * PI).tan()),
+                    ky: Ratio::new((self.f2dot14(offset + 6)? * PI).tan()),
+                    ..Transform::identity()
+                };
+                let ts = if format >= 30 { around(ts, center(8)?) } else { ts };
+                self.transformed(frame, child(1)?, ts, bounds, depth);
+            }
+            // PaintComposite
+            32 => {
+                let mut source = self.layer();
+                let mut backdrop = self.layer();
+                self.paint(&mut source, child(1)?, bounds, depth);
+                self.paint(&mut backdrop, child(5)?, bounds, depth);
+                for layer in composite(self.u8(offset + 4)?, source, backdrop, bounds) {
+                    frame.push_frame(Point::zero(), layer);
+                }
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// Draw a paint with a transform applied to it.
+    fn transformed(
+        &self,
+        frame: &mut Frame,
+        paint: usize,
+        ts: Transform,
+        bounds: Bounds,
+        depth: usize,
+    ) -> Option<()> {
+        let mut inner = self.layer();
+        self.paint(&mut inner, paint, bounds.transform(ts.invert()?), depth)?;
+        let mut group = GroupItem::new(inner);
+        group.transform = ts;
+        frame.push(Point::zero(), FrameItem::Group(group));
+        Some(())
+    }
+
+    /// Fill the bounds with a linear, radial or sweep gradient.
+    ///
+    /// The gradient is drawn into a square so that its geometry isn't
+    /// distorted by the aspect ratio of the bounds.
+    fn fill_gradient(
+        &self,
+        frame: &mut Frame,
+        offset: usize,
+        format: u8,
+        line: &ColorLine,
+        bounds: Bounds,
+    ) -> Option<()> {
+        let origin = bounds.min;
+        let side = bounds.size().x.max(bounds.size().y).to_pt();
+        if side <= 0.0 {
+            return None;
+        }
+
+        let at =
+            |p: Point| ((p.x - origin.x).to_pt() / side, (p.y - origin.y).to_pt() / side);
+        let coord = |at: usize| {
+            Some(point(self.fword(offset + at)?, self.fword(offset + at + 2)?))
+        };
+        let space = ColorSpace::Srgb;
+        let relative = Smart::Custom(RelativeTo::Self_);
+
+        let gradient = match format {
+            // PaintLinearGradient
+            4 | 5 => {
+                let (p0, p1, p2) = (coord(4)?, coord(8)?, coord(12)?);
+
+                // The gradient runs perpendicular to the line from p0 to p2,
+                // so p1 is projected onto the normal of that line.
+                let (dx, dy) = ((p1.x - p0.x).to_pt(), (p1.y - p0.y).to_pt());
+                let (nx, ny) = ((p0.y - p2.y).to_pt(), (p2.x - p0.x).to_pt());
+                let norm = nx * nx + ny * ny;
+                let (dx, dy) = if norm > 0.0 {
+                    let k = (dx * nx + dy * ny) / norm;
+                    (nx * k, ny * k)
+                } else {
+                    (dx, dy)
+                };
+
+                let length = dx.hypot(dy);
+                if length <= 0.0 {
+                    return None;
+                }
+
+                // Typst's linear gradients span the whole square along their
+                // direction, so find the color line's offsets at the corners
+                // where the gradient starts and ends.
+                let (ux, uy) = (dx / length, dy / length);
+                let start = side * (ux.min(0.0) + uy.min(0.0))
+                    + (origin.x - p0.x).to_pt() * ux
+                    + (origin.y - p0.y).to_pt() * uy;
+                let from = start / length;
+                let to = from + side * (ux.abs() + uy.abs()) / length;
+                Gradient::Linear(Arc::new(LinearGradient {
+                    stops: line.stops(from, to),
+                    angle: Angle::rad(uy.atan2(ux).rem_euclid(TAU)),
+                    space,
+                    relative,
+                    anti_alias: true,
+                }))
+            }
+            // PaintRadialGradient
+            6 | 7 => {
+                let (c0, r0) = (coord(4)?, self.u16(offset + 8)? as f64);
+                let (c1, r1) = (coord(10)?, self.u16(offset + 14)? as f64);
+
+                // Move the circles to the first and last stops, beyond which
+                // the gradient is padded.
+                let (a, b) = line.range();
+                let circle = |t: f64| {
+                    let c = point(
+                        c0.x.to_pt() + t * (c1.x - c0.x).to_pt(),
+                        c0.y.to_pt() + t * (c1.y - c0.y).to_pt(),
+                    );
+                    (at(c), (r0 + t * (r1 - r0)).max(0.0) / side)
+                };
+
+                // The focal circle must be the smaller one.
+                let (mut start, mut end) = (circle(a), circle(b));
+                let mut stops = line.stops(a, b);
+                if end.1 < start.1 {
+                    std::mem::swap(&mut start, &mut end);
+                    stops = line.stops(b, a);
+                }
+
+                let ratios = |(x, y): (f64, f64)| Axes::new(Ratio::new(x), Ratio::new(y));
+                Gradient::Radial(Arc::new(RadialGradient {
+                    stops,
+                    center: ratios(end.0),
+                    radius: Ratio::new(end.1),
+                    focal_center: ratios(start.0),
+                    focal_radius: Ratio::new(start.1),
+                    space,
+                    relative,
+                    anti_alias: true,
+                }))
+            }
+            // PaintSweepGradient
+            _ => {
+                let center = at(coord(4)?);
+                // Sweep angles are biased by half a turn, so that a full turn
+                // can be represented.
+                let start = (self.f2dot14(offset + 8)? + 1.0) * PI;
+                let end = (self.f2dot14(offset + 10)? + 1.0) * PI;
+                let sweep = end - start;
+                if sweep == 0.0 {
+                    return None;
+                }
+
+                // Conic gradients start at their angle and progress
+                // clockwise, while sweeps can go either way.
+                let stops = if sweep > 0.0 {
+                    line.stops(TAU / sweep, 0.0)
+                } else {
+                    line.stops(0.0, TAU / -sweep)
+                };
+
+                Gradient::Conic(Arc::new(ConicGradient {
+                    stops,
+                    angle: Angle::rad((start - PI).rem_euclid(TAU)),
+                    center: Axes::new(Ratio::new(center.0), Ratio::new(center.1)),
+                    space,
+                    relative,
+                    anti_alias: true,
+                }))
+            }
+        };
+
+        let size = Size::splat(Abs::pt(GRADIENT_SIZE));
+        let mut inner = Frame::soft(size);
+        inner.push(
+            Point::zero(),
+            FrameItem::Shape(
+                Geometry::Rect(size).filled(Paint::Gradient(gradient)),
+                Span::detached(),
+            ),
+        );
+
+        let scale = Ratio::new(side / GRADIENT_SIZE);
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::translate(origin.x, origin.y)
+            .pre_concat(Transform::scale(scale, scale));
+        frame.push(Point::zero(), FrameItem::Group(group));
+        Some(())
+    }
+
+    /// Read the color line at the given offset.
+    fn color_line(&self, offset: usize, var: bool) -> Option<ColorLine> {
+        let extend = match self.u8(offset)? {
+            1 => Extend::Repeat,
+            2 => Extend::Reflect,
+            _ => Extend::Pad,
+        };
+
+        let count = self.u16(offset + 1)? as usize;
+        let size = if var { 10 } else { 6 };
+        let mut stops = (0..count)
+            .map(|i| {
+                let at = offset + 3 + i * size;
+                let color = self.rgba(self.u16(at + 2)?, self.f2dot14(at + 4)?)?;
+                Some((self.f2dot14(at)?, color))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if stops.is_empty() {
+            return None;
+        }
+
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Some(ColorLine { extend, stops })
+    }
+
+    /// The paint of a glyph in the base glyph list.
+    fn base_paint(&self, glyph: u16) -> Option<usize> {
+        if self.base_glyph_list == 0 {
+            return None;
+        }
+
+        let count = self.u32(self.base_glyph_list)? as usize;
+        let record = self.search(self.base_glyph_list + 4, count, 6, glyph)?;
+        Some(self.base_glyph_list + self.u32(record + 2)? as usize)
+    }
+
+    /// The index of the first layer and the number of layers of a glyph in
+    /// the version 0 base glyph records.
+    fn base_layers(&self, glyph: u16) -> Option<(usize, usize)> {
+        let (offset, count) = self.base_glyph_records;
+        if offset == 0 {
+            return None;
+        }
+
+        let record = self.search(offset, count, 6, glyph)?;
+        Some((self.u16(record + 2)? as usize, self.u16(record + 4)? as usize))
+    }
+
+    /// The clip box of a glyph.
+    fn clip_box(&self, glyph: u16) -> Option<Bounds> {
+        if self.clip_list == 0 {
+            return None;
+        }
+
+        let count = self.u32(self.clip_list + 1)? as usize;
+        for i in 0..count {
+            let clip = self.clip_list + 5 + 7 * i;
+            if (self.u16(clip)?..=self.u16(clip + 2)?).contains(&glyph) {
+                let at = self.clip_list + self.u24(clip + 4)? as usize;
+                return Some(Bounds::new(
+                    point(self.fword(at + 1)?, self.fword(at + 3)?),
+                    point(self.fword(at + 5)?, self.fword(at + 7)?),
+                ));
+            }
+        }
+
+        None
+    }
+
+    /// Binary search for a glyph in an array of records that start with a
+    /// glyph ID, returning the record's offset.
+    fn search(
+        &self,
+        offset: usize,
+        count: usize,
+        size: usize,
+        glyph: u16,
+    ) -> Option<usize> {
+        let (mut lo, mut hi) = (0, count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let record = offset + mid * size;
+            match self.u16(record)?.cmp(&glyph) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(record),
+            }
+        }
+        None
+    }
+
+    /// The outline of a glyph in font units.
+    fn outline(&self, glyph: u16) -> Option<Outline> {
+        let mut outline = Outline::default();
+        self.font.ttf().outline_glyph(GlyphId(glyph), &mut outline)?;
+        Some(outline)
+    }
+
+    /// A color from the default palette with an additional alpha. The special
+    /// index `0xFFFF` refers to the text color.
+    fn color(&self, index: u16, alpha: f64) -> Option<Color> {
+        let [r, g, b, a] = self.rgba(index, alpha)?;
+        Some(Color::Rgb(Rgb::new(r, g, b, a)))
+    }
+
+    /// The components of a color from the default palette with an additional
+    /// alpha.
+    fn rgba(&self, index: u16, alpha: f64) -> Option<[f32; 4]> {
+        let alpha = alpha.clamp(0.0, 1.0) as f32;
+        if index == 0xFFFF {
+            let [r, g, b, a] = self.foreground.to_rgb().to_vec4();
+            return Some([r, g, b, a * alpha]);
+        }
+
+        // `ttf-parser` only reads version 0 of the `CPAL` table, but both
+        // versions start with the same fields, which is all that's needed for
+        // the default palette.
+        let cpal = self.palettes;
+        let u16 =
+            |at: usize| Some(u16::from_be_bytes([*cpal.get(at)?, *cpal.get(at + 1)?]));
+        if index >= u16(2)? {
+            return None;
+        }
+
+        let records = u32::from_be_bytes(cpal.get(8..12)?.try_into().ok()?) as usize;
+        let at = records + 4 * (u16(12)? as usize + index as usize);
+        let [b, g, r, a]: [u8; 4] = cpal.get(at..at + 4)?.try_into().ok()?;
+        Some([
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0 * alpha,
+        ])
+    }
+
+    /// An empty frame for a layer of the paint graph.
+    fn layer(&self) -> Frame {
+        Frame::soft(Size::splat(Abs::pt(self.font.units_per_em())))
+    }
+
+    fn u8(&self, at: usize) -> Option<u8> {
+        self.data.get(at).copied()
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        Some(u16::from_be_bytes(self.data.get(at..at + 2)?.try_into().ok()?))
+    }
+
+    fn u24(&self, at: usize) -> Option<u32> {
+        let [a, b, c]: [u8; 3] = self.data.get(at..at + 3)?.try_into().ok()?;
+        Some(u32::from_be_bytes([0, a, b, c]))
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        Some(u32::from_be_bytes(self.data.get(at..at + 4)?.try_into().ok()?))
+    }
+
+    /// A signed 16-bit coordinate in font units.
+    fn fword(&self, at: usize) -> Option<f64> {
+        Some(self.u16(at)? as i16 as f64)
+    }
+
+    /// A signed 2.14 fixed-point number.
+    fn f2dot14(&self, at: usize) -> Option<f64> {
+        Some(self.u16(at)? as i16 as f64 / 16384.0)
+    }
+
+    /// A signed 16.16 fixed-point number.
+    fn fixed(&self, at: usize) -> Option<f64> {
+        Some(self.u32(at)? as i32 as f64 / 65536.0)
+    }
+}
+
+/// Combine the layers of a composite paint with the given mode.
+///
+/// Modes that keep parts of one layer depending on the other clip it with the
+/// other layer's coverage. If the coverage can't be determined, or the mode
+/// blends colors, the layers are approximated by simple ordering.
+fn composite(mode: u8, source: Frame, backdrop: Frame, bounds: Bounds) -> Vec<Frame> {
+    let inside = |layer: Frame, mask: &Frame| match coverage(mask) {
+        Some(path) => vec![clipped(layer, path)],
+        None => vec![layer],
+    };
+    let outside = |layer: Frame, mask: &Frame| match coverage(mask) {
+        Some(path) => vec![clipped(layer, complement(&path, bounds))],
+        None => vec![layer],
+    };
+
+    match mode {
+        // Clear.
+        0 => vec![],
+        // Source.
+        1 => vec![source],
+        // Destination.
+        2 => vec![backdrop],
+        // Destination over.
+        4 => vec![source, backdrop],
+        // Source in.
+        5 => inside(source, &backdrop),
+        // Destination in.
+        6 => inside(backdrop, &source),
+        // Source out.
+        7 => outside(source, &backdrop),
+        // Destination out.
+        8 => outside(backdrop, &source),
+        // Source atop.
+        9 => {
+            let top = inside(source, &backdrop);
+            [vec![backdrop], top].concat()
+        }
+        // Destination atop.
+        10 => {
+            let top = inside(backdrop, &source);
+            [vec![source], top].concat()
+        }
+        // Xor.
+        11 => {
+            let (Some(s), Some(b)) = (coverage(&source), coverage(&backdrop)) else {
+                return vec![backdrop, source];
+            };
+            vec![
+                clipped(source, complement(&b, bounds)),
+                clipped(backdrop, complement(&s, bounds)),
+            ]
+        }
+        // Source over and the blending modes.
+        _ => vec![backdrop, source],
+    }
+}
+
+/// Wrap a layer in a group that is clipped to a path.
+fn clipped(layer: Frame, path: Path) -> Frame {
+    let mut frame = Frame::soft(layer.size());
+    let mut group = GroupItem::new(layer);
+    group.clip_path = Some(path);
+    frame.push(Point::zero(), FrameItem::Group(group));
+    frame
+}
+
+/// The area that a layer of the paint graph draws to, as a path.
+///
+/// Filled shapes and clipped groups are assumed to cover their whole outline,
+/// which holds for the frames that paints produce. Returns `None` if the frame
+/// contains anything else.
+fn coverage(frame: &Frame) -> Option<Path> {
+    let mut path = Path::new();
+    for (pos, item) in frame.items() {
+        let ts = Transform::translate(pos.x, pos.y);
+        match item {
+            FrameItem::Group(group) => {
+                let inner = match &group.clip_path {
+                    Some(clip) => clip.clone(),
+                    None => coverage(&group.frame)?,
+                };
+                path.0
+                    .extend(transform_path(&inner, ts.pre_concat(group.transform)).0);
+            }
+            FrameItem::Shape(shape, _) if shape.fill.is_some() => {
+                let outline = match &shape.geometry {
+                    Geometry::Rect(size) => Path::rect(*size),
+                    Geometry::Path(path) => path.clone(),
+                    Geometry::Line(_) => continue,
+                };
+                path.0.extend(transform_path(&outline, ts).0);
+            }
+            FrameItem::Shape(..) => {}
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+/// The part of the bounds that a path doesn't cover, under the nonzero fill
+/// rule that clip paths use.
+///
+/// The bounds are grown generously, as layers may draw outlines beyond them.
+/// They are traced against the path's orientation, so that the winding
+/// numbers cancel out inside of the path.
+fn complement(path: &Path, bounds: Bounds) -> Path {
+    let grow = bounds.size().x.max(bounds.size().y) + Abs::pt(1.0);
+    let min = bounds.min - Point::splat(grow);
+    let max = bounds.max + Point::splat(grow);
+
+    let mut corners = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+    if signed_area(path) > 0.0 {
+        corners.reverse();
+    }
+
+    let mut result = Path::new();
+    result.move_to(corners[0]);
+    for corner in &corners[1..] {
+        result.line_to(*corner);
+    }
+    result.close_path();
+    result.0.extend(path.0.iter().cloned());
+    result
+}
+
+/// The signed area of a path's control polygon, which is positive if its
+/// outer contours run counter-clockwise in a coordinate system whose Y axis
+/// goes upwards.
+fn signed_area(path: &Path) -> f64 {
+    let mut area = 0.0;
+    let mut start = Point::zero();
+    let mut last = Point::zero();
+    let mut edge = |a: Point, b: Point| {
+        area += a.x.to_pt() * b.y.to_pt() - b.x.to_pt() * a.y.to_pt();
+    };
+
+    for item in &path.0 {
+        match *item {
+            PathItem::MoveTo(p) => {
+                edge(last, start);
+                start = p;
+                last = p;
+            }
+            PathItem::LineTo(p) => {
+                edge(last, p);
+                last = p;
+            }
+            PathItem::CubicTo(p1, p2, p) => {
+                edge(last, p1);
+                edge(p1, p2);
+                edge(p2, p);
+                last = p;
+            }
+            PathItem::ClosePath => {
+                edge(last, start);
+                last = start;
+            }
+        }
+    }
+    edge(last, start);
+    area / 2.0
+}
+
+/// Apply a transform to all points of a path.
+fn transform_path(path: &Path, ts: Transform) -> Path {
+    Path(
+        path.0
+            .iter()
+            .map(|item| match *item {
+                PathItem::MoveTo(p) => PathItem::MoveTo(p.transform(ts)),
+                PathItem::LineTo(p) => PathItem::LineTo(p.transform(ts)),
+                PathItem::CubicTo(p1, p2, p) => {
+                    PathItem::CubicTo(p1.transform(ts), p2.transform(ts), p.transform(ts))
+                }
+                PathItem::ClosePath => PathItem::ClosePath,
+            })
+            .collect(),
+    )
+}
+
+/// Apply a transform around a center point.
+fn around(ts: Transform, center: Point) -> Transform {
+    Transform::translate(center.x, center.y)
+        .pre_concat(ts)
+        .pre_concat(Transform::translate(-center.x, -center.y))
+}
+
+/// A point in font units.
+fn point(x: f64, y: f64) -> Point {
+    Point::new(Abs::pt(x), Abs::pt(y))
+}
+
+/// An axis-aligned rectangle.
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+impl Bounds {
+    /// Create bounds from two corners.
+    fn new(a: Point, b: Point) -> Self {
+        Self { min: a.min(b), max: a.max(b) }
+    }
+
+    /// The size of the bounds.
+    fn size(self) -> Size {
+        (self.max - self.min).to_size()
+    }
+
+    /// The bounds of the transformed rectangle.
+    fn transform(self, ts: Transform) -> Self {
+        let corners = [
+            self.min,
+            Point::new(self.max.x, self.min.y),
+            self.max,
+            Point::new(self.min.x, self.max.y),
+        ]
+        .map(|p| p.transform(ts));
+        let mut bounds = Self::new(corners[0], corners[1]);
+        for corner in &corners[2..] {
+            bounds.min = bounds.min.min(*corner);
+            bounds.max = bounds.max.max(*corner);
+        }
+        bounds
+    }
+}
+
+/// A glyph outline with its bounds.
+struct Outline {
+    path: Path,
+    bounds: Bounds,
+    last: Point,
+}
+
+impl Default for Outline {
+    fn default() -> Self {
+        Self {
+            path: Path::new(),
+            bounds: Bounds {
+                min: Point::splat(Abs::inf()),
+                max: Point::splat(-Abs::inf()),
+            },
+            last: Point::zero(),
+        }
+    }
+}
+
+impl Outline {
+    fn extend(&mut self, p: Point) {
+        self.bounds.min = self.bounds.min.min(p);
+        self.bounds.max = self.bounds.max.max(p);
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.last = point(x as f64, y as f64);
+        self.extend(self.last);
+        self.path.move_to(self.last);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.last = point(x as f64, y as f64);
+        self.extend(self.last);
+        self.path.line_to(self.last);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1) = (self.last, point(x1 as f64, y1 as f64));
+        let p = point(x as f64, y as f64);
+        self.path
+            .cubic_to(p0 + (p1 - p0) * (2.0 / 3.0), p + (p1 - p) * (2.0 / 3.0), p);
+        self.extend(p1);
+        self.extend(p);
+        self.last = p;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p1, p2) = (point(x1 as f64, y1 as f64), point(x2 as f64, y2 as f64));
+        self.last = point(x as f64, y as f64);
+        self.path.cubic_to(p1, p2, self.last);
+        self.extend(p1);
+        self.extend(p2);
+        self.extend(self.last);
+    }
+
+    fn close(&mut self) {
+        self.path.close_path();
+    }
+}
+
+/// How a color line continues beyond its first and last stop.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Extend {
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+/// The color stops of a gradient paint.
+struct ColorLine {
+    extend: Extend,
+    /// The offsets and colors of the stops, sorted by offset.
+    stops: Vec<(f64, [f32; 4])>,
+}
+
+impl ColorLine {
+    /// The offsets of the first and last stops.
+    fn range(&self) -> (f64, f64) {
+        (self.stops[0].0, self.stops[self.stops.len() - 1].0)
+    }
+
+    /// Gradient stops for the part of the color line between two offsets,
+    /// which are mapped to the start and end of the gradient.
+    fn stops(&self, from: f64, to: f64) -> Vec<(Color, Ratio)> {
+        let (first, last) = self.range();
+        let period = last - first;
+        let (lo, hi) = (from.min(to), from.max(to));
+
+        // Unroll the repetitions of the color line that fall into the range.
+        let mut events = self.stops.clone();
+        if self.extend != Extend::Pad && period > 0.0 {
+            let start = ((lo - first) / period).floor().max(-64.0) as i64;
+            let end = ((hi - first) / period).ceil().min(64.0) as i64;
+            events.clear();
+            for k in start..end {
+                let base = first + k as f64 * period;
+                if self.extend == Extend::Reflect && k % 2 != 0 {
+                    events.extend(
+                        self.stops.iter().rev().map(|&(o, c)| (base + last - o, c)),
+                    );
+                } else {
+                    events.extend(self.stops.iter().map(|&(o, c)| (base + o - first, c)));
+                }
+            }
+        }
+
+        let sample = |s: f64| -> [f32; 4] {
+            let i = events.partition_point(|&(o, _)| o <= s);
+            if i == 0 {
+                return events[0].1;
+            } else if i == events.len() {
+                return events[i - 1].1;
+            }
+
+            let ((a, ca), (b, cb)) = (events[i - 1], events[i]);
+            let t = if b > a { ((s - a) / (b - a)) as f32 } else { 0.0 };
+            std::array::from_fn(|j| ca[j] + (cb[j] - ca[j]) * t)
+        };
+
+        let (start, end) = (sample(from), sample(to));
+        if to < from {
+            events.reverse();
+        }
+
+        let color = |[r, g, b, a]: [f32; 4]| Color::Rgb(Rgb::new(r, g, b, a));
+        let mut stops = vec![(color(start), Ratio::zero())];
+        for &(o, c) in &events {
+            if lo < o && o < hi {
+                stops.push((color(c), Ratio::new((o - from) / (to - from))));
+            }
+        }
+        stops.push((color(end), Ratio::one()));
+        stops
+    }
+}
+
 /// Draws an SVG glyph in a frame.
 fn draw_svg_glyph(
     frame: &mut Frame,
@@ -271,3 +1315,501 @@ fn make_svg_unsized(svg: &mut String) {
         svg.replace_range(range, "");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foundations::Bytes;
+    use crate::visualize::Shape;
+
+    /// A paint of the fixture's `COLR` table.
+    enum P {
+        Layers(u8, u32),
+        Solid(u16),
+        Linear([i16; 6], Vec<(f64, u16)>),
+        Glyph(u16, Box<P>),
+        ColrGlyph(u16),
+        Transform([f64; 6], Box<P>),
+        Translate(i16, i16, Box<P>),
+        Composite(u8, Box<P>, Box<P>),
+    }
+
+    const RED: u16 = 0;
+    const BLUE: u16 = 1;
+    const GREEN: u16 = 2;
+
+    fn glyph(id: u16, paint: P) -> P {
+        P::Glyph(id, Box::new(paint))
+    }
+
+    fn composite(mode: u8, source: P, backdrop: P) -> P {
+        P::Composite(mode, Box::new(source), Box::new(backdrop))
+    }
+
+    fn set(out: &mut [u8], at: usize, bytes: &[u8]) {
+        out[at..at + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn link(out: &mut Vec<u8>, paint: usize, at: usize, child: &P) {
+        let offset = write(out, child) - paint;
+        set(out, paint + at, &(offset as u32).to_be_bytes()[1..]);
+    }
+
+    /// Write a paint and its children, returning the paint's offset.
+    fn write(out: &mut Vec<u8>, paint: &P) -> usize {
+        let start = out.len();
+        match paint {
+            P::Layers(count, first) => {
+                out.extend([1, *count]);
+                out.extend(first.to_be_bytes());
+            }
+            P::Solid(index) => {
+                out.push(2);
+                out.extend(index.to_be_bytes());
+                out.extend(0x4000u16.to_be_bytes());
+            }
+            P::Linear(coords, stops) => {
+                out.extend([4, 0, 0, 0]);
+                coords.iter().for_each(|c| out.extend(c.to_be_bytes()));
+                let line = out.len() - start;
+                set(out, start + 1, &(line as u32).to_be_bytes()[1..]);
+                out.push(0);
+                out.extend((stops.len() as u16).to_be_bytes());
+                for &(offset, index) in stops {
+                    out.extend(((offset * 16384.0) as i16).to_be_bytes());
+                    out.extend(index.to_be_bytes());
+                    out.extend(0x4000u16.to_be_bytes());
+                }
+            }
+            P::Glyph(id, child) => {
+                out.extend([10, 0, 0, 0]);
+                out.extend(id.to_be_bytes());
+                link(out, start, 1, child);
+            }
+            P::ColrGlyph(id) => {
+                out.push(11);
+                out.extend(id.to_be_bytes());
+            }
+            P::Transform(matrix, child) => {
+                out.extend([12, 0, 0, 0, 0, 0, 0]);
+                let affine = out.len() - start;
+                set(out, start + 4, &(affine as u32).to_be_bytes()[1..]);
+                matrix
+                    .iter()
+                    .for_each(|v| out.extend(((v * 65536.0) as i32).to_be_bytes()));
+                link(out, start, 1, child);
+            }
+            P::Translate(dx, dy, child) => {
+                out.extend([14, 0, 0, 0]);
+                out.extend(dx.to_be_bytes());
+                out.extend(dy.to_be_bytes());
+                link(out, start, 1, child);
+            }
+            P::Composite(mode, source, backdrop) => {
+                out.extend([32, 0, 0, 0, *mode, 0, 0, 0]);
+                link(out, start, 1, source);
+                link(out, start, 5, backdrop);
+            }
+        }
+        start
+    }
+
+    /// Build a `COLR` table of version 1.
+    fn colr(bases: &[(u16, P)], layers: &[P], clips: &[(u16, u16, [i16; 4])]) -> Vec<u8> {
+        let mut out = vec![0; 34];
+        set(&mut out, 0, &1u16.to_be_bytes());
+
+        let base_list = out.len();
+        out.extend((bases.len() as u32).to_be_bytes());
+        for (id, _) in bases {
+            out.extend(id.to_be_bytes());
+            out.extend([0; 4]);
+        }
+
+        let layer_list = out.len();
+        out.extend((layers.len() as u32).to_be_bytes());
+        out.extend(vec![0; 4 * layers.len()]);
+
+        let clip_list = if clips.is_empty() { 0 } else { out.len() };
+        if !clips.is_empty() {
+            out.push(1);
+            out.extend((clips.len() as u32).to_be_bytes());
+            for &(first, last, _) in clips {
+                out.extend(first.to_be_bytes());
+                out.extend(last.to_be_bytes());
+                out.extend([0; 3]);
+            }
+            for (i, (_, _, bounds)) in clips.iter().enumerate() {
+                let offset = (out.len() - clip_list) as u32;
+                set(&mut out, clip_list + 5 + 7 * i + 4, &offset.to_be_bytes()[1..]);
+                out.push(1);
+                bounds.iter().for_each(|v| out.extend(v.to_be_bytes()));
+            }
+        }
+
+        for (i, (_, paint)) in bases.iter().enumerate() {
+            let offset = (write(&mut out, paint) - base_list) as u32;
+            set(&mut out, base_list + 4 + 6 * i + 2, &offset.to_be_bytes());
+        }
+
+        for (i, paint) in layers.iter().enumerate() {
+            let offset = (write(&mut out, paint) - layer_list) as u32;
+            set(&mut out, layer_list + 4 + 4 * i, &offset.to_be_bytes());
+        }
+
+        set(&mut out, 14, &(base_list as u32).to_be_bytes());
+        set(&mut out, 18, &(layer_list as u32).to_be_bytes());
+        set(&mut out, 22, &(clip_list as u32).to_be_bytes());
+        out
+    }
+
+    /// Build a font with five glyphs: an empty one, a square from 0 to 500 font
+    /// units, a square from 250 to 750 and two empty glyphs that the `COLR`
+    /// table can describe. The palette has red, blue and green.
+    fn font(colr: Option<&[u8]>) -> Option<Font> {
+        let squares = [None, Some((0, 500)), Some((250, 750)), None, None];
+        let mut glyf = vec![];
+        let mut loca = vec![];
+        for square in squares {
+            loca.extend((glyf.len() as u32).to_be_bytes());
+            let Some((lo, hi)) = square else { continue };
+            for v in [1, lo, lo, hi, hi, 3, 0] {
+                glyf.extend((v as i16).to_be_bytes());
+            }
+            glyf.extend([1; 4]);
+            for v in [lo, 0, hi - lo, 0] {
+                glyf.extend((v as i16).to_be_bytes());
+            }
+            for v in [lo, hi - lo, 0, lo - hi] {
+                glyf.extend((v as i16).to_be_bytes());
+            }
+        }
+        loca.extend((glyf.len() as u32).to_be_bytes());
+
+        let mut head = vec![0; 54];
+        set(&mut head, 0, &0x10000u32.to_be_bytes());
+        set(&mut head, 12, &0x5F0F3CF5u32.to_be_bytes());
+        set(&mut head, 18, &1000u16.to_be_bytes());
+        set(&mut head, 38, &750u16.to_be_bytes());
+        set(&mut head, 40, &750u16.to_be_bytes());
+        set(&mut head, 50, &1u16.to_be_bytes());
+
+        let mut hhea = vec![0; 36];
+        set(&mut hhea, 0, &0x10000u32.to_be_bytes());
+        set(&mut hhea, 4, &800u16.to_be_bytes());
+        set(&mut hhea, 6, &(-200i16).to_be_bytes());
+        set(&mut hhea, 18, &1u16.to_be_bytes());
+        set(&mut hhea, 34, &5u16.to_be_bytes());
+
+        let hmtx = [0x03, 0xE8, 0, 0].repeat(5);
+        let maxp = [0, 0, 0x50, 0, 0, 5].to_vec();
+
+        let family = "Colr Test".encode_utf16().flat_map(u16::to_be_bytes);
+        let family: Vec<u8> = family.collect();
+        let mut name = vec![0, 0, 0, 1, 0, 18, 0, 3, 0, 1, 0x04, 0x09, 0, 1];
+        name.extend((family.len() as u16).to_be_bytes());
+        name.extend([0, 0]);
+        name.extend(family);
+
+        let mut cpal = vec![0, 0, 0, 3, 0, 1, 0, 3, 0, 0, 0, 14, 0, 0];
+        cpal.extend([0, 0, 255, 255, 255, 0, 0, 255, 0, 255, 0, 255]);
+
+        let mut tables = vec![
+            (b"CPAL", cpal),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"loca", loca),
+            (b"maxp", maxp),
+            (b"name", name),
+        ];
+        if let Some(colr) = colr {
+            tables.insert(0, (b"COLR", colr.to_vec()));
+        }
+
+        let mut data = vec![0, 1, 0, 0];
+        data.extend((tables.len() as u16).to_be_bytes());
+        data.extend([0; 6]);
+        let mut offset = data.len() + 16 * tables.len();
+        for (tag, table) in &tables {
+            data.extend(*tag);
+            data.extend([0; 4]);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((table.len() as u32).to_be_bytes());
+            offset += table.len().next_multiple_of(4);
+        }
+        for (_, table) in &tables {
+            data.extend(table);
+            data.resize(data.len().next_multiple_of(4), 0);
+        }
+
+        Font::new(Bytes::from(data), 0)
+    }
+
+    /// Draw a glyph of a font with the given `COLR` table.
+    fn draw(colr: &[u8], glyph: u16) -> Frame {
+        let font = font(Some(colr)).unwrap();
+        let mut frame = Frame::soft(Size::splat(Abs::pt(1000.0)));
+        Colr::new(&font, Color::BLACK).unwrap().draw(&mut frame, glyph);
+        frame
+    }
+
+    /// The fills of all shapes in a frame.
+    fn fills(frame: &Frame) -> Vec<Paint> {
+        let mut fills = vec![];
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => fills.extend(self::fills(&group.frame)),
+                FrameItem::Shape(shape, _) => fills.extend(shape.fill.clone()),
+                _ => {}
+            }
+        }
+        fills
+    }
+
+    /// All groups in a frame.
+    fn groups(frame: &Frame) -> Vec<&GroupItem> {
+        let mut groups = vec![];
+        for (_, item) in frame.items() {
+            if let FrameItem::Group(group) = item {
+                groups.push(group);
+                groups.extend(self::groups(&group.frame));
+            }
+        }
+        groups
+    }
+
+    /// The clip paths in a frame.
+    fn clips(frame: &Frame) -> Vec<&Path> {
+        groups(frame)
+            .into_iter()
+            .filter_map(|g| g.clip_path.as_ref())
+            .collect()
+    }
+
+    fn solid(index: u16) -> Paint {
+        let [r, g, b] =
+            [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]][index as usize];
+        Paint::Solid(Color::Rgb(Rgb::new(r, g, b, 1.0)))
+    }
+
+    #[test]
+    fn test_colr_header_is_cached() {
+        let table = colr(&[(3, glyph(1, P::Solid(RED)))], &[], &[]);
+        let font = font(Some(&table)).unwrap();
+        assert!(font.colr().is_some());
+        assert!(is_colr_glyph(&font, 3));
+        assert!(!is_colr_glyph(&font, 1));
+        assert!(!is_colr_glyph(&font, 4));
+
+        let plain = self::font(None).unwrap();
+        assert!(plain.colr().is_none());
+        assert!(!is_colr_glyph(&plain, 3));
+    }
+
+    #[test]
+    fn test_colr_solid_glyph() {
+        let frame = draw(&colr(&[(3, glyph(1, P::Solid(RED)))], &[], &[]), 3);
+        assert_eq!(fills(&frame), [solid(RED)]);
+        assert!(clips(&frame).is_empty());
+    }
+
+    #[test]
+    fn test_colr_foreground_uses_text_fill() {
+        let table = colr(&[(3, glyph(1, P::Solid(0xFFFF)))], &[], &[]);
+        let font = font(Some(&table)).unwrap();
+        let fill = Paint::Solid(Color::Rgb(Rgb::new(0.0, 0.5, 1.0, 1.0)));
+        assert_eq!(fills(&frame_for_glyph(&font, 3, &fill)), [fill]);
+
+        let black = Paint::Solid(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, 1.0)));
+        let gradient = Paint::Gradient(Gradient::Linear(Arc::new(LinearGradient {
+            stops: vec![],
+            angle: Angle::zero(),
+            space: ColorSpace::Srgb,
+            relative: Smart::Auto,
+            anti_alias: true,
+        })));
+        assert_eq!(fills(&frame_for_glyph(&font, 3, &gradient)), [black]);
+    }
+
+    #[test]
+    fn test_colr_layers() {
+        let layers = [glyph(1, P::Solid(RED)), glyph(2, P::Solid(BLUE))];
+        let frame = draw(&colr(&[(3, P::Layers(2, 0))], &layers, &[]), 3);
+        assert_eq!(fills(&frame), [solid(RED), solid(BLUE)]);
+    }
+
+    #[test]
+    fn test_colr_linear_gradient() {
+        let paint = P::Linear([0, 0, 500, 0, 0, 500], vec![(0.0, RED), (1.0, BLUE)]);
+        let frame = draw(&colr(&[(3, glyph(1, paint))], &[], &[]), 3);
+        assert_eq!(clips(&frame).len(), 1);
+
+        let fills = fills(&frame);
+        let [Paint::Gradient(Gradient::Linear(gradient))] = fills.as_slice() else {
+            panic!("expected a linear gradient, got {fills:?}");
+        };
+        assert_eq!(gradient.angle, Angle::zero());
+        assert_eq!(
+            gradient.stops.first().unwrap().0,
+            Color::Rgb(Rgb::new(1.0, 0.0, 0.0, 1.0))
+        );
+        assert_eq!(
+            gradient.stops.last().unwrap().0,
+            Color::Rgb(Rgb::new(0.0, 0.0, 1.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_colr_transforms() {
+        let scaled = P::Transform(
+            [2.0, 0.0, 0.0, 2.0, 10.0, 0.0],
+            Box::new(glyph(1, P::Solid(RED))),
+        );
+        let translated = P::Translate(100, -50, Box::new(scaled));
+        let frame = draw(&colr(&[(3, translated)], &[], &[]), 3);
+        let transforms: Vec<_> = groups(&frame).iter().map(|g| g.transform).collect();
+        assert!(
+            transforms.contains(&Transform::translate(Abs::pt(100.0), Abs::pt(-50.0)))
+        );
+        assert!(transforms.contains(&Transform {
+            sx: Ratio::new(2.0),
+            sy: Ratio::new(2.0),
+            tx: Abs::pt(10.0),
+            ..Transform::identity()
+        }));
+        assert_eq!(fills(&frame), [solid(RED)]);
+    }
+
+    #[test]
+    fn test_colr_composite_in() {
+        let source = glyph(1, P::Solid(RED));
+        let backdrop = glyph(2, P::Solid(BLUE));
+        let frame = draw(&colr(&[(3, composite(5, source, backdrop))], &[], &[]), 3);
+        assert_eq!(fills(&frame), [solid(RED)]);
+
+        let clips = clips(&frame);
+        assert_eq!(clips.len(), 1);
+        assert_eq!(clips[0].0[0], PathItem::MoveTo(point(250.0, 250.0)));
+    }
+
+    #[test]
+    fn test_colr_composite_out() {
+        let source = glyph(2, P::Solid(BLUE));
+        let backdrop = glyph(1, P::Solid(RED));
+        let frame = draw(&colr(&[(3, composite(8, source, backdrop))], &[], &[]), 3);
+        assert_eq!(fills(&frame), [solid(RED)]);
+
+        // The clip path is a rectangle with the source's outline cut out of
+        // it, so the two must run in opposite directions.
+        let clips = clips(&frame);
+        assert_eq!(clips.len(), 1);
+        let split = clips[0].0.iter().rposition(|i| matches!(i, PathItem::MoveTo(_)));
+        let (outer, inner) = clips[0].0.split_at(split.unwrap());
+        let outer = signed_area(&Path(outer.to_vec()));
+        let inner = signed_area(&Path(inner.to_vec()));
+        assert!(outer * inner < 0.0);
+        assert_eq!(inner.abs(), 250000.0);
+    }
+
+    #[test]
+    fn test_colr_composite_modes() {
+        let draw_mode = |mode| {
+            let source = glyph(1, P::Solid(RED));
+            let backdrop = glyph(2, P::Solid(BLUE));
+            let frame =
+                draw(&colr(&[(3, composite(mode, source, backdrop))], &[], &[]), 3);
+            (fills(&frame), clips(&frame).len())
+        };
+
+        assert_eq!(draw_mode(0), (vec![], 0));
+        assert_eq!(draw_mode(1), (vec![solid(RED)], 0));
+        assert_eq!(draw_mode(2), (vec![solid(BLUE)], 0));
+        assert_eq!(draw_mode(3), (vec![solid(BLUE), solid(RED)], 0));
+        assert_eq!(draw_mode(4), (vec![solid(RED), solid(BLUE)], 0));
+        assert_eq!(draw_mode(9), (vec![solid(BLUE), solid(RED)], 1));
+        assert_eq!(draw_mode(10), (vec![solid(RED), solid(BLUE)], 1));
+        assert_eq!(draw_mode(11), (vec![solid(RED), solid(BLUE)], 2));
+        // Multiply.
+        assert_eq!(draw_mode(23), (vec![solid(BLUE), solid(RED)], 0));
+    }
+
+    #[test]
+    fn test_colr_clip_box() {
+        let table = colr(
+            &[(3, P::Solid(GREEN)), (4, P::Solid(GREEN))],
+            &[],
+            &[(3, 3, [0, 0, 100, 200])],
+        );
+
+        let rect = |frame: &Frame| {
+            let group = groups(frame)[0];
+            group.frame.items().find_map(|(_, item)| match item {
+                FrameItem::Shape(Shape { geometry: Geometry::Rect(size), .. }, _) => {
+                    Some(*size)
+                }
+                _ => None,
+            })
+        };
+
+        assert_eq!(
+            rect(&draw(&table, 3)),
+            Some(Size::new(Abs::pt(100.0), Abs::pt(200.0)))
+        );
+        // Without a clip box or outline, the glyph fills the em square.
+        assert_eq!(rect(&draw(&table, 4)), Some(Size::splat(Abs::pt(1000.0))));
+    }
+
+    #[test]
+    fn test_colr_cycles_terminate() {
+        let table =
+            colr(&[(3, P::ColrGlyph(3)), (4, P::Layers(1, 0))], &[P::Layers(1, 0)], &[]);
+        assert!(fills(&draw(&table, 3)).is_empty());
+        assert!(fills(&draw(&table, 4)).is_empty());
+    }
+
+    /// A table that uses most kinds of paints.
+    fn rich() -> Vec<u8> {
+        let gradient = P::Linear([0, 0, 500, 0, 0, 500], vec![(0.0, RED), (1.0, BLUE)]);
+        let layers = [
+            glyph(1, gradient),
+            P::Translate(10, 10, Box::new(glyph(2, P::Solid(GREEN)))),
+        ];
+        let paint = composite(7, P::Layers(2, 0), P::ColrGlyph(4));
+        colr(
+            &[(3, paint), (4, glyph(2, P::Solid(RED)))],
+            &layers,
+            &[(3, 4, [0, 0, 800, 800])],
+        )
+    }
+
+    #[test]
+    fn test_colr_truncated_table() {
+        let table = rich();
+        assert_eq!(fills(&draw(&table, 3)).len(), 2);
+        for len in 0..table.len() {
+            let font = font(Some(&table[..len])).unwrap();
+            if let Some(colr) = Colr::new(&font, Color::BLACK) {
+                colr.draw(&mut Frame::soft(Size::zero()), 3);
+            }
+            is_colr_glyph(&font, 3);
+        }
+    }
+
+    #[test]
+    fn test_colr_malformed_offsets() {
+        let table = rich();
+        for i in 0..table.len() {
+            for byte in [0x00, 0x7F, 0xFF] {
+                let mut table = table.clone();
+                table[i] = byte;
+                let font = font(Some(&table)).unwrap();
+                if let Some(colr) = Colr::new(&font, Color::BLACK) {
+                    colr.draw(&mut Frame::soft(Size::zero()), 3);
+                    colr.draw(&mut Frame::soft(Size::zero()), 4);
+                }
+            }
+        }
+    }
+}
@@ -13,11 +13,12 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use ttf_parser::GlyphId;
+use ttf_parser::{GlyphId, Tag, VariationAxis};
 
-use self::book::find_name;
+use self::book::{declared_optical_size, find_name, optical_size_distance};
+use self::color::ColrHeader;
 use crate::foundations::{Bytes, Cast};
-use crate::layout::Em;
+use crate::layout::{Abs, Em};
 
 /// An OpenType font.
 ///
@@ -37,10 +38,15 @@ struct Repr {
     info: FontInfo,
     /// The font's metrics.
     metrics: FontMetrics,
+    /// The offsets into the font's `COLR` table, if it has one of version 1.
+    colr: Option<ColrHeader>,
     /// The underlying ttf-parser face.
     ttf: ttf_parser::Face<'static>,
     /// The underlying rustybuzz face.
     rusty: rustybuzz::Face<'static>,
+    /// The coordinates of the variation axes that differ from the axes'
+    /// defaults, sorted by tag.
+    variations: Vec<(Tag, f32)>,
 }
 
 impl Font {
@@ -59,8 +65,18 @@ impl Font {
         let rusty = rustybuzz::Face::from_slice(slice, index)?;
         let metrics = FontMetrics::from_ttf(&ttf);
         let info = FontInfo::from_ttf(&ttf)?;
-
-        Some(Self(Arc::new(Repr { data, index, info, metrics, ttf, rusty })))
+        let colr = ColrHeader::from_ttf(&ttf);
+
+        Some(Self(Arc::new(Repr {
+            data,
+            index,
+            info,
+            metrics,
+            colr,
+            ttf,
+            rusty,
+            variations: vec![],
+        })))
     }
 
     /// Parse all fonts in the given data.
@@ -107,6 +123,103 @@ impl Font {
             .map(|units| self.to_em(units))
     }
 
+    /// The variation axes of the font, if it is a variable font.
+    pub fn axes(&self) -> impl Iterator<Item = VariationAxis> + '_ {
+        self.0.ttf.variation_axes().into_iter()
+    }
+
+    /// The coordinates of the variation axes that this instance of the font
+    /// uses and that differ from the axes' defaults, sorted by tag.
+    pub fn variations(&self) -> &[(Tag, f32)] {
+        &self.0.variations
+    }
+
+    /// Create an instance of this font with the given variation axis
+    /// coordinates in addition to the ones it already has.
+    ///
+    /// Coordinates for axes that the font doesn't have are ignored and values
+    /// are clamped to the range of their axis. Coordinates that are equal to
+    /// an axis' default don't create a new instance.
+    pub fn with_variations(&self, variations: &[(Tag, f32)]) -> Font {
+        // Static fonts have nothing to instantiate.
+        if !self.info().flags.contains(FontFlags::VARIABLE) {
+            return self.clone();
+        }
+
+        let mut coords = self.0.variations.clone();
+        for &(tag, value) in variations {
+            let Some(axis) = self.axes().find(|axis| axis.tag == tag) else {
+                continue;
+            };
+
+            let value = value.clamp(axis.min_value, axis.max_value);
+            coords.retain(|&(t, _)| t != tag);
+            if value != axis.def_value {
+                coords.push((tag, value));
+            }
+        }
+
+        coords.sort_by_key(|&(tag, _)| tag);
+        if coords == self.0.variations {
+            return self.clone();
+        }
+
+        let bits = coords.iter().map(|&(tag, value)| (tag, value.to_bits())).collect();
+        instantiate(self.0.data.clone(), self.0.index, bits)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// Create an instance of this font for the given variant and font size,
+    /// with the given explicit variation axis coordinates on top.
+    ///
+    /// The `wght` and `wdth` axes only follow the variant if it differs from
+    /// the one of the font's default instance. Likewise, the `opsz` axis only
+    /// follows the font size if the font declares that its default instance
+    /// is designed for a range of sizes that doesn't include it. This way, the
+    /// default instance is used whenever it matches the request.
+    pub fn instance(
+        &self,
+        variant: FontVariant,
+        size: Abs,
+        variations: &[(Tag, f32)],
+    ) -> Font {
+        if !self.info().flags.contains(FontFlags::VARIABLE) {
+            return self.clone();
+        }
+
+        let default = self.info().variant;
+        let mut coords = vec![];
+        if variant.weight != default.weight {
+            let weight = f32::from(variant.weight.to_number());
+            coords.push((Tag::from_bytes(b"wght"), weight));
+        }
+
+        if variant.stretch != default.stretch {
+            let width = 100.0 * variant.stretch.to_ratio().get() as f32;
+            coords.push((Tag::from_bytes(b"wdth"), width));
+        }
+
+        if declared_optical_size(self.ttf())
+            .is_some_and(|range| optical_size_distance(size, range) > 0)
+        {
+            coords.push((Tag::from_bytes(b"opsz"), size.to_pt() as f32));
+        }
+
+        // Explicit coordinates come last so that they take precedence.
+        coords.extend_from_slice(variations);
+        self.with_variations(&coords)
+    }
+
+    /// The default instance of this font, without any variation coordinates.
+    pub fn without_variations(&self) -> Font {
+        if self.0.variations.is_empty() {
+            return self.clone();
+        }
+
+        instantiate(self.0.data.clone(), self.0.index, vec![])
+            .unwrap_or_else(|| self.clone())
+    }
+
     /// Lookup a name by id.
     pub fn find_name(&self, id: u16) -> Option<String> {
         find_name(&self.0.ttf, id)
@@ -125,12 +238,21 @@ impl Font {
         // internal 'static lifetime.
         &self.0.rusty
     }
+
+    /// The offsets into the font's `COLR` table, if it has one of version 1.
+    fn colr(&self) -> Option<ColrHeader> {
+        self.0.colr
+    }
 }
 
 impl Hash for Font {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.data.hash(state);
         self.0.index.hash(state);
+        for &(tag, value) in &self.0.variations {
+            tag.hash(state);
+            value.to_bits().hash(state);
+        }
     }
 }
 
@@ -144,8 +266,29 @@ impl Eq for Font {}
 
 impl PartialEq for Font {
     fn eq(&self, other: &Self) -> bool {
-        self.0.data == other.0.data && self.0.index == other.0.index
+        self.0.data == other.0.data
+            && self.0.index == other.0.index
+            && self.0.variations == other.0.variations
+    }
+}
+
+/// Parse a font and apply variation coordinates to it.
+///
+/// The coordinates are given as the bit patterns of the values to make them
+/// hashable.
+#[comemo::memoize]
+fn instantiate(data: Bytes, index: u32, variations: Vec<(Tag, u32)>) -> Option<Font> {
+    let mut repr = Arc::into_inner(Font::new(data, index)?.0)?;
+    for (tag, bits) in variations {
+        let value = f32::from_bits(bits);
+        repr.ttf.set_variation(tag, value)?;
+        repr.rusty.set_variation(tag, value)?;
+        repr.variations.push((tag, value));
     }
+
+    // Metrics may be affected by the variation coordinates.
+    repr.metrics = FontMetrics::from_ttf(&repr.ttf);
+    Some(Font(Arc::new(repr)))
 }
 
 /// Metrics of a font.
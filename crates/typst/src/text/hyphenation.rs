@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, FileError, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{cast, Args, Array, Bytes, Dict, Fold, StyleChain, Value};
+use crate::syntax::Spanned;
+use crate::text::{Lang, TextElem};
+use crate::World;
+
+/// The minimum number of characters before the first and after the last
+/// hyphenation opportunity of a word when using custom patterns.
+const MIN_CHARS: usize = 2;
+
+/// Determine where a word may be hyphenated.
+///
+/// Returns the byte offsets in the word after which it may be broken. Custom
+/// exceptions take precedence over custom patterns for the language, which in
+/// turn take precedence over the built-in patterns.
+pub(crate) fn hyphenate(word: &str, lang: Lang, styles: StyleChain) -> Vec<usize> {
+    if let Some(breaks) = TextElem::hyphenation_exceptions_in(styles).find(word) {
+        return breaks;
+    }
+
+    let paths = TextElem::hyphenation_patterns_in(styles);
+    if let Some(i) = paths.0.iter().rposition(|&(l, _)| l == lang) {
+        let data = TextElem::hyphenation_patterns_data_in(styles);
+        if let Some(patterns) = data.get(i).and_then(|data| load_patterns(data).ok()) {
+            return patterns.hyphenate(word);
+        }
+    }
+
+    let Some(lang) = lang
+        .as_str()
+        .as_bytes()
+        .try_into()
+        .ok()
+        .and_then(hypher::Lang::from_iso)
+    else {
+        return vec![];
+    };
+
+    let mut offset = 0;
+    let mut breaks = vec![];
+    for syllable in hypher::hyphenate(word, lang) {
+        offset += syllable.len();
+        if offset < word.len() {
+            breaks.push(offset);
+        }
+    }
+    breaks
+}
+
+/// Paths to files with custom hyphenation patterns, per language.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct HyphenationPatternPaths(pub Vec<(Lang, EcoString)>);
+
+cast! {
+    HyphenationPatternPaths,
+    self => self.0
+        .into_iter()
+        .map(|(lang, path)| (lang.as_str().into(), path.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            let lang = k.parse::<Lang>()?;
+            Ok((lang, v.cast::<EcoString>()?))
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for HyphenationPatternPaths {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
+/// Words with custom hyphenation opportunities, marked by hyphens.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct HyphenationExceptions(pub Vec<EcoString>);
+
+impl HyphenationExceptions {
+    /// Find the hyphenation opportunities for a word if it is an exception.
+    fn find(&self, word: &str) -> Option<Vec<usize>> {
+        self.0
+            .iter()
+            .rev()
+            .find_map(|exception| exception_breaks(exception, word))
+    }
+}
+
+cast! {
+    HyphenationExceptions,
+    self => self.0.into_value(),
+    values: Array => Self(values
+        .into_iter()
+        .map(Value::cast)
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for HyphenationExceptions {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
+/// Hyphenation patterns in the format used by TeX.
+///
+/// Each pattern consists of letters interspersed with digits. Odd digits
+/// permit hyphenation at their position and even digits forbid it, with the
+/// highest digit from all matching patterns winning. A period matches the
+/// start or end of a word.
+#[derive(Debug, Default)]
+struct Patterns {
+    /// Maps from the letters of a pattern to its digits.
+    patterns: HashMap<EcoString, Vec<u8>>,
+    /// The number of letters in the longest pattern.
+    longest: usize,
+    /// Words with explicit hyphenation opportunities.
+    exceptions: Vec<EcoString>,
+}
+
+impl Patterns {
+    /// Parse patterns from a TeX file.
+    ///
+    /// If the file contains a `\patterns` or `\hyphenation` group, only
+    /// those are read. Otherwise, the whole file is treated as a list of
+    /// patterns.
+    fn parse(text: &str) -> StrResult<Self> {
+        let text: String = text
+            .lines()
+            .map(|line| line.split('%').next().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut out = Self::default();
+        let (patterns, exceptions) = if text.contains('\\') {
+            (group(&text, "patterns")?, group(&text, "hyphenation")?)
+        } else {
+            (Some(text.as_str()), None)
+        };
+
+        for pattern in patterns.into_iter().flat_map(str::split_whitespace) {
+            let mut letters = EcoString::new();
+            let mut digits = vec![0];
+            for c in pattern.chars() {
+                match c.to_digit(10) {
+                    Some(_) if digits.last() != Some(&0) => {
+                        bail!("invalid hyphenation pattern `{pattern}`")
+                    }
+                    Some(d) => *digits.last_mut().unwrap() = d as u8,
+                    None => {
+                        letters.extend(c.to_lowercase());
+                        digits.push(0);
+                    }
+                }
+            }
+
+            out.longest = out.longest.max(digits.len() - 1);
+            out.patterns.insert(letters, digits);
+        }
+
+        out.exceptions = exceptions
+            .into_iter()
+            .flat_map(str::split_whitespace)
+            .map(Into::into)
+            .collect();
+
+        Ok(out)
+    }
+
+    /// Determine where a word may be hyphenated.
+    fn hyphenate(&self, word: &str) -> Vec<usize> {
+        if let Some(breaks) = self
+            .exceptions
+            .iter()
+            .rev()
+            .find_map(|exception| exception_breaks(exception, word))
+        {
+            return breaks;
+        }
+
+        let chars: Vec<char> = std::iter::once('.')
+            .chain(word.chars().map(|c| c.to_lowercase().next().unwrap_or(c)))
+            .chain(std::iter::once('.'))
+            .collect();
+
+        // The digit at index `i` is the one before `chars[i]`.
+        let mut levels = vec![0; chars.len() + 1];
+        let mut key = EcoString::new();
+        for start in 0..chars.len() {
+            key.clear();
+            for &c in chars[start..].iter().take(self.longest) {
+                key.push(c);
+                if let Some(digits) = self.patterns.get(&key) {
+                    for (level, &digit) in levels[start..].iter_mut().zip(digits) {
+                        *level = (*level).max(digit);
+                    }
+                }
+            }
+        }
+
+        let count = chars.len() - 2;
+        word.char_indices()
+            .enumerate()
+            .filter(|&(i, _)| {
+                i >= MIN_CHARS && i + MIN_CHARS <= count && levels[i + 1] % 2 == 1
+            })
+            .map(|(_, (offset, _))| offset)
+            .collect()
+    }
+}
+
+/// Extract the contents of a TeX group like `\patterns{...}`.
+fn group<'a>(text: &'a str, name: &str) -> StrResult<Option<&'a str>> {
+    let command = eco_format!("\\{name}");
+    let Some(start) = text.find(command.as_str()) else { return Ok(None) };
+    let rest = text[start + command.len()..].trim_start();
+    let Some(rest) = rest.strip_prefix('{') else {
+        bail!("expected opening brace after `{command}`");
+    };
+    let Some(end) = rest.find('}') else {
+        bail!("unclosed `{command}` group");
+    };
+    Ok(Some(&rest[..end]))
+}
+
+/// Match a word against an exception with hyphens at its hyphenation
+/// opportunities, returning the byte offsets of the opportunities in the
+/// word.
+///
+/// Matching is case-insensitive.
+fn exception_breaks(exception: &str, word: &str) -> Option<Vec<usize>> {
+    let mut breaks = vec![];
+    let mut chars = word.char_indices();
+    let mut offset = 0;
+    for e in exception.chars() {
+        if e == '-' {
+            breaks.push(offset);
+            continue;
+        }
+
+        let (i, c) = chars.next()?;
+        if !c.to_lowercase().eq(e.to_lowercase()) {
+            return None;
+        }
+        offset = i + c.len_utf8();
+    }
+
+    if chars.next().is_some() {
+        return None;
+    }
+
+    breaks.retain(|&offset| offset > 0 && offset < word.len());
+    Some(breaks)
+}
+
+/// Load hyphenation patterns from a file.
+#[comemo::memoize]
+#[typst_macros::time(name = "load hyphenation patterns")]
+fn load_patterns(data: &Bytes) -> StrResult<Arc<Patterns>> {
+    let text = std::str::from_utf8(data).map_err(FileError::from)?;
+    Patterns::parse(text).map(Arc::new)
+}
+
+/// Function to parse the hyphenation patterns argument.
+/// Much nicer than having it be part of the `element` macro.
+pub(crate) fn parse_hyphenation_patterns(
+    engine: &mut Engine,
+    args: &mut Args,
+) -> SourceResult<(Option<HyphenationPatternPaths>, Option<Vec<Bytes>>)> {
+    let Some(Spanned { v: paths, span }) =
+        args.named::<Spanned<HyphenationPatternPaths>>("hyphenation-patterns")?
+    else {
+        return Ok((None, None));
+    };
+
+    // Load and check the pattern files.
+    let data = paths
+        .0
+        .iter()
+        .map(|(_, path)| {
+            let id = span.resolve_path(path).at(span)?;
+            let data = engine.world.file(id).at(span)?;
+            load_patterns(&data)
+                .map_err(|err| {
+                    eco_format!("failed to parse hyphenation patterns `{path}` ({err})")
+                })
+                .at(span)?;
+            Ok(data)
+        })
+        .collect::<SourceResult<Vec<Bytes>>>()?;
+
+    Ok((Some(paths), Some(data)))
+}
@@ -1,14 +1,17 @@
 //! Text handling.
 
 mod case;
+mod collation;
 mod deco;
 mod font;
+mod hyphenation;
 mod item;
 mod lang;
 mod linebreak;
 #[path = "lorem.rs"]
 mod lorem_;
 mod raw;
+mod ruby;
 mod shift;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
@@ -16,13 +19,16 @@ mod smartquote;
 mod space;
 
 pub use self::case::*;
+pub use self::collation::*;
 pub use self::deco::*;
 pub use self::font::*;
+pub use self::hyphenation::*;
 pub use self::item::*;
 pub use self::lang::*;
 pub use self::linebreak::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
+pub use self::ruby::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
@@ -34,17 +40,19 @@ use ecow::{eco_format, EcoString};
 use rustybuzz::{Feature, Tag};
 use smallvec::SmallVec;
 use ttf_parser::Rect;
+use unicode_script::{Script, UnicodeScript};
 
 use crate::diag::{bail, warning, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, category, dict, elem, Args, Array, Cast, Category, Construct, Content, Dict,
-    Fold, NativeElement, Never, Packed, PlainText, Repr, Resolve, Scope, Set, Smart,
-    StyleChain,
+    cast, category, dict, elem, Args, Array, Bytes, Cast, Category, Construct, Content,
+    Dict, Fold, NativeElement, Never, Packed, PlainText, Repr, Resolve, Scope, Set,
+    Smart, StyleChain, Value,
 };
-use crate::layout::{Abs, Axis, Dir, Em, Length, Ratio, Rel};
+use crate::layout::{Abs, Dir, Em, Length, Ratio, Rel};
 use crate::model::ParElem;
 use crate::syntax::Spanned;
+use crate::utils::Scalar;
 use crate::visualize::{Color, Paint, RelativeTo, Stroke};
 use crate::World;
 
@@ -68,6 +76,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<HighlightElem>();
     global.define_elem::<SmallcapsElem>();
     global.define_elem::<RawElem>();
+    global.define_elem::<RubyElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
     global.define_func::<lorem>();
@@ -150,11 +159,11 @@ pub struct TextElem {
     /// contains no match. This lets Typst search through all available fonts
     /// for the most similar one that has the necessary glyphs.
     ///
-    /// _Note:_ Currently, there are no warnings when fallback is disabled and
-    /// no glyphs are found. Instead, your text shows up in the form of "tofus":
-    /// Small boxes that indicate the lack of an appropriate glyph. In the
-    /// future, you will be able to instruct Typst to issue warnings so you know
-    /// something is up.
+    /// When no font has glyphs for some characters, Typst issues a warning and
+    /// the characters show up in the form of "tofus": Small boxes that
+    /// indicate the lack of an appropriate glyph. To control which fonts are
+    /// used for which scripts and languages, use the
+    /// [`fallbacks`]($text.fallbacks) property.
     ///
     /// ```example
     /// #set text(font: "Inria Serif")
@@ -167,6 +176,29 @@ pub struct TextElem {
     #[ghost]
     pub fallback: bool,
 
+    /// Fallback font families for specific scripts and languages.
+    ///
+    /// A dictionary mapping
+    /// [ISO 15924 script codes](https://en.wikipedia.org/wiki/ISO_15924) like
+    /// `Arab` or [language codes]($text.lang) like `ja` to a font family or a
+    /// list of font families. When the [font list]($text.font) doesn't cover
+    /// some characters, the families for the text language and then the ones
+    /// for the script of the characters are tried, before any last resort
+    /// [fallback]($text.fallback) happens. This way, the same fonts are used
+    /// regardless of which other fonts are installed.
+    ///
+    /// ```example
+    /// #set text(fallbacks: (
+    ///   Arab: "Noto Sans Arabic",
+    /// ))
+    ///
+    /// This is Latin. \
+    /// هذا عربي.
+    /// ```
+    #[fold]
+    #[ghost]
+    pub fallbacks: FontFallbacks,
+
     /// The desired font style.
     ///
     /// When an italic style is requested and only an oblique one is available,
@@ -312,6 +344,37 @@ pub struct TextElem {
     #[ghost]
     pub cjk_latin_spacing: Smart<Option<Never>>,
 
+    /// How to trim the blank space that full-width CJK punctuation carries.
+    ///
+    /// Full-width punctuation marks like `「` or `。` only fill half of their
+    /// em square with ink. Following the Chinese and Japanese layout
+    /// requirements, this blank space can be removed between adjacent marks
+    /// (so that `」「` only takes up one and a half em) and at the start or end
+    /// of lines.
+    ///
+    /// - `{none}`: Punctuation keeps its full width. It may still be
+    ///   compressed slightly to justify a line.
+    /// - `{"adjacent"}`: Compresses adjacent punctuation marks.
+    /// - `{"trim-end"}`: Like `{"adjacent"}`, but also trims closing
+    ///   punctuation at the end of lines.
+    /// - `{"trim-both"}`: Like `{"trim-end"}`, but also trims opening
+    ///   punctuation at the start of lines.
+    ///
+    /// When set to `{auto}`, the trimming follows the conventions of the
+    /// [text language]($text.lang) and [region]($text.region): Simplified
+    /// Chinese and Japanese use `{"trim-both"}` and Traditional Chinese (the regions `{"TW"}` and `{"HK"}`) keeps
+    /// punctuation at full width.
+    ///
+    /// ```example
+    /// #set text(lang: "zh", font: "Noto Serif CJK SC")
+    /// 他说：「你好。」「再见。」
+    ///
+    /// #set text(cjk-punctuation-trim: none)
+    /// 他说：「你好。」「再见。」
+    /// ```
+    #[ghost]
+    pub cjk_punctuation_trim: Smart<Option<CjkPunctuationTrim>>,
+
     /// An amount to shift the text baseline by.
     ///
     /// ```example
@@ -342,6 +405,35 @@ pub struct TextElem {
     #[ghost]
     pub overhang: bool,
 
+    /// How far individual characters hang into the margins when
+    /// [`overhang`]($text.overhang) is enabled.
+    ///
+    /// The dictionary maps characters to the fraction of their width that
+    /// protrudes. A ratio applies to the end of a line. To let a character
+    /// hang into the margin at the start of a line, pass a dictionary with
+    /// `start` and `end` keys instead. The entries extend a built-in table,
+    /// which makes dashes and punctuation hang into the end margin.
+    ///
+    /// Line breaking takes the protrusion into account, so a line may end up
+    /// containing a bit more text than would fit otherwise.
+    ///
+    /// ```example
+    /// #set par(justify: true)
+    /// #set text(protrusion: (
+    ///   "“": (start: 50%),
+    ///   "”": 50%,
+    ///   "T": (start: 5%, end: 5%),
+    /// ))
+    ///
+    /// “Typst's hanging quotes and
+    /// letters line up optically with
+    /// the margins. The first line's
+    /// opening quote protrudes.”
+    /// ```
+    #[fold]
+    #[ghost]
+    pub protrusion: Protrusion,
+
     /// The top end of the conceptual frame around the text used for layout and
     /// positioning. This affects the size of containers that hold text.
     ///
@@ -438,6 +530,8 @@ pub struct TextElem {
     /// - `{auto}`: Automatically infer the direction from the `lang` property.
     /// - `{ltr}`: Layout text from left to right.
     /// - `{rtl}`: Layout text from right to left.
+    /// - `{ttb}`: Layout text from top to bottom, with lines stacking from
+    ///   right to left.
     ///
     /// When writing in right-to-left scripts like Arabic or Hebrew, you should
     /// set the [text language]($text.lang) or direction. While individual runs
@@ -457,10 +551,40 @@ pub struct TextElem {
     /// #set text(dir: rtl)
     /// هذا عربي.
     /// ```
+    /// Setting the direction to `{ttb}` enables vertical writing as used in
+    /// Japanese and traditional Chinese publishing. Ideographs, kana, and
+    /// other characters that are upright in vertical text use their vertical
+    /// alternates and metrics, while other text is turned sideways. Short
+    /// runs of digits are set upright as a unit (see
+    /// [`upright-digits`]($text.upright-digits)). As vertical writing changes
+    /// how pages are filled, it should be enabled with a set rule at the very
+    /// start of the document. Using it for only a part of a page is an error.
+    /// Headers and footers are still set horizontally and images, equations,
+    /// tables, and boxes are kept upright.
+    ///
+    /// ```example
+    /// #set page(width: 120pt, height: 100pt)
+    /// #set text(dir: ttb)
+    /// Text flows from top to bottom in vertical writing mode.
+    /// ```
     #[resolve]
     #[ghost]
     pub dir: TextDir,
 
+    /// The maximum number of consecutive digits that are set upright as a
+    /// single unit in vertical text (known as _tate-chū-yoko_). Longer runs
+    /// of digits are turned sideways like other Latin text. Set this to `{0}`
+    /// to turn all digits sideways.
+    ///
+    /// ```example
+    /// #set page(width: 80pt, height: 120pt)
+    /// #set text(dir: ttb)
+    /// In 24 hours or 2024.
+    /// ```
+    #[ghost]
+    #[default(2)]
+    pub upright_digits: usize,
+
     /// Whether to hyphenate text to improve line breaking. When `{auto}`, text
     /// will be hyphenated if and only if justification is enabled.
     ///
@@ -484,6 +608,58 @@ pub struct TextElem {
     #[ghost]
     pub hyphenate: Hyphenate,
 
+    /// Custom hyphenation patterns for languages.
+    ///
+    /// A dictionary mapping language codes to paths of files with hyphenation
+    /// patterns in the format used by TeX. The patterns replace the built-in
+    /// ones for the language and can also add hyphenation for languages that
+    /// Typst has no built-in patterns for. If a file contains a
+    /// `\hyphenation` group, the words therein are used as
+    /// [exceptions]($text.hyphenation-exceptions) for the language.
+    ///
+    /// ```typ
+    /// #set text(
+    ///   lang: "la",
+    ///   hyphenation-patterns: (la: "hyph-la.tex"),
+    /// )
+    /// ```
+    #[parse(
+        let (hyphenation_patterns, hyphenation_patterns_data) =
+            parse_hyphenation_patterns(engine, args)?;
+        hyphenation_patterns
+    )]
+    #[fold]
+    #[ghost]
+    pub hyphenation_patterns: HyphenationPatternPaths,
+
+    /// The raw file buffers of the hyphenation pattern files.
+    #[internal]
+    #[parse(hyphenation_patterns_data)]
+    #[fold]
+    #[ghost]
+    pub hyphenation_patterns_data: Vec<Bytes>,
+
+    /// Words with custom hyphenation.
+    ///
+    /// Each word is written with hyphens at the positions where it may be
+    /// hyphenated, so a word without hyphens is never hyphenated. Words are
+    /// matched regardless of case and take precedence over all hyphenation
+    /// patterns.
+    ///
+    /// ```example
+    /// #set page(width: 140pt)
+    /// #set par(justify: true)
+    /// #set text(hyphenation-exceptions: (
+    ///   "Typst", "type-set-ting",
+    /// ))
+    ///
+    /// Typst is a new markup-based
+    /// typesetting system.
+    /// ```
+    #[fold]
+    #[ghost]
+    pub hyphenation_exceptions: HyphenationExceptions,
+
     /// The "cost" of various choices when laying out text. A higher cost means
     /// the layout engine will make the choice less often. Costs are specified
     /// as a ratio of the default cost, so `50%` will make text layout twice as
@@ -494,6 +670,7 @@ pub struct TextElem {
     /// - `runt`: ending a paragraph with a line with a single word
     /// - `widow`: leaving a single line of paragraph on the next page
     /// - `orphan`: leaving single line of paragraph on the previous page
+    /// - `kashida`: elongating Arabic words with [kashidas]($par.kashida)
     ///
     /// Hyphenation is generally avoided by placing the whole word on the next
     /// line, so a higher hyphenation cost can result in awkward justification
@@ -679,6 +856,30 @@ pub struct TextElem {
     #[ghost]
     pub features: FontFeatures,
 
+    /// Coordinates for the variation axes of variable fonts.
+    ///
+    /// A variable font can produce a continuous range of styles, for example
+    /// any weight between light and black. This maps four-letter axis tags
+    /// like `wght` (weight), `wdth` (width), `slnt` (slant), `opsz` (optical
+    /// size) or custom axes defined by the font to the desired values. Axes
+    /// that the font doesn't have are ignored and values outside of an axis'
+    /// range are clamped to it.
+    ///
+    /// Unless they are set explicitly, the `wght` and `wdth` axes follow the
+    /// [weight]($text.weight) and [stretch]($text.stretch) if these differ from
+    /// the font's default instance. The `opsz` axis follows the
+    /// [text size]($text.size) if the font declares that its default instance
+    /// is designed for other sizes.
+    ///
+    /// ```typ
+    /// #set text(font: "Roboto Flex")
+    /// #text(variations: (wght: 250))[Thin]
+    /// #text(variations: (wght: 850, wdth: 30))[Condensed]
+    /// ```
+    #[fold]
+    #[ghost]
+    pub variations: FontVariations,
+
     /// Content in which all text is styled according to the other arguments.
     #[external]
     #[required]
@@ -723,6 +924,13 @@ pub struct TextElem {
     #[default(false)]
     #[ghost]
     pub smallcaps: bool,
+
+    /// Whether the page is laid out vertically. Vertical text is only
+    /// supported on such pages.
+    #[internal]
+    #[default(false)]
+    #[ghost]
+    pub vertical_page: bool,
 }
 
 impl TextElem {
@@ -813,6 +1021,84 @@ cast! {
     values: Array => Self(values.into_iter().map(|v| v.cast()).collect::<StrResult<_>>()?),
 }
 
+/// Fallback font families for scripts and languages.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct FontFallbacks(pub Vec<(FallbackTarget, FontList)>);
+
+impl FontFallbacks {
+    /// The fallback families for text in the given language, in order of
+    /// priority: First the ones for the language and then the ones for the
+    /// script of the text.
+    pub fn families<'a>(
+        &'a self,
+        lang: Lang,
+        text: &str,
+    ) -> impl Iterator<Item = &'a str> + Clone {
+        let script = text.chars().map(|c| c.script()).find(|&s| {
+            !matches!(s, Script::Common | Script::Inherited | Script::Unknown)
+        });
+        let matching = move |target: FallbackTarget| {
+            self.0
+                .iter()
+                .rev()
+                .filter(move |(t, _)| *t == target)
+                .flat_map(|(_, list)| list)
+                .map(FontFamily::as_str)
+        };
+        matching(FallbackTarget::Lang(lang)).chain(
+            script
+                .into_iter()
+                .flat_map(move |s| matching(FallbackTarget::Script(s))),
+        )
+    }
+}
+
+cast! {
+    FontFallbacks,
+    self => self.0
+        .into_iter()
+        .map(|(target, list)| {
+            let key: EcoString = match target {
+                FallbackTarget::Script(script) => script.short_name().into(),
+                FallbackTarget::Lang(lang) => lang.as_str().into(),
+            };
+            (key.into(), list.into_value())
+        })
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            let target = if k.len() == 4 {
+                let mut name = k.to_ascii_lowercase();
+                name[..1].make_ascii_uppercase();
+                let Some(script) = Script::from_short_name(&name) else {
+                    bail!("unknown script: {}", k.repr());
+                };
+                FallbackTarget::Script(script)
+            } else {
+                FallbackTarget::Lang(k.parse()?)
+            };
+            Ok((target, v.cast()?))
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for FontFallbacks {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
+/// What fallback families are defined for.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FallbackTarget {
+    /// Text in a specific script.
+    Script(Script),
+    /// Text in a specific language.
+    Lang(Lang),
+}
+
 /// Resolve a prioritized iterator over the font families.
 pub(crate) fn families(styles: StyleChain) -> impl Iterator<Item = &str> + Clone {
     const FALLBACKS: &[&str] = &[
@@ -1024,8 +1310,8 @@ cast! {
     TextDir,
     self => self.0.into_value(),
     v: Smart<Dir> => {
-        if v.is_custom_and(|dir| dir.axis() == Axis::Y) {
-            bail!("text direction must be horizontal");
+        if v.is_custom_and(|dir| dir == Dir::BTT) {
+            bail!("text direction must be horizontal or top-to-bottom");
         }
         Self(v)
     },
@@ -1108,6 +1394,31 @@ pub enum NumberWidth {
     Tabular,
 }
 
+/// How to trim the blank space of full-width CJK punctuation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum CjkPunctuationTrim {
+    /// Compress adjacent punctuation marks.
+    Adjacent,
+    /// Compress adjacent punctuation marks and trim closing punctuation at
+    /// the end of lines.
+    TrimEnd,
+    /// Compress adjacent punctuation marks and trim punctuation at the start
+    /// and end of lines.
+    TrimBoth,
+}
+
+impl CjkPunctuationTrim {
+    /// Whether closing punctuation is trimmed at the end of lines.
+    pub fn trims_end(self) -> bool {
+        matches!(self, Self::TrimEnd | Self::TrimBoth)
+    }
+
+    /// Whether opening punctuation is trimmed at the start of lines.
+    pub fn trims_start(self) -> bool {
+        matches!(self, Self::TrimBoth)
+    }
+}
+
 /// OpenType font features settings.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct FontFeatures(pub Vec<(Tag, u32)>);
@@ -1146,6 +1457,39 @@ impl Fold for FontFeatures {
     }
 }
 
+/// Coordinates for the variation axes of variable fonts.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct FontVariations(pub Vec<(Tag, Scalar)>);
+
+cast! {
+    FontVariations,
+    self => self.0
+        .into_iter()
+        .map(|(tag, value)| {
+            let bytes = tag.to_bytes();
+            let key = std::str::from_utf8(&bytes).unwrap_or_default();
+            (key.into(), value.get().into_value())
+        })
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            if k.len() != 4 || !k.is_ascii() {
+                bail!("axis tag must consist of four ASCII characters, found {}", k.repr());
+            }
+            let value = v.cast::<f64>()?;
+            Ok((Tag::from_bytes_lossy(k.as_bytes()), Scalar::new(value)))
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for FontVariations {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
 /// Collect the OpenType features to apply.
 pub(crate) fn features(styles: StyleChain) -> Vec<Feature> {
     let mut tags = vec![];
@@ -1213,6 +1557,18 @@ pub(crate) fn features(styles: StyleChain) -> Vec<Feature> {
     tags
 }
 
+/// Collect the explicitly set variation axis coordinates.
+///
+/// The coordinates that follow the font variant and size are added per font,
+/// see [`Font::instance`].
+pub(crate) fn variations(styles: StyleChain) -> Vec<(Tag, f32)> {
+    TextElem::variations_in(styles)
+        .0
+        .into_iter()
+        .map(|(tag, value)| (tag, value.get() as f32))
+        .collect()
+}
+
 /// A toggle that turns on and off alternatingly if folded.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct ItalicToggle(pub bool);
@@ -1233,6 +1589,64 @@ impl Fold for WeightDelta {
     }
 }
 
+/// A table of how far characters hang into the start and end margins of a
+/// line, relative to their width.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct Protrusion(pub Vec<(char, (Ratio, Ratio))>);
+
+impl Protrusion {
+    /// The protrusion of a character at the start and end of a line, if the
+    /// table contains it.
+    pub fn get(&self, c: char) -> Option<(Ratio, Ratio)> {
+        self.0
+            .iter()
+            .rev()
+            .find(|&&(k, _)| k == c)
+            .map(|&(_, amounts)| amounts)
+    }
+}
+
+cast! {
+    Protrusion,
+    self => self.0
+        .into_iter()
+        .map(|(c, (start, end))| {
+            (c.into(), dict! { "start" => start, "end" => end }.into_value())
+        })
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| {
+            let mut chars = k.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                bail!("expected a single character, found {}", k.repr());
+            };
+            let amounts = match v {
+                Value::Dict(mut dict) => {
+                    let mut take = |key| {
+                        dict.take(key).ok().map(Value::cast).transpose()
+                    };
+                    let amounts = (
+                        take("start")?.unwrap_or_default(),
+                        take("end")?.unwrap_or_default(),
+                    );
+                    dict.finish(&["start", "end"])?;
+                    amounts
+                }
+                v => (Ratio::zero(), v.cast()?),
+            };
+            Ok((c, amounts))
+        })
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for Protrusion {
+    fn fold(self, outer: Self) -> Self {
+        Self(self.0.fold(outer.0))
+    }
+}
+
 /// Costs that are updated (prioritizing the later value) when folded.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 #[non_exhaustive] // We may add more costs in the future.
@@ -1241,6 +1655,7 @@ pub struct Costs {
     pub runt: Option<Ratio>,
     pub widow: Option<Ratio>,
     pub orphan: Option<Ratio>,
+    pub kashida: Option<Ratio>,
 }
 
 impl Costs {
@@ -1267,6 +1682,12 @@ impl Costs {
     pub fn orphan(&self) -> Ratio {
         self.orphan.unwrap_or(Ratio::one())
     }
+
+    #[inline]
+    #[must_use]
+    pub fn kashida(&self) -> Ratio {
+        self.kashida.unwrap_or(Ratio::one())
+    }
 }
 
 impl Fold for Costs {
@@ -1277,6 +1698,7 @@ impl Fold for Costs {
             runt: self.runt.or(outer.runt),
             widow: self.widow.or(outer.widow),
             orphan: self.orphan.or(outer.orphan),
+            kashida: self.kashida.or(outer.kashida),
         }
     }
 }
@@ -1288,6 +1710,7 @@ cast! {
         "runt" => self.runt(),
         "widow" => self.widow(),
         "orphan" => self.orphan(),
+        "kashida" => self.kashida(),
     ].into_value(),
     mut v: Dict => {
         let ret = Self {
@@ -1295,8 +1718,9 @@ cast! {
             runt: v.take("runt").ok().map(|v| v.cast()).transpose()?,
             widow: v.take("widow").ok().map(|v| v.cast()).transpose()?,
             orphan: v.take("orphan").ok().map(|v| v.cast()).transpose()?,
+            kashida: v.take("kashida").ok().map(|v| v.cast()).transpose()?,
         };
-        v.finish(&["hyphenation", "runt", "widow", "orphan"])?;
+        v.finish(&["hyphenation", "runt", "widow", "orphan", "kashida"])?;
         ret
     },
 }
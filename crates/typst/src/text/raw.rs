@@ -14,8 +14,9 @@ use crate::diag::{At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, Args, Array, Bytes, Content, Fold, NativeElement, Packed,
-    PlainText, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, Value,
+    PlainText, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, TargetElem, Value,
 };
+use crate::html::{HtmlElem, HtmlTag};
 use crate::layout::{BlockElem, Em, HAlignment};
 use crate::model::{Figurable, ParElem};
 use crate::syntax::{split_newlines, LinkedNode, Span, Spanned};
@@ -420,6 +421,10 @@ impl Show for Packed<RawElem> {
     fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let lines = self.lines().map(|v| v.as_slice()).unwrap_or_default();
 
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(self.show_html(lines, styles));
+        }
+
         let mut seq = EcoVec::with_capacity((2 * lines.len()).saturating_sub(1));
         for (i, line) in lines.iter().enumerate() {
             if i != 0 {
@@ -441,6 +446,34 @@ impl Show for Packed<RawElem> {
     }
 }
 
+impl Packed<RawElem> {
+    /// Produce a `code` element, wrapped in a `pre` element for raw blocks.
+    fn show_html(&self, lines: &[Packed<RawLine>], styles: StyleChain) -> Content {
+        let mut seq = EcoVec::with_capacity((2 * lines.len()).saturating_sub(1));
+        for (i, line) in lines.iter().enumerate() {
+            if i != 0 {
+                seq.push(TextElem::packed('\n'));
+            }
+
+            seq.push(line.clone().pack());
+        }
+
+        let mut code = HtmlElem::new(HtmlTag::constant("code"));
+        if let Some(lang) = self.lang(styles) {
+            code = code.with_attr("class", eco_format!("language-{lang}"));
+        }
+
+        let mut realized = code.with_body(Some(Content::sequence(seq))).pack();
+        if self.block(styles) {
+            realized = HtmlElem::new(HtmlTag::constant("pre"))
+                .with_body(Some(realized))
+                .pack();
+        }
+
+        realized.spanned(self.span())
+    }
+}
+
 impl ShowSet for Packed<RawElem> {
     fn show_set(&self, styles: StyleChain) -> Styles {
         let mut out = Styles::new();
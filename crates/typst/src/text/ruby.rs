@@ -0,0 +1,192 @@
+use crate::diag::{bail, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, Args, Array, Cast, Construct, Content, NativeElement, Packed, Show,
+    StyleChain, TargetElem, Unlabellable, Value,
+};
+use crate::html::{HtmlElem, HtmlTag};
+use crate::layout::Em;
+use crate::realize::{Behave, Behaviour};
+use crate::text::TextSize;
+
+/// Annotates text with ruby.
+///
+/// Ruby annotations are small pieces of text set above their base text. They
+/// are commonly used to indicate the reading of Chinese characters in Japanese
+/// (_furigana_) and Chinese texts. Unlike a `box` with a `stack`, the base text
+/// stays part of the paragraph: It is shaped together with the surrounding
+/// text and takes part in line breaking and justification. In vertical text,
+/// the annotations are set to the right of their base.
+///
+/// To annotate each character of a word separately, pass arrays with the
+/// same number of parts as the base and the annotation.
+///
+/// # Example
+/// ```example
+/// #set text(lang: "ja")
+/// #ruby[東京][とうきょう]に行く。\
+/// #ruby(("東", "京"), ("とう", "きょう"))に行く。\
+/// #ruby(
+///   ("東", "京"),
+///   ("とう", "きょう"),
+///   placement: "group",
+/// )に行く。
+/// ```
+///
+/// The base can be styled like any other text:
+/// ```example
+/// #set text(lang: "ja")
+/// #ruby[*漢字*][かんじ]を読む。
+/// ```
+#[elem(Show)]
+pub struct RubyElem {
+    /// How the annotations are placed over the base text.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// #for placement in ("mono", "group", "jukugo") [
+    ///   #ruby(
+    ///     ("境", "界", "線"),
+    ///     ("きょう", "かい", "せん"),
+    ///     placement: placement,
+    ///   ) \
+    /// ]
+    /// ```
+    pub placement: RubyPlacement,
+
+    /// The size of the annotation text, relative to the size of the base
+    /// text.
+    ///
+    /// ```example
+    /// #ruby(size: 0.6em)[Typst][ˈtaɪpst]
+    /// ```
+    #[default(TextSize(Em::new(0.5).into()))]
+    pub size: TextSize,
+
+    /// Whether an annotation that is wider than its base may overhang the
+    /// adjacent text if that text consists of kana or punctuation. An
+    /// annotation never overhangs by more than one of its characters. Where
+    /// it can't overhang, space is added around the base instead.
+    ///
+    /// ```example
+    /// #set text(lang: "ja")
+    /// の#ruby[鱚][きす]の \
+    /// の#ruby(overhang: false)[鱚][きす]の
+    /// ```
+    #[default(true)]
+    pub overhang: bool,
+
+    /// The base text. Either content or an array of parts.
+    #[required]
+    pub base: RubyParts,
+
+    /// The annotation. Either content or an array with one annotation for
+    /// each part of the base.
+    #[required]
+    pub annotation: RubyParts,
+}
+
+impl Show for Packed<RubyElem> {
+    #[typst_macros::time(name = "ruby", span = self.span())]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let (bases, annotations) = (&self.base().0, &self.annotation().0);
+        if bases.len() != annotations.len() {
+            bail!(
+                self.span(),
+                "expected {} ruby annotations, found {}",
+                bases.len(),
+                annotations.len();
+                hint: "provide one annotation for each part of the base"
+            );
+        }
+
+        if TargetElem::target_in(styles).is_html() {
+            let mut children = vec![];
+            for (base, annotation) in bases.iter().zip(annotations) {
+                children.push(base.clone());
+                children.push(
+                    HtmlElem::new(HtmlTag::constant("rt"))
+                        .with_body(Some(annotation.clone()))
+                        .pack(),
+                );
+            }
+            return Ok(HtmlElem::new(HtmlTag::constant("ruby"))
+                .with_body(Some(Content::sequence(children)))
+                .pack());
+        }
+
+        // The base stays part of the paragraph so that it is shaped, broken,
+        // and justified like the surrounding text. Marks around its parts
+        // tell the paragraph layout where the annotations belong.
+        let mark =
+            |index| RubyMarkElem::new(self.clone(), index).pack().spanned(self.span());
+        let mut children = vec![mark(0)];
+        for (i, base) in bases.iter().enumerate() {
+            children.push(base.clone());
+            children.push(mark(i + 1));
+        }
+
+        Ok(Content::sequence(children))
+    }
+}
+
+/// Marks a boundary between the parts of a ruby base within a paragraph.
+///
+/// A ruby element shows as its base parts with a mark before the first part
+/// and after each part.
+#[elem(Behave, Unlabellable, Construct)]
+pub struct RubyMarkElem {
+    /// The ruby element whose base is marked.
+    #[required]
+    #[internal]
+    pub ruby: Packed<RubyElem>,
+
+    /// How many base parts precede the mark.
+    #[required]
+    #[internal]
+    pub index: usize,
+}
+
+impl Construct for RubyMarkElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually")
+    }
+}
+
+impl Unlabellable for Packed<RubyMarkElem> {}
+
+impl Behave for Packed<RubyMarkElem> {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Invisible
+    }
+}
+
+/// How ruby annotations are placed over their base text.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RubyPlacement {
+    /// Each annotation is centered over its part of the base. Lines may break
+    /// between the parts.
+    #[default]
+    Mono,
+    /// All annotations are joined and spread over the whole base, which is
+    /// never broken across lines.
+    Group,
+    /// Each annotation is centered over its part of the base like with
+    /// `mono`, unless one of them is wider than its part, in which case the
+    /// annotations are joined and centered over the whole base.
+    Jukugo,
+}
+
+/// The parts of a ruby base or annotation.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct RubyParts(pub Vec<Content>);
+
+cast! {
+    RubyParts,
+    self => self.0.into_value(),
+    v: Content => Self(vec![v]),
+    values: Array => Self(values
+        .into_iter()
+        .map(Value::cast)
+        .collect::<StrResult<_>>()?),
+}
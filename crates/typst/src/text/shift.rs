@@ -2,7 +2,10 @@ use ecow::EcoString;
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{elem, Content, Packed, SequenceElem, Show, StyleChain};
+use crate::foundations::{
+    elem, Content, NativeElement, Packed, SequenceElem, Show, StyleChain, TargetElem,
+};
+use crate::html::{HtmlElem, HtmlTag};
 use crate::layout::{Em, Length};
 use crate::text::{variant, SpaceElem, TextElem, TextSize};
 use crate::World;
@@ -51,6 +54,12 @@ impl Show for Packed<SubElem> {
     #[typst_macros::time(name = "sub", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let body = self.body().clone();
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(HtmlElem::new(HtmlTag::constant("sub"))
+                .with_body(Some(body))
+                .pack());
+        }
+
         let mut transformed = None;
         if self.typographic(styles) {
             if let Some(text) = search_text(&body, true) {
@@ -111,6 +120,12 @@ impl Show for Packed<SuperElem> {
     #[typst_macros::time(name = "super", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
         let body = self.body().clone();
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(HtmlElem::new(HtmlTag::constant("sup"))
+                .with_body(Some(body))
+                .pack());
+        }
+
         let mut transformed = None;
         if self.typographic(styles) {
             if let Some(text) = search_text(&body, false) {
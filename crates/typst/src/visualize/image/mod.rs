@@ -154,14 +154,9 @@ impl ImageElem {
     }
 }
 
-impl LayoutSingle for Packed<ImageElem> {
-    #[typst_macros::time(name = "image", span = self.span())]
-    fn layout(
-        &self,
-        engine: &mut Engine,
-        styles: StyleChain,
-        regions: Regions,
-    ) -> SourceResult<Frame> {
+impl Packed<ImageElem> {
+    /// Load the image from its data.
+    pub fn load(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Image> {
         // Take the format that was explicitly defined, or parse the extension,
         // or try to detect the format.
         let data = self.data();
@@ -190,14 +185,26 @@ impl LayoutSingle for Packed<ImageElem> {
             }
         };
 
-        let image = Image::with_fonts(
+        Image::with_fonts(
             data.clone().into(),
             format,
             self.alt(styles),
             engine.world,
             &families(styles).map(|s| s.into()).collect::<Vec<_>>(),
         )
-        .at(self.span())?;
+        .at(self.span())
+    }
+}
+
+impl LayoutSingle for Packed<ImageElem> {
+    #[typst_macros::time(name = "image", span = self.span())]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Frame> {
+        let image = self.load(engine, styles)?;
 
         let sizing = Axes::new(self.width(styles), self.height(styles));
         let region = sizing
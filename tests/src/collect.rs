@@ -25,6 +25,7 @@ pub struct Test {
     pub source: Source,
     pub notes: Vec<Note>,
     pub large: bool,
+    pub html: bool,
 }
 
 impl Display for Test {
@@ -157,7 +158,8 @@ impl Collector {
         for entry in walkdir::WalkDir::new(crate::REF_PATH).sort_by_file_name() {
             let entry = entry.unwrap();
             let path = entry.path();
-            if !path.extension().is_some_and(|ext| ext == "png") {
+            let is_html = path.extension().is_some_and(|ext| ext == "html");
+            if !is_html && !path.extension().is_some_and(|ext| ext == "png") {
                 continue;
             }
 
@@ -173,7 +175,7 @@ impl Collector {
             };
 
             let len = path.metadata().unwrap().len() as usize;
-            if !self.large.contains(name) && len > crate::REF_LIMIT {
+            if !is_html && !self.large.contains(name) && len > crate::REF_LIMIT {
                 self.errors.push(TestParseError {
                     pos: pos.clone(),
                     message: format!(
@@ -257,6 +259,8 @@ impl<'a> Parser<'a> {
                 self.collector.large.insert(name.clone());
             }
 
+            let html = text.starts_with("// HTML");
+
             if !selected(&name, self.path.canonicalize().unwrap()) {
                 self.collector.skipped += 1;
                 continue;
@@ -280,7 +284,9 @@ impl<'a> Parser<'a> {
                 }
             }
 
-            self.collector.tests.push(Test { pos, name, source, notes, large });
+            self.collector
+                .tests
+                .push(Test { pos, name, source, notes, large, html });
         }
     }
 
@@ -2,11 +2,11 @@ use std::fmt::Write;
 use std::ops::Range;
 use std::path::Path;
 
-use ecow::eco_vec;
 use tiny_skia as sk;
 use typst::diag::SourceDiagnostic;
 use typst::eval::Tracer;
 use typst::foundations::Smart;
+use typst::html::{HtmlDocument, HtmlNode};
 use typst::layout::{Abs, Frame, FrameItem, Page, Transform};
 use typst::model::Document;
 use typst::visualize::Color;
@@ -81,18 +81,21 @@ impl<'a> Runner<'a> {
         }
 
         let mut tracer = Tracer::new();
-        let (doc, errors) = match typst::compile(&self.world, &mut tracer) {
-            Ok(doc) => (Some(doc), eco_vec![]),
-            Err(errors) => (None, errors),
+        let (ok, errors) = if self.test.html {
+            let result = typst::compile_html(&self.world, &mut tracer);
+            self.check_html(result.as_ref().ok());
+            (result.is_ok(), result.err().unwrap_or_default())
+        } else {
+            let result = typst::compile(&self.world, &mut tracer);
+            self.check_document(result.as_ref().ok());
+            (result.is_ok(), result.err().unwrap_or_default())
         };
 
         let warnings = tracer.warnings();
-        if doc.is_none() && errors.is_empty() {
+        if !ok && errors.is_empty() {
             log!(self, "no document, but also no errors");
         }
 
-        self.check_document(doc.as_ref());
-
         for error in &errors {
             self.check_diagnostic(NoteKind::Error, error);
         }
@@ -237,6 +240,54 @@ impl<'a> Runner<'a> {
         }
     }
 
+    /// Check that the HTML output is correct.
+    fn check_html(&mut self, document: Option<&HtmlDocument>) {
+        let live_path = format!("{}/html/{}.html", crate::STORE_PATH, self.test.name);
+        let ref_path = format!("{}/html/{}.html", crate::REF_PATH, self.test.name);
+        let has_ref = Path::new(&ref_path).exists();
+
+        let Some(document) = document else {
+            if has_ref {
+                log!(self, "missing document");
+                log!(self, "  ref       | {ref_path}");
+            }
+            return;
+        };
+
+        // Tests without visible output and no reference don't need to be
+        // compared.
+        let skippable = document.body.iter().all(|node| matches!(node, HtmlNode::Tag(_)));
+        if skippable && !has_ref {
+            std::fs::remove_file(&live_path).ok();
+            return;
+        }
+
+        let html = typst_html::html(document);
+        std::fs::write(&live_path, &html).unwrap();
+
+        if has_ref && std::fs::read_to_string(&ref_path).unwrap() == html {
+            return;
+        }
+
+        if crate::ARGS.update {
+            if skippable {
+                std::fs::remove_file(&ref_path).unwrap();
+                log!(into: self.result.infos, "removed reference HTML ({ref_path})");
+            } else {
+                std::fs::create_dir_all(Path::new(&ref_path).parent().unwrap()).unwrap();
+                std::fs::write(&ref_path, &html).unwrap();
+                log!(into: self.result.infos, "Updated reference HTML ({ref_path})");
+            }
+        } else if has_ref {
+            log!(self, "mismatched HTML");
+            log!(self, "  live      | {live_path}");
+            log!(self, "  ref       | {ref_path}");
+        } else {
+            log!(self, "missing reference HTML");
+            log!(self, "  live      | {live_path}");
+        }
+    }
+
     /// Compare a subset of notes with a given kind against diagnostics of
     /// that same kind.
     fn check_diagnostic(&mut self, kind: NoteKind, diag: &SourceDiagnostic) {
@@ -91,7 +91,9 @@ impl Default for TestBase {
     fn default() -> Self {
         let fonts: Vec<_> = typst_assets::fonts()
             .chain(typst_dev_assets::fonts())
-            .flat_map(|data| Font::iter(Bytes::from_static(data)))
+            .map(Bytes::from_static)
+            .chain(test_fonts())
+            .flat_map(Font::iter)
             .collect();
 
         Self {
@@ -103,6 +105,20 @@ impl Default for TestBase {
     }
 }
 
+/// Load the fonts that are made just for the test suite.
+fn test_fonts() -> impl Iterator<Item = Bytes> {
+    let mut paths: Vec<_> = fs::read_dir(crate::FONTS_PATH)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| fs::read(path).ok())
+        .map(Bytes::from)
+}
+
 /// Holds the processed data for a file ID.
 #[derive(Clone)]
 struct FileSlot {
@@ -355,6 +355,34 @@
 #test((2, 1, 3, -10, -5, 8, 6, -7, 2).sorted(key: x => x), (-10, -7, -5, 1, 2, 2, 3, 6, 8))
 #test((2, 1, 3, -10, -5, 8, 6, -7, 2).sorted(key: x => x * x), (1, 2, 2, 3, -5, 6, -7, 8, -10))
 
+--- array-sorted-locale ---
+// Test sorting with a locale.
+#let names = ("Zoë", "Ängel", "zara", "Anna", "Öl", "Ål")
+#test(names.sorted(), ("Anna", "Zoë", "zara", "Ängel", "Ål", "Öl"))
+#test(names.sorted(locale: "de"), ("Ål", "Ängel", "Anna", "Öl", "zara", "Zoë"))
+#test(names.sorted(locale: "sv"), ("Anna", "zara", "Zoë", "Ål", "Ängel", "Öl"))
+#test(("ñu", "nube", "oso").sorted(locale: "es"), ("nube", "ñu", "oso"))
+#test(
+  (("Émile", 2), ("Ada", 3), ("emile", 1)).sorted(locale: "fr"),
+  (("Ada", 3), ("emile", 1), ("Émile", 2)),
+)
+#test(
+  ("Bernd Zorn", "Anna Zorn", "Carl Ärger")
+    .sorted(key: n => n.split(" ").rev(), locale: "de"),
+  ("Carl Ärger", "Anna Zorn", "Bernd Zorn"),
+)
+#test((3, 1, 2).sorted(locale: "de"), (1, 2, 3))
+
+--- array-sorted-locale-script ---
+// Other scripts are sorted too and the language's own script comes first.
+#test(("β", "b", "α", "a").sorted(locale: "en"), ("a", "b", "α", "β"))
+#test(("β", "b", "α", "a").sorted(locale: "el"), ("α", "β", "a", "b"))
+#test(("ぱん", "はし", "ばら").sorted(locale: "ja"), ("はし", "ばら", "ぱん"))
+
+--- array-sorted-locale-uncomparable ---
+// Error: 2-31 cannot compare integer and string
+#("a", 1).sorted(locale: "en")
+
 --- array-sorted-key-function-positional-1 ---
 // Error: 12-18 unexpected argument
 #().sorted(x => x)
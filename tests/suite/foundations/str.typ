@@ -52,6 +52,19 @@
 #test(str.from-unicode(97), "a")
 #test(str.to-unicode("a"), 97)
 
+--- str-normalize ---
+// Test the `normalize` method.
+#test("e\u{0301}".normalize(), "é")
+#test("é".normalize(form: "nfd"), "e\u{0301}")
+#test("ﬁ".normalize(form: "nfc"), "ﬁ")
+#test("ﬁ".normalize(form: "nfkc"), "fi")
+#test("ẛ̣".normalize(form: "nfkd"), "s\u{0323}\u{0307}")
+#test("Å".normalize(form: "nfd").len(), 3)
+
+--- str-normalize-bad-form ---
+// Error: 24-29 expected "nfc", "nfd", "nfkc", or "nfkd"
+#"abc".normalize(form: "nfx")
+
 --- str-from-unicode-bad-type ---
 // Error: 19-22 expected integer, found content
 #str.from-unicode([a])
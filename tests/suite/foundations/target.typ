@@ -0,0 +1,24 @@
+--- target-paged ---
+#context test(target(), "paged")
+
+--- target-html ---
+// HTML
+#context test(target(), "html")
+
+--- target-show-rule ---
+// HTML
+#show heading: it => context {
+  if target() == "html" {
+    html.elem("h2", attrs: (class: "title"), it.body)
+  } else {
+    it
+  }
+}
+
+= Introduction
+
+--- target-no-context ---
+// Error: 2-10 can only be used when context is known
+// Hint: 2-10 try wrapping this in a `context` expression
+// Hint: 2-10 the `context` expression should wrap everything that depends on this function
+#target()
@@ -0,0 +1,37 @@
+--- html-elem-paged ---
+// Without HTML export, the element just shows its body.
+#html.elem("aside", attrs: (class: "note"))[
+  This is an *aside.*
+]
+Before #html.elem("mark")[marked] after.
+
+--- html-elem-fields ---
+#let elem = html.elem("a", attrs: (href: "https://typst.app"))[Typst]
+#test(elem.tag, "a")
+#test(elem.attrs, (href: "https://typst.app"))
+#test(elem.body, [Typst])
+#test(html.elem("BR").tag, "br")
+
+--- html-elem-export ---
+// HTML
+#html.elem("aside", attrs: (class: "note", data-level: "2"))[
+  This is an *aside.*
+]
+Before #html.elem("mark")[marked] after.
+#html.elem("hr")
+
+--- html-elem-tag-invalid ---
+// Error: 12-20 invalid tag name: `my tag`
+#html.elem("my tag")
+
+--- html-elem-tag-empty ---
+// Error: 12-14 tag name must not be empty
+#html.elem("")
+
+--- html-elem-attr-invalid ---
+// Error: 24-36 invalid attribute name: `1st`
+#html.elem("p", attrs: ("1st": "x"))
+
+--- html-elem-attr-value-invalid ---
+// Error: 24-31 expected string, found integer
+#html.elem("p", attrs: (id: 1))
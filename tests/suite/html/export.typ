@@ -0,0 +1,74 @@
+--- html-export-basic ---
+// HTML
+#set document(title: "A Document", author: ("Jane", "John"), keywords: "test")
+#set heading(numbering: "1.")
+
+= Introduction <intro>
+This is _emphasized_ and *strong* text with a #link("https://typst.app")[link],
+H#sub[2]O, and 2#super[nd]. See @intro and "quotes".
+
+== Lists
+- Bullet
+- List
+
++ First
++ Second
+5. Fifth
+
+/ Term: Description
+
+--- html-export-figure-table ---
+// HTML
+#figure(
+  table(
+    columns: 2,
+    table.header[*Name*][*Value*],
+    [a], [1 < 2],
+    table.cell(colspan: 2)[Spanning],
+  ),
+  caption: [A table],
+) <tab>
+
+#figure(
+  ```rust
+  fn main() {
+      println!("Hi");
+  }
+  ```,
+  caption: [Some code],
+)
+
+Inline `raw` text.
+
+--- html-export-footnote ---
+// HTML
+Typst#footnote[A typesetting system.] is great.#footnote[Really.]
+
+--- html-export-math ---
+// HTML
+The formula $x_1$ is inline.
+
+$ a/b $
+
+--- html-export-frame ---
+// HTML
+#rect(width: 20pt, height: 10pt)
+Text with a #box(width: 5pt, height: 5pt, fill: red) box.
+
+--- html-export-image ---
+// HTML
+#figure(
+  image.decode(
+    "<svg xmlns='http://www.w3.org/2000/svg' width='10' height='10'><rect width='10' height='10'/></svg>",
+    alt: "A square",
+  ),
+  caption: [A square],
+)
+
+--- html-export-outline ---
+// HTML
+#outline()
+
+= Introduction
+== Details
+#heading(outlined: false)[Appendix]
@@ -65,13 +65,15 @@ Lריווח #h(1cm) R
 לתכנת בעברית `אם א == ב:`
 
 --- bidi-vertical ---
-// Test setting a vertical direction.
-// Error: 16-19 text direction must be horizontal
-#set text(dir: ttb)
+// Test setting a bottom-to-top direction.
+// Error: 16-19 text direction must be horizontal or top-to-bottom
+#set text(dir: btt)
 
 --- issue-1373-bidi-tofus ---
 // Test that shaping missing characters in both left-to-right and
 // right-to-left directions does not cause a crash.
+// Warning: 2-32 no font could be found that covers the characters "/u{590}/u{591}/u{592}/u{593}"
 #"\u{590}\u{591}\u{592}\u{593}"
 
+// Warning: 2-40 no font could be found that covers the characters "𰀀𰀁𰀂𰀃"
 #"\u{30000}\u{30001}\u{30002}\u{30003}"
@@ -88,3 +88,60 @@ abc字\
 测a试
 
 测#context [a]试
+
+--- cjk-punctuation-trim ---
+#set text(lang: "zh", font: "Noto Serif CJK SC")
+#let width(trim, body) = {
+  measure(text(cjk-punctuation-trim: trim, body)).width
+}
+
+#context {
+  // Adjacent punctuation is compressed.
+  let full = width(none, [中」「中])
+  test(width("adjacent", [中」「中]) < full, true)
+  test(width("trim-both", [中」「中]), width("adjacent", [中」「中]))
+
+  // Closing punctuation is trimmed at line ends.
+  test(width("adjacent", [中。]), width(none, [中。]))
+  test(width("trim-end", [中。]) < width("adjacent", [中。]), true)
+
+  // Opening punctuation is trimmed at line starts.
+  test(width("trim-end", [「中]), width(none, [「中]))
+  test(width("trim-both", [「中]) < width("trim-end", [「中]), true)
+}
+
+--- cjk-punctuation-trim-auto ---
+#set text(font: "Noto Serif CJK SC")
+#let width(lang, region: none, trim: auto, body) = {
+  measure(text(lang: lang, region: region, cjk-punctuation-trim: trim, body)).width
+}
+
+#context {
+  // Simplified Chinese and Japanese trim punctuation at both ends of lines.
+  test(width("zh", [「中」「中」]), width("zh", trim: "trim-both", [「中」「中」]))
+  test(width("ja", [「中」「中」]), width("ja", trim: "trim-both", [「中」「中」]))
+  test(width("ja", [「中]) < width("ja", trim: "trim-end", [「中]), true)
+
+  // Traditional Chinese keeps punctuation at full width.
+  test(
+    width("zh", region: "TW", [「中」「中」]),
+    width("zh", region: "TW", trim: none, [「中」「中」]),
+  )
+}
+
+--- cjk-punctuation-trim-invalid ---
+// Error: 33-40 expected "adjacent", "trim-end", "trim-both", none, or auto
+#set text(cjk-punctuation-trim: "start")
+
+--- cjk-kinsoku ---
+// Closing punctuation doesn't start a line in Chinese and Japanese text, even
+// where UAX #14 allows a break before it.
+#set page(width: 60pt, margin: 5pt)
+#set text(lang: "zh", font: "Noto Serif CJK SC", size: 10pt)
+中文中文中 ：中文中文中 ；
+
+--- cjk-kinsoku-mixed-lang ---
+// The rules also apply to Japanese text in a paragraph of another language.
+#set page(width: 60pt, margin: 5pt)
+#set text(font: "Noto Serif CJK JP", size: 10pt)
+#text(lang: "ja")[日本語です “引用”日本語です ：] a
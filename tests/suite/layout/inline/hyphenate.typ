@@ -148,12 +148,52 @@ el objetivo de protegerle de las patrullas de milicianos.
 #set text(costs: (hyphenation: auto))
 
 --- costs-invalid-key ---
-// Error: 18-52 unexpected key "invalid-key", valid keys are "hyphenation", "runt", "widow", and "orphan"
+// Error: 18-52 unexpected key "invalid-key", valid keys are "hyphenation", "runt", "widow", "orphan", and "kashida"
 #set text(costs: (hyphenation: 1%, invalid-key: 3%))
 
 --- costs-access ---
 #set text(costs: (hyphenation: 1%, runt: 2%))
-#set text(costs: (widow: 3%))
+#set text(costs: (widow: 3%, kashida: 4%))
 #context {
-  assert.eq(text.costs, (hyphenation: 1%, runt: 2%, widow: 3%, orphan: 100%))
+  assert.eq(
+    text.costs,
+    (hyphenation: 1%, runt: 2%, widow: 3%, orphan: 100%, kashida: 4%),
+  )
 }
+
+--- hyphenate-exceptions ---
+// Test custom hyphenation exceptions.
+#set page(width: auto)
+#set text(hyphenate: true)
+#set text(hyphenation-exceptions: ("Typst", "wel-co-mes", "exper-iences"))
+#grid(
+  columns: (50pt, 50pt),
+  [Warm welcomes to Typst.],
+  [Wonderful experiences with typst.],
+)
+
+--- hyphenate-patterns ---
+// Test custom hyphenation patterns.
+#set page(width: auto)
+#set text(
+  lang: "qaa",
+  hyphenate: true,
+  hyphenation-patterns: (qaa: "patterns/qaa.tex"),
+)
+#grid(
+  columns: (40pt, 40pt),
+  [Lamasabaratanaka],
+  [Ta karavan],
+)
+
+--- hyphenate-patterns-not-found ---
+// Error: 33-59 file not found (searched at tests/suite/layout/inline/patterns/nope.tex)
+#set text(hyphenation-patterns: (qaa: "patterns/nope.tex"))
+
+--- hyphenate-patterns-invalid ---
+// Error: 33-62 failed to parse hyphenation patterns `patterns/invalid.tex` (invalid hyphenation pattern `2c12d`)
+#set text(hyphenation-patterns: (qaa: "patterns/invalid.tex"))
+
+--- hyphenate-patterns-bad-lang ---
+// Error: 33-60 expected two or three letter language code (ISO 639-1/2/3)
+#set text(hyphenation-patterns: (latin: "patterns/qaa.tex"))
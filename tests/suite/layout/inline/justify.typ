@@ -168,3 +168,47 @@ int main() {
 // an underfull first line.
 #set par(hanging-indent: 2.5cm, justify: true)
 #lorem(5)
+
+--- justify-kashida-non-arabic ---
+// Kashidas only affect Arabic script, so other text is justified as usual.
+#set par(justify: true, kashida: 1em)
+#set text(costs: (kashida: 50%))
+#block(width: 4cm, lorem(12))
+
+--- justify-kashida ---
+// The preferred kashida points: After seen and sad, before a final taa
+// marbuta, alef, or ra, and never within lam-alef.
+#set page(width: 160pt)
+#set par(justify: true, kashida: 1em)
+#set text(lang: "ar", font: "Noto Sans Arabic")
+يستخدم الكاتب نصوصا عربية طويلة في السطور المضبوطة كلا لكي تظهر الكشيدة بين
+الحروف المتصلة بوضوح.
+
+--- justify-kashida-none ---
+// Without kashidas, only the spaces are widened.
+#set page(width: 160pt)
+#set par(justify: true, kashida: none)
+#set text(lang: "ar", font: "Noto Sans Arabic")
+يستخدم الكاتب نصوصا عربية طويلة في السطور المضبوطة كلا لكي تظهر الكشيدة بين
+الحروف المتصلة بوضوح.
+
+--- justify-kashida-limit ---
+// A small limit leaves most of the space to the spaces between words.
+#set page(width: 160pt)
+#set par(justify: true, kashida: 0.15em)
+#set text(lang: "ar", font: "Noto Sans Arabic")
+يستخدم الكاتب نصوصا عربية طويلة في السطور المضبوطة كلا لكي تظهر الكشيدة بين
+الحروف المتصلة بوضوح.
+
+--- justify-kashida-costs ---
+// High kashida costs make the optimized line breaker avoid lines that need a
+// lot of elongation.
+#set page(width: 160pt)
+#set par(justify: true, kashida: 2em)
+#set text(lang: "ar", font: "Noto Sans Arabic", costs: (kashida: 1000%))
+يستخدم الكاتب نصوصا عربية طويلة في السطور المضبوطة كلا لكي تظهر الكشيدة بين
+الحروف المتصلة بوضوح.
+
+--- justify-kashida-invalid ---
+// Error: 19-24 expected length or none, found string
+#set par(kashida: "1em")
@@ -22,3 +22,19 @@
 #set align(end)
 #set text(dir: rtl)
 :
+
+--- overhang-protrusion ---
+// Test custom protrusion at both edges.
+#set par(justify: true)
+#set text(protrusion: ("“": (start: 50%), "”": 50%, "T": (start: 5%)))
+#rect(inset: 0pt, width: 100%)[
+  “To be or not to be, that is the question,” he said. “There is no other.”
+]
+
+--- overhang-protrusion-invalid-key ---
+// Error: 23-34 expected a single character, found "ab"
+#set text(protrusion: ("ab": 50%))
+
+--- overhang-protrusion-invalid-side ---
+// Error: 23-41 unexpected key "left", valid keys are "start" and "end"
+#set text(protrusion: ("a": (left: 10%)))
@@ -58,6 +58,52 @@ fi vs. #text(features: (liga: 0))[No fi]
 // Error: 21-35 expected string, found boolean
 #set text(features: ("tag", false))
 
+--- text-variations ---
+// Axes that the font doesn't have are ignored.
+#set text(variations: (wght: 700))
+#context test(text.variations, (wght: 700.0))
+#context test(measure(text(variations: (wdth: 75))[Variable]), measure[Variable])
+
+--- text-variations-axes ---
+#set page(width: auto)
+#set text(font: "Test Variable")
+#text(variations: (wght: 100))[HOTEL]
+#text(variations: (wght: 900))[HOTEL] \
+#text(variations: (wdth: 50))[HOTEL]
+#text(variations: (wdth: 200))[HOTEL] \
+#text(variations: (wght: 900, wdth: 50))[HOTEL]
+
+--- text-variations-weight-stretch ---
+// The weight and width axes follow the font weight and stretch.
+#set page(width: auto)
+#set text(font: "Test Variable")
+#text(weight: "thin")[FLAT]
+#text(weight: "regular")[FLAT]
+*FLAT*
+#text(weight: "black")[FLAT] \
+#text(stretch: 50%)[FLAT]
+#text(stretch: 150%)[FLAT]
+#text(weight: "black", variations: (wght: 100))[FLAT]
+
+--- text-variations-optical-size ---
+// The optical size axis follows the text size and changes the contrast. The
+// default instance is designed for 9pt to 16pt and used within that range.
+#set page(width: auto)
+#set text(font: "Test Variable")
+#text(6pt)[NOTE]
+#text(11pt)[NOTE]
+#text(15pt)[NOTE]
+#text(24pt)[NOTE]
+#text(24pt, variations: (opsz: 6))[NOTE]
+
+--- text-variations-bad-tag ---
+// Error: 23-36 axis tag must consist of four ASCII characters, found "weight"
+#set text(variations: (weight: 700))
+
+--- text-variations-bad-type ---
+// Error: 23-37 expected float, found string
+#set text(variations: (wght: "bold"))
+
 --- text-tracking-negative ---
 // Test tracking.
 #set text(tracking: -0.01em)
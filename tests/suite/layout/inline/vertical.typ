@@ -0,0 +1,78 @@
+--- vertical-basic ---
+#set page(width: 80pt, height: 100pt)
+#set text(dir: ttb)
+§1 Vertical text © Typst × 2024.
+
+--- vertical-upright-digits ---
+#set page(width: 100pt, height: 60pt)
+#set text(dir: ttb)
+§7 §24 §365 \
+#set text(upright-digits: 3)
+§7 §24 §365 \
+#set text(upright-digits: 0)
+§7 §24 §365
+
+--- vertical-pages ---
+#set page(width: 60pt, height: 60pt, numbering: "1")
+#set text(dir: ttb)
+#lorem(12)
+
+--- vertical-columns ---
+#set page(width: 80pt, height: 120pt, columns: 2)
+#set text(dir: ttb)
+#lorem(16)
+
+--- vertical-align ---
+#set page(width: 60pt, height: 80pt)
+#set text(dir: ttb)
+#align(end)[§ End] \
+#align(start)[§ Start]
+
+--- vertical-cjk ---
+#set page(width: 80pt, height: 100pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP", dir: ttb)
+「縦書き」は、日本語の文章で使われます。第12章、2024年。
+
+--- vertical-cjk-upright-digits ---
+#set page(width: 60pt, height: 80pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP", dir: ttb)
+令和6年 \
+#set text(upright-digits: 4)
+2024年 \
+全角１２３
+
+--- vertical-cjk-latin ---
+#set page(width: 60pt, height: 100pt)
+#set text(lang: "ja", font: ("Linux Libertine", "Noto Serif CJK JP"), dir: ttb)
+これはTypstで組んだ（縦書き）の例です。
+
+--- vertical-upright-objects ---
+#set page(width: 160pt, height: 100pt)
+#set text(dir: ttb)
+§ #box(rect(width: 16pt, height: 8pt, fill: eastern)) § $x^2$ §
+#image.decode(
+  `<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20"><rect width="40" height="20" fill="orange"/><circle cx="10" cy="10" r="6" fill="blue"/></svg>`.text,
+  format: "svg",
+)
+$ a + b = c $
+#table(columns: 2, [A], [B], [C], [D])
+
+--- vertical-inline ---
+// Error: 19-27 vertical text must be enabled for the whole page
+// Hint: 19-27 use `set text(dir: ttb)` at the start of the document
+A #text(dir: ttb)[vertical] word.
+
+--- vertical-block ---
+#block[
+  #set text(dir: ttb)
+  // Error: 4-20 vertical text must be enabled for the whole page
+  // Hint: 4-20 use `set text(dir: ttb)` at the start of the document
+  #rect(width: 1em)
+]
+
+--- vertical-in-upright-box ---
+#set page(width: 60pt, height: 60pt)
+#set text(dir: ttb)
+// Error: 23-31 vertical text must be enabled for the whole page
+// Hint: 23-31 use `set text(dir: ttb)` at the start of the document
+§ #box(text(dir: ttb)[Vertical]) §
@@ -0,0 +1,33 @@
+// Tests the ce() function.
+
+--- math-ce-formulas ---
+// Subscripts, coefficients, charges and groups.
+#set page(width: auto)
+$ ce("H2SO4") quad ce("2H2O") quad ce("1/2O2") quad ce("(NH4)2SO4") $
+$ ce("SO4^2-") quad ce("Na+") quad ce("Cl-") quad ce("[Fe(CN)6]^3-") quad ce("Cu^{2+}") $
+$ ce("^227_90Th") quad ce("CuSO4*5H2O") quad ce("NaCl(aq)") $
+
+--- math-ce-bonds ---
+#set page(width: auto)
+$ ce("CH3-CH3") quad ce("CH2=CH2") quad ce("HC#CH") $
+
+--- math-ce-reaction ---
+// Reactions with arrows, annotations and markers.
+#set page(width: auto)
+$ ce("2H2 + O2 -> 2H2O") $
+$ ce("CaCO3 ->[heat] CaO + CO2 ^") $
+$ ce("N2 + 3H2 <=>[Fe][400 °C, high p] 2NH3") $
+$ ce("Ag+ + Cl- -> AgCl v") $
+$ ce("A <- B <-> C") $
+
+--- math-ce-inline ---
+// A formula outside of math.
+Water is #math.ce("H2O") and rust is $ce("Fe2O3")$.
+
+--- math-ce-unclosed-group ---
+// Error: 2-14 unclosed group in chemical formula
+$ce("Fe(OH3")$
+
+--- math-ce-unclosed-annotation ---
+// Error: 2-16 unclosed arrow annotation
+$ce("A ->[B C")$
@@ -62,3 +62,8 @@ $ 1/(2 y (x) (2(3)) $
 // Test ignoring weak spacing immediately after the opening
 // and immediately before the closing.
 $ [#h(1em, weak: true)A(dif x, f(x) dif x)sum#h(1em, weak: true)] $
+
+--- math-lr-drawn ---
+// Test delimiters that are drawn because the font can't stretch them.
+$ lr(⟦ frac(a, b) ⟧) quad lr(⟦ mat(1; 2; 3; 4; 5) ⟧)
+  quad lr(⟨ mat(1; 2; 3; 4; 5; 6; 7) ⟩) $
@@ -0,0 +1,49 @@
+// Tests the diagram() and edge() functions.
+
+--- math-diagram-square ---
+// A commutative square.
+$ diagram(
+  A edge("r", f) edge("d", g, swap: #true), B edge("d", h);
+  C edge("r", k, swap: #true), D;
+) $
+
+--- math-diagram-diagonal ---
+// Diagonal arrows and empty nodes.
+$ diagram(
+  X edge("dr", u) edge("drr", f, bend: #20deg) edge("ddr", g, swap: #true, bend: #(-20deg)), , ;
+  , X times_Z Y edge("r", p) edge("d", q, swap: #true), Y edge("d");
+  , X edge("r"), Z;
+) $
+
+--- math-diagram-inline ---
+// A diagram in inline math is centered on the axis.
+#set page(width: auto)
+Consider $diagram(A edge("r", f), B)$ here.
+
+--- math-diagram-edge-styles ---
+// Tips, bends, shifts and strokes.
+#set page(width: auto)
+$ diagram(
+  A edge("r", f, shift: #0.2em) edge("r", g, shift: #(-0.2em), swap: #true), B
+    edge("r", tail: "hook"), C
+    edge("r", head: "double", stroke: #(dash: "dashed")), D;
+  E edge("r", head: "harpoon", tail: "bar"), F
+    edge("r", head: #none, stroke: #blue), G
+    edge("l", bend: #50deg, head: "arrow"), H,
+) $
+
+--- math-diagram-gap ---
+#set math.diagram(row-gap: 1em, column-gap: 1.5em)
+$ diagram(A edge("r") edge("d"), B; C, D) $
+
+--- math-diagram-nested ---
+// The edges of a nested diagram are not edges of the outer diagram.
+$ diagram(diagram(A edge("r", f), B) edge("d", g); C) $
+
+--- math-diagram-edge-out-of-bounds ---
+// Error: 13-22 edge points outside of the diagram
+$ diagram(A edge("u"), B) $
+
+--- math-diagram-edge-bad-direction ---
+// Error: 18-21 expected a direction made of `u`, `d`, `l`, and `r`
+$ diagram(A edge("x"), B) $
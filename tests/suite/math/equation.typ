@@ -256,6 +256,56 @@ $ q &= sum_k ln A \
   ],
 )
 
+--- math-equation-number-lines ---
+// Test numbering the lines of an equation individually.
+#set page(width: 150pt)
+#set math.equation(numbering: "(1)", number-lines: true)
+
+$ x = y #math.number(<before>) $
+$ a &= b #math.number(<first>) \
+  c &= d #math.number(numbered: false) \
+  e &= f #math.number(<third>) \ $
+$ z = w #math.number(<after>) $
+
+See @before, @first, @third and @after.
+
+--- math-equation-number-lines-align ---
+// Test the number alignment of individually numbered lines.
+#set page(width: 150pt)
+#set math.equation(
+  numbering: "(1)",
+  number-lines: true,
+  number-align: left + bottom,
+  line-breaking: true,
+)
+
+$ a &= b + c \
+  d &= e + f + g + h + i + j + k $
+
+--- math-equation-sub-numbering ---
+// Test sub-numbering of individually numbered lines.
+#set page(width: 150pt)
+#set math.equation(
+  numbering: "(1a)",
+  number-lines: true,
+  sub-numbering: true,
+)
+
+$ x = y $
+$ a &= b \
+  c &= d #math.number(<second>) $ <system>
+$ z = w $
+
+See @system and @second.
+
+--- math-equation-number-lines-unnumbered ---
+#set math.equation(numbering: "(1)", number-lines: true)
+$ a = b #math.number(<hidden>, numbered: false) $
+
+// Error: 1-8 cannot reference number without numbering
+// Hint: 1-8 this line was excluded from numbering with `math.number(numbered: false)`
+@hidden
+
 --- issue-numbering-hint ---
 // In this bug, the hint and error messages for an equation
 // being reference mentioned that it was a "heading" and was
@@ -0,0 +1,43 @@
+// Test math font selection.
+
+--- math-font-alphabet ---
+#set math.equation(alphabet-font: "Linux Libertine")
+$ f(x) = alpha x^2 + bold(v) dot 3 $
+$ sin(x) + bold(italic(A)) + upright(b) + x_"max" $
+
+--- math-font-operator ---
+#set text(font: "Linux Libertine")
+#set math.equation(operator-font: "New Computer Modern Math")
+$ sum_(i=1)^n (a_i + b_i)^2 = integral_0^1 f(x) dif x $
+
+--- math-font-fallback-font ---
+#set text(fallback: false)
+#set math.equation(fallback-font: "DejaVu Sans Mono")
+$ x ⚠ y = ☃ $
+
+--- math-font-fallback-missing ---
+#set text(fallback: false)
+// Warning: 5-6 no font could be found that covers the characters "☃"
+// Hint: 5-6 font fallback is disabled
+$ x ☃ y $
+
+--- math-font-ranges ---
+#set math.equation(font-ranges: (
+  "α-ω": "Linux Libertine",
+  "U+002B": "DejaVu Sans Mono",
+  "∑": "New Computer Modern Math",
+))
+$ alpha + beta = sum gamma $
+
+--- math-font-ranges-fold ---
+#set math.equation(font-ranges: ("a-z": "Linux Libertine"))
+#set math.equation(font-ranges: ("x": "DejaVu Sans Mono"))
+$ a x + b y $
+
+--- math-font-ranges-reversed ---
+// Error: 33-59 character range "z-a" is reversed
+#set math.equation(font-ranges: ("z-a": "Linux Libertine"))
+
+--- math-font-ranges-invalid ---
+// Error: 33-59 expected a character or a range of characters, found "abc"
+#set math.equation(font-ranges: ("abc": "Linux Libertine"))
@@ -0,0 +1,36 @@
+// Test LaTeX math import.
+
+--- math-latex-basic ---
+$ #math.latex("\\frac{1}{2} \\sum_{i=0}^n x_i^2 + \\sqrt[3]{\\alpha + \\beta}") $
+$ #math.latex("f'(x) = \\lim_{h \\to 0} \\frac{f(x+h) - f(x)}{h}") $
+
+--- math-latex-inline ---
+The formula #math.latex("e^{i\\pi} + 1 = 0") is famous.
+
+--- math-latex-styles ---
+$ #math.latex("\\mathbb{R} \\mathbf{v} \\mathcal{L} \\mathfrak{g} \\mathrm{d}x \\operatorname{rank} A \\text{ for all } \\hat{x} \\vec{v} \\overline{z}") $
+
+--- math-latex-delimiters ---
+$ #math.latex("\\left( \\frac{a}{b} \\right] \\quad \\left\\{ x \\middle| x > 0 \\right\\} \\quad \\big( \\Big[ \\langle x \\rangle") $
+
+--- math-latex-environments ---
+$ #math.latex("\\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\end{pmatrix} \\begin{vmatrix} a & b \\\\ c & d \\end{vmatrix}") $
+$ #math.latex("|x| = \\begin{cases} x & \\text{if } x \\ge 0 \\\\ -x & \\text{otherwise} \\end{cases}") $
+
+--- math-latex-aligned ---
+$ #math.latex("\\begin{aligned} (a+b)^2 &= a^2 + 2ab + b^2 \\\\ &\\ne a^2 + b^2 \\end{aligned}") $
+
+--- math-latex-braces ---
+$ #math.latex("\\overbrace{a + b}^{n} \\cdot \\underbrace{c + d}_{m} \\quad {}^{14}C \\quad \\sum\\nolimits_{i} \\quad \\overset{!}{=}") $
+
+--- math-latex-unsupported ---
+// Error: 13-27 unsupported LaTeX command `/foo`
+#math.latex("x + \\foo{y}")
+
+--- math-latex-unclosed ---
+// Error: 13-23 unclosed group in LaTeX formula
+#math.latex("\\frac{a")
+
+--- math-latex-environment-unsupported ---
+// Error: 13-45 unsupported LaTeX environment `tabular`
+#math.latex("\\begin{tabular}\\end{tabular}")
@@ -104,6 +104,70 @@ Multiple trailing line breaks.
 $ $\
 Nothing: $ $, just empty.
 
+--- math-linebreaking-block ---
+// Test automatic line breaking of block equations.
+#set page(width: 150pt)
+#set math.equation(line-breaking: true)
+$ f(x) = a_0 + a_1 x + a_2 x^2 + a_3 x^3 + a_4 x^4 $
+$ (a + b + c + d + e) / 2 = x - y + z - w + v $
+
+--- math-linebreaking-block-disabled ---
+// Equations are not broken by default.
+#set page(width: 150pt)
+$ f(x) = a_0 + a_1 x + a_2 x^2 + a_3 x^3 + a_4 x^4 $
+
+--- math-linebreaking-block-continuation ---
+// Test continuation indent and alignment.
+#set page(width: 150pt)
+#set math.equation(line-breaking: true)
+#let eq = $ f(x) = a_0 + a_1 x + a_2 x^2 + a_3 x^3 + a_4 x^4 $
+#eq
+#set math.equation(continuation-indent: 0pt)
+#eq
+#set math.equation(continuation-align: start)
+#eq
+#set math.equation(continuation-align: end, continuation-indent: 1em)
+#eq
+
+--- math-linebreaking-block-aligned ---
+// Test that only overflowing rows are broken.
+#set page(width: 150pt)
+#set math.equation(line-breaking: true)
+$ x &= a + b \
+    &= a_0 + a_1 + a_2 + a_3 + a_4 + a_5 \
+    &= c $
+
+--- math-linebreaking-block-numbered ---
+// Test that breaking leaves room for the number.
+#set page(width: 150pt)
+#set math.equation(line-breaking: true, numbering: "(1)")
+$ a + b + c + d + e + f + g + h $
+
+--- math-equation-breakable ---
+// Test breaking a multi-line equation across pages.
+#set page(width: 150pt, height: 60pt)
+#set math.equation(breakable: true, numbering: "(1)", number-align: bottom)
+$ x &= (a + b)^2 \
+    &= (a + b)(a + b) \
+    &= a^2 + a b + b a + b^2 \
+    &= a^2 + 2 a b + b^2 $
+
+--- math-equation-breakable-bottom ---
+// The number stays with the first line when the equation starts at the bottom
+// of a page that the first line doesn't fit on.
+#set page(width: 150pt, height: 60pt)
+#set math.equation(breakable: true, numbering: "(1)")
+#v(30pt)
+$ x &= (a + b)^2 \
+    &= a^2 + 2 a b + b^2 $
+
+--- math-equation-breakable-disabled ---
+#set page(width: 150pt, height: 60pt)
+$ x &= (a + b)^2 \
+    &= (a + b)(a + b) \
+    &= a^2 + a b + b a + b^2 \
+    &= a^2 + 2 a b + b^2 $
+
 --- issue-1948-math-text-break ---
 // Test text with linebreaks in math.
 $ x := "a\nb\nc\nd\ne" $
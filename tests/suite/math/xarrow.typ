@@ -0,0 +1,20 @@
+// Test extensible arrows.
+
+--- math-xarrow ---
+$ A xarrow("f") B xarrow(sym: arrow.l, below: g) C $
+$ X xarrow(sym: arrow.r.double, "a long annotation", below: n -> oo) Y $
+
+--- math-xarrow-drawn ---
+// Test arrows that are drawn because the font can't stretch them.
+$ A xarrow(sym: harpoons.rtlb, "equilibrium") B
+  xarrow(sym: harpoons.ltrb, below: "reverse") C $
+
+--- math-xarrow-inline ---
+Since $f: A xarrow("iso") B$, we are done.
+
+--- math-xarrow-empty ---
+$ A xarrow() B $
+
+--- math-xarrow-sym-invalid ---
+// Error: 17-21 expected exactly one character
+$ A xarrow(sym: "ab") B $
@@ -53,3 +53,71 @@ Now we have multiple bibliographies containing @glacier-melt @keshav2007read
 
 @Zee04
 #bibliography("/assets/bib/works_too.bib", style: "mla")
+
+--- bibliography-multiple-types ---
+#set page(width: 200pt)
+Books @leeson @feynman and articles @gellmann @higgs.
+
+#bibliography("sources.bib", title: [Books], types: "book")
+#bibliography("sources.bib", title: [Articles], types: "article")
+
+--- bibliography-multiple-keywords ---
+// Keywords are read from both BibLaTeX and Hayagriva files.
+#set page(width: 200pt)
+@leeson @rediker @johnson @kuhn
+
+#let sources = ("sources.bib", "sources.yml")
+#bibliography(sources, title: [Primary sources], keywords: "primary")
+#bibliography(sources, title: [Secondary sources], keywords: "secondary")
+
+--- bibliography-scope-section ---
+#set page(width: 200pt)
+#set heading(numbering: "1.")
+#show bibliography: set text(0.8em)
+
+= Pirates
+Pirates were well organized @leeson @rediker.
+#bibliography("sources.bib", title: none, scope: "section")
+
+= Physics
+Quarks were proposed twice @gellmann @zweig.
+#bibliography("sources.bib", title: none, scope: "section")
+
+--- bibliography-scope-section-and-document ---
+// Citations after the last section bibliography fall back to the document
+// bibliography, which also lists the works of the sections.
+#set page(width: 200pt)
+#set heading(numbering: "1.")
+
+= Chapter
+See @leeson.
+#bibliography("sources.bib", title: none, scope: "section")
+
+= Conclusion
+See @feynman and @leeson.
+#bibliography("sources.bib", title: [All works])
+
+--- bibliography-scope-invalid ---
+// Error: 47-56 expected "document" or "section"
+#bibliography("/assets/bib/works.bib", scope: "chapter")
+
+--- bibliography-types-unknown ---
+// Error: 47-54 unknown entry type: `novel`
+#bibliography("/assets/bib/works.bib", types: "novel")
+
+--- bibliography-types-excluded ---
+// Error: 1-8 key `netwok` is excluded from all bibliographies
+@netwok
+#bibliography("/assets/bib/works.bib", types: "patent")
+
+--- bibliography-keywords-excluded ---
+// Error: 1-8 key `netwok` is excluded from all bibliographies
+@netwok
+#bibliography("/assets/bib/works.bib", keywords: "nonexistent")
+#bibliography("/assets/bib/works.bib", types: ("patent", "legislation"))
+
+--- bibliography-multiple-key-missing ---
+// Error: 2-13 key `bad` does not exist in the bibliography
+#cite(<bad>)
+#bibliography("/assets/bib/works.bib", scope: "section")
+#bibliography("/assets/bib/works_too.bib")
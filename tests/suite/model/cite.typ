@@ -114,3 +114,51 @@ B #cite(<netwok>) #cite(<arrgh>).
 @mcintosh_anxiety
 #show bibliography: none
 #bibliography("/assets/bib/works.bib", style: "chicago-author-date")
+
+--- cite-locator-numeric ---
+#set page(width: 200pt)
+Compare #cite(<leeson>, locator: (page: "45")) with
+#cite(<feynman>, locator: (chapter: "2")).
+Grouped with affixes:
+#cite(<gellmann>, prefix: [see ]) #cite(<zweig>, suffix: [, aces]).
+
+#bibliography("sources.bib", title: none)
+
+--- cite-group-compression ---
+#set page(width: 200pt)
+#show bibliography: set text(6pt)
+@rediker, @higgs, @griffiths.
+Compressed: @gellmann @zweig @englert @higgs @feynman.
+
+#bibliography("sources.bib", title: none)
+
+--- cite-group-compression-affixes ---
+#set page(width: 200pt)
+#show bibliography: none
+@gellmann @zweig @englert @higgs.
+#cite(<gellmann>, prefix: [see ]) @zweig #cite(<englert>, suffix: [, p. 2])
+@higgs.
+
+#bibliography("sources.bib", title: none)
+
+--- cite-locator-author-date ---
+#set page(width: 200pt)
+#set cite(style: "chicago-author-date")
+Compare #cite(<leeson>, locator: (page: "45")) with
+#cite(<feynman>, locator: (chapter: "2")).
+Grouped with affixes:
+#cite(<gellmann>, prefix: [see ]) #cite(<zweig>, suffix: [, aces]).
+
+#bibliography("sources.bib", title: none, style: "chicago-author-date")
+
+--- cite-locator-unknown-kind ---
+// Error: 26-40 unknown locator kind: `pages`
+#cite(<netwok>, locator: (pages: "1-4"))
+
+--- cite-locator-multiple ---
+// Error: 26-47 locator must have exactly one entry
+#cite(<netwok>, locator: (page: 1, chapter: 2))
+
+--- cite-locator-and-supplement ---
+// Error: 2-56 a citation cannot have both a supplement and a locator
+#cite(<netwok>, supplement: [p. 1], locator: (page: 1))
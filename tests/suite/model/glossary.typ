@@ -0,0 +1,42 @@
+--- glossary-first-use ---
+#glossary.entry([API], long: [Application Programming Interface]) <api>
+#glossary.entry([GPU], long: [graphics processing unit]) <gpu>
+
+@api, @api, #glossary.ref(<api>, form: "long").
+
+#glossary.ref(<gpu>, capitalize: true, plural: true) and
+#glossary.ref(<gpu>, plural: true).
+
+--- glossary-ref-supplement ---
+#glossary.entry([API], long: [Application Programming Interface]) <api>
+
+The @api[interface] and @api[APIs]. Then @api.
+
+--- glossary-list ---
+#set page(height: 80pt, numbering: "1")
+#glossary.entry(
+  [PDF],
+  long: [Portable Document Format],
+  description: [A format],
+) <pdf>
+#glossary.entry([CSS], long: [Cascading Style Sheets]) <css>
+@pdf and @css.
+#pagebreak()
+@pdf again.
+#set page(height: auto)
+#glossary(title: none)
+
+--- glossary-ref-unprinted ---
+// Without a printed glossary, references have nothing to link to.
+#glossary.entry([API], long: [Application Programming Interface]) <api>
+@api and @api.
+
+--- glossary-ref-not-entry ---
+= Heading <heading>
+// Error: 2-25 label does not refer to a glossary entry
+#glossary.ref(<heading>)
+
+--- glossary-form-invalid ---
+#glossary.entry([A]) <a>
+// Error: 26-32 expected "short", "long", "full", or auto
+#glossary.ref(<a>, form: "tiny")
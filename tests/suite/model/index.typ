@@ -0,0 +1,33 @@
+--- index-basic ---
+#set page(height: 50pt, numbering: "1")
+Typst#index.marker[Typst]
+Rust#index.marker[Rust]
+#index.marker(sub: [compiler])[Typst]
+#pagebreak()
+Typst#index.marker[Typst]
+#index.marker(see: [Typst])[Typesetting]
+#set page(height: auto)
+#index.marker[Typst]
+#index.marker(see-also: [Rust])[Cargo]
+#index()
+
+--- index-collation-swedish ---
+#set text(lang: "sv")
+#index.marker[Zebra]
+#index.marker[Ål]
+#index.marker[Äpple]
+#index.marker[Apa]
+#index(title: none)
+
+--- index-key ---
+#index.marker(key: "Twenty")[20th century]
+#index.marker[Nineties]
+#index(title: none, grouped: false)
+
+--- index-marker-invisible ---
+#index.marker[Invisible]
+#context test(query(index.marker).len(), 1)
+
+--- index-marker-missing-term ---
+// Error: 2-16 missing argument: term
+#index.marker()
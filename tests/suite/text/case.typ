@@ -6,6 +6,29 @@
 #test(upper(memes), "ARE MEMES GREAT?")
 #test(upper("Ελλάδα"), "ΕΛΛΆΔΑ")
 
+--- lower-and-upper-lang ---
+#test(upper("istanbul", lang: "tr"), "İSTANBUL")
+#test(lower("ISPARTA", lang: "tr"), "ısparta")
+#test(lower("I\u{0307}ZMİR", lang: "az"), "izmir")
+#test(upper("Ελλάδα", lang: "el"), "ΕΛΛΑΔΑ")
+#test(upper("ϊ", lang: "el"), "Ϊ")
+#test(upper("Μάιος", lang: "el"), "ΜΑΪΟΣ")
+#test(upper("άυλος", lang: "el"), "ΑΫΛΟΣ")
+#test(upper("παιδιά", lang: "el"), "ΠΑΙΔΙΑ")
+#test(upper("νεράιδα", lang: "el"), "ΝΕΡΑΪΔΑ")
+#test(upper("straße", lang: "de"), "STRASSE")
+#test(upper("istanbul"), "ISTANBUL")
+
+--- upper-content-lang ---
+#set text(lang: "tr")
+#upper[istanbul] \
+#lower[ISPARTA]
+
+--- upper-content-lang-argument ---
+// Error: 2-26 the language can only be specified for strings
+// Hint: 2-26 content is converted according to the text language
+#upper([abc], lang: "tr")
+
 --- upper-bad-type ---
 // Error: 8-9 expected string or content, found integer
 #upper(1)
@@ -39,6 +39,8 @@ Emoji: 🐪, 🌋, 🏞
 // Disable font fallback beyond the user-specified list.
 // Without disabling, New Computer Modern Math would come to the rescue.
 #set text(font: ("PT Sans", "Twitter Color Emoji"), fallback: false)
+// Warning: 6-7 no font could be found that covers the characters "𝛼𝛽"
+// Hint: 6-7 font fallback is disabled
 2π = 𝛼 + 𝛽. ✅
 
 --- text-call-body ---
@@ -76,3 +78,48 @@ I
 // Warning: 23-56 unknown font family: non-existing-fonts
 #let var = text(font: ("list-of", "non-existing-fonts"))[don't]
 #var
+
+--- text-font-optical-size ---
+// The cut of a family is selected according to the text size.
+#set page(width: auto)
+#set text(font: "Test Optical")
+#text(6pt)[HOTEL]
+#text(12pt)[HOTEL]
+#text(24pt)[HOTEL] \
+// Each cut can also be selected by its own family name.
+#text(24pt, font: "Test Optical Caption")[HOTEL]
+#text(6pt, font: "Test Optical Display")[HOTEL]
+
+--- text-fallbacks ---
+// Test fallback families for scripts and languages.
+#set text(font: "DejaVu Sans Mono", fallback: false)
+#set text(fallbacks: (
+  Hebr: "Linux Libertine",
+  en: "New Computer Modern Math",
+))
+שלום 𝛼
+
+--- text-fallbacks-uncovered ---
+#set text(font: "DejaVu Sans Mono", fallback: false)
+#set text(fallbacks: (Arab: "Linux Libertine"))
+// Warning: 1-9 no font could be found that covers the characters "שלום"
+// Hint: 1-9 font fallback is disabled
+שלום 123
+
+--- text-fallbacks-unknown-script ---
+// Error: 22-41 unknown script: "Abcd"
+#set text(fallbacks: (Abcd: "Noto Sans"))
+
+--- text-fallbacks-bad-key ---
+// Error: 22-42 expected two or three letter language code (ISO 639-1/2/3)
+#set text(fallbacks: (latin: "Noto Sans"))
+
+--- text-font-colr-v1 ---
+// Test COLRv1 glyphs with gradients, transforms, clip boxes and compositing.
+#set page(width: auto)
+#set text(font: "COLRv1 Static Test Glyphs", size: 16pt)
+#let glyphs(from, to) = range(from, to + 1).map(str.from-unicode).join()
+#glyphs(0xF0100, 0xF0103) #glyphs(0xF0200, 0xF0203) \
+#glyphs(0xF0500, 0xF0505) #glyphs(0xF0600, 0xF0603) \
+#glyphs(0xF0700, 0xF0703) #glyphs(0xF0800, 0xF0803) \
+#glyphs(0xF0900, 0xF0902) #glyphs(0xF0A00, 0xF0A0B)
@@ -0,0 +1,58 @@
+// Test ruby annotations.
+
+--- ruby ---
+#ruby[Typst][ˈtaɪpst] is a #ruby[typesetting][ˈtaɪpˌsɛtɪŋ] system.
+
+--- ruby-placement ---
+#set page(width: 120pt)
+#for placement in ("mono", "group", "jukugo") [
+  #ruby(("§", "§", "§"), ("ab", "c", "de"), placement: placement) \
+]
+
+--- ruby-overhang ---
+#set page(width: 120pt)
+#set par(leading: 0.3em)
+x#ruby[§][annotation]x \
+x#ruby(overhang: false)[§][annotation]x \
+:#ruby[§][annotation]: \
+:#ruby(overhang: false)[§][annotation]:
+
+--- ruby-linebreak ---
+#set page(width: 60pt)
+#set par(justify: true)
+A #ruby[baseline][annotation] and #ruby(("a", "b", "c", "d"), ("1", "2", "3", "4")) and more text.
+
+--- ruby-japanese ---
+#set page(width: 120pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP")
+#ruby[東京][とうきょう]に行く。\
+#ruby(("漢", "字"), ("かん", "じ"))を読む。\
+#ruby(("境", "界", "線"), ("きょう", "かい", "せん"), placement: "jukugo")を引く。
+
+--- ruby-styled-base ---
+#set text(lang: "ja", font: ("Linux Libertine", "Noto Serif CJK JP"))
+#show "type": set text(red)
+#ruby[*漢字*][かんじ] and #ruby[*bold*][strong] and
+#ruby[#link("https://typst.app")[Typst]][link] and #ruby[typeset][show]
+
+--- ruby-vertical ---
+#set page(width: 60pt, height: 80pt)
+#set text(lang: "ja", font: "Noto Serif CJK JP", dir: ttb)
+#ruby[東京][とうきょう]に行く。#ruby[§§][ab]
+
+--- ruby-html ---
+// HTML
+#ruby[*漢字*][かんじ]と#ruby(("東", "京"), ("とう", "きょう"))
+
+--- ruby-size ---
+#ruby(size: 0.8em)[base][larger]
+#ruby(size: 6pt)[base][absolute]
+
+--- ruby-mismatch ---
+// Error: 2-32 expected 2 ruby annotations, found 1
+// Hint: 2-32 provide one annotation for each part of the base
+#ruby(("a", "b"), [annotation])
+
+--- ruby-bad-placement ---
+// Error: 18-26 expected "mono", "group", or "jukugo"
+#ruby(placement: "middle")[a][b]